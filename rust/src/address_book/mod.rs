@@ -0,0 +1,239 @@
+//! Address book of runtime-editable public key labels
+
+pub mod store;
+
+use crate::base::public_key::PublicKey;
+use anyhow::{anyhow, bail};
+use bincode::{Decode, Encode};
+use csv::Reader;
+use serde::{Deserialize, Serialize};
+use std::path::Path;
+
+/// Label attributed to a public key
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Label {
+    pub public_key: PublicKey,
+    pub label: String,
+    pub category: Category,
+
+    /// Who attributed the label, e.g. an exchange's announcement
+    pub source: String,
+}
+
+#[derive(
+    Debug,
+    Default,
+    Clone,
+    Copy,
+    PartialEq,
+    Eq,
+    PartialOrd,
+    Ord,
+    Hash,
+    Serialize,
+    Deserialize,
+    clap::ValueEnum,
+    Encode,
+    Decode,
+)]
+#[serde(rename_all = "lowercase")]
+pub enum Category {
+    Exchange,
+    Foundation,
+    Pool,
+
+    #[default]
+    Other,
+}
+
+/// CSV row or JSON object, `category` & `source` are optional
+#[derive(Debug, Deserialize)]
+struct Record {
+    public_key: String,
+    label: String,
+    category: Option<String>,
+    source: Option<String>,
+}
+
+//////////
+// impl //
+//////////
+
+impl Label {
+    /// Label file contents are parsed according to the file extension
+    /// (`csv` or `json`), the file name is the default source
+    pub fn from_path(path: &Path) -> anyhow::Result<Vec<Self>> {
+        let contents = std::fs::read_to_string(path)?;
+        let default_source = path
+            .file_name()
+            .map(|name| name.to_string_lossy().to_string())
+            .unwrap_or_default();
+
+        match path.extension().and_then(|ext| ext.to_str()) {
+            Some("csv") => Self::from_csv(&contents, &default_source),
+            Some("json") => Self::from_json(&contents, &default_source),
+            _ => bail!("Label file must be csv or json: {path:?}"),
+        }
+    }
+
+    /// CSV with header `public_key,label[,category][,source]`
+    pub fn from_csv(contents: &str, default_source: &str) -> anyhow::Result<Vec<Self>> {
+        let mut labels = vec![];
+        let mut rdr = Reader::from_reader(contents.as_bytes());
+
+        for result in rdr.deserialize() {
+            let record: Record = result?;
+            labels.push(Self::from_record(record, default_source)?);
+        }
+
+        Ok(labels)
+    }
+
+    /// JSON array of `{public_key, label, category?, source?}` objects
+    pub fn from_json(contents: &str, default_source: &str) -> anyhow::Result<Vec<Self>> {
+        let records: Vec<Record> = serde_json::from_str(contents)?;
+
+        records
+            .into_iter()
+            .map(|record| Self::from_record(record, default_source))
+            .collect()
+    }
+
+    fn from_record(record: Record, default_source: &str) -> anyhow::Result<Self> {
+        let public_key = PublicKey::new(record.public_key)?;
+        if record.label.is_empty() {
+            bail!("Empty label for {public_key}");
+        }
+
+        Ok(Self {
+            public_key,
+            label: record.label,
+            category: match record.category.as_deref() {
+                None | Some("") => Category::default(),
+                Some(category) => category.parse()?,
+            },
+            source: record.source.unwrap_or_else(|| default_source.to_string()),
+        })
+    }
+}
+
+impl Category {
+    pub const ALL: [Self; 4] = [Self::Exchange, Self::Foundation, Self::Pool, Self::Other];
+
+    /// Category byte prefixing the category-sorted label keys
+    pub fn to_byte(self) -> u8 {
+        self as u8
+    }
+
+    pub fn from_byte(byte: u8) -> anyhow::Result<Self> {
+        Self::ALL
+            .get(byte as usize)
+            .copied()
+            .ok_or_else(|| anyhow!("Invalid label category byte: {byte}"))
+    }
+}
+
+/////////////////
+// conversions //
+/////////////////
+
+impl std::str::FromStr for Category {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "exchange" => Ok(Self::Exchange),
+            "foundation" => Ok(Self::Foundation),
+            "pool" => Ok(Self::Pool),
+            "other" => Ok(Self::Other),
+            _ => bail!("Invalid label category: {s}"),
+        }
+    }
+}
+
+///////////////////
+// display/debug //
+///////////////////
+
+impl std::fmt::Display for Category {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "{}",
+            match self {
+                Self::Exchange => "exchange",
+                Self::Foundation => "foundation",
+                Self::Pool => "pool",
+                Self::Other => "other",
+            }
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const PK: &str = "B62qpge4uMq4Vv5Rvc8Gw9qSquUYd6xoW1pz7HQkMSHm6h1o7pvLPAN";
+
+    #[test]
+    fn category_bytes() -> anyhow::Result<()> {
+        for category in Category::ALL {
+            assert_eq!(Category::from_byte(category.to_byte())?, category);
+            assert_eq!(category.to_string().parse::<Category>()?, category);
+        }
+
+        assert!(Category::from_byte(Category::ALL.len() as u8).is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn labels_from_csv() -> anyhow::Result<()> {
+        let contents = format!(
+            "public_key,label,category,source\n{PK},MinaExplorer,pool,explorer\n{PK},Someone,,\n"
+        );
+        let labels = Label::from_csv(&contents, "labels.csv")?;
+
+        assert_eq!(
+            labels,
+            vec![
+                Label {
+                    public_key: PK.into(),
+                    label: "MinaExplorer".into(),
+                    category: Category::Pool,
+                    source: "explorer".into(),
+                },
+                Label {
+                    public_key: PK.into(),
+                    label: "Someone".into(),
+                    category: Category::Other,
+                    source: "labels.csv".into(),
+                },
+            ]
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn labels_from_json() -> anyhow::Result<()> {
+        let contents =
+            format!(r#"[{{"public_key":"{PK}","label":"Binance","category":"Exchange"}}]"#);
+        let labels = Label::from_json(&contents, "labels.json")?;
+
+        assert_eq!(
+            labels,
+            vec![Label {
+                public_key: PK.into(),
+                label: "Binance".into(),
+                category: Category::Exchange,
+                source: "labels.json".into(),
+            }]
+        );
+
+        // invalid category & public key
+        let contents = format!(r#"[{{"public_key":"{PK}","label":"x","category":"bank"}}]"#);
+        assert!(Label::from_json(&contents, "").is_err());
+        assert!(Label::from_json(r#"[{"public_key":"B62","label":"x"}]"#, "").is_err());
+        Ok(())
+    }
+}
@@ -0,0 +1,28 @@
+//! Address book store trait
+
+use super::{Category, Label};
+use crate::{
+    base::{public_key::PublicKey, username::Username},
+    store::Result,
+};
+
+pub trait AddressBookStore {
+    /// Add or replace the public key's label
+    fn add_label(&self, label: &Label) -> Result<()>;
+
+    /// Remove `pk`'s label, returns whether the label existed
+    fn remove_label(&self, pk: &PublicKey) -> Result<bool>;
+
+    /// Get `pk`'s label
+    fn get_label(&self, pk: &PublicKey) -> Result<Option<Label>>;
+
+    /// Get all labels, or only the category's labels, sorted by public key
+    fn get_labels(&self, category: Option<Category>) -> Result<Vec<Label>>;
+
+    /// Public keys labeled `label`, sorted
+    fn get_label_pks(&self, label: &str) -> Result<Vec<PublicKey>>;
+
+    /// Display name of `pk`, its label takes precedence over its username
+    /// unless the label isn't a valid username
+    fn resolve_username(&self, pk: &PublicKey) -> Result<Option<Username>>;
+}
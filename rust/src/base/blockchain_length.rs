@@ -1,6 +1,9 @@
 //! Indexer blockchain length type
 
-use crate::{block::precomputed::PcbVersion, constants::HARDFORK_GENESIS_BLOCKCHAIN_LENGTH};
+use crate::{
+    block::{extract_block_height, is_bin_prot_block_file, precomputed::PcbVersion},
+    constants::HARDFORK_GENESIS_BLOCKCHAIN_LENGTH,
+};
 use anyhow::bail;
 use serde::{Deserialize, Serialize};
 use std::{
@@ -49,6 +52,11 @@ impl BlockchainLength {
     pub fn from_path(path: &Path) -> anyhow::Result<u32> {
         const BUFFER_CAPACITY: usize = 1000;
 
+        // bin_prot blocks have no readable prefix, use the file name
+        if is_bin_prot_block_file(path) {
+            return Ok(extract_block_height(path));
+        }
+
         let file = File::open(path)?;
         let reader = BufReader::new(file);
         let mut buffer = String::with_capacity(BUFFER_CAPACITY);
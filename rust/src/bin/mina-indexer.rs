@@ -2,17 +2,28 @@ use clap::{Parser, Subcommand};
 use log::{debug, error, info, warn, LevelFilter};
 use mina_indexer::{
     block::precomputed::PcbVersion,
-    chain::ChainId,
+    chain::{ChainId, Network},
     cli::{
         database::DatabaseArgs,
-        server::{ServerArgs, ServerArgsJson},
+        output::OutputFormat,
+        server::{ReplicaArgs, ServerArgs, ServerArgsJson},
         LogLevelFilter,
     },
     client,
+    command::signed::verify::SignatureVerifier,
     constants::*,
     ledger::genesis::GenesisLedger,
-    server::{GenesisVersion, IndexerConfiguration, IndexerVersion, InitializationMode},
-    store::{restore_snapshot, version::IndexerStoreVersion, IndexerStore},
+    proof_systems::poseidon::{PoseidonKind, SpongeParams},
+    server::{
+        start_replica, GenesisVersion, IndexerConfiguration, IndexerVersion, InitializationMode,
+    },
+    state::missing::SharedMissingBlocks,
+    store::{
+        migration::{self, pending_migrations},
+        read_indexer_version, restore_snapshot,
+        version::IndexerStoreVersion,
+        IndexerStore,
+    },
     unix_socket_server::remove_unix_socket,
     web::start_web_server,
 };
@@ -40,6 +51,10 @@ struct Cli {
     /// Path to the Unix domain socket file
     #[arg(long, default_value = "./mina-indexer.sock", num_args = 1)]
     socket: PathBuf,
+
+    /// Client output format
+    #[arg(long, global = true, value_enum, default_value_t = OutputFormat::Text)]
+    output: OutputFormat,
 }
 
 #[derive(Subcommand, Debug)]
@@ -70,6 +85,9 @@ enum ServerCommand {
     /// Start a new mina indexer
     Start(Box<ServerArgs>),
 
+    /// Start a read-only replica of a running mina indexer's database
+    Replica(Box<ReplicaArgs>),
+
     /// Shutdown the server
     Shutdown,
 }
@@ -102,6 +120,17 @@ enum DatabaseCommand {
         restore_dir: PathBuf,
     },
 
+    /// Migrate a mina indexer database to the current version
+    Migrate {
+        /// Full path to the database directory
+        #[arg(long)]
+        database_dir: PathBuf,
+
+        /// List the pending migration steps without running them
+        #[arg(long, default_value_t = false)]
+        dry_run: bool,
+    },
+
     /// Query mina indexer database version
     Version {
         /// Output JSON data
@@ -124,14 +153,17 @@ impl From<&DatabaseCommand> for LevelFilter {
 pub async fn main() -> anyhow::Result<()> {
     let args = Cli::parse();
     let domain_socket_path = args.socket;
+    let output = args.output;
 
     let result = Toplevel::new(|s| async move {
         s.start(SubsystemBuilder::new("Main", |s| async move {
             match args.command {
-                IndexerCommand::Client(cli) => cli.run(domain_socket_path).await,
-                IndexerCommand::Database { db_command } => db_command.run(domain_socket_path).await,
+                IndexerCommand::Client(cli) => cli.run(domain_socket_path, output).await,
+                IndexerCommand::Database { db_command } => {
+                    db_command.run(domain_socket_path, output).await
+                }
                 IndexerCommand::Server { server_command } => {
-                    server_command.run(s, domain_socket_path).await
+                    server_command.run(s, domain_socket_path, output).await
                 }
                 IndexerCommand::Version => {
                     println!("{VERSION}");
@@ -167,9 +199,19 @@ pub async fn main() -> anyhow::Result<()> {
 }
 
 impl ServerCommand {
-    async fn run(self, subsys: SubsystemHandle, domain_socket_path: PathBuf) -> anyhow::Result<()> {
+    async fn run(
+        self,
+        subsys: SubsystemHandle,
+        domain_socket_path: PathBuf,
+        output: OutputFormat,
+    ) -> anyhow::Result<()> {
         let (args, mode) = match self {
-            Self::Shutdown => return client::ClientCli::Shutdown.run(domain_socket_path).await,
+            Self::Shutdown => {
+                return client::ClientCli::Shutdown
+                    .run(domain_socket_path, output)
+                    .await
+            }
+            Self::Replica(args) => return run_replica(subsys, *args, domain_socket_path).await,
             Self::Start(args) => {
                 if let Some(config_path) = args.db.config {
                     let contents = std::fs::read(config_path)?;
@@ -186,6 +228,11 @@ impl ServerCommand {
         let database_dir = args.db.database_dir.clone();
         let web_hostname = args.web_hostname.clone();
         let web_port = args.web_port;
+        let poseidon_params = args.db.poseidon_params.clone();
+        let verify_ledger_hashes = args.db.verify_ledger_hashes;
+        let verify_signatures = args.db.verify_signatures;
+        let network = args.db.network.clone();
+        let mainnet = network == Network::Mainnet;
 
         // initialize logging
         stderrlog::new()
@@ -204,8 +251,29 @@ impl ServerCommand {
         let db = Arc::new(IndexerStore::new(&database_dir, false)?);
         let store = db.clone();
 
-        subsys.start(SubsystemBuilder::new("Indexer", move |s| {
-            config.start_indexer(s, store)
+        if verify_ledger_hashes {
+            info!("Verifying staking ledger hashes");
+            db.ledger_hash_params
+                .set(SpongeParams::resolve(
+                    poseidon_params.as_deref(),
+                    PoseidonKind::Legacy,
+                )?)
+                .expect("ledger hash params are only set once");
+        }
+
+        if verify_signatures {
+            info!("Verifying user command signatures");
+            db.signature_verifier
+                .set(SignatureVerifier::new(poseidon_params.as_deref(), mainnet)?)
+                .expect("signature verifier is only set once");
+        }
+
+        // maintained by the indexer, read by the web server
+        let missing_blocks = SharedMissingBlocks::default();
+
+        subsys.start(SubsystemBuilder::new("Indexer", {
+            let missing_blocks = missing_blocks.clone();
+            move |s| config.start_indexer(s, store, missing_blocks)
         }));
 
         info!("Starting the web server listening on {web_hostname}:{web_port}");
@@ -213,7 +281,7 @@ impl ServerCommand {
         let host = web_hostname.clone();
 
         subsys.start(SubsystemBuilder::new("Web Server", move |s| {
-            start_web_server(s, store, (host, web_port))
+            start_web_server(s, store, missing_blocks, network, (host, web_port))
         }));
 
         info!("GraphQL server started at: http://{web_hostname}:{web_port}/graphql");
@@ -230,8 +298,74 @@ impl ServerCommand {
     }
 }
 
+/// Serves queries from a secondary instance of the primary's database
+async fn run_replica(
+    subsys: SubsystemHandle,
+    args: ReplicaArgs,
+    domain_socket_path: PathBuf,
+) -> anyhow::Result<()> {
+    // initialize logging
+    stderrlog::new()
+        .module(module_path!())
+        .color(ColorChoice::Never)
+        .timestamp(Timestamp::Microsecond)
+        .verbosity(args.log_level.0)
+        .init()
+        .unwrap();
+
+    // a temporary secondary dir, if needed, lives as long as the replica
+    let (secondary_dir, _tmp_dir) = match args.secondary_db {
+        Some(secondary_dir) => (secondary_dir, None),
+        None => {
+            let tmp_dir = TempDir::new()?;
+            (tmp_dir.path().to_path_buf(), Some(tmp_dir))
+        }
+    };
+
+    info!(
+        "Opening read replica of {:#?} in {secondary_dir:#?}",
+        args.primary_db
+    );
+    let db = Arc::new(IndexerStore::read_only(&args.primary_db, &secondary_dir)?);
+    let store = db.clone();
+    let socket_path = domain_socket_path.clone();
+    let catch_up_interval = Duration::from_millis(args.catch_up_interval);
+
+    subsys.start(SubsystemBuilder::new("Replica", move |s| {
+        start_replica(s, store, socket_path, catch_up_interval)
+    }));
+
+    let web_hostname = args.web_hostname;
+    let web_port = args.web_port;
+    let network = args.network;
+
+    info!("Starting the web server listening on {web_hostname}:{web_port}");
+    let store = db.clone();
+    let host = web_hostname.clone();
+
+    // no witness tree on replicas
+    subsys.start(SubsystemBuilder::new("Web Server", move |s| {
+        start_web_server(
+            s,
+            store,
+            SharedMissingBlocks::default(),
+            network,
+            (host, web_port),
+        )
+    }));
+
+    info!("GraphQL server started at: http://{web_hostname}:{web_port}/graphql");
+    subsys.on_shutdown_requested().await;
+
+    debug!("Shutting down secondary database instance");
+    drop(db);
+    remove_unix_socket(&domain_socket_path)?;
+
+    Ok(())
+}
+
 impl DatabaseCommand {
-    async fn run(self, domain_socket_path: PathBuf) -> anyhow::Result<()> {
+    async fn run(self, domain_socket_path: PathBuf, output: OutputFormat) -> anyhow::Result<()> {
         // initialize logging
         stderrlog::new()
             .module(module_path!())
@@ -253,6 +387,36 @@ impl DatabaseCommand {
                     }
                 )
             }
+            Self::Migrate {
+                database_dir,
+                dry_run,
+            } => {
+                let Some(version) = read_indexer_version(&database_dir)? else {
+                    error!("No mina indexer database in {database_dir:#?}");
+                    process::exit(1);
+                };
+
+                let current = IndexerStoreVersion::current_semver();
+                let registry = migration::registry();
+                let steps = pending_migrations(&registry, version.semver(), current)?;
+
+                if steps.is_empty() {
+                    println!("Database v{} is up to date", version.major_minor_patch());
+                } else if dry_run {
+                    for (from, to, step) in steps {
+                        println!(
+                            "v{} -> v{}: {}",
+                            migration::display(from),
+                            migration::display(to),
+                            step.description
+                        );
+                    }
+                } else {
+                    info!("Migrating database dir {database_dir:#?}");
+                    IndexerStore::new(&database_dir, false)?;
+                    println!("Migrated database to v{}", migration::display(current));
+                }
+            }
             Self::Snapshot {
                 output_path,
                 database_dir,
@@ -269,7 +433,7 @@ impl DatabaseCommand {
                 } else {
                     info!("Creating snapshot of running mina indexer");
                     return client::ClientCli::CreateSnapshot { output_path }
-                        .run(domain_socket_path)
+                        .run(domain_socket_path, output)
                         .await;
                 }
             }
@@ -337,6 +501,7 @@ fn process_indexer_configuration(
 ) -> anyhow::Result<IndexerConfiguration> {
     let genesis_hash = args.db.genesis_hash;
     let blocks_dir = args.db.blocks_dir;
+    let blocks_source = args.db.blocks_source;
     let staking_ledgers_dir = args.db.staking_ledgers_dir;
     let prune_interval = args.db.prune_interval;
     let canonical_threshold = args.db.canonical_threshold;
@@ -402,6 +567,7 @@ fn process_indexer_configuration(
         genesis_ledger,
         version,
         blocks_dir,
+        blocks_source,
         staking_ledgers_dir,
         prune_interval,
         canonical_threshold,
@@ -0,0 +1,158 @@
+//! Long-range fork choice data
+//!
+//! https://github.com/MinaProtocol/mina/tree/develop/docs/specs/consensus#62-select-chain
+
+use super::precomputed::PrecomputedBlock;
+use crate::{base::state_hash::StateHash, constants::*};
+use serde::{Deserialize, Serialize};
+
+/// Consensus state needed to compare chains which diverge before the most
+/// recent lock checkpoint
+#[derive(Debug, Hash, PartialEq, Eq, Clone, Serialize, Deserialize)]
+pub struct ForkChoiceData {
+    pub epoch_count: u32,
+    pub curr_global_slot: u32,
+    pub min_window_density: u32,
+    pub sub_window_densities: Vec<u32>,
+    pub staking_epoch_lock_checkpoint: StateHash,
+    pub next_epoch_lock_checkpoint: StateHash,
+}
+
+impl ForkChoiceData {
+    /// Follows `isShortRange`
+    ///
+    /// Chains are short-range forks if they share the same staking epoch
+    /// lock checkpoint, or if one is an epoch ahead & its staking epoch
+    /// lock checkpoint is the other's next epoch lock checkpoint
+    pub fn is_short_range(&self, other: &Self) -> bool {
+        if self.epoch_count == other.epoch_count {
+            self.staking_epoch_lock_checkpoint == other.staking_epoch_lock_checkpoint
+        } else if self.epoch_count == other.epoch_count + 1 {
+            self.staking_epoch_lock_checkpoint == other.next_epoch_lock_checkpoint
+        } else if other.epoch_count == self.epoch_count + 1 {
+            other.staking_epoch_lock_checkpoint == self.next_epoch_lock_checkpoint
+        } else {
+            false
+        }
+    }
+
+    /// Follows `relativeMinWindowDensity`
+    ///
+    /// Projects this chain's sliding window forward to the later of the two
+    /// chains' slots, zeroing the sub-windows which have been shifted out
+    pub fn relative_min_window_density(&self, other: &Self) -> u32 {
+        let max_slot = self.curr_global_slot.max(other.curr_global_slot);
+        if max_slot < MAINNET_GRACE_PERIOD_END {
+            return self.min_window_density;
+        }
+
+        let shift_count = (sub_window(max_slot) - sub_window(self.curr_global_slot))
+            .saturating_sub(1)
+            .min(MAINNET_SUB_WINDOWS_PER_WINDOW);
+
+        let mut projected_window = self.sub_window_densities.clone();
+        let num_sub_windows = projected_window.len();
+        let mut index = relative_sub_window(self.curr_global_slot) as usize;
+
+        if num_sub_windows > 0 {
+            for _ in 0..shift_count {
+                index = (index + 1) % num_sub_windows;
+                projected_window[index] = 0;
+            }
+        }
+
+        self.min_window_density
+            .min(projected_window.into_iter().sum())
+    }
+}
+
+impl From<&PrecomputedBlock> for ForkChoiceData {
+    fn from(value: &PrecomputedBlock) -> Self {
+        Self {
+            epoch_count: value.epoch_count(),
+            curr_global_slot: value.curr_global_slot_since_hard_fork(),
+            min_window_density: value.min_window_density(),
+            sub_window_densities: value.sub_window_densities(),
+            staking_epoch_lock_checkpoint: value.staking_epoch_lock_checkpoint(),
+            next_epoch_lock_checkpoint: value.next_epoch_lock_checkpoint(),
+        }
+    }
+}
+
+/// Global sub-window containing the slot
+fn sub_window(slot: u32) -> u32 {
+    slot / MAINNET_SLOTS_PER_SUB_WINDOW
+}
+
+/// Index of the slot's sub-window within the sliding window
+fn relative_sub_window(slot: u32) -> u32 {
+    sub_window(slot) % MAINNET_SUB_WINDOWS_PER_WINDOW
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn data(epoch_count: u32, staking: &str, next: &str) -> ForkChoiceData {
+        ForkChoiceData {
+            epoch_count,
+            curr_global_slot: 0,
+            min_window_density: 0,
+            sub_window_densities: vec![],
+            staking_epoch_lock_checkpoint: staking.into(),
+            next_epoch_lock_checkpoint: next.into(),
+        }
+    }
+
+    #[test]
+    fn short_range() {
+        // same epoch, same checkpoint
+        assert!(data(1, "a", "b").is_short_range(&data(1, "a", "c")));
+
+        // same epoch, different checkpoints
+        assert!(!data(1, "a", "b").is_short_range(&data(1, "c", "b")));
+
+        // adjacent epochs
+        assert!(data(2, "b", "c").is_short_range(&data(1, "a", "b")));
+        assert!(data(1, "a", "b").is_short_range(&data(2, "b", "c")));
+        assert!(!data(2, "d", "c").is_short_range(&data(1, "a", "b")));
+
+        // distant epochs
+        assert!(!data(3, "b", "c").is_short_range(&data(1, "a", "b")));
+    }
+
+    #[test]
+    fn relative_min_window_density() {
+        let slots_per_window = MAINNET_SLOTS_PER_SUB_WINDOW * MAINNET_SUB_WINDOWS_PER_WINDOW;
+        let chain = ForkChoiceData {
+            curr_global_slot: MAINNET_GRACE_PERIOD_END,
+            min_window_density: 60,
+            sub_window_densities: vec![6; MAINNET_SUB_WINDOWS_PER_WINDOW as usize],
+            ..data(0, "a", "b")
+        };
+
+        // within the grace period
+        let early = ForkChoiceData {
+            curr_global_slot: 0,
+            ..chain.clone()
+        };
+        assert_eq!(early.relative_min_window_density(&early), 60);
+
+        // no projection needed
+        assert_eq!(chain.relative_min_window_density(&chain), 60);
+
+        // other chain is 3 sub-windows ahead, 2 sub-windows shifted out
+        let ahead = ForkChoiceData {
+            curr_global_slot: chain.curr_global_slot + 3 * MAINNET_SLOTS_PER_SUB_WINDOW,
+            ..chain.clone()
+        };
+        assert_eq!(chain.relative_min_window_density(&ahead), 54);
+
+        // other chain is more than a window ahead, entire window shifted out
+        let far_ahead = ForkChoiceData {
+            curr_global_slot: chain.curr_global_slot + 2 * slots_per_window,
+            ..chain.clone()
+        };
+        assert_eq!(chain.relative_min_window_density(&far_ahead), 0);
+    }
+}
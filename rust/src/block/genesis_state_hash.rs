@@ -1,4 +1,4 @@
-use super::StateHash;
+use super::{is_bin_prot_block_file, precomputed::bin_prot, StateHash};
 use anyhow::bail;
 use std::{
     fs::File,
@@ -12,6 +12,11 @@ impl GenesisStateHash {
     pub fn from_path(path: &Path) -> anyhow::Result<StateHash> {
         const BUFFER_CAPACITY: usize = 400;
 
+        // bin_prot blocks lead with the state hashes
+        if is_bin_prot_block_file(path) {
+            return Ok(bin_prot::read_state_hashes(path)?.1);
+        }
+
         let file = File::open(path)?;
         let reader = BufReader::new(file);
         let mut buffer = String::with_capacity(BUFFER_CAPACITY);
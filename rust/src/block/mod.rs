@@ -1,17 +1,20 @@
 //! Indexer internal block representation used in the witness tree
 
 pub mod epoch_data;
+pub mod fork_choice;
 pub mod genesis;
 pub mod genesis_state_hash;
 pub mod parser;
 pub mod precomputed;
 pub mod previous_state_hash;
+pub mod production;
+pub mod source;
 pub mod store;
 pub mod vrf_output;
 
 mod post_hardfork;
 
-use self::{precomputed::PrecomputedBlock, vrf_output::VrfOutput};
+use self::{fork_choice::ForkChoiceData, precomputed::PrecomputedBlock, vrf_output::VrfOutput};
 use crate::{
     base::{blockchain_length::BlockchainLength, state_hash::StateHash},
     canonicity::Canonicity,
@@ -38,6 +41,10 @@ pub struct Block {
     pub genesis_state_hash: StateHash,
     pub global_slot_since_genesis: u32,
     pub hash_last_vrf_output: VrfOutput,
+
+    /// Only absent for genesis blocks
+    #[serde(default)]
+    pub fork_choice: Option<ForkChoiceData>,
 }
 
 #[derive(Hash, PartialEq, Eq, Clone, Serialize, Deserialize)]
@@ -59,6 +66,7 @@ impl Block {
             hash_last_vrf_output: precomputed_block.hash_last_vrf_output(),
             global_slot_since_genesis: precomputed_block.global_slot_since_genesis(),
             genesis_state_hash: precomputed_block.genesis_state_hash(),
+            fork_choice: Some(precomputed_block.into()),
         }
     }
 
@@ -121,6 +129,7 @@ pub struct BlockComparison {
 impl From<PrecomputedBlock> for Block {
     fn from(value: PrecomputedBlock) -> Self {
         Self {
+            fork_choice: Some((&value).into()),
             height: value.blockchain_length().saturating_sub(1),
             parent_hash: value.previous_state_hash(),
             blockchain_length: value.blockchain_length(),
@@ -135,6 +144,7 @@ impl From<PrecomputedBlock> for Block {
 impl From<&PrecomputedBlock> for Block {
     fn from(value: &PrecomputedBlock) -> Self {
         Self {
+            fork_choice: Some(value.into()),
             height: value.blockchain_length().saturating_sub(1),
             parent_hash: value.previous_state_hash(),
             blockchain_length: value.blockchain_length(),
@@ -210,7 +220,8 @@ impl std::cmp::PartialOrd for Block {
 }
 
 impl std::cmp::Ord for Block {
-    /// Follows `selectLongerChain`
+    /// Follows `selectSecureChain`, long-range forks prefer the chain with
+    /// the greater relative min window density, otherwise `selectLongerChain`
     /// A < B means A is better than B
     /// https://github.com/MinaProtocol/mina/tree/develop/docs/specs/consensus#62-select-chain
     fn cmp(&self, other: &Self) -> Ordering {
@@ -224,6 +235,20 @@ impl std::cmp::Ord for Block {
             _ => (),
         }
 
+        // long-range fork
+        if let (Some(self_data), Some(other_data)) = (&self.fork_choice, &other.fork_choice) {
+            if !self_data.is_short_range(other_data) {
+                let self_density = self_data.relative_min_window_density(other_data);
+                let other_density = other_data.relative_min_window_density(self_data);
+
+                match self_density.cmp(&other_density) {
+                    Ordering::Greater => return Ordering::Less,
+                    Ordering::Less => return Ordering::Greater,
+                    Ordering::Equal => (),
+                }
+            }
+        }
+
         let length_cmp = self.blockchain_length.cmp(&other.blockchain_length);
         let vrf_cmp = self.hash_last_vrf_output.cmp(&other.hash_last_vrf_output);
         let hash_cmp = self.state_hash.cmp(&other.state_hash);
@@ -301,14 +326,28 @@ impl std::fmt::Display for StateHash {
 // helpers //
 /////////////
 
+/// Extension of bin_prot encoded precomputed block files
+pub const BIN_PROT_BLOCK_EXT: &str = "bin";
+
+/// Checks if the path is a JSON or bin_prot block file
 pub fn is_valid_block_file<P>(path: P) -> bool
 where
     P: AsRef<Path>,
     P: Into<PathBuf>,
 {
+    if is_bin_prot_block_file(path.as_ref()) {
+        return is_valid_file_name(path.as_ref().with_extension("json"), &StateHash::is_valid);
+    }
+
     is_valid_file_name(path, &StateHash::is_valid)
 }
 
+/// Checks if the path has the bin_prot block file extension
+pub fn is_bin_prot_block_file(path: &Path) -> bool {
+    path.extension()
+        .is_some_and(|ext| ext == BIN_PROT_BLOCK_EXT)
+}
+
 pub fn sort_by_height_and_lexicographical_order(paths: &mut [&std::path::PathBuf]) {
     paths.sort_by(|a, b| {
         let (height_a, hash_a) = extract_height_and_hash(a);
@@ -444,6 +483,25 @@ mod tests {
         Ok(())
     }
 
+    #[test]
+    fn bin_prot_block_file_is_valid() {
+        let json = Path::new("mainnet-2-3NLyWnjZqUECniE1q719CoLmes6WDQAod4vrTeLfN7XXJbHv6EHH.json");
+        let bin = Path::new(
+            "/tmp/blocks/mainnet-359605-3NK4huLvUDiL4XuCUcyrWCKynmvhqfKsx5h2MfBXVVUq2Qwzi5uT.bin",
+        );
+
+        assert!(is_valid_block_file(json));
+        assert!(is_valid_block_file(bin));
+        assert!(is_bin_prot_block_file(bin));
+        assert!(!is_bin_prot_block_file(json));
+
+        // invalid state hash or extension
+        assert!(!is_valid_block_file(Path::new("mainnet-2-abc123.bin")));
+        assert!(!is_valid_block_file(Path::new(
+            "mainnet-2-3NLyWnjZqUECniE1q719CoLmes6WDQAod4vrTeLfN7XXJbHv6EHH.txt"
+        )));
+    }
+
     #[test]
     fn test_sort_by_height_and_lexicographical_order() {
         let filename1 = PathBuf::from("mainnet-1-abc123.json");
@@ -2,6 +2,8 @@ use super::{
     extract_block_height,
     genesis_state_hash::GenesisStateHash,
     precomputed::{PcbVersion, PrecomputedBlock},
+    source::{self, BlockSource},
+    BIN_PROT_BLOCK_EXT,
 };
 use crate::{
     block::extract_network_height_hash,
@@ -27,6 +29,8 @@ use std::{
 ///
 /// Traverses deep canoncial, recent, then orphaned (orphaned paths bypass the
 /// witness tree)
+///
+/// A parser opened on a [BlockSource] yields its blocks as _recent_
 pub struct BlockParser {
     pub blocks_dir: PathBuf,
     pub blocks_processed: u32,
@@ -42,6 +46,7 @@ pub struct BlockParser {
     canonical_paths: IntoIter<PathBuf>,
     recent_paths: IntoIter<PathBuf>,
     orphaned_paths: IntoIter<PathBuf>,
+    source: Option<Box<dyn BlockSource + Send>>,
 }
 
 #[derive(Debug, PartialEq, Eq)]
@@ -105,9 +110,7 @@ impl BlockParser {
     ) -> anyhow::Result<Self> {
         if blocks_dir.exists() {
             let blocks_dir = blocks_dir.to_owned();
-            let mut paths: Vec<PathBuf> = glob(&format!("{}/*-*-*.json", blocks_dir.display()))?
-                .flatten()
-                .collect();
+            let mut paths = block_file_paths(&blocks_dir)?;
             let total_num_bytes = paths
                 .iter()
                 .fold(0, |acc, p| acc + p.metadata().unwrap().len());
@@ -135,6 +138,7 @@ impl BlockParser {
                 canonical_paths: vec![].into_iter(),
                 orphaned_paths: vec![].into_iter(),
                 chain_data: ChainData::default(),
+                source: None,
             })
         } else {
             Ok(Self::empty(blocks_dir, &[]))
@@ -145,9 +149,7 @@ impl BlockParser {
     pub fn new_testing(blocks_dir: &Path) -> anyhow::Result<Self> {
         if blocks_dir.exists() {
             let blocks_dir = blocks_dir.to_owned();
-            let mut paths: Vec<PathBuf> = glob(&format!("{}/*-*-*.json", blocks_dir.display()))?
-                .flatten()
-                .collect();
+            let mut paths = block_file_paths(&blocks_dir)?;
             paths.sort_by_cached_key(|path| extract_block_height(path));
 
             println!("===== Testing block parser paths =====");
@@ -162,6 +164,19 @@ impl BlockParser {
         }
     }
 
+    /// Returns a new block parser reading the block source at `path`, see
+    /// [source::open]
+    pub fn new_with_source(path: &Path) -> anyhow::Result<Self> {
+        let source = source::open(path)?;
+        let mut block_parser = Self::empty(path, &[]);
+
+        block_parser.total_num_blocks = source.num_blocks().unwrap_or_default();
+        block_parser.num_recent_blocks = block_parser.total_num_blocks;
+        block_parser.source = Some(source);
+
+        Ok(block_parser)
+    }
+
     /// Length-sorts `block_dir`'s paths and performs _canonical chain
     /// discovery_ separating the block paths into two categories:
     /// - blocks known to be _canonical_
@@ -181,9 +196,8 @@ impl BlockParser {
         };
 
         if blocks_dir.exists() {
-            let pattern = format!("{}/*-*-*.json", blocks_dir.display());
             let blocks_dir = blocks_dir.to_owned();
-            let paths: Vec<PathBuf> = glob(&pattern)?.flatten().collect();
+            let paths = block_file_paths(&blocks_dir)?;
             if let Ok((canonical_paths, recent_paths, orphaned_paths)) = discovery(
                 &genesis_state_hash.into(),
                 canonical_threshold,
@@ -225,6 +239,7 @@ impl BlockParser {
                         orphaned_paths.into_iter()
                     },
                     chain_data: ChainData::default(),
+                    source: None,
                 })
             } else {
                 Ok(Self::empty(&blocks_dir, &paths))
@@ -297,7 +312,25 @@ impl BlockParser {
             return self.consume_block(&next_path, &ParsedBlock::Orphaned);
         }
 
-        Ok(None)
+        self.next_source_block()
+    }
+
+    /// Reads the next block of the parser's block source, blocking until it's
+    /// available. `None` if there is no source or it's exhausted
+    pub fn next_source_block(&mut self) -> anyhow::Result<Option<(ParsedBlock, u64)>> {
+        let Some(source) = self.source.as_mut() else {
+            return Ok(None);
+        };
+
+        match source.next_block()? {
+            Some((block, block_bytes)) => {
+                self.blocks_processed += 1;
+                self.bytes_processed += block_bytes;
+
+                Ok(Some((ParsedBlock::Recent(block), block_bytes)))
+            }
+            None => Ok(None),
+        }
     }
 
     /// Gets the precomputed block with supplied `state_hash`, it must exist
@@ -341,10 +374,21 @@ impl BlockParser {
             recent_paths: Vec::from(paths).into_iter(),
             orphaned_paths: vec![].into_iter(),
             chain_data: ChainData::default(),
+            source: None,
         }
     }
 }
 
+/// JSON & bin_prot block files in `blocks_dir`
+fn block_file_paths(blocks_dir: &Path) -> anyhow::Result<Vec<PathBuf>> {
+    let mut paths = vec![];
+    for ext in ["json", BIN_PROT_BLOCK_EXT] {
+        paths.extend(glob(&format!("{}/*-*-*.{ext}", blocks_dir.display()))?.flatten());
+    }
+
+    Ok(paths)
+}
+
 impl From<ParsedBlock> for PrecomputedBlock {
     fn from(value: ParsedBlock) -> Self {
         match value {
@@ -357,9 +401,50 @@ impl From<ParsedBlock> for PrecomputedBlock {
 
 #[cfg(test)]
 mod tests {
+    use super::{block_file_paths, BlockParser, ParsedBlock};
     use crate::{base::state_hash::StateHash, chain::Network};
     use quickcheck::{Arbitrary, Gen};
-    use std::path::PathBuf;
+    use std::path::{Path, PathBuf};
+    use tempfile::TempDir;
+
+    #[test]
+    fn block_file_paths_include_bin_prot_blocks() -> anyhow::Result<()> {
+        let dir = TempDir::new()?;
+        let json = "mainnet-2-3NLyWnjZqUECniE1q719CoLmes6WDQAod4vrTeLfN7XXJbHv6EHH.json";
+        let bin = "mainnet-359605-3NK4huLvUDiL4XuCUcyrWCKynmvhqfKsx5h2MfBXVVUq2Qwzi5uT.bin";
+
+        for name in [json, bin, "notes.txt", "mainnet-2.bin"] {
+            std::fs::write(dir.path().join(name), b"")?;
+        }
+
+        let mut paths = block_file_paths(dir.path())?;
+        paths.sort();
+
+        assert_eq!(paths, vec![dir.path().join(json), dir.path().join(bin)]);
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn block_source_blocks_are_recent() -> anyhow::Result<()> {
+        let blocks_dir = Path::new("./tests/data/sequential_blocks");
+        let mut block_parser = BlockParser::new_with_source(blocks_dir)?;
+        let total_num_blocks = block_parser.total_num_blocks;
+        assert!(total_num_blocks > 0);
+
+        let mut prev_height = 0;
+        while let Some((parsed_block, _)) = block_parser.next_block().await? {
+            let ParsedBlock::Recent(block) = parsed_block else {
+                panic!("expected a recent block")
+            };
+
+            assert!(block.blockchain_length() >= prev_height);
+            prev_height = block.blockchain_length();
+        }
+
+        assert_eq!(block_parser.blocks_processed, total_num_blocks);
+        Ok(())
+    }
+
     impl Arbitrary for Network {
         fn arbitrary(g: &mut Gen) -> Self {
             let idx = usize::arbitrary(g) % 4;
@@ -0,0 +1,90 @@
+//! bin_prot encoded precomputed blocks
+//!
+//! Pre-hardfork blocks are read in the daemon's versioned external transition
+//! format & post-hardfork blocks in its precomputed block format. Both lead
+//! with the protocol state, so the state hashes needed to place a block are
+//! read without parsing the rest of the file.
+
+#[cfg(feature = "loose_deserialization")]
+mod v1;
+mod v2;
+
+#[cfg(feature = "loose_deserialization")]
+pub use v1::parse_v1;
+pub use v2::parse_v2;
+
+use crate::{
+    base::state_hash::StateHash,
+    block::{extract_network_height_hash, precomputed::PcbVersion},
+    protocol::{
+        bin_prot,
+        serialization_types::protocol_state::{ProtocolState, ProtocolStateV1},
+    },
+};
+use mina_serialization_versioned::Versioned;
+use serde::Deserialize;
+use std::{fs::File, io::Read, path::Path};
+
+/// Leading field of the versioned external transition
+#[derive(Deserialize)]
+struct ExternalTransitionPrefix {
+    protocol_state: ProtocolStateV1,
+}
+
+/// Previous & genesis state hashes of the bin_prot block file `path`
+pub fn read_state_hashes(path: &Path) -> anyhow::Result<(StateHash, StateHash)> {
+    let (_, blockchain_length, _) = extract_network_height_hash(path);
+
+    match blockchain_length.into() {
+        PcbVersion::V1 => {
+            // only the protocol state is read
+            let prefix: Versioned<ExternalTransitionPrefix, 1> =
+                bin_prot::from_reader(File::open(path)?)?;
+            let ProtocolState {
+                previous_state_hash,
+                body,
+            } = prefix.t.protocol_state.t.t;
+
+            Ok((
+                StateHash::from_hashv1(previous_state_hash),
+                StateHash::from_hashv1(body.t.t.genesis_state_hash),
+            ))
+        }
+        PcbVersion::V2 => {
+            let mut prefix = Vec::with_capacity(v2::STATE_HASHES_PREFIX_LEN as usize);
+            File::open(path)?
+                .take(v2::STATE_HASHES_PREFIX_LEN)
+                .read_to_end(&mut prefix)?;
+
+            v2::state_hashes(&prefix)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::block::precomputed::PrecomputedBlock;
+    use tempfile::TempDir;
+
+    #[test]
+    fn read_v1_state_hashes() -> anyhow::Result<()> {
+        let name = "mainnet-77749-3NK3P5bJHhqR7xkZBquGGfq3sERUeXNYNma5YXRMjgCNsTJRZpgL";
+        let hex = std::fs::read_to_string(
+            "./tests/protocol/fixtures/data/3NK3P5bJHhqR7xkZBquGGfq3sERUeXNYNma5YXRMjgCNsTJRZpgL.hex",
+        )?;
+
+        let blocks_dir = TempDir::new()?;
+        let path = blocks_dir.path().join(format!("{name}.bin"));
+        std::fs::write(&path, hex::decode(hex.trim())?)?;
+
+        let json_path = format!("./tests/protocol/fixtures/data/{name}.json");
+        let block = PrecomputedBlock::parse_file(Path::new(&json_path), PcbVersion::V1)?;
+        assert_eq!(
+            read_state_hashes(&path)?,
+            (block.previous_state_hash(), block.genesis_state_hash())
+        );
+
+        Ok(())
+    }
+}
@@ -0,0 +1,97 @@
+//! Pre-hardfork external transitions
+//!
+//! The transition's proof has no typed representation, so it is read loosely
+//! via its layout & the protocol state & staged ledger diff are then
+//! re-encoded & read into their typed representations.
+
+use crate::protocol::{
+    bin_prot::{self, value::Index, BinProtRule, Deserializer, Layout, Value},
+    serialization_types::{
+        protocol_state::{ProtocolState, ProtocolStateV1},
+        staged_ledger_diff::StagedLedgerDiff,
+    },
+};
+use anyhow::Context;
+use mina_serialization_versioned::Versioned;
+use serde::{de::DeserializeOwned, Deserialize};
+use std::sync::OnceLock;
+
+/// Layout of the versioned external transition
+const EXTERNAL_TRANSITION_LAYOUT: &str =
+    include_str!("../../../protocol/layouts/external_transition.json");
+
+fn external_transition_rule() -> anyhow::Result<&'static BinProtRule> {
+    static RULE: OnceLock<BinProtRule> = OnceLock::new();
+
+    if let Some(rule) = RULE.get() {
+        return Ok(rule);
+    }
+
+    // the layout nests too deeply for the default recursion limit
+    let mut de = serde_json::Deserializer::from_str(EXTERNAL_TRANSITION_LAYOUT);
+    de.disable_recursion_limit();
+
+    let layout = Layout::deserialize(serde_stacker::Deserializer::new(&mut de))?;
+    Ok(RULE.get_or_init(|| layout.bin_prot_rule))
+}
+
+/// Protocol state & staged ledger diff of a v1 external transition
+pub fn parse_v1(contents: &[u8]) -> anyhow::Result<(ProtocolState, StagedLedgerDiff)> {
+    let mut de = Deserializer::from_reader(contents).with_layout(external_transition_rule()?);
+    let transition = Value::deserialize(&mut de)?;
+
+    let protocol_state: ProtocolStateV1 = retype(&transition, "protocol_state")?;
+    let staged_ledger_diff: Versioned<StagedLedgerDiff, 1> =
+        retype(&transition, "staged_ledger_diff")?;
+
+    Ok((protocol_state.t.t, staged_ledger_diff.t))
+}
+
+/// Re-encodes the transition's loosely typed `field` & reads it as `T`
+fn retype<T: DeserializeOwned>(transition: &Value, field: &str) -> anyhow::Result<T> {
+    let value = "t"
+        .index_into(transition)
+        .and_then(|t| field.index_into(t))
+        .with_context(|| format!("external transition missing {field}"))?;
+
+    let mut bytes = vec![];
+    bin_prot::to_writer(&mut bytes, value)?;
+
+    bin_prot::from_reader_strict(bytes.as_slice())
+        .with_context(|| format!("invalid external transition {field}"))
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::block::precomputed::{PcbVersion, PrecomputedBlock};
+    use std::path::Path;
+    use tempfile::TempDir;
+
+    #[test]
+    fn v1_external_transition() -> anyhow::Result<()> {
+        let name = "mainnet-77749-3NK3P5bJHhqR7xkZBquGGfq3sERUeXNYNma5YXRMjgCNsTJRZpgL";
+        let hex = std::fs::read_to_string(
+            "./tests/protocol/fixtures/data/3NK3P5bJHhqR7xkZBquGGfq3sERUeXNYNma5YXRMjgCNsTJRZpgL.hex",
+        )?;
+
+        let blocks_dir = TempDir::new()?;
+        let path = blocks_dir.path().join(format!("{name}.bin"));
+        std::fs::write(&path, hex::decode(hex.trim())?)?;
+
+        let json_path = format!("./tests/protocol/fixtures/data/{name}.json");
+        match (
+            PrecomputedBlock::from_path(&path)?,
+            PrecomputedBlock::parse_file(Path::new(&json_path), PcbVersion::V1)?,
+        ) {
+            (PrecomputedBlock::V1(bin), PrecomputedBlock::V1(json)) => {
+                assert_eq!(bin.state_hash, json.state_hash);
+                assert_eq!(bin.blockchain_length, json.blockchain_length);
+                assert_eq!(bin.protocol_state, json.protocol_state);
+                assert_eq!(bin.staged_ledger_diff, json.staged_ledger_diff);
+            }
+            _ => panic!("expected v1 blocks"),
+        }
+
+        Ok(())
+    }
+}
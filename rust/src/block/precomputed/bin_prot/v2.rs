@@ -0,0 +1,1206 @@
+//! Post-hardfork precomputed blocks
+//!
+//! Blocks are read in the daemon's `Precomputed.Stable.V3` format, optionally
+//! preceded by its version tag. Values are read into the daemon's JSON
+//! representation & deserialized like JSON blocks, so both encodings share the
+//! v2 block types.
+//!
+//! Unlike pre-hardfork values, stable types carry no version tags. Fixed length
+//! vectors end with a unit byte. Snark work proofs are read structurally &
+//! dropped, while zkApp proof authorizations are kept as base64 encoded
+//! S-expressions, like the daemon's JSON.
+//!
+//! Account update digests, stack hashes & verification key hashes are not
+//! serialized, the daemon recomputes them while deserializing. They are left
+//! empty.
+
+use super::super::v2::BlockFileDataV2;
+use crate::{
+    base::state_hash::StateHash,
+    block::vrf_output::VrfOutput,
+    constants::ZKAPP_STATE_FIELD_ELEMENTS_NUM,
+    protocol::{
+        bin_prot::{caml_hash_variant, ReadBinProtExt},
+        serialization_types::{common::HashV1, version_bytes},
+    },
+    utility::functions::nanomina_to_mina,
+};
+use anyhow::{bail, Context};
+use base64::{engine::general_purpose::STANDARD, Engine};
+use serde_json::{json, Value};
+use std::io::Read;
+
+/// Version of the daemon's precomputed block format
+const PRECOMPUTED_BLOCK_VERSION: i64 = 3;
+
+/// Upper bound on the length of the version tag, scheduled time & state hashes
+pub(super) const STATE_HASHES_PREFIX_LEN: u64 = 128;
+
+/// Number of zkApp action state field elements
+const ACTION_STATE_LEN: usize = 5;
+
+/// Number of witness columns of a kimchi proof
+const COLUMNS: usize = 15;
+
+/// Number of permutation columns of a kimchi proof
+const PERMUTS: usize = 7;
+
+/// Number of quotient polynomial chunks of a kimchi proof
+const QUOTIENT_CHUNKS: usize = 7;
+
+/// Number of bulletproof challenges of a step proof
+const STEP_CHALLENGES: usize = 16;
+
+/// Number of bulletproof challenges of a wrap proof
+const WRAP_CHALLENGES: usize = 15;
+
+/// Transaction failures, in declaration order
+const FAILURES: [&str; 45] = [
+    "Predicate",
+    "Source_not_present",
+    "Receiver_not_present",
+    "Amount_insufficient_to_create_account",
+    "Cannot_pay_creation_fee_in_token",
+    "Source_insufficient_balance",
+    "Source_minimum_balance_violation",
+    "Receiver_already_exists",
+    "Token_owner_not_caller",
+    "Overflow",
+    "Global_excess_overflow",
+    "Local_excess_overflow",
+    "Local_supply_increase_overflow",
+    "Global_supply_increase_overflow",
+    "Signed_command_on_zkapp_account",
+    "Zkapp_account_not_present",
+    "Update_not_permitted_balance",
+    "Update_not_permitted_access",
+    "Update_not_permitted_timing",
+    "Update_not_permitted_delegate",
+    "Update_not_permitted_app_state",
+    "Update_not_permitted_verification_key",
+    "Update_not_permitted_action_state",
+    "Update_not_permitted_zkapp_uri",
+    "Update_not_permitted_token_symbol",
+    "Update_not_permitted_permissions",
+    "Update_not_permitted_nonce",
+    "Update_not_permitted_voting_for",
+    "Zkapp_command_replay_check_failed",
+    "Fee_payer_nonce_must_increase",
+    "Fee_payer_must_be_signed",
+    "Account_balance_precondition_unsatisfied",
+    "Account_nonce_precondition_unsatisfied",
+    "Account_receipt_chain_hash_precondition_unsatisfied",
+    "Account_delegate_precondition_unsatisfied",
+    "Account_action_state_precondition_unsatisfied",
+    "Account_app_state_precondition_unsatisfied",
+    "Account_proved_state_precondition_unsatisfied",
+    "Account_is_new_precondition_unsatisfied",
+    "Protocol_state_precondition_unsatisfied",
+    "Unexpected_verification_key_hash",
+    "Valid_while_precondition_unsatisfied",
+    "Incorrect_nonce",
+    "Invalid_fee_excess",
+    "Cancelled",
+];
+
+/// The only failure carrying data, the index of the unsatisfied app state
+const APP_STATE_PRECONDITION_FAILURE: &str = "Account_app_state_precondition_unsatisfied";
+
+const AUTH_REQUIRED: [&str; 5] = ["None", "Either", "Proof", "Signature", "Impossible"];
+
+const PROOFS_VERIFIED: [&str; 3] = ["N0", "N1", "N2"];
+
+const FEATURE_FLAGS: [&str; 8] = [
+    "range_check0",
+    "range_check1",
+    "foreign_field_add",
+    "foreign_field_mul",
+    "xor",
+    "rot",
+    "lookup",
+    "runtime_tables",
+];
+
+const SELECTORS: [&str; 6] = [
+    "generic_selector",
+    "poseidon_selector",
+    "complete_add_selector",
+    "mul_selector",
+    "emul_selector",
+    "endomul_scalar_selector",
+];
+
+const OPTIONAL_SELECTORS: [&str; 8] = [
+    "range_check0_selector",
+    "range_check1_selector",
+    "foreign_field_add_selector",
+    "foreign_field_mul_selector",
+    "xor_selector",
+    "rot_selector",
+    "lookup_aggregation",
+    "lookup_table",
+];
+
+const LOOKUP_SELECTORS: [&str; 6] = [
+    "runtime_lookup_table",
+    "runtime_lookup_table_selector",
+    "xor_lookup_selector",
+    "lookup_gate_lookup_selector",
+    "range_check_lookup_selector",
+    "foreign_field_mul_lookup_selector",
+];
+
+type Reader<'a, 'b> = &'a mut &'b [u8];
+
+/// Parses the contents of a bin_prot encoded v2 precomputed block
+pub fn parse_v2(contents: &[u8]) -> anyhow::Result<BlockFileDataV2> {
+    let r = &mut &contents[..];
+    version_tag(r)?;
+
+    let scheduled_time = int(r)?;
+    let protocol_state = protocol_state(r)?;
+    proof(r)?;
+    let staged_ledger_diff = staged_ledger_diff(r)?;
+
+    // delta transition chain proof, current & proposed protocol versions
+    field(r)?;
+    list(r, field)?;
+    protocol_version(r)?;
+    option(r, protocol_version)?;
+
+    let block = json!({
+        "scheduled_time": scheduled_time.to_string(),
+        "protocol_state": protocol_state,
+        "staged_ledger_diff": staged_ledger_diff,
+        "accounts_accessed": list(r, |r| Ok(json!([int(r)?, account(r)?])))?,
+        "accounts_created": list(r, |r| {
+            Ok(json!([[public_key(r)?, token_id(r)?], fee(r)?]))
+        })?,
+        "tokens_used": list(r, |r| {
+            Ok(json!([token_id(r)?, option(r, |r| Ok(json!([public_key(r)?, token_id(r)?])))?]))
+        })?,
+    });
+
+    if !r.is_empty() {
+        bail!("{} unread bytes following the block", r.len())
+    }
+    serde_json::from_value(block).context("invalid v2 block")
+}
+
+/// Previous & genesis state hashes from the leading bytes of a v2 block
+pub(super) fn state_hashes(prefix: &[u8]) -> anyhow::Result<(StateHash, StateHash)> {
+    let r = &mut &prefix[..];
+    version_tag(r)?;
+
+    // scheduled time
+    int(r)?;
+
+    let previous_state_hash = bytes::<32>(r)?;
+    let genesis_state_hash = bytes::<32>(r)?;
+    Ok((
+        StateHash::from_hashv1(HashV1::from(previous_state_hash)),
+        StateHash::from_hashv1(HashV1::from(genesis_state_hash)),
+    ))
+}
+
+////////////////////
+// protocol state //
+////////////////////
+
+fn protocol_state(r: Reader) -> anyhow::Result<Value> {
+    Ok(json!({
+        "previous_state_hash": hash(r, version_bytes::STATE_HASH)?,
+        "body": {
+            "genesis_state_hash": hash(r, version_bytes::STATE_HASH)?,
+            "blockchain_state": blockchain_state(r)?,
+            "consensus_state": consensus_state(r)?,
+            "constants": {
+                "k": uint32(r)?,
+                "slots_per_epoch": uint32(r)?,
+                "slots_per_sub_window": uint32(r)?,
+                "grace_period_slots": uint32(r)?,
+                "delta": uint32(r)?,
+                "genesis_state_timestamp": uint64(r)?,
+            },
+        },
+    }))
+}
+
+fn blockchain_state(r: Reader) -> anyhow::Result<Value> {
+    Ok(json!({
+        "staged_ledger_hash": {
+            "non_snark": {
+                "ledger_hash": hash(r, version_bytes::LEDGER_HASH)?,
+                "aux_hash": base58_string(r, version_bytes::STAGED_LEDGER_HASH_AUX_HASH)?,
+                "pending_coinbase_aux": base58_string(
+                    r,
+                    version_bytes::STAGED_LEDGER_HASH_PENDING_COINBASE_AUX,
+                )?,
+            },
+            // the daemon reuses the receipt chain hash version byte
+            "pending_coinbase_hash": hash(r, version_bytes::RECEIPT_CHAIN_HASH)?,
+        },
+        "genesis_ledger_hash": hash(r, version_bytes::LEDGER_HASH)?,
+        "ledger_proof_statement": statement(r, |r| unit(r).map(|_| Value::Null))?,
+        "timestamp": uint64(r)?,
+        "body_reference": hex::encode(r.bin_read_bytes()?),
+    }))
+}
+
+fn consensus_state(r: Reader) -> anyhow::Result<Value> {
+    Ok(json!({
+        "blockchain_length": uint32(r)?,
+        "epoch_count": uint32(r)?,
+        "min_window_density": uint32(r)?,
+        "sub_window_densities": list(r, uint32)?,
+        "last_vrf_output": VrfOutput::new(r.bin_read_bytes()?).base64_encode(),
+        "total_currency": uint64(r)?,
+        "curr_global_slot_since_hard_fork": {
+            "slot_number": global_slot(r)?,
+            "slots_per_epoch": uint32(r)?,
+        },
+        "global_slot_since_genesis": global_slot(r)?,
+        "staking_epoch_data": epoch_data(r)?,
+        "next_epoch_data": epoch_data(r)?,
+        "has_ancestor_in_same_checkpoint_window": bool_(r)?,
+        "block_stake_winner": public_key(r)?,
+        "block_creator": public_key(r)?,
+        "coinbase_receiver": public_key(r)?,
+        "supercharge_coinbase": bool_(r)?,
+    }))
+}
+
+fn epoch_data(r: Reader) -> anyhow::Result<Value> {
+    Ok(json!({
+        "ledger": {
+            "hash": hash(r, version_bytes::LEDGER_HASH)?,
+            "total_currency": uint64(r)?,
+        },
+        "seed": hash(r, version_bytes::EPOCH_SEED)?,
+        "start_checkpoint": hash(r, version_bytes::STATE_HASH)?,
+        "lock_checkpoint": hash(r, version_bytes::STATE_HASH)?,
+        "epoch_length": uint32(r)?,
+    }))
+}
+
+/// Snark statement, the blockchain state's has no sok digest
+fn statement(
+    r: Reader,
+    sok_digest: impl FnOnce(Reader) -> anyhow::Result<Value>,
+) -> anyhow::Result<Value> {
+    Ok(json!({
+        "source": registers(r)?,
+        "target": registers(r)?,
+        "connecting_ledger_left": hash(r, version_bytes::LEDGER_HASH)?,
+        "connecting_ledger_right": hash(r, version_bytes::LEDGER_HASH)?,
+        "supply_increase": signed(r, uint64)?,
+        "fee_excess": [fee_excess(r)?, fee_excess(r)?],
+        "sok_digest": sok_digest(r)?,
+    }))
+}
+
+fn registers(r: Reader) -> anyhow::Result<Value> {
+    Ok(json!({
+        "first_pass_ledger": hash(r, version_bytes::LEDGER_HASH)?,
+        "second_pass_ledger": hash(r, version_bytes::LEDGER_HASH)?,
+        "pending_coinbase_stack": {
+            "data": hash(r, version_bytes::COINBASE_STACK_DATA)?,
+            "state": {
+                "init": hash(r, version_bytes::COINBASE_STACK_HASH)?,
+                "curr": hash(r, version_bytes::COINBASE_STACK_HASH)?,
+            },
+        },
+        "local_state": {
+            "stack_frame": field(r)?,
+            "call_stack": field(r)?,
+            "transaction_commitment": field(r)?,
+            "full_transaction_commitment": field(r)?,
+            "excess": signed(r, uint64)?,
+            "supply_increase": signed(r, uint64)?,
+            "ledger": hash(r, version_bytes::LEDGER_HASH)?,
+            "success": bool_(r)?,
+            "account_update_index": uint32(r)?,
+            "failure_status_tbl": list(r, |r| Ok(Value::Array(list(r, failure)?)))?,
+            "will_succeed": bool_(r)?,
+        },
+    }))
+}
+
+/// One side of a fee excess
+fn fee_excess(r: Reader) -> anyhow::Result<Value> {
+    Ok(json!({
+        "token": token_id(r)?,
+        "amount": signed(r, fee)?,
+    }))
+}
+
+/// Transaction, network & patch versions
+fn protocol_version(r: Reader) -> anyhow::Result<()> {
+    for _ in 0..3 {
+        int(r)?;
+    }
+    Ok(())
+}
+
+////////////////////////
+// staged ledger diff //
+////////////////////////
+
+fn staged_ledger_diff(r: Reader) -> anyhow::Result<Value> {
+    let two = pre_diff(r, true)?;
+    let one = option(r, |r| pre_diff(r, false))?;
+    Ok(json!({ "diff": [two, one] }))
+}
+
+/// Pre diff with at most two or one coinbase fee transfers
+fn pre_diff(r: Reader, at_most_two: bool) -> anyhow::Result<Value> {
+    let completed_works = list(r, completed_work)?;
+    let commands = list(r, |r| {
+        Ok(json!({
+            "data": user_command(r)?,
+            "status": status(r)?,
+        }))
+    })?;
+
+    let ft = |r: Reader| -> anyhow::Result<Value> {
+        Ok(json!({
+            "receiver_pk": public_key(r)?,
+            "fee": fee(r)?,
+        }))
+    };
+    let coinbase = match (constructor(r, &["Zero", "One", "Two"])?, at_most_two) {
+        ("Zero", _) => json!(["Zero"]),
+        ("One", _) => json!(["One", option(r, ft)?]),
+        ("Two", true) => json!(["Two", option(r, |r| Ok(json!([ft(r)?, option(r, ft)?])))?]),
+        (_, _) => bail!("at most one coinbase fee transfer"),
+    };
+
+    Ok(json!({
+        "completed_works": completed_works,
+        "commands": commands,
+        "coinbase": coinbase,
+        "internal_command_statuses": list(r, status)?,
+    }))
+}
+
+/// Only the fee & prover of completed work are kept
+fn completed_work(r: Reader) -> anyhow::Result<Value> {
+    let fee = fee(r)?;
+
+    let ledger_proof = |r: Reader| -> anyhow::Result<()> {
+        statement(r, |r| Ok(hex::encode(r.bin_read_bytes()?).into()))?;
+        proof(r)?;
+        Ok(())
+    };
+    let proofs = r.bin_read_polyvar_tag()?;
+    if proofs == caml_hash_variant("One") {
+        ledger_proof(r)?;
+    } else if proofs == caml_hash_variant("Two") {
+        ledger_proof(r)?;
+        ledger_proof(r)?;
+    } else {
+        bail!("invalid one or two tag {proofs}")
+    }
+
+    Ok(json!({
+        "fee": fee,
+        "prover": public_key(r)?,
+    }))
+}
+
+fn status(r: Reader) -> anyhow::Result<Value> {
+    Ok(match constructor(r, &["Applied", "Failed"])? {
+        "Failed" => json!(["Failed", list(r, |r| Ok(Value::Array(list(r, failure)?)))?]),
+        applied => json!([applied]),
+    })
+}
+
+fn failure(r: Reader) -> anyhow::Result<Value> {
+    Ok(match constructor(r, &FAILURES)? {
+        APP_STATE_PRECONDITION_FAILURE => json!([APP_STATE_PRECONDITION_FAILURE, int(r)?]),
+        failure => json!([failure]),
+    })
+}
+
+fn user_command(r: Reader) -> anyhow::Result<Value> {
+    Ok(
+        match constructor(r, &["Signed_command", "Zkapp_command"])? {
+            "Signed_command" => json!(["Signed_command", signed_command(r)?]),
+            zkapp_command => json!([zkapp_command, self::zkapp_command(r)?]),
+        },
+    )
+}
+
+fn signed_command(r: Reader) -> anyhow::Result<Value> {
+    let common = json!({
+        "fee": fee(r)?,
+        "fee_payer_pk": public_key(r)?,
+        "nonce": uint32(r)?,
+        "valid_until": global_slot(r)?,
+        "memo": base58_string(r, version_bytes::USER_COMMAND_MEMO)?,
+    });
+    let body = match constructor(r, &["Payment", "Stake_delegation"])? {
+        "Payment" => json!(["Payment", {
+            "receiver_pk": public_key(r)?,
+            "amount": uint64(r)?,
+        }]),
+        stake_delegation => json!([stake_delegation, ["Set_delegate", {
+            "new_delegate": public_key(r)?,
+        }]]),
+    };
+
+    Ok(json!({
+        "payload": {
+            "common": common,
+            "body": body,
+        },
+        "signer": public_key(r)?,
+        "signature": signature(r)?,
+    }))
+}
+
+////////////////////
+// zkApp commands //
+////////////////////
+
+fn zkapp_command(r: Reader) -> anyhow::Result<Value> {
+    Ok(json!({
+        "fee_payer": {
+            "body": {
+                "public_key": public_key(r)?,
+                "fee": fee(r)?,
+                "valid_until": option(r, global_slot)?,
+                "nonce": uint32(r)?,
+            },
+            "authorization": signature(r)?,
+        },
+        "account_updates": call_forest(r)?,
+        "memo": base58_string(r, version_bytes::USER_COMMAND_MEMO)?,
+    }))
+}
+
+fn call_forest(r: Reader) -> anyhow::Result<Value> {
+    list(r, |r| {
+        Ok(json!({
+            "elt": {
+                "account_update": {
+                    "body": account_update_body(r)?,
+                    "authorization": authorization(r)?,
+                },
+                "account_update_digest": empty_hash(r)?,
+                "calls": call_forest(r)?,
+            },
+            "stack_hash": empty_hash(r)?,
+        }))
+    })
+    .map(Value::Array)
+}
+
+fn account_update_body(r: Reader) -> anyhow::Result<Value> {
+    Ok(json!({
+        "public_key": public_key(r)?,
+        "token_id": token_id(r)?,
+        "update": {
+            "app_state": vector(r, ZKAPP_STATE_FIELD_ELEMENTS_NUM, |r| set_or_keep(r, field))?,
+            "delegate": set_or_keep(r, public_key)?,
+            "verification_key": set_or_keep(r, verification_key)?,
+            "permissions": set_or_keep(r, permissions)?,
+            "zkapp_uri": set_or_keep(r, string)?,
+            "token_symbol": set_or_keep(r, string)?,
+            "timing": set_or_keep(r, timing)?,
+            "voting_for": set_or_keep(r, |r| hash(r, version_bytes::STATE_HASH))?,
+        },
+        "balance_change": signed(r, uint64)?,
+        "increment_nonce": bool_(r)?,
+        "events": list(r, |r| Ok(Value::Array(list(r, field)?)))?,
+        "actions": list(r, |r| Ok(Value::Array(list(r, field)?)))?,
+        "call_data": field(r)?,
+        "preconditions": {
+            "network": network_precondition(r)?,
+            "account": account_precondition(r)?,
+            "valid_while": or_ignore(r, |r| interval(r, global_slot))?,
+        },
+        "use_full_commitment": bool_(r)?,
+        "implicit_account_creation_fee": bool_(r)?,
+        "may_use_token": nullary(r, &["No", "Parents_own_token", "Inherit_from_parent"])?,
+        "authorization_kind": match constructor(r, &["Signature", "Proof", "None_given"])? {
+            "Proof" => json!(["Proof", num::BigUint::from_bytes_le(&bytes::<32>(r)?).to_string()]),
+            kind => json!([kind]),
+        },
+    }))
+}
+
+fn authorization(r: Reader) -> anyhow::Result<Value> {
+    Ok(
+        match constructor(r, &["Proof", "Signature", "None_given"])? {
+            "Proof" => json!(["Proof", STANDARD.encode(proof(r)?.to_string())]),
+            "Signature" => json!(["Signature", signature(r)?]),
+            none_given => json!([none_given]),
+        },
+    )
+}
+
+fn network_precondition(r: Reader) -> anyhow::Result<Value> {
+    Ok(json!({
+        "snarked_ledger_hash": or_ignore(r, |r| hash(r, version_bytes::LEDGER_HASH))?,
+        "blockchain_length": or_ignore(r, |r| interval(r, uint32))?,
+        "min_window_density": or_ignore(r, |r| interval(r, uint32))?,
+        "total_currency": or_ignore(r, |r| interval(r, uint64))?,
+        "global_slot_since_genesis": or_ignore(r, |r| interval(r, global_slot))?,
+        "staking_epoch_data": epoch_data_precondition(r)?,
+        "next_epoch_data": epoch_data_precondition(r)?,
+    }))
+}
+
+fn epoch_data_precondition(r: Reader) -> anyhow::Result<Value> {
+    Ok(json!({
+        "ledger": {
+            "hash": or_ignore(r, |r| hash(r, version_bytes::LEDGER_HASH))?,
+            "total_currency": or_ignore(r, |r| interval(r, uint64))?,
+        },
+        "seed": or_ignore(r, |r| hash(r, version_bytes::EPOCH_SEED))?,
+        "start_checkpoint": or_ignore(r, |r| hash(r, version_bytes::STATE_HASH))?,
+        "lock_checkpoint": or_ignore(r, |r| hash(r, version_bytes::STATE_HASH))?,
+        "epoch_length": or_ignore(r, |r| interval(r, uint32))?,
+    }))
+}
+
+fn account_precondition(r: Reader) -> anyhow::Result<Value> {
+    Ok(json!({
+        "balance": or_ignore(r, |r| interval(r, uint64))?,
+        "nonce": or_ignore(r, |r| interval(r, uint32))?,
+        "receipt_chain_hash": or_ignore(r, |r| hash(r, version_bytes::RECEIPT_CHAIN_HASH))?,
+        "delegate": or_ignore(r, public_key)?,
+        "state": vector(r, ZKAPP_STATE_FIELD_ELEMENTS_NUM, |r| or_ignore(r, field))?,
+        "action_state": or_ignore(r, field)?,
+        "proved_state": or_ignore(r, |r| Ok(bool_(r)?.into()))?,
+        "is_new": or_ignore(r, |r| Ok(bool_(r)?.into()))?,
+    }))
+}
+
+fn interval(
+    r: Reader,
+    mut bound: impl FnMut(Reader) -> anyhow::Result<Value>,
+) -> anyhow::Result<Value> {
+    Ok(json!({
+        "lower": bound(r)?,
+        "upper": bound(r)?,
+    }))
+}
+
+fn set_or_keep(
+    r: Reader,
+    value: impl FnOnce(Reader) -> anyhow::Result<Value>,
+) -> anyhow::Result<Value> {
+    Ok(match constructor(r, &["Set", "Keep"])? {
+        "Set" => json!(["Set", value(r)?]),
+        keep => json!([keep]),
+    })
+}
+
+fn or_ignore(
+    r: Reader,
+    value: impl FnOnce(Reader) -> anyhow::Result<Value>,
+) -> anyhow::Result<Value> {
+    Ok(match constructor(r, &["Check", "Ignore"])? {
+        "Check" => json!(["Check", value(r)?]),
+        ignore => json!([ignore]),
+    })
+}
+
+//////////////
+// accounts //
+//////////////
+
+fn account(r: Reader) -> anyhow::Result<Value> {
+    Ok(json!({
+        "public_key": public_key(r)?,
+        "token_id": token_id(r)?,
+        "token_symbol": string(r)?,
+        "balance": uint64(r)?,
+        "nonce": uint32(r)?,
+        "receipt_chain_hash": hash(r, version_bytes::RECEIPT_CHAIN_HASH)?,
+        "delegate": option(r, public_key)?,
+        "voting_for": hash(r, version_bytes::STATE_HASH)?,
+        "timing": match constructor(r, &["Untimed", "Timed"])? {
+            "Timed" => json!(["Timed", timing(r)?]),
+            untimed => json!([untimed]),
+        },
+        "permissions": permissions(r)?,
+        "zkapp": option(r, |r| {
+            Ok(json!({
+                "app_state": vector(r, ZKAPP_STATE_FIELD_ELEMENTS_NUM, field)?,
+                "verification_key": option(r, verification_key)?,
+                "zkapp_version": uint32(r)?,
+                "action_state": vector(r, ACTION_STATE_LEN, field)?,
+                "last_action_slot": global_slot(r)?,
+                "proved_state": bool_(r)?,
+                "zkapp_uri": string(r)?,
+            }))
+        })?,
+    }))
+}
+
+fn timing(r: Reader) -> anyhow::Result<Value> {
+    Ok(json!({
+        "initial_minimum_balance": uint64(r)?,
+        "cliff_time": global_slot(r)?,
+        "cliff_amount": uint64(r)?,
+        "vesting_period": global_slot(r)?,
+        "vesting_increment": uint64(r)?,
+    }))
+}
+
+fn permissions(r: Reader) -> anyhow::Result<Value> {
+    let auth_required = |r: Reader| nullary(r, &AUTH_REQUIRED);
+    Ok(json!({
+        "edit_state": auth_required(r)?,
+        "access": auth_required(r)?,
+        "send": auth_required(r)?,
+        "receive": auth_required(r)?,
+        "set_delegate": auth_required(r)?,
+        "set_permissions": auth_required(r)?,
+        "set_verification_key": [auth_required(r)?, uint32(r)?],
+        "set_zkapp_uri": auth_required(r)?,
+        "edit_action_state": auth_required(r)?,
+        "set_token_symbol": auth_required(r)?,
+        "increment_nonce": auth_required(r)?,
+        "set_voting_for": auth_required(r)?,
+        "set_timing": auth_required(r)?,
+    }))
+}
+
+/// Side loaded verification key, rendered as its base58 encoded bytes
+fn verification_key(r: Reader) -> anyhow::Result<Value> {
+    let start = *r;
+
+    // max proofs verified & actual wrap domain size
+    constructor(r, &PROOFS_VERIFIED)?;
+    constructor(r, &PROOFS_VERIFIED)?;
+
+    // wrap index: sigma, coefficient & selector commitments
+    vector(r, PERMUTS, point)?;
+    vector(r, COLUMNS, point)?;
+    for _ in SELECTORS {
+        point(r)?;
+    }
+
+    let data = &start[..start.len() - r.len()];
+    Ok(json!({
+        "data": base58(data, version_bytes::VERIFICATION_KEY),
+        "hash": "",
+    }))
+}
+
+////////////
+// proofs //
+////////////
+
+/// S-expression, as rendered by the daemon in machine format
+enum Sexp {
+    Atom(String),
+    List(Vec<Sexp>),
+}
+
+impl Sexp {
+    fn field(name: &str, value: Self) -> Self {
+        Self::List(vec![Self::Atom(name.to_string()), value])
+    }
+
+    fn record<const N: usize>(fields: [(&str, Self); N]) -> Self {
+        Self::List(
+            fields
+                .into_iter()
+                .map(|(name, value)| Self::field(name, value))
+                .collect(),
+        )
+    }
+
+    /// Atoms are only separated when neither is quoted. Returns whether the
+    /// next atom needs separating
+    fn write(&self, out: &mut String, separate: bool) -> bool {
+        match self {
+            Self::Atom(atom) => match escape(atom) {
+                Some(quoted) => {
+                    out.push_str(&quoted);
+                    false
+                }
+                None => {
+                    if separate {
+                        out.push(' ');
+                    }
+                    out.push_str(atom);
+                    true
+                }
+            },
+            Self::List(items) => {
+                out.push('(');
+                items
+                    .iter()
+                    .fold(false, |separate, item| item.write(out, separate));
+                out.push(')');
+                false
+            }
+        }
+    }
+}
+
+impl std::fmt::Display for Sexp {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let mut out = String::new();
+        self.write(&mut out, false);
+        f.write_str(&out)
+    }
+}
+
+impl From<Vec<Sexp>> for Sexp {
+    fn from(items: Vec<Sexp>) -> Self {
+        Self::List(items)
+    }
+}
+
+impl From<Option<Sexp>> for Sexp {
+    fn from(value: Option<Sexp>) -> Self {
+        Self::List(value.into_iter().collect())
+    }
+}
+
+/// Quotes atoms with OCaml string escapes, if needed
+fn escape(atom: &str) -> Option<String> {
+    let special = |c: char| c <= ' ' || c > '~' || "\"();\\".contains(c);
+    if !atom.is_empty() && !atom.contains(special) {
+        return None;
+    }
+
+    let mut quoted = String::from('"');
+    for c in atom.chars() {
+        match c {
+            '"' => quoted.push_str("\\\""),
+            '\\' => quoted.push_str("\\\\"),
+            '\n' => quoted.push_str("\\n"),
+            '\t' => quoted.push_str("\\t"),
+            '\r' => quoted.push_str("\\r"),
+            '\x08' => quoted.push_str("\\b"),
+            ' '..='~' => quoted.push(c),
+            _ => quoted.push_str(&format!("\\{:03}", c as u32)),
+        }
+    }
+    quoted.push('"');
+    Some(quoted)
+}
+
+/// Pickles proof with two previous proofs
+fn proof(r: Reader) -> anyhow::Result<Sexp> {
+    let statement = Sexp::record([
+        (
+            "proof_state",
+            Sexp::record([
+                (
+                    "deferred_values",
+                    Sexp::record([
+                        (
+                            "plonk",
+                            Sexp::record([
+                                ("alpha", scalar_challenge(r)?),
+                                ("beta", challenge(r)?),
+                                ("gamma", challenge(r)?),
+                                ("zeta", scalar_challenge(r)?),
+                                ("joint_combiner", option(r, scalar_challenge)?.into()),
+                                ("feature_flags", flags(r, &FEATURE_FLAGS)?),
+                            ]),
+                        ),
+                        (
+                            "bulletproof_challenges",
+                            vector(r, STEP_CHALLENGES, bulletproof_challenge)?.into(),
+                        ),
+                        (
+                            "branch_data",
+                            Sexp::record([
+                                (
+                                    "proofs_verified",
+                                    Sexp::List(vec![Sexp::Atom(
+                                        constructor(r, &PROOFS_VERIFIED)?.to_string(),
+                                    )]),
+                                ),
+                                ("domain_log2", Sexp::Atom(r.bin_read_char()?.to_string())),
+                            ]),
+                        ),
+                    ]),
+                ),
+                (
+                    "sponge_digest_before_evaluations",
+                    vector(r, 4, hex64)?.into(),
+                ),
+                (
+                    "messages_for_next_wrap_proof",
+                    Sexp::record([
+                        ("challenge_polynomial_commitment", point(r)?),
+                        (
+                            "old_bulletproof_challenges",
+                            vector(r, 2, |r| {
+                                Ok(Sexp::from(vector(
+                                    r,
+                                    WRAP_CHALLENGES,
+                                    bulletproof_challenge,
+                                )?))
+                            })?
+                            .into(),
+                        ),
+                    ]),
+                ),
+            ]),
+        ),
+        (
+            "messages_for_next_step_proof",
+            Sexp::record([
+                ("app_state", unit(r).map(|_| Sexp::List(vec![]))?),
+                ("challenge_polynomial_commitments", list(r, point)?.into()),
+                (
+                    "old_bulletproof_challenges",
+                    list(r, |r| {
+                        Ok(Sexp::from(vector(
+                            r,
+                            STEP_CHALLENGES,
+                            bulletproof_challenge,
+                        )?))
+                    })?
+                    .into(),
+                ),
+            ]),
+        ),
+    ]);
+
+    let prev_evals = Sexp::record([
+        (
+            "evals",
+            Sexp::record([("public_input", point(r)?), ("evals", evaluations(r)?)]),
+        ),
+        ("ft_eval1", field_atom(r)?),
+    ]);
+
+    let commitments = Sexp::record([
+        ("w_comm", vector(r, COLUMNS, point)?.into()),
+        ("z_comm", point(r)?),
+        ("t_comm", vector(r, QUOTIENT_CHUNKS, point)?.into()),
+    ]);
+    let mut evaluations = vec![
+        Sexp::field("w", vector(r, COLUMNS, point)?.into()),
+        Sexp::field("coefficients", vector(r, COLUMNS, point)?.into()),
+        Sexp::field("z", point(r)?),
+        Sexp::field("s", vector(r, PERMUTS - 1, point)?.into()),
+    ];
+    for name in SELECTORS {
+        evaluations.push(Sexp::field(name, point(r)?));
+    }
+    let proof = Sexp::record([
+        ("commitments", commitments),
+        ("evaluations", evaluations.into()),
+        ("ft_eval1", field_atom(r)?),
+        (
+            "bulletproof",
+            Sexp::record([
+                (
+                    "lr",
+                    list(r, |r| Ok(Sexp::List(vec![point(r)?, point(r)?])))?.into(),
+                ),
+                ("z_1", field_atom(r)?),
+                ("z_2", field_atom(r)?),
+                ("delta", point(r)?),
+                ("challenge_polynomial_commitment", point(r)?),
+            ]),
+        ),
+    ]);
+
+    Ok(Sexp::record([
+        ("statement", statement),
+        ("prev_evals", prev_evals),
+        ("proof", proof),
+    ]))
+}
+
+/// Evaluations of the previous proof's polynomials
+fn evaluations(r: Reader) -> anyhow::Result<Sexp> {
+    let mut fields = vec![
+        Sexp::field("w", vector(r, COLUMNS, point_evaluations)?.into()),
+        Sexp::field(
+            "coefficients",
+            vector(r, COLUMNS, point_evaluations)?.into(),
+        ),
+        Sexp::field("z", point_evaluations(r)?),
+        Sexp::field("s", vector(r, PERMUTS - 1, point_evaluations)?.into()),
+    ];
+    for name in SELECTORS {
+        fields.push(Sexp::field(name, point_evaluations(r)?));
+    }
+    for name in OPTIONAL_SELECTORS {
+        fields.push(Sexp::field(name, option(r, point_evaluations)?.into()));
+    }
+    fields.push(Sexp::field(
+        "lookup_sorted",
+        vector(r, 5, |r| Ok(Sexp::from(option(r, point_evaluations)?)))?.into(),
+    ));
+    for name in LOOKUP_SELECTORS {
+        fields.push(Sexp::field(name, option(r, point_evaluations)?.into()));
+    }
+    Ok(fields.into())
+}
+
+/// Evaluations at zeta & zeta * omega
+fn point_evaluations(r: Reader) -> anyhow::Result<Sexp> {
+    Ok(Sexp::List(vec![
+        list(r, field_atom)?.into(),
+        list(r, field_atom)?.into(),
+    ]))
+}
+
+fn flags(r: Reader, names: &[&str]) -> anyhow::Result<Sexp> {
+    names
+        .iter()
+        .map(|name| Ok(Sexp::field(name, Sexp::Atom(bool_(r)?.to_string()))))
+        .collect::<anyhow::Result<Vec<_>>>()
+        .map(Sexp::List)
+}
+
+fn bulletproof_challenge(r: Reader) -> anyhow::Result<Sexp> {
+    Ok(Sexp::record([("prechallenge", scalar_challenge(r)?)]))
+}
+
+fn scalar_challenge(r: Reader) -> anyhow::Result<Sexp> {
+    Ok(Sexp::record([("inner", challenge(r)?)]))
+}
+
+/// 128 bit challenges are a pair of 64 bit limbs
+fn challenge(r: Reader) -> anyhow::Result<Sexp> {
+    Ok(vector(r, 2, hex64)?.into())
+}
+
+fn hex64(r: Reader) -> anyhow::Result<Sexp> {
+    Ok(Sexp::Atom(format!("{:016x}", int(r)? as u64)))
+}
+
+fn point(r: Reader) -> anyhow::Result<Sexp> {
+    Ok(Sexp::List(vec![field_atom(r)?, field_atom(r)?]))
+}
+
+fn field_atom(r: Reader) -> anyhow::Result<Sexp> {
+    Ok(Sexp::Atom(field_hex(bytes(r)?)))
+}
+
+////////////////
+// primitives //
+////////////////
+
+/// The scheduled time leading untagged blocks is never the version
+fn version_tag(r: Reader) -> anyhow::Result<()> {
+    let mut tagged = *r;
+    if int(&mut tagged)? == PRECOMPUTED_BLOCK_VERSION {
+        *r = tagged;
+    }
+    Ok(())
+}
+
+fn int(r: Reader) -> anyhow::Result<i64> {
+    Ok(r.bin_read_integer()?)
+}
+
+fn bool_(r: Reader) -> anyhow::Result<bool> {
+    Ok(r.bin_read_bool()?)
+}
+
+fn unit(r: Reader) -> anyhow::Result<()> {
+    Ok(r.bin_read_unit()?)
+}
+
+fn bytes<const N: usize>(r: Reader) -> anyhow::Result<[u8; N]> {
+    let mut bytes = [0; N];
+    r.read_exact(&mut bytes)?;
+    Ok(bytes)
+}
+
+/// Unsigned 32 bit integers are encoded as OCaml `Int32`
+fn uint32(r: Reader) -> anyhow::Result<Value> {
+    Ok((int(r)? as u32).to_string().into())
+}
+
+/// Unsigned 64 bit integers are encoded as OCaml `Int64`
+fn uint64(r: Reader) -> anyhow::Result<Value> {
+    Ok((int(r)? as u64).to_string().into())
+}
+
+fn fee(r: Reader) -> anyhow::Result<Value> {
+    Ok(nanomina_to_mina(int(r)? as u64).into())
+}
+
+/// Global slots & slot spans have a single constructor wrapping an unsigned
+/// 32 bit integer
+fn global_slot(r: Reader) -> anyhow::Result<Value> {
+    constructor(r, &["Global_slot"])?;
+    uint32(r)
+}
+
+fn signed(
+    r: Reader,
+    magnitude: impl FnOnce(Reader) -> anyhow::Result<Value>,
+) -> anyhow::Result<Value> {
+    Ok(json!({
+        "magnitude": magnitude(r)?,
+        "sgn": nullary(r, &["Pos", "Neg"])?,
+    }))
+}
+
+/// Hashes serialized as units
+fn empty_hash(r: Reader) -> anyhow::Result<Value> {
+    unit(r)?;
+    Ok("".into())
+}
+
+/// Field elements are little-endian & rendered big-endian
+fn field(r: Reader) -> anyhow::Result<Value> {
+    Ok(field_hex(bytes(r)?).into())
+}
+
+fn field_hex(mut bytes: [u8; 32]) -> String {
+    bytes.reverse();
+    format!("0x{}", hex::encode_upper(bytes))
+}
+
+fn base58(bytes: &[u8], version_byte: u8) -> Value {
+    bs58::encode(bytes)
+        .with_check_version(version_byte)
+        .into_string()
+        .into()
+}
+
+/// Hashes are base58 encoded with a version tag
+fn hash(r: Reader, version_byte: u8) -> anyhow::Result<Value> {
+    let mut tagged = vec![1];
+    tagged.extend(bytes::<32>(r)?);
+    Ok(base58(&tagged, version_byte))
+}
+
+fn base58_string(r: Reader, version_byte: u8) -> anyhow::Result<Value> {
+    Ok(base58(&r.bin_read_bytes()?, version_byte))
+}
+
+/// Token ids are base58 encoded without a version tag
+fn token_id(r: Reader) -> anyhow::Result<Value> {
+    Ok(base58(&bytes::<32>(r)?, version_bytes::TOKEN_ID_KEY))
+}
+
+/// Compressed public keys are base58 encoded with two version tags
+fn public_key(r: Reader) -> anyhow::Result<Value> {
+    let mut tagged = vec![1, 1];
+    tagged.extend(bytes::<32>(r)?);
+    tagged.push(bool_(r)? as u8);
+    Ok(base58(
+        &tagged,
+        version_bytes::NON_ZERO_CURVE_POINT_COMPRESSED,
+    ))
+}
+
+fn signature(r: Reader) -> anyhow::Result<Value> {
+    let mut tagged = vec![1];
+    tagged.extend(bytes::<64>(r)?);
+    Ok(base58(&tagged, version_bytes::SIGNATURE))
+}
+
+fn string(r: Reader) -> anyhow::Result<Value> {
+    Ok(r.bin_read_string()?.into())
+}
+
+/// Constructor name of a variant
+fn constructor<'a>(r: Reader, names: &[&'a str]) -> anyhow::Result<&'a str> {
+    let index = r.bin_read_variant_index()?;
+    match names.get(index as usize) {
+        Some(name) => Ok(*name),
+        None => bail!("invalid variant index {index} of {names:?}"),
+    }
+}
+
+/// Variant without arguments
+fn nullary(r: Reader, names: &[&str]) -> anyhow::Result<Value> {
+    Ok(json!([constructor(r, names)?]))
+}
+
+fn list<T>(r: Reader, mut item: impl FnMut(Reader) -> anyhow::Result<T>) -> anyhow::Result<Vec<T>> {
+    let len: usize = r.bin_read_nat0()?;
+    (0..len).map(|_| item(r)).collect()
+}
+
+/// Fixed length vectors end with a unit
+fn vector<T>(
+    r: Reader,
+    len: usize,
+    mut item: impl FnMut(Reader) -> anyhow::Result<T>,
+) -> anyhow::Result<Vec<T>> {
+    let items = (0..len).map(|_| item(r)).collect::<anyhow::Result<_>>()?;
+    unit(r)?;
+    Ok(items)
+}
+
+fn option<T>(
+    r: Reader,
+    value: impl FnOnce(Reader) -> anyhow::Result<T>,
+) -> anyhow::Result<Option<T>> {
+    Ok(if bool_(r)? { Some(value(r)?) } else { None })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::path::PathBuf;
+
+    #[test]
+    fn verification_key() -> anyhow::Result<()> {
+        let path = PathBuf::from("./tests/data/misc_blocks/mainnet-359763-3NKf6ocu98sTKEn1TdXP5ceRbd7s8kfg9bUkVA7wq5R4VmGyVA3m.json");
+        let block: Value = serde_json::from_slice(&std::fs::read(path)?)?;
+        let vk = block["data"]["accounts_accessed"]
+            .as_array()
+            .into_iter()
+            .flatten()
+            .find_map(|accessed| accessed[1]["zkapp"]["verification_key"].as_object())
+            .expect("zkapp account with a verification key");
+
+        // versioned base58 data, the hash is not serialized
+        let data = bs58::decode(vk["data"].as_str().unwrap())
+            .with_check(Some(version_bytes::VERIFICATION_KEY))
+            .into_vec()?;
+
+        let r = &mut &data[1..];
+        let decoded = super::verification_key(r)?;
+        assert!(r.is_empty());
+        assert_eq!(decoded["data"], vk["data"]);
+        assert_eq!(decoded["hash"], "");
+        Ok(())
+    }
+
+    #[test]
+    fn state_hashes() -> anyhow::Result<()> {
+        let previous_state_hash =
+            StateHash::from("3NKJUN6vp1vxRjb88XHnCCZz1cHtR24GyT4Mip3GEapCRzm1uGuW");
+        let genesis_state_hash =
+            StateHash::from("3NK4BpDSekaqsG6tx8Nse2zJchRft2JpnbvMiog55WCr5xJZaKeP");
+
+        // version tag, scheduled time & state hash bytes
+        let mut prefix = vec![PRECOMPUTED_BLOCK_VERSION as u8, 0xfc];
+        prefix.extend(1718302980000i64.to_le_bytes());
+        for state_hash in [&previous_state_hash, &genesis_state_hash] {
+            let bytes = bs58::decode(&state_hash.0)
+                .with_check(Some(version_bytes::STATE_HASH))
+                .into_vec()?;
+            prefix.extend(&bytes[2..]);
+        }
+
+        assert_eq!(
+            super::state_hashes(&prefix)?,
+            (previous_state_hash.clone(), genesis_state_hash.clone())
+        );
+
+        // untagged
+        assert_eq!(
+            super::state_hashes(&prefix[1..])?,
+            (previous_state_hash, genesis_state_hash)
+        );
+        Ok(())
+    }
+}
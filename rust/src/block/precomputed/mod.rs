@@ -1,18 +1,22 @@
 //! Indexer internal precomputed block representation
 
+pub(crate) mod bin_prot;
 pub(crate) mod v1;
 pub(crate) mod v2;
 
 use super::{
     epoch_data::EpochSeed,
-    extract_network_height_hash,
+    extract_network_height_hash, is_bin_prot_block_file,
     post_hardfork::{
         account_accessed::AccountAccessed, account_created::AccountCreated, token_used::TokenUsed,
     },
     Block, StateHash, VrfOutput,
 };
 use crate::{
-    base::{amount::Amount, blockchain_length::BlockchainLength, public_key::PublicKey},
+    base::{
+        amount::Amount, blockchain_length::BlockchainLength, public_key::PublicKey,
+        scheduled_time::ScheduledTime,
+    },
     canonicity::Canonicity,
     chain::Network,
     command::{signed::TxnHash, UserCommandWithStatus, UserCommandWithStatusT},
@@ -84,6 +88,7 @@ impl PrecomputedBlock {
                     network: block_file_contents.network,
                     protocol_state: protocol_state.into(),
                     staged_ledger_diff: staged_ledger_diff.into(),
+                    scheduled_time_derived: false,
                 })))
             }
             PcbVersion::V2 => {
@@ -114,6 +119,64 @@ impl PrecomputedBlock {
         }
     }
 
+    /// Parses a bin_prot encoded block, either a pre-hardfork external
+    /// transition or a post-hardfork precomputed block
+    pub fn from_bin_prot(
+        block_file_contents: BlockFileContents,
+        version: PcbVersion,
+    ) -> anyhow::Result<Self> {
+        let state_hash = block_file_contents.state_hash;
+
+        match version {
+            #[cfg(feature = "loose_deserialization")]
+            PcbVersion::V1 => {
+                let (protocol_state, staged_ledger_diff) =
+                    bin_prot::parse_v1(&block_file_contents.contents)?;
+
+                // the external transition does not record when the block was
+                // scheduled, so the scheduled time is derived from its timestamp
+                let scheduled_time =
+                    ScheduledTime(protocol_state.body.t.t.blockchain_state.t.t.timestamp.t.t);
+
+                Ok(Self::V1(Box::new(PrecomputedBlockV1 {
+                    state_hash,
+                    scheduled_time,
+                    blockchain_length: block_file_contents.blockchain_length,
+                    network: block_file_contents.network,
+                    protocol_state,
+                    staged_ledger_diff,
+                    scheduled_time_derived: true,
+                })))
+            }
+            #[cfg(not(feature = "loose_deserialization"))]
+            PcbVersion::V1 => {
+                anyhow::bail!("bin_prot block {state_hash} requires loose_deserialization")
+            }
+            PcbVersion::V2 => {
+                let BlockFileDataV2 {
+                    scheduled_time,
+                    protocol_state,
+                    staged_ledger_diff,
+                    tokens_used,
+                    accounts_accessed,
+                    accounts_created,
+                } = bin_prot::parse_v2(&block_file_contents.contents)?;
+
+                Ok(Self::V2(Box::new(PrecomputedBlockV2 {
+                    state_hash,
+                    scheduled_time,
+                    blockchain_length: block_file_contents.blockchain_length,
+                    network: block_file_contents.network,
+                    protocol_state,
+                    staged_ledger_diff,
+                    tokens_used,
+                    accounts_accessed,
+                    accounts_created,
+                })))
+            }
+        }
+    }
+
     pub fn new(
         network: Network,
         blockchain_length: BlockchainLength,
@@ -135,19 +198,35 @@ impl PrecomputedBlock {
     /// Parses the precomputed block if the path is a valid block file and
     /// automatically determines the version.
     pub fn from_path(path: &Path) -> anyhow::Result<Self> {
-        let (network, blockchain_length, state_hash) = extract_network_height_hash(path);
-        let version: PcbVersion = blockchain_length.into();
-        let contents = std::fs::read(path)?;
-
-        Self::new(network, blockchain_length, state_hash, contents, version)
+        let (_, blockchain_length, _) = extract_network_height_hash(path);
+        Self::parse_file(path, blockchain_length.into())
     }
 
-    /// Parses the precomputed block if the path is a valid block file
+    /// Parses the precomputed block if the path is a valid JSON or bin_prot
+    /// block file
     pub fn parse_file(path: &Path, version: PcbVersion) -> anyhow::Result<Self> {
+        Self::from_path_and_contents(path, std::fs::read(path)?, version)
+    }
+
+    /// Parses the contents of the JSON or bin_prot block file named `path`
+    pub fn from_path_and_contents(
+        path: &Path,
+        contents: Vec<u8>,
+        version: PcbVersion,
+    ) -> anyhow::Result<Self> {
         let (network, blockchain_length, state_hash) = extract_network_height_hash(path);
-        let contents = std::fs::read(path)?;
+        let block_file_contents = BlockFileContents {
+            contents,
+            network,
+            state_hash,
+            blockchain_length,
+        };
 
-        Self::new(network, blockchain_length, state_hash, contents, version)
+        if is_bin_prot_block_file(path) {
+            return Self::from_bin_prot(block_file_contents, version);
+        }
+
+        Self::from_file_contents(block_file_contents, version)
     }
 
     pub fn scheduled_time(&self) -> String {
@@ -157,6 +236,15 @@ impl PrecomputedBlock {
         }
     }
 
+    /// Whether the scheduled time was derived from the block's timestamp
+    /// rather than read from the block file
+    pub fn is_scheduled_time_derived(&self) -> bool {
+        match self {
+            Self::V1(v1) => v1.scheduled_time_derived,
+            Self::V2(_) => false,
+        }
+    }
+
     pub fn previous_state_hash(&self) -> StateHash {
         match self {
             Self::V1(v1) => {
@@ -881,6 +969,61 @@ impl PrecomputedBlock {
         }
     }
 
+    /// Densities of the sub-windows in the current sliding window
+    pub fn sub_window_densities(&self) -> Vec<u32> {
+        match self {
+            Self::V1(v1) => v1
+                .protocol_state
+                .body
+                .t
+                .t
+                .consensus_state
+                .t
+                .t
+                .sub_window_densities
+                .iter()
+                .map(|density| density.t.t)
+                .collect(),
+            Self::V2(v2) => v2
+                .protocol_state
+                .body
+                .consensus_state
+                .sub_window_densities
+                .iter()
+                .map(|density| density.0)
+                .collect(),
+        }
+    }
+
+    /// Global slot relative to the current hardfork
+    pub fn curr_global_slot_since_hard_fork(&self) -> u32 {
+        match self {
+            Self::V1(v1) => {
+                v1.protocol_state
+                    .body
+                    .t
+                    .t
+                    .consensus_state
+                    .t
+                    .t
+                    .curr_global_slot
+                    .t
+                    .t
+                    .slot_number
+                    .t
+                    .t
+            }
+            Self::V2(v2) => {
+                v2.protocol_state
+                    .body
+                    .consensus_state
+                    .curr_global_slot_since_hard_fork
+                    .slot_number
+                    .0
+            }
+        }
+    }
+
     // next epoch data
 
     pub fn next_epoch_seed(&self) -> String {
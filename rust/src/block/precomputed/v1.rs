@@ -32,6 +32,10 @@ pub struct PrecomputedBlockV1 {
     pub scheduled_time: ScheduledTime,
     pub protocol_state: ProtocolState,
     pub staged_ledger_diff: mina_rs::StagedLedgerDiff,
+    // bin_prot external transitions don't record a scheduled time, so it is
+    // derived from the block's timestamp
+    #[serde(default)]
+    pub scheduled_time_derived: bool,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
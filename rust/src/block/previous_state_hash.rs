@@ -1,4 +1,4 @@
-use crate::block::StateHash;
+use crate::block::{is_bin_prot_block_file, precomputed::bin_prot, StateHash};
 use anyhow::bail;
 use std::{
     fs::File,
@@ -13,6 +13,11 @@ impl PreviousStateHash {
     pub fn from_path(path: &Path) -> anyhow::Result<StateHash> {
         const BUFFER_CAPACITY: usize = 200;
 
+        // bin_prot blocks lead with the state hashes
+        if is_bin_prot_block_file(path) {
+            return Ok(bin_prot::read_state_hashes(path)?.0);
+        }
+
         let file = File::open(path)?;
         let reader = BufReader::new(file);
         let mut buffer = String::with_capacity(BUFFER_CAPACITY);
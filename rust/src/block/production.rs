@@ -0,0 +1,217 @@
+//! Block producer epoch performance
+
+use super::store::BlockStore;
+use crate::{
+    base::{public_key::PublicKey, state_hash::StateHash},
+    constants::{MAINNET_ACTIVE_SLOT_COEFFICIENT, MAINNET_EPOCH_SLOT_COUNT},
+    ledger::store::staking::StakingLedgerStore,
+    store::IndexerStore,
+};
+use serde::{Deserialize, Serialize};
+
+/// Blocks produced vs expected from the producer's stake share
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ProducerReport {
+    pub public_key: PublicKey,
+    pub epoch: u32,
+    pub genesis_state_hash: StateHash,
+
+    /// Stake delegated to the producer (nanomina)
+    pub stake: u64,
+
+    /// Staking ledger total currency (nanomina)
+    pub total_stake: u64,
+    pub stake_share: f64,
+
+    /// Slots of the epoch which have passed
+    pub slots_elapsed: u32,
+    pub slots_produced: u32,
+    pub num_blocks_produced: u32,
+    pub num_canonical_blocks: u32,
+    pub num_supercharged_blocks: u32,
+
+    /// Share of produced blocks which were not canonical
+    pub orphan_rate: f64,
+
+    /// Slots the stake share is expected to win in the elapsed slots
+    pub expected_blocks: f64,
+
+    /// Expected slots won in excess of slots produced
+    pub missed_slots: f64,
+}
+
+//////////
+// impl //
+//////////
+
+impl ProducerReport {
+    /// Report for the producer's epoch, `None` if the epoch's staking ledger
+    /// is missing
+    pub fn from_store(
+        db: &IndexerStore,
+        pk: &PublicKey,
+        epoch: u32,
+        genesis_state_hash: &StateHash,
+    ) -> anyhow::Result<Option<Self>> {
+        let Some(ledger_hash) = db.get_staking_ledger_hash_by_epoch(epoch, genesis_state_hash)?
+        else {
+            return Ok(None);
+        };
+
+        let stake = db
+            .get_epoch_delegations(pk, epoch, genesis_state_hash)?
+            .map_or(0, |delegations| delegations.total_delegated);
+        let total_stake = db.get_total_currency(&ledger_hash)?.unwrap_or_default();
+
+        let epoch_opt = Some(epoch);
+        let genesis_opt = Some(genesis_state_hash);
+
+        Ok(Some(Self::new(
+            pk.clone(),
+            epoch,
+            genesis_state_hash.clone(),
+            stake,
+            total_stake,
+            Self::slots_elapsed(db, epoch, genesis_state_hash)?,
+            db.get_pk_epoch_slots_produced_count(pk, epoch_opt, genesis_opt)?,
+            db.get_block_production_pk_epoch_count(pk, epoch_opt, genesis_opt)?,
+            db.get_block_production_pk_canonical_epoch_count(pk, epoch_opt, genesis_opt)?,
+            db.get_block_production_pk_supercharged_epoch_count(pk, epoch_opt, genesis_opt)?,
+        )))
+    }
+
+    #[allow(clippy::too_many_arguments)]
+    pub fn new(
+        public_key: PublicKey,
+        epoch: u32,
+        genesis_state_hash: StateHash,
+        stake: u64,
+        total_stake: u64,
+        slots_elapsed: u32,
+        slots_produced: u32,
+        num_blocks_produced: u32,
+        num_canonical_blocks: u32,
+        num_supercharged_blocks: u32,
+    ) -> Self {
+        let stake_share = if total_stake == 0 {
+            0.0
+        } else {
+            stake as f64 / total_stake as f64
+        };
+        let orphan_rate = if num_blocks_produced == 0 {
+            0.0
+        } else {
+            num_blocks_produced.saturating_sub(num_canonical_blocks) as f64
+                / num_blocks_produced as f64
+        };
+        let expected_blocks = slots_elapsed as f64 * Self::slot_win_probability(stake_share);
+
+        Self {
+            public_key,
+            epoch,
+            genesis_state_hash,
+            stake,
+            total_stake,
+            stake_share,
+            slots_elapsed,
+            slots_produced,
+            num_blocks_produced,
+            num_canonical_blocks,
+            num_supercharged_blocks,
+            orphan_rate,
+            expected_blocks,
+            missed_slots: (expected_blocks - slots_produced as f64).max(0.0),
+        }
+    }
+
+    /// Probability of winning a slot's VRF with the stake share
+    /// as per https://docs.minaprotocol.com/mina-protocol/proof-of-stake
+    pub fn slot_win_probability(stake_share: f64) -> f64 {
+        1.0 - (1.0 - MAINNET_ACTIVE_SLOT_COEFFICIENT).powf(stake_share.clamp(0.0, 1.0))
+    }
+
+    /// Slots of the epoch up to the best block, all for past epochs
+    fn slots_elapsed(
+        db: &IndexerStore,
+        epoch: u32,
+        genesis_state_hash: &StateHash,
+    ) -> anyhow::Result<u32> {
+        let Some(best_block_hash) = db.get_best_block_hash()? else {
+            return Ok(0);
+        };
+
+        if db.get_block_genesis_state_hash(&best_block_hash)?.as_ref() != Some(genesis_state_hash) {
+            return Ok(MAINNET_EPOCH_SLOT_COUNT);
+        }
+
+        let best_epoch = db.get_block_epoch(&best_block_hash)?.unwrap_or_default();
+        Ok(match epoch.cmp(&best_epoch) {
+            std::cmp::Ordering::Less => MAINNET_EPOCH_SLOT_COUNT,
+            std::cmp::Ordering::Greater => 0,
+            std::cmp::Ordering::Equal => {
+                let global_slot = db.get_best_block_global_slot()?.unwrap_or_default();
+                global_slot % MAINNET_EPOCH_SLOT_COUNT + 1
+            }
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn report(stake: u64, slots_produced: u32, produced: u32, canonical: u32) -> ProducerReport {
+        ProducerReport::new(
+            PublicKey::default(),
+            0,
+            StateHash::default(),
+            stake,
+            100,
+            MAINNET_EPOCH_SLOT_COUNT,
+            slots_produced,
+            produced,
+            canonical,
+            0,
+        )
+    }
+
+    #[test]
+    fn slot_win_probability() {
+        assert_eq!(ProducerReport::slot_win_probability(0.0), 0.0);
+        assert_eq!(
+            ProducerReport::slot_win_probability(1.0),
+            MAINNET_ACTIVE_SLOT_COEFFICIENT
+        );
+
+        // concave in stake share
+        let p = ProducerReport::slot_win_probability(0.01);
+        assert!(p > 0.01 * MAINNET_ACTIVE_SLOT_COEFFICIENT);
+        assert!(p < 0.02 * MAINNET_ACTIVE_SLOT_COEFFICIENT);
+    }
+
+    #[test]
+    fn expected_and_missed() {
+        let res = report(1, 10, 12, 9);
+        let expected = MAINNET_EPOCH_SLOT_COUNT as f64 * ProducerReport::slot_win_probability(0.01);
+
+        assert_eq!(res.stake_share, 0.01);
+        assert_eq!(res.expected_blocks, expected);
+        assert_eq!(res.missed_slots, expected - 10.0);
+        assert_eq!(res.orphan_rate, 0.25);
+
+        // producing more than expected misses nothing
+        let res = report(1, 100, 100, 100);
+        assert_eq!(res.missed_slots, 0.0);
+        assert_eq!(res.orphan_rate, 0.0);
+    }
+
+    #[test]
+    fn no_stake_or_blocks() {
+        let res = report(0, 0, 0, 0);
+
+        assert_eq!(res.stake_share, 0.0);
+        assert_eq!(res.expected_blocks, 0.0);
+        assert_eq!(res.missed_slots, 0.0);
+        assert_eq!(res.orphan_rate, 0.0);
+    }
+}
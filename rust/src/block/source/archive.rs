@@ -0,0 +1,154 @@
+//! Tar archive block source
+//!
+//! Block files are read from the archive in order on a background thread, the
+//! archive is never unpacked. Entries which are not block files are skipped.
+
+use super::{parse_block, BlockSource};
+use crate::block::{is_valid_block_file, precomputed::PrecomputedBlock};
+use flate2::read::GzDecoder;
+use std::{
+    fs::File,
+    io::{BufReader, Read},
+    path::Path,
+    sync::mpsc::{sync_channel, Receiver, SyncSender},
+};
+
+/// Number of block files read ahead of ingestion
+const READ_AHEAD: usize = 16;
+
+type BlockFile = anyhow::Result<(String, Vec<u8>)>;
+
+/// Block files of a `.tar`, `.tar.gz` or `.tgz` archive
+pub struct ArchiveSource {
+    block_files: Receiver<BlockFile>,
+}
+
+impl ArchiveSource {
+    pub fn new(path: &Path) -> anyhow::Result<Self> {
+        let file = BufReader::new(File::open(path)?);
+        let reader: Box<dyn Read + Send> = if is_gzip_archive(path) {
+            Box::new(GzDecoder::new(file))
+        } else {
+            Box::new(file)
+        };
+
+        let (tx, rx) = sync_channel(READ_AHEAD);
+        std::thread::spawn(move || {
+            if let Err(e) = read_block_files(reader, &tx) {
+                tx.send(Err(e)).ok();
+            }
+        });
+
+        Ok(Self { block_files: rx })
+    }
+
+    /// Checks for a tar archive extension
+    pub fn is_archive(path: &Path) -> bool {
+        path.extension().is_some_and(|ext| ext == "tar") || is_gzip_archive(path)
+    }
+}
+
+fn is_gzip_archive(path: &Path) -> bool {
+    let name = path.file_name().and_then(|name| name.to_str());
+    name.is_some_and(|name| name.ends_with(".tar.gz") || name.ends_with(".tgz"))
+}
+
+/// Sends the archive's block files until it's exhausted or the source is
+/// dropped
+fn read_block_files(reader: impl Read, tx: &SyncSender<BlockFile>) -> anyhow::Result<()> {
+    let mut archive = tar::Archive::new(reader);
+
+    for entry in archive.entries()? {
+        let mut entry = entry?;
+        if !entry.header().entry_type().is_file() {
+            continue;
+        }
+
+        let path = entry.path()?;
+        let name = match path.file_name().and_then(|name| name.to_str()) {
+            Some(name) if is_valid_block_file(name) => name.to_string(),
+            _ => continue,
+        };
+
+        let mut contents = Vec::with_capacity(entry.size() as usize);
+        entry.read_to_end(&mut contents)?;
+
+        if tx.send(Ok((name, contents))).is_err() {
+            break;
+        }
+    }
+
+    Ok(())
+}
+
+impl BlockSource for ArchiveSource {
+    fn next_block(&mut self) -> anyhow::Result<Option<(PrecomputedBlock, u64)>> {
+        match self.block_files.recv() {
+            Ok(block_file) => {
+                let (name, contents) = block_file?;
+                parse_block(&name, contents).map(Some)
+            }
+            // the archive is exhausted
+            Err(_) => Ok(None),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use flate2::{write::GzEncoder, Compression};
+    use tempfile::TempDir;
+
+    const BLOCKS: [&str; 2] = [
+        "mainnet-105489-3NK4huLvUDiL4XuCUcyrWCKynmvhqfKsx5h2MfBXVVUq2Qwzi5uT.json",
+        "mainnet-105490-3NKxEA9gztvEGxL4uk4eTncZAxuRmMsB8n81UkeAMevUjMbLHmkC.json",
+    ];
+
+    fn append_blocks<W: std::io::Write>(builder: &mut tar::Builder<W>) -> anyhow::Result<()> {
+        for name in BLOCKS {
+            builder.append_path_with_name(
+                Path::new("./tests/data/sequential_blocks").join(name),
+                format!("blocks/{name}"),
+            )?;
+        }
+
+        // skipped
+        builder.append_path_with_name("./Cargo.toml", "Cargo.toml")?;
+        Ok(())
+    }
+
+    fn assert_blocks(path: &Path) -> anyhow::Result<()> {
+        assert!(ArchiveSource::is_archive(path));
+
+        let mut source = ArchiveSource::new(path)?;
+        for name in BLOCKS {
+            let (block, _) = source.next_block()?.unwrap();
+            assert_eq!(name, format!("{}.json", block.summary()));
+        }
+
+        assert!(source.next_block()?.is_none());
+        Ok(())
+    }
+
+    #[test]
+    fn tar_and_tar_gz() -> anyhow::Result<()> {
+        let dir = TempDir::new()?;
+
+        let tar_path = dir.path().join("blocks.tar");
+        let mut builder = tar::Builder::new(File::create(&tar_path)?);
+        append_blocks(&mut builder)?;
+        builder.into_inner()?;
+        assert_blocks(&tar_path)?;
+
+        let tar_gz_path = dir.path().join("blocks.tar.gz");
+        let encoder = GzEncoder::new(File::create(&tar_gz_path)?, Compression::default());
+        let mut builder = tar::Builder::new(encoder);
+        append_blocks(&mut builder)?;
+        builder.into_inner()?.finish()?;
+        assert_blocks(&tar_gz_path)?;
+
+        assert!(!ArchiveSource::is_archive(Path::new("blocks.zip")));
+        Ok(())
+    }
+}
@@ -0,0 +1,163 @@
+//! Directory block sources
+
+use super::{parse_block, BlockSource};
+use crate::block::{extract_block_height, is_valid_block_file, precomputed::PrecomputedBlock};
+use anyhow::Context;
+use std::{
+    path::{Path, PathBuf},
+    vec::IntoIter,
+};
+
+/// Height-sorted block files of a directory
+pub struct DirectorySource {
+    num_blocks: u32,
+    paths: IntoIter<PathBuf>,
+}
+
+impl DirectorySource {
+    /// Block files directly in `blocks_dir`, i.e. the block parser's layout
+    pub fn new(blocks_dir: &Path) -> anyhow::Result<Self> {
+        Self::with_max_depth(blocks_dir, 0)
+    }
+
+    /// Block files of `blocks_dir`, walked recursively if it has
+    /// subdirectories, e.g. height buckets, otherwise read as a flat blocks
+    /// dir
+    pub fn open(blocks_dir: &Path) -> anyhow::Result<Self> {
+        if is_nested(blocks_dir)? {
+            Self::nested(blocks_dir)
+        } else {
+            Self::new(blocks_dir)
+        }
+    }
+
+    /// Block files in `blocks_dir` & all of its subdirectories, e.g. height
+    /// buckets
+    pub fn nested(blocks_dir: &Path) -> anyhow::Result<Self> {
+        Self::with_max_depth(blocks_dir, usize::MAX)
+    }
+
+    fn with_max_depth(blocks_dir: &Path, max_depth: usize) -> anyhow::Result<Self> {
+        let mut paths = vec![];
+        collect_block_files(blocks_dir, max_depth, &mut paths)
+            .with_context(|| format!("blocks dir {}", blocks_dir.display()))?;
+
+        paths.sort_by_cached_key(|path| {
+            (
+                extract_block_height(path),
+                path.file_name().map(ToOwned::to_owned),
+            )
+        });
+        Ok(Self {
+            num_blocks: paths.len() as u32,
+            paths: paths.into_iter(),
+        })
+    }
+}
+
+/// Whether `dir` has any subdirectories
+fn is_nested(dir: &Path) -> anyhow::Result<bool> {
+    for entry in std::fs::read_dir(dir).with_context(|| format!("blocks dir {}", dir.display()))? {
+        if entry?.file_type()?.is_dir() {
+            return Ok(true);
+        }
+    }
+
+    Ok(false)
+}
+
+fn collect_block_files(
+    dir: &Path,
+    max_depth: usize,
+    paths: &mut Vec<PathBuf>,
+) -> anyhow::Result<()> {
+    for entry in std::fs::read_dir(dir)? {
+        let path = entry?.path();
+
+        if path.is_dir() {
+            if max_depth > 0 {
+                collect_block_files(&path, max_depth - 1, paths)?;
+            }
+        } else if is_valid_block_file(&path) {
+            paths.push(path);
+        }
+    }
+
+    Ok(())
+}
+
+impl BlockSource for DirectorySource {
+    fn next_block(&mut self) -> anyhow::Result<Option<(PrecomputedBlock, u64)>> {
+        match self.paths.next() {
+            Some(path) => {
+                let name = path
+                    .file_name()
+                    .and_then(|name| name.to_str())
+                    .expect("valid block file name");
+                let contents =
+                    std::fs::read(&path).with_context(|| format!("{}", path.display()))?;
+
+                parse_block(name, contents).map(Some)
+            }
+            None => Ok(None),
+        }
+    }
+
+    fn num_blocks(&self) -> Option<u32> {
+        Some(self.num_blocks)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    const BLOCKS_DIR: &str = "./tests/data/sequential_blocks";
+
+    #[test]
+    fn flat_and_nested() -> anyhow::Result<()> {
+        let mut names: Vec<_> = std::fs::read_dir(BLOCKS_DIR)?
+            .flatten()
+            .map(|entry| entry.file_name().to_str().unwrap().to_string())
+            .filter(|name| is_valid_block_file(name))
+            .collect();
+        names.sort();
+
+        // bucket blocks by height, 10 per bucket
+        let nested_dir = TempDir::new()?;
+        let top_name = names.pop().unwrap();
+        std::fs::copy(
+            Path::new(BLOCKS_DIR).join(&top_name),
+            nested_dir.path().join(&top_name),
+        )?;
+
+        for name in &names {
+            let height = extract_block_height(Path::new(name));
+            let bucket = nested_dir.path().join((height / 10).to_string());
+
+            std::fs::create_dir_all(&bucket)?;
+            std::fs::copy(Path::new(BLOCKS_DIR).join(name), bucket.join(name))?;
+        }
+
+        let flat = DirectorySource::new(nested_dir.path())?;
+        assert_eq!(flat.num_blocks(), Some(1));
+
+        let mut nested = DirectorySource::nested(nested_dir.path())?;
+        assert_eq!(nested.num_blocks(), Some(names.len() as u32 + 1));
+
+        let opened = DirectorySource::open(nested_dir.path())?;
+        assert_eq!(opened.num_blocks(), nested.num_blocks());
+
+        let opened = DirectorySource::open(Path::new(BLOCKS_DIR))?;
+        assert_eq!(opened.num_blocks(), Some(names.len() as u32 + 1));
+
+        let mut prev_height = 0;
+        while let Some((block, _)) = nested.next_block()? {
+            assert!(block.blockchain_length() >= prev_height);
+            prev_height = block.blockchain_length();
+        }
+
+        Ok(())
+    }
+}
@@ -0,0 +1,108 @@
+//! Precomputed block sources
+//!
+//! Besides the block parser's flat blocks directory, blocks can be read from
+//! - nested, e.g. height-bucketed, directories of block files
+//! - `.tar`, `.tar.gz` or `.tgz` archives of block files
+//! - length-prefixed block file frames on stdin or a unix socket
+//!
+//! Block files are named `<network>-<height>-<state hash>` with a `.json`,
+//! `.json.gz` or `.bin` extension. Directory sources are height-sorted,
+//! archives & streams are read in order & should be height-sorted too.
+
+pub mod archive;
+pub mod dir;
+pub mod stream;
+
+use super::{is_valid_block_file, precomputed::PrecomputedBlock};
+use crate::{base::blockchain_length::BlockchainLength, utility::compression::decompress_gzip};
+use anyhow::{bail, Context};
+use archive::ArchiveSource;
+use dir::DirectorySource;
+use std::{
+    os::unix::{fs::FileTypeExt, net::UnixStream},
+    path::Path,
+};
+use stream::StreamSource;
+
+/// Block source path for stdin
+pub const STDIN_SOURCE: &str = "-";
+
+/// Source of precomputed blocks
+pub trait BlockSource {
+    /// Next block & its file size (bytes), `None` once the source is exhausted
+    fn next_block(&mut self) -> anyhow::Result<Option<(PrecomputedBlock, u64)>>;
+
+    /// Number of blocks, if known before reading them
+    fn num_blocks(&self) -> Option<u32> {
+        None
+    }
+}
+
+/// Opens the block source at `path`
+/// - `-` is a stream on stdin
+/// - a unix socket is connected to & read as a stream
+/// - a directory is walked recursively if it has subdirectories, otherwise
+///   it's read as a flat blocks dir
+/// - a tar file is read as an archive
+pub fn open(path: &Path) -> anyhow::Result<Box<dyn BlockSource + Send>> {
+    if path == Path::new(STDIN_SOURCE) {
+        return Ok(Box::new(StreamSource::new(std::io::stdin())));
+    }
+
+    let file_type = std::fs::metadata(path)
+        .with_context(|| format!("block source {}", path.display()))?
+        .file_type();
+
+    if file_type.is_socket() {
+        return Ok(Box::new(StreamSource::new(UnixStream::connect(path)?)));
+    }
+
+    if file_type.is_dir() {
+        return Ok(Box::new(DirectorySource::open(path)?));
+    }
+
+    if ArchiveSource::is_archive(path) {
+        return Ok(Box::new(ArchiveSource::new(path)?));
+    }
+
+    bail!("Unsupported block source {}", path.display())
+}
+
+/// Parses the contents of the block file `name`, gzip compressed files are
+/// decompressed first
+fn parse_block(name: &str, contents: Vec<u8>) -> anyhow::Result<(PrecomputedBlock, u64)> {
+    if !is_valid_block_file(name) {
+        bail!("Invalid block file name {name}")
+    }
+
+    let num_bytes = contents.len() as u64;
+    let (path, contents) = match name.strip_suffix(".gz") {
+        Some(name) => (Path::new(name), decompress_gzip(&contents)?),
+        None => (Path::new(name), contents),
+    };
+
+    let version = BlockchainLength(super::extract_block_height(path)).into();
+    let block = PrecomputedBlock::from_path_and_contents(path, contents, version)
+        .with_context(|| format!("Block parsing error {name}"))?;
+
+    Ok((block, num_bytes))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_block_file_names() -> anyhow::Result<()> {
+        let name = "mainnet-105489-3NK4huLvUDiL4XuCUcyrWCKynmvhqfKsx5h2MfBXVVUq2Qwzi5uT.json";
+        let contents = std::fs::read(format!("./tests/data/sequential_blocks/{name}"))?;
+        let num_bytes = contents.len() as u64;
+
+        let (block, block_bytes) = parse_block(name, contents.clone())?;
+        assert_eq!(block_bytes, num_bytes);
+        assert_eq!(block.blockchain_length(), 105489);
+
+        assert!(parse_block("mainnet-105489.json", contents).is_err());
+        Ok(())
+    }
+}
@@ -0,0 +1,142 @@
+//! Length-prefixed block stream source
+//!
+//! Each frame is a block file name followed by its contents, each prefixed
+//! by its length (bytes) as a big-endian `u32`
+//!
+//! ```text
+//! | name len | name | contents len | contents |
+//! ```
+//!
+//! The stream ends at EOF between frames.
+
+use super::{parse_block, BlockSource};
+use crate::block::precomputed::PrecomputedBlock;
+use anyhow::bail;
+use byteorder::{BigEndian, ReadBytesExt, WriteBytesExt};
+use std::io::{BufReader, ErrorKind, Read, Write};
+
+/// Longest accepted block file name (bytes)
+const MAX_NAME_LEN: u32 = 256;
+
+/// Largest accepted block file (bytes)
+const MAX_CONTENTS_LEN: u32 = 128 * 1024 * 1024;
+
+/// Block files framed on a byte stream, e.g. stdin or a unix socket
+pub struct StreamSource<R> {
+    reader: BufReader<R>,
+}
+
+impl<R: Read> StreamSource<R> {
+    pub fn new(reader: R) -> Self {
+        Self {
+            reader: BufReader::new(reader),
+        }
+    }
+
+    /// Next frame's block file name & contents
+    fn read_frame(&mut self) -> anyhow::Result<Option<(String, Vec<u8>)>> {
+        let name_len = match self.read_first_len()? {
+            Some(len) => len,
+            None => return Ok(None),
+        };
+
+        if name_len > MAX_NAME_LEN {
+            bail!("Block file name too long: {name_len} bytes")
+        }
+
+        let mut name = vec![0; name_len as usize];
+        self.reader.read_exact(&mut name)?;
+
+        let contents_len = self.reader.read_u32::<BigEndian>()?;
+        if contents_len > MAX_CONTENTS_LEN {
+            bail!("Block file too large: {contents_len} bytes")
+        }
+
+        let mut contents = vec![0; contents_len as usize];
+        self.reader.read_exact(&mut contents)?;
+
+        Ok(Some((String::from_utf8(name)?, contents)))
+    }
+
+    /// Frame's first length prefix, `None` at EOF
+    fn read_first_len(&mut self) -> anyhow::Result<Option<u32>> {
+        let mut len = [0; 4];
+        let mut num_read = 0;
+
+        while num_read < len.len() {
+            match self.reader.read(&mut len[num_read..]) {
+                Ok(0) if num_read == 0 => return Ok(None),
+                Ok(0) => bail!("Block stream ended mid-frame"),
+                Ok(n) => num_read += n,
+                Err(e) if e.kind() == ErrorKind::Interrupted => continue,
+                Err(e) => return Err(e.into()),
+            }
+        }
+
+        Ok(Some(u32::from_be_bytes(len)))
+    }
+}
+
+impl<R: Read> BlockSource for StreamSource<R> {
+    fn next_block(&mut self) -> anyhow::Result<Option<(PrecomputedBlock, u64)>> {
+        match self.read_frame()? {
+            Some((name, contents)) => parse_block(&name, contents).map(Some),
+            None => Ok(None),
+        }
+    }
+}
+
+/// Writes a block file frame
+pub fn write_frame<W: Write>(writer: &mut W, name: &str, contents: &[u8]) -> anyhow::Result<()> {
+    writer.write_u32::<BigEndian>(name.len() as u32)?;
+    writer.write_all(name.as_bytes())?;
+    writer.write_u32::<BigEndian>(contents.len() as u32)?;
+    writer.write_all(contents)?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn framed_blocks() -> anyhow::Result<()> {
+        let names = [
+            "mainnet-105489-3NK4huLvUDiL4XuCUcyrWCKynmvhqfKsx5h2MfBXVVUq2Qwzi5uT.json",
+            "mainnet-105490-3NKxEA9gztvEGxL4uk4eTncZAxuRmMsB8n81UkeAMevUjMbLHmkC.json",
+        ];
+
+        let mut stream = vec![];
+        for name in names {
+            let contents = std::fs::read(format!("./tests/data/sequential_blocks/{name}"))?;
+            write_frame(&mut stream, name, &contents)?;
+        }
+
+        let mut source = StreamSource::new(stream.as_slice());
+        for name in names {
+            let (block, _) = source.next_block()?.unwrap();
+            assert_eq!(name, format!("{}.json", block.summary()));
+        }
+        assert!(source.next_block()?.is_none());
+
+        // truncated frame
+        let mut source = StreamSource::new(&stream[..stream.len() - 1]);
+        assert!(source.next_block().is_ok());
+        assert!(source.next_block().is_err());
+
+        // truncated length prefix
+        let mut source = StreamSource::new(&stream[..2]);
+        assert!(source.next_block().is_err());
+
+        // oversized contents are rejected before allocating
+        let mut oversized = vec![];
+        oversized.write_u32::<BigEndian>(names[0].len() as u32)?;
+        oversized.write_all(names[0].as_bytes())?;
+        oversized.write_u32::<BigEndian>(MAX_CONTENTS_LEN + 1)?;
+
+        let mut source = StreamSource::new(oversized.as_slice());
+        assert!(source.next_block().is_err());
+
+        Ok(())
+    }
+}
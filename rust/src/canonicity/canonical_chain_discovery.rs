@@ -2,11 +2,13 @@ use crate::{
     base::state_hash::StateHash,
     block::{
         extract_block_height, extract_state_hash, genesis_state_hash::GenesisStateHash,
-        previous_state_hash::*, sort_by_height_and_lexicographical_order,
+        precomputed::PrecomputedBlock, previous_state_hash::*,
+        sort_by_height_and_lexicographical_order, Block,
     },
+    constants::MAINNET_TRANSITION_FRONTIER_K,
     utility::functions::{extract_height_and_hash, pretty_print_duration},
 };
-use log::info;
+use log::{info, warn};
 use std::{
     collections::{BTreeMap, HashMap, HashSet, VecDeque},
     path::PathBuf,
@@ -100,9 +102,13 @@ fn find_best_tip<'a>(
         }
     }
 
+    // blocks without children
+    let mut leaves = vec![];
+
     while let Some(best_tip_candidate) = queue.pop_front() {
         let (height, state_hash) = extract_height_and_hash(best_tip_candidate);
         let next_height = height + 1;
+        let mut is_leaf = true;
 
         log_progress(height, reporting_freq, &time);
 
@@ -114,12 +120,22 @@ fn find_best_tip<'a>(
 
                         best_tip = Some(possible_next_tip);
                         queue.push_back(possible_next_tip);
+                        is_leaf = false;
                     }
                 }
             }
         }
+
+        if is_leaf {
+            leaves.push(*best_tip_candidate);
+        }
     }
 
+    // competing branches may be long-range forks
+    let best_tip = best_tip.map(|best_tip| {
+        select_best_leaf(&leaves, extract_block_height(best_tip)).unwrap_or(*best_tip)
+    });
+
     best_tip.map(|best_tip| {
         info!(
             "Found best tip {} in {}",
@@ -127,10 +143,35 @@ fn find_best_tip<'a>(
             pretty_print_duration(time.elapsed())
         );
 
-        *best_tip
+        best_tip
     })
 }
 
+/// Selects the best leaf within a transition frontier of the highest leaf
+/// via the witness tree's fork choice rule
+fn select_best_leaf<'a>(leaves: &[&'a PathBuf], max_height: u32) -> Option<&'a PathBuf> {
+    let candidates: Vec<_> = leaves
+        .iter()
+        .filter(|leaf| extract_block_height(leaf) + MAINNET_TRANSITION_FRONTIER_K >= max_height)
+        .collect();
+
+    if candidates.len() < 2 {
+        return None;
+    }
+
+    candidates
+        .into_iter()
+        .filter_map(|leaf| match PrecomputedBlock::from_path(leaf) {
+            Ok(block) => Some((Block::from(block), *leaf)),
+            Err(e) => {
+                warn!("Unable to parse best tip candidate {}: {e}", leaf.display());
+                None
+            }
+        })
+        .min_by(|(a, _), (b, _)| a.cmp(b))
+        .map(|(_, leaf)| leaf)
+}
+
 fn canonical_branch_from_best_tip<'a>(
     tree_map: &mut BTreeMap<u32, Vec<&'a PathBuf>>,
     parent_hash_map: &HashMap<&'a str, &'a str>,
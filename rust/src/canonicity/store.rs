@@ -28,6 +28,13 @@ pub trait CanonicityStore {
     /// Get the state hash of the canonical block at the given global slot
     fn get_canonical_hash_at_slot(&self, global_slot: u32) -> anyhow::Result<Option<StateHash>>;
 
+    /// Get the state hash of the last canonical block at or before the given
+    /// global slot
+    fn get_canonical_hash_at_or_before_slot(
+        &self,
+        global_slot: u32,
+    ) -> anyhow::Result<Option<StateHash>>;
+
     /// Get block canonicity
     fn get_block_canonicity(&self, state_hash: &StateHash) -> anyhow::Result<Option<Canonicity>>;
 
@@ -35,6 +35,12 @@ pub struct DatabaseArgs {
     #[arg(long)]
     pub blocks_dir: Option<PathBuf>,
 
+    /// Additional source of precomputed blocks: a nested blocks directory, a
+    /// `.tar`/`.tar.gz` archive, a unix socket or `-` for stdin. The server
+    /// ingests it in the background.
+    #[arg(long, value_name = "SOURCE")]
+    pub blocks_source: Option<PathBuf>,
+
     /// Directory of staking ledgers
     #[arg(long)]
     pub staking_ledgers_dir: Option<PathBuf>,
@@ -79,4 +85,17 @@ pub struct DatabaseArgs {
     /// Switch to not ingest orphan blocks
     #[arg(long, default_value_t = false)]
     pub do_not_ingest_orphan_blocks: bool,
+
+    /// Directory of the o1-labs Poseidon parameter tables (`legacy.json` &
+    /// `kimchi.json`), the compiled-in tables aren't vendored yet
+    #[arg(long, value_name = "DIR")]
+    pub poseidon_params: Option<PathBuf>,
+
+    /// Verify user command signatures during ingestion
+    #[arg(long, default_value_t = false, requires = "poseidon_params")]
+    pub verify_signatures: bool,
+
+    /// Verify staking ledger hashes against their accounts during ingestion
+    #[arg(long, default_value_t = false, requires = "poseidon_params")]
+    pub verify_ledger_hashes: bool,
 }
@@ -1,6 +1,8 @@
 use log::LevelFilter;
 
 pub mod database;
+pub mod output;
+pub mod response;
 pub mod server;
 
 #[derive(Debug, Clone)]
@@ -0,0 +1,178 @@
+//! Machine-readable client output
+
+use super::response::CommandData;
+use crate::unix_socket_server::{ServerCliError, ServerCliResponse};
+use anyhow::Context;
+use bincode::{Decode, Encode};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+/// Version of the [OutputEnvelope] schema, bumped on breaking changes
+pub const OUTPUT_SCHEMA_VERSION: u32 = 2;
+
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, clap::ValueEnum, Encode, Decode)]
+pub enum OutputFormat {
+    /// Human-readable output
+    #[default]
+    Text,
+
+    /// Single JSON document
+    Json,
+
+    /// One compact JSON document per line, arrays are split into one
+    /// document per element
+    Ndjson,
+}
+
+/// Every machine-readable response is wrapped in an envelope
+#[derive(Debug, Serialize, Deserialize)]
+pub struct OutputEnvelope {
+    pub schema_version: u32,
+
+    /// Client subcommand, e.g. `accounts public-key`
+    pub command: String,
+
+    #[serde(flatten)]
+    pub result: OutputResult,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(tag = "status", rename_all = "snake_case")]
+pub enum OutputResult {
+    Ok { data: CommandData },
+    Error { error: ServerCliError },
+}
+
+impl OutputFormat {
+    /// JSON or NDJSON
+    pub fn is_machine_readable(self) -> bool {
+        !matches!(self, Self::Text)
+    }
+}
+
+impl OutputResult {
+    /// Successful machine-readable responses carry the subcommand's typed
+    /// [CommandData]
+    pub fn from_response(response: ServerCliResponse) -> anyhow::Result<Self> {
+        match response {
+            ServerCliResponse::Success(data) => Ok(Self::Ok {
+                data: serde_json::from_str(&data)
+                    .with_context(|| format!("Invalid response data: {data}"))?,
+            }),
+            ServerCliResponse::Error(error) => Ok(Self::Error { error }),
+        }
+    }
+}
+
+impl OutputEnvelope {
+    pub fn new(command: &str, response: ServerCliResponse) -> anyhow::Result<Self> {
+        Ok(Self {
+            schema_version: OUTPUT_SCHEMA_VERSION,
+            command: command.to_string(),
+            result: OutputResult::from_response(response)?,
+        })
+    }
+
+    /// Lines to print in the given format
+    pub fn render(self, output: OutputFormat) -> anyhow::Result<Vec<String>> {
+        match output {
+            OutputFormat::Text => unreachable!("text output is not enveloped"),
+            OutputFormat::Json => Ok(vec![serde_json::to_string_pretty(&self)?]),
+            OutputFormat::Ndjson => {
+                let mut envelope = serde_json::to_value(&self)?;
+                let items = match envelope.pointer_mut("/data/value") {
+                    Some(Value::Array(items)) => std::mem::take(items),
+                    _ => return Ok(vec![serde_json::to_string(&envelope)?]),
+                };
+
+                items
+                    .into_iter()
+                    .map(|item| {
+                        envelope["data"]["value"] = item;
+                        Ok(serde_json::to_string(&envelope)?)
+                    })
+                    .collect()
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        base::public_key::PublicKey, unix_socket_server::ServerCliErrorCode, webhook::Webhook,
+    };
+
+    #[test]
+    fn json_envelope() -> anyhow::Result<()> {
+        let data = CommandData::TransactionsCommandHash {
+            hash: "5Ju6ku4DY5McpfqPvduQyQASjv3AhsBTvR3QQZ7guYx4ymbuDRqm".to_string(),
+        };
+        let response = ServerCliResponse::Success(serde_json::to_string(&data)?);
+        let envelope = OutputEnvelope::new("transactions hash", response)?;
+        let json: Value = serde_json::from_str(&envelope.render(OutputFormat::Json)?[0])?;
+
+        assert_eq!(json["schema_version"], OUTPUT_SCHEMA_VERSION);
+        assert_eq!(json["command"], "transactions hash");
+        assert_eq!(json["status"], "ok");
+        assert_eq!(json["data"]["type"], "transactions_command_hash");
+        assert_eq!(
+            json["data"]["value"]["hash"],
+            "5Ju6ku4DY5McpfqPvduQyQASjv3AhsBTvR3QQZ7guYx4ymbuDRqm"
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn untyped_data() {
+        // responses must be typed data
+        for data in ["Best ledger written to \"x\"", "{\"balance\":1}"] {
+            let response = ServerCliResponse::Success(data.to_string());
+            assert!(OutputEnvelope::new("ledgers best", response).is_err());
+        }
+    }
+
+    #[test]
+    fn ndjson_lines() -> anyhow::Result<()> {
+        let data = CommandData::WebhooksList(
+            (0..3)
+                .map(|n| Webhook {
+                    public_key: PublicKey::default(),
+                    url: format!("http://localhost:808{n}/hook"),
+                })
+                .collect(),
+        );
+        let response = ServerCliResponse::Success(serde_json::to_string(&data)?);
+        let lines = OutputEnvelope::new("webhooks list", response)?.render(OutputFormat::Ndjson)?;
+        assert_eq!(lines.len(), 3);
+
+        for (n, line) in lines.iter().enumerate() {
+            let json: Value = serde_json::from_str(line)?;
+            assert_eq!(json["data"]["type"], "webhooks_list");
+            assert_eq!(
+                json["data"]["value"]["url"],
+                format!("http://localhost:808{n}/hook")
+            );
+        }
+
+        // unit data is a single line
+        let response = ServerCliResponse::Success(serde_json::to_string(&CommandData::Shutdown)?);
+        let lines = OutputEnvelope::new("shutdown", response)?.render(OutputFormat::Ndjson)?;
+        let json: Value = serde_json::from_str(&lines[0])?;
+
+        assert_eq!(lines.len(), 1);
+        assert_eq!(json["data"], serde_json::json!({ "type": "shutdown" }));
+
+        // errors are a single line
+        let response = ServerCliResponse::error(ServerCliErrorCode::NotFound, "missing");
+        let lines = OutputEnvelope::new("chain best", response)?.render(OutputFormat::Ndjson)?;
+        let json: Value = serde_json::from_str(&lines[0])?;
+
+        assert_eq!(lines.len(), 1);
+        assert_eq!(json["status"], "error");
+        assert_eq!(json["error"]["code"], "not_found");
+        assert_eq!(json["error"]["message"], "missing");
+        Ok(())
+    }
+}
@@ -0,0 +1,330 @@
+//! Typed data of the server's successful responses
+
+use super::output::OutputFormat;
+use crate::{
+    address_book::Label,
+    base::public_key::PublicKey,
+    block::{
+        precomputed::PrecomputedBlockWithCanonicity, production::ProducerReport, BlockWithoutHeight,
+    },
+    command::{internal::DbInternalCommandWithData, signed::SignedCommandWithData, Command},
+    ledger::{
+        account::Account,
+        staking::{
+            payout::PoolPayouts, AggregatedEpochStakeDelegation, AggregatedEpochStakeDelegations,
+            StakingLedger,
+        },
+        store::balance_history::BalanceHistoryEntry,
+        LedgerAccounts,
+    },
+    snark_work::{SnarkWorkSummary, SnarkWorkSummaryWithStateHash, SnarkWorkTotal},
+    state::{
+        missing::MissingBlocks,
+        summary::{SummaryShort, SummaryVerbose},
+    },
+    store::version::IndexerStoreVersion,
+    webhook::Webhook,
+};
+use serde::{Deserialize, Serialize};
+use std::path::PathBuf;
+
+/// Data of a successful response, one variant per client subcommand
+///
+/// Serialized as `{"type": <subcommand>, "value": <data>}`, the schema is
+/// versioned by [super::output::OUTPUT_SCHEMA_VERSION]
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(tag = "type", content = "value", rename_all = "snake_case")]
+pub enum CommandData {
+    AccountsPublicKey(Account),
+    AccountsBalanceHistory(Vec<BalanceHistoryEntry>),
+    BlocksBest(BlockData),
+    BlocksStateHash(BlockData),
+    BlocksGlobalSlot(Vec<BlockData>),
+    BlocksHeight(Vec<BlockData>),
+    BlocksPublicKey(Vec<BlockData>),
+    BlocksChildren(Vec<BlockData>),
+    BlocksMissing(MissingBlocks),
+    BlocksProduction(ProducerReport),
+    ChainBest(Vec<BlockData>),
+    LedgersBest(LedgerAccounts),
+    LedgersHash(LedgerAccounts),
+    LedgersHeight(LedgerAccounts),
+    StakingLedgersHash(StakingLedger),
+    StakingLedgersEpoch(StakingLedger),
+    StakingLedgersDelegations(AggregatedEpochStakeDelegations),
+    StakingLedgersPublicKey(AggregatedEpochStakeDelegation),
+    StakingLedgersPayouts(PoolPayouts),
+    SnarksStateHash(Vec<SnarkWorkSummary>),
+    SnarksPublicKey(Vec<SnarkWorkSummaryWithStateHash>),
+    SnarksTop(Vec<SnarkWorkTotal>),
+    Shutdown,
+    Summary(SummaryData),
+    TransactionsHash(TransactionData),
+    TransactionsCommandHash {
+        hash: String,
+    },
+    TransactionsPublicKey(Vec<TransactionData>),
+    TransactionsStateHash(Vec<TransactionData>),
+    InternalCommandsPublicKey(Vec<DbInternalCommandWithData>),
+    InternalCommandsStateHash(Vec<DbInternalCommandWithData>),
+    WebhooksAdd(Webhook),
+    WebhooksRemove(Webhook),
+    WebhooksList(Vec<Webhook>),
+    AddressBookAdd(Label),
+    AddressBookRemove {
+        public_key: PublicKey,
+    },
+    AddressBookLoad {
+        path: PathBuf,
+        count: usize,
+    },
+    AddressBookList(Vec<Label>),
+    DbVersion(IndexerStoreVersion),
+
+    /// Output written to a file, e.g. with `--path`
+    Written {
+        path: PathBuf,
+    },
+}
+
+/// Verbose blocks are precomputed blocks
+#[derive(Serialize, Deserialize)]
+#[serde(untagged)]
+pub enum BlockData {
+    Verbose(Box<PrecomputedBlockWithCanonicity>),
+    Short(BlockWithoutHeight),
+}
+
+/// Verbose transactions carry their block data
+#[derive(Serialize, Deserialize)]
+#[serde(untagged)]
+pub enum TransactionData {
+    Verbose(Box<SignedCommandWithData>),
+    Short(Command),
+}
+
+#[derive(Serialize, Deserialize)]
+#[serde(untagged)]
+pub enum SummaryData {
+    Verbose(Box<SummaryVerbose>),
+    Short(SummaryShort),
+}
+
+impl CommandData {
+    /// Human-readable text of the data
+    pub fn text(&self) -> anyhow::Result<String> {
+        use serde_json::to_string_pretty;
+
+        Ok(match self {
+            Self::AccountsPublicKey(account) => account.to_string(),
+            Self::AccountsBalanceHistory(history) => to_string_pretty(history)?,
+            Self::BlocksBest(block) | Self::BlocksStateHash(block) => to_string_pretty(block)?,
+            Self::BlocksGlobalSlot(blocks)
+            | Self::BlocksHeight(blocks)
+            | Self::BlocksPublicKey(blocks)
+            | Self::BlocksChildren(blocks)
+            | Self::ChainBest(blocks) => {
+                // verbose blocks are always JSON
+                let verbose = blocks
+                    .iter()
+                    .any(|block| matches!(block, BlockData::Verbose(_)));
+                let output = if verbose {
+                    OutputFormat::Json
+                } else {
+                    OutputFormat::Text
+                };
+
+                format_vec_jq_compatible(blocks, output)?
+            }
+            Self::BlocksMissing(missing_blocks) => to_string_pretty(missing_blocks)?,
+            Self::BlocksProduction(report) => to_string_pretty(report)?,
+            Self::LedgersBest(ledger) | Self::LedgersHash(ledger) | Self::LedgersHeight(ledger) => {
+                to_string_pretty(ledger)?
+            }
+            Self::StakingLedgersHash(staking_ledger)
+            | Self::StakingLedgersEpoch(staking_ledger) => to_string_pretty(staking_ledger)?,
+            Self::StakingLedgersDelegations(delegations) => to_string_pretty(delegations)?,
+            Self::StakingLedgersPublicKey(delegation) => to_string_pretty(delegation)?,
+            Self::StakingLedgersPayouts(payouts) => to_string_pretty(payouts)?,
+            Self::SnarksStateHash(snarks) => format_vec_jq_compatible(snarks, OutputFormat::Text)?,
+            Self::SnarksPublicKey(snarks) => format_vec_jq_compatible(snarks, OutputFormat::Text)?,
+            Self::SnarksTop(snarkers) => to_string_pretty(snarkers)?,
+            Self::Shutdown => "Shutting down Mina Indexer...".to_string(),
+            Self::Summary(SummaryData::Verbose(summary)) => summary.to_string(),
+            Self::Summary(SummaryData::Short(summary)) => summary.to_string(),
+            Self::TransactionsHash(txn) => format!("{txn:?}"),
+            Self::TransactionsCommandHash { hash } => hash.to_owned(),
+            Self::TransactionsPublicKey(txns) | Self::TransactionsStateHash(txns) => {
+                format_vec_jq_compatible(txns, OutputFormat::Text)?
+            }
+            Self::InternalCommandsPublicKey(cmds) | Self::InternalCommandsStateHash(cmds) => {
+                to_string_pretty(cmds)?
+            }
+            Self::WebhooksAdd(Webhook { public_key, url }) => {
+                format!("Added webhook {url} for {public_key}")
+            }
+            Self::WebhooksRemove(Webhook { public_key, url }) => {
+                format!("Removed webhook {url} for {public_key}")
+            }
+            Self::WebhooksList(webhooks) => to_string_pretty(webhooks)?,
+            Self::AddressBookAdd(Label {
+                public_key,
+                label,
+                category,
+                ..
+            }) => format!("Labeled {public_key} {label} ({category})"),
+            Self::AddressBookRemove { public_key } => format!("Removed label for {public_key}"),
+            Self::AddressBookLoad { path, count } => {
+                format!("Loaded {count} labels from {}", path.display())
+            }
+            Self::AddressBookList(labels) => to_string_pretty(labels)?,
+            Self::DbVersion(version) => format!("mina-indexer database v{version}"),
+            Self::Written { path } => format!("Written to {path:?}"),
+        })
+    }
+
+    /// The data rendered in the output format, machine-readable output is
+    /// the JSON value without the type tag
+    pub fn render(&self, output: OutputFormat) -> anyhow::Result<String> {
+        if output.is_machine_readable() {
+            let mut data = serde_json::to_value(self)?;
+            return Ok(serde_json::to_string(&data["value"].take())?);
+        }
+
+        self.text()
+    }
+
+    /// Type tag of the data
+    pub fn tag(&self) -> anyhow::Result<String> {
+        match serde_json::to_value(self)?["type"].take() {
+            serde_json::Value::String(tag) => Ok(tag),
+            tag => anyhow::bail!("Unexpected command data type {tag}"),
+        }
+    }
+}
+
+/// Writes the formatted items, one at a time, as [format_vec_jq_compatible]
+/// formats the collected items
+pub fn write_items<T, I, W>(items: I, output: OutputFormat, mut write: W) -> anyhow::Result<()>
+where
+    T: std::fmt::Debug + Serialize,
+    I: IntoIterator<Item = anyhow::Result<T>>,
+    W: FnMut(String) -> anyhow::Result<()>,
+{
+    let json = output.is_machine_readable();
+    let mut items = items.into_iter().peekable();
+
+    if items.peek().is_none() {
+        return write("[]".to_string());
+    }
+
+    write(if json { "[" } else { "[\n" }.to_string())?;
+    for (n, item) in items.enumerate() {
+        let item = item?;
+        let sep = match (n, json) {
+            (0, _) => "",
+            (_, true) => ",",
+            (_, false) => ",\n",
+        };
+
+        if json {
+            write(format!("{sep}{}", serde_json::to_string(&item)?))?;
+        } else {
+            let item = format!("{item:#?}")
+                .lines()
+                .map(|line| format!("    {line}"))
+                .collect::<Vec<_>>()
+                .join("\n");
+            write(format!("{sep}{item}"))?;
+        }
+    }
+
+    write(if json { "]" } else { "\n]" }.to_string())
+}
+
+/// Debug output for text, JSON otherwise
+pub fn format_vec_jq_compatible<T>(vec: &[T], output: OutputFormat) -> anyhow::Result<String>
+where
+    T: std::fmt::Debug + Serialize,
+{
+    if output.is_machine_readable() {
+        return Ok(serde_json::to_string(vec)?);
+    }
+
+    let pp = format!("{vec:#?}");
+    Ok(pp.replace(",\n]", "\n]"))
+}
+
+impl std::fmt::Debug for BlockData {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Verbose(block) => std::fmt::Debug::fmt(block, f),
+            Self::Short(block) => std::fmt::Debug::fmt(block, f),
+        }
+    }
+}
+
+impl std::fmt::Debug for TransactionData {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Verbose(txn) => std::fmt::Debug::fmt(txn, f),
+            Self::Short(txn) => std::fmt::Debug::fmt(txn, f),
+        }
+    }
+}
+
+impl std::fmt::Debug for SummaryData {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Verbose(summary) => std::fmt::Debug::fmt(summary, f),
+            Self::Short(summary) => std::fmt::Debug::fmt(summary, f),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn typed_json() -> anyhow::Result<()> {
+        let webhook = Webhook {
+            public_key: PublicKey::default(),
+            url: "http://localhost:8080/hook".to_string(),
+        };
+        let data = CommandData::WebhooksAdd(webhook.clone());
+        let json: serde_json::Value = serde_json::from_str(&serde_json::to_string(&data)?)?;
+
+        assert_eq!(json["type"], "webhooks_add");
+        assert_eq!(json["value"]["url"], webhook.url);
+        assert_eq!(data.tag()?, "webhooks_add");
+        assert_eq!(
+            data.render(OutputFormat::Json)?,
+            serde_json::to_string(&webhook)?
+        );
+
+        // text is unchanged
+        assert_eq!(
+            data.text()?,
+            format!("Added webhook {} for {}", webhook.url, webhook.public_key)
+        );
+
+        match serde_json::from_value(json)? {
+            CommandData::WebhooksAdd(received) => assert_eq!(received, webhook),
+            data => panic!("Unexpected data {data:?}"),
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn unit_data() -> anyhow::Result<()> {
+        let json = serde_json::to_string(&CommandData::Shutdown)?;
+
+        assert_eq!(json, "{\"type\":\"shutdown\"}");
+        assert!(matches!(
+            serde_json::from_str(&json)?,
+            CommandData::Shutdown
+        ));
+        Ok(())
+    }
+}
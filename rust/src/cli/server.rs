@@ -1,5 +1,5 @@
 use super::{database::DatabaseArgs, LogLevelFilter};
-use crate::constants::*;
+use crate::{chain::Network, constants::*};
 use std::{path::PathBuf, str::FromStr};
 
 #[derive(clap::Parser, Debug, Clone, Default)]
@@ -45,6 +45,39 @@ pub struct ServerArgs {
     pub pid: Option<u32>,
 }
 
+#[derive(clap::Parser, Debug, Clone)]
+#[command(author, version, about, long_about = None)]
+pub struct ReplicaArgs {
+    /// Path to the primary indexer's database directory
+    #[arg(long)]
+    pub primary_db: PathBuf,
+
+    /// Path to the secondary database directory, defaults to a temporary
+    /// directory
+    #[arg(long)]
+    pub secondary_db: Option<PathBuf>,
+
+    /// Web server hostname for REST and GraphQL
+    #[arg(long, default_value = DEFAULT_WEB_HOSTNAME)]
+    pub web_hostname: String,
+
+    /// Web server port for REST and GraphQL
+    #[arg(long, default_value_t = DEFAULT_WEB_PORT)]
+    pub web_port: u16,
+
+    /// Delay (ms) in between catching up with the primary
+    #[arg(long, default_value_t = DEFAULT_REPLICA_CATCH_UP_INTERVAL_MS)]
+    pub catch_up_interval: u64,
+
+    /// Network name, served by the Rosetta API
+    #[arg(long, default_value = Network::Mainnet)]
+    pub network: Network,
+
+    /// Max stderr log level
+    #[arg(long, default_value_t = LogLevelFilter::default())]
+    pub log_level: LogLevelFilter,
+}
+
 #[derive(serde::Serialize, serde::Deserialize)]
 pub struct ServerArgsJson {
     pub genesis_ledger: Option<String>,
@@ -54,6 +87,7 @@ pub struct ServerArgsJson {
     pub protocol_txn_version_digest: Option<String>,
     pub protocol_network_version_digest: Option<String>,
     pub blocks_dir: Option<String>,
+    pub blocks_source: Option<String>,
     pub staking_ledgers_dir: Option<String>,
     pub database_dir: String,
     pub log_level: String,
@@ -66,6 +100,11 @@ pub struct ServerArgsJson {
     pub web_port: u16,
     pub pid: Option<u32>,
     pub do_not_ingest_orphan_blocks: bool,
+    pub poseidon_params: Option<String>,
+    #[serde(default)]
+    pub verify_ledger_hashes: bool,
+    #[serde(default)]
+    pub verify_signatures: bool,
     pub fetch_new_blocks_exe: Option<String>,
     pub fetch_new_blocks_delay: Option<u64>,
     pub missing_block_recovery_exe: Option<String>,
@@ -104,6 +143,7 @@ impl From<ServerArgs> for ServerArgsJson {
             protocol_txn_version_digest: value.db.protocol_txn_version_digest,
             protocol_network_version_digest: value.db.protocol_network_version_digest,
             blocks_dir: value.db.blocks_dir.map(|d| d.display().to_string()),
+            blocks_source: value.db.blocks_source.map(|s| s.display().to_string()),
             staking_ledgers_dir: value
                 .db
                 .staking_ledgers_dir
@@ -127,6 +167,9 @@ impl From<ServerArgs> for ServerArgsJson {
             missing_block_recovery_batch: value.missing_block_recovery_batch,
             network: value.db.network.to_string(),
             do_not_ingest_orphan_blocks: value.db.do_not_ingest_orphan_blocks,
+            poseidon_params: value.db.poseidon_params.map(|d| d.display().to_string()),
+            verify_ledger_hashes: value.db.verify_ledger_hashes,
+            verify_signatures: value.db.verify_signatures,
         }
     }
 }
@@ -141,6 +184,7 @@ impl From<ServerArgsJson> for ServerArgs {
             protocol_network_version_digest: value.protocol_network_version_digest,
             constraint_system_digests: value.constraint_system_digests,
             blocks_dir: value.blocks_dir.map(Into::into),
+            blocks_source: value.blocks_source.map(Into::into),
             staking_ledgers_dir: value.staking_ledgers_dir.map(Into::into),
             database_dir: value.database_dir.into(),
             log_level: LogLevelFilter::from_str(&value.log_level).expect("log level"),
@@ -152,6 +196,9 @@ impl From<ServerArgsJson> for ServerArgs {
             config: None,
             network: (&value.network as &str).into(),
             do_not_ingest_orphan_blocks: value.do_not_ingest_orphan_blocks,
+            poseidon_params: value.poseidon_params.map(Into::into),
+            verify_ledger_hashes: value.verify_ledger_hashes,
+            verify_signatures: value.verify_signatures,
         };
         Self {
             db,
@@ -1,15 +1,26 @@
-use crate::{constants::MAINNET_GENESIS_HASH, unix_socket_server::ServerCliResponse};
+use crate::{
+    address_book::Category,
+    cli::output::{OutputEnvelope, OutputFormat},
+    constants::MAINNET_GENESIS_HASH,
+    ledger::staking::payout::PayoutScheme,
+    unix_socket_server::{read_frames, read_response, write_request, ServerCliResponse},
+};
+use anyhow::Context;
 use bincode::{config, Decode, Encode};
 use clap::{Parser, Subcommand};
 use std::{path::PathBuf, process};
-use tokio::{
-    io::{AsyncReadExt, AsyncWriteExt, BufReader},
-    net::UnixStream,
-};
+use tokio::{io::BufReader, net::UnixStream};
 
 pub const BIN_CODE_CONFIG: config::Configuration = config::standard();
 pub const BUFFER_SIZE: usize = 1024;
 
+/// Command & requested output format sent to the Unix domain socket server
+#[derive(Debug, Encode, Decode)]
+pub struct ClientRequest {
+    pub command: ClientCli,
+    pub output: OutputFormat,
+}
+
 #[derive(Parser, Debug, Encode, Decode)]
 #[command(author, version, about, long_about = None)]
 pub enum ClientCli {
@@ -71,6 +82,14 @@ pub enum ClientCli {
     #[clap(subcommand)]
     InternalCommands(InternalCommands),
 
+    /// Manage webhook notifications of public key activity
+    #[clap(subcommand)]
+    Webhooks(Webhooks),
+
+    /// Manage public key labels
+    #[clap(subcommand)]
+    AddressBook(AddressBook),
+
     /// Query a running mina indexer for database version
     DbVersion,
 }
@@ -84,6 +103,26 @@ pub enum Accounts {
         #[arg(long)]
         public_key: String,
     },
+
+    /// Query an account's balance after each best chain block which
+    /// changed it
+    BalanceHistory {
+        /// Public key of the account
+        #[arg(long)]
+        public_key: String,
+
+        /// Token of the account [default: MINA]
+        #[arg(long)]
+        token: Option<String>,
+
+        /// Lowest block height (inclusive)
+        #[arg(long)]
+        from_height: Option<u32>,
+
+        /// Highest block height (inclusive)
+        #[arg(long)]
+        to_height: Option<u32>,
+    },
 }
 
 #[derive(Subcommand, Debug, Encode, Decode)]
@@ -174,6 +213,32 @@ pub enum Blocks {
         #[arg(long, default_value_t = false)]
         verbose: bool,
     },
+
+    /// Query blocks missing from the witness tree
+    Missing {
+        /// Path to write the missing blocks [default: stdout]
+        #[arg(long)]
+        path: Option<PathBuf>,
+    },
+
+    /// Query a block producer's epoch performance vs its stake share
+    Production {
+        /// Block producer public key
+        #[arg(long)]
+        public_key: String,
+
+        /// Epoch of the blocks & staking ledger [default: current epoch]
+        #[arg(long)]
+        epoch: Option<u32>,
+
+        /// Genesis state hash
+        #[arg(long, default_value = MAINNET_GENESIS_HASH)]
+        genesis_state_hash: String,
+
+        /// Path to write the report [default: stdout]
+        #[arg(long)]
+        path: Option<PathBuf>,
+    },
 }
 
 #[derive(Subcommand, Debug, Encode, Decode)]
@@ -203,16 +268,6 @@ pub enum Chain {
     },
 }
 
-#[derive(Subcommand, Debug, Encode, Decode)]
-#[command(author, version, about, long_about = None)]
-pub enum HashTxn {
-    Txn {
-        /// V2 transaction JSON
-        #[arg(long)]
-        json: String,
-    },
-}
-
 #[derive(Subcommand, Debug, Encode, Decode)]
 #[command(author, version, about, long_about = None)]
 pub enum Ledgers {
@@ -316,6 +371,33 @@ pub enum StakingLedgers {
         #[arg(long)]
         public_key: String,
     },
+
+    /// Compute a staking pool's delegator payouts for an epoch
+    Payouts {
+        /// Pool delegate public key
+        #[arg(long)]
+        delegate: String,
+
+        /// Epoch of the pool's blocks & staking ledger
+        #[arg(long)]
+        epoch: u32,
+
+        /// Genesis state hash
+        #[arg(long, default_value = MAINNET_GENESIS_HASH)]
+        genesis_state_hash: String,
+
+        /// Pool fee percent
+        #[arg(long, default_value_t = 0.0)]
+        fee_percent: f64,
+
+        /// Payout scheme
+        #[arg(long, value_enum, default_value_t = PayoutScheme::Proportional)]
+        scheme: PayoutScheme,
+
+        /// Path to write the payouts [default: stdout]
+        #[arg(long)]
+        path: Option<PathBuf>,
+    },
 }
 
 #[derive(Subcommand, Debug, Encode, Decode)]
@@ -354,11 +436,20 @@ pub enum Snarks {
 #[derive(Subcommand, Debug, Encode, Decode)]
 #[command(author, version, about, long_about = None)]
 pub enum Transactions {
-    /// Query transactions by hash
+    /// Query transactions by hash, or compute a transaction's hash
     Hash {
         /// Hash of the transaction
+        #[arg(long, required_unless_present = "command", conflicts_with = "command")]
+        hash: Option<String>,
+
+        /// Path to a signed command to compute the hash of, in precomputed
+        /// block JSON form (v1 or v2)
         #[arg(long)]
-        hash: String,
+        command: Option<PathBuf>,
+
+        /// The command is bin_prot serialized
+        #[arg(long, default_value_t = false, requires = "command")]
+        bin_prot: bool,
 
         /// Verbose transaction output
         #[arg(long, default_value_t = false)]
@@ -438,8 +529,87 @@ pub enum InternalCommands {
     },
 }
 
+#[derive(Subcommand, Debug, Encode, Decode)]
+#[command(author, version, about, long_about = None)]
+pub enum Webhooks {
+    /// Notify the url of the public key's activity
+    Add {
+        /// Public key to watch
+        #[arg(long)]
+        public_key: String,
+
+        /// Url to POST notifications to (http:// only)
+        #[arg(long)]
+        url: String,
+    },
+
+    /// Stop notifying the url of the public key's activity
+    Remove {
+        /// Watched public key
+        #[arg(long)]
+        public_key: String,
+
+        /// Url receiving notifications
+        #[arg(long)]
+        url: String,
+    },
+
+    /// List watched public keys & their urls
+    List,
+}
+
+#[derive(Subcommand, Debug, Encode, Decode)]
+pub enum AddressBook {
+    /// Label the public key, replacing any existing label
+    Add {
+        /// Public key to label
+        #[arg(long)]
+        public_key: String,
+
+        /// Label shown in place of the public key's username
+        #[arg(long)]
+        label: String,
+
+        /// Label category
+        #[arg(long, value_enum, default_value_t = Category::Other)]
+        category: Category,
+
+        /// Label attribution
+        #[arg(long, default_value = "client")]
+        source: String,
+    },
+
+    /// Remove the public key's label
+    Remove {
+        /// Labeled public key
+        #[arg(long)]
+        public_key: String,
+    },
+
+    /// Load labels from a CSV or JSON file
+    ///
+    /// CSV header: public_key,label,category,source (category & source are
+    /// optional). JSON: array of objects with the same fields.
+    Load {
+        /// Path to the label file (.csv or .json)
+        #[arg(long)]
+        path: PathBuf,
+    },
+
+    /// List labels
+    List {
+        /// Only list the category's labels
+        #[arg(long, value_enum)]
+        category: Option<Category>,
+    },
+}
+
 impl ClientCli {
-    pub async fn run(&self, domain_socket_path: PathBuf) -> anyhow::Result<()> {
+    pub async fn run(
+        self,
+        domain_socket_path: PathBuf,
+        output: OutputFormat,
+    ) -> anyhow::Result<()> {
         let conn = UnixStream::connect(domain_socket_path)
             .await
             .unwrap_or_else(|e| {
@@ -448,24 +618,162 @@ impl ClientCli {
             });
         let (reader, mut writer) = conn.into_split();
         let mut reader = BufReader::new(reader);
-        let mut buffer = Vec::with_capacity(BUFFER_SIZE);
-        let encoded = bincode::encode_to_vec(self, BIN_CODE_CONFIG)?;
+        let command = self.name();
+        let request = ClientRequest {
+            command: self.canonicalize_paths()?,
+            output,
+        };
+
+        write_request(&mut writer, request).await?;
+
+        // text output is written as it arrives
+        if !output.is_machine_readable() {
+            let error = read_frames(&mut reader, |frame| {
+                print!("{frame}");
+                Ok(())
+            })
+            .await?;
+
+            return match error {
+                None => {
+                    println!();
+                    Ok(())
+                }
+                Some(err) => {
+                    eprintln!("{err}");
+                    process::exit(1);
+                }
+            };
+        }
 
-        writer.write_all(&encoded).await?;
-        reader.read_to_end(&mut buffer).await?;
+        // machine-readable output is enveloped once the typed data is complete
+        let response = read_response(&mut reader).await?;
+        let is_error = matches!(response, ServerCliResponse::Error(_));
+        for line in OutputEnvelope::new(&command, response)?.render(output)? {
+            println!("{line}");
+        }
 
-        let (response, _): (ServerCliResponse, usize) =
-            bincode::decode_from_slice(&buffer, BIN_CODE_CONFIG)?;
+        if is_error {
+            process::exit(1);
+        }
+        Ok(())
+    }
 
-        match response {
-            ServerCliResponse::Success(msg) => {
-                println!("{msg}");
-                Ok(())
-            }
-            ServerCliResponse::Error(err) => {
-                eprintln!("{err}");
-                process::exit(1);
+    /// Resolves paths the server reads against the client's working
+    /// directory, the server's may differ
+    fn canonicalize_paths(self) -> anyhow::Result<Self> {
+        match self {
+            Self::AddressBook(AddressBook::Load { path }) => {
+                let path = path
+                    .canonicalize()
+                    .with_context(|| format!("Label file not found: {}", path.display()))?;
+                Ok(Self::AddressBook(AddressBook::Load { path }))
             }
+            command => Ok(command),
         }
     }
+
+    /// Whether the command writes to the store, these are unavailable on
+    /// read replicas
+    pub fn is_mutating(&self) -> bool {
+        matches!(
+            self,
+            Self::CreateSnapshot { .. }
+                | Self::Webhooks(Webhooks::Add { .. } | Webhooks::Remove { .. })
+                | Self::AddressBook(
+                    AddressBook::Add { .. } | AddressBook::Remove { .. } | AddressBook::Load { .. }
+                )
+        )
+    }
+
+    /// Subcommand name, e.g. `accounts public-key`
+    pub fn name(&self) -> String {
+        let (command, subcommand) = match self {
+            Self::Accounts(cmd) => (
+                "accounts",
+                match cmd {
+                    Accounts::PublicKey { .. } => "public-key",
+                    Accounts::BalanceHistory { .. } => "balance-history",
+                },
+            ),
+            Self::Blocks(cmd) => (
+                "blocks",
+                match cmd {
+                    Blocks::StateHash { .. } => "state-hash",
+                    Blocks::Best { .. } => "best",
+                    Blocks::GlobalSlot { .. } => "global-slot",
+                    Blocks::Height { .. } => "height",
+                    Blocks::PublicKey { .. } => "public-key",
+                    Blocks::Children { .. } => "children",
+                    Blocks::Missing { .. } => "missing",
+                    Blocks::Production { .. } => "production",
+                },
+            ),
+            Self::Chain(Chain::Best { .. }) => ("chain", "best"),
+            Self::CreateSnapshot { .. } => return "create-snapshot".to_string(),
+            Self::Ledgers(cmd) => (
+                "ledgers",
+                match cmd {
+                    Ledgers::Best { .. } => "best",
+                    Ledgers::Hash { .. } => "hash",
+                    Ledgers::Height { .. } => "height",
+                },
+            ),
+            Self::StakingLedgers(cmd) => (
+                "staking-ledgers",
+                match cmd {
+                    StakingLedgers::Hash { .. } => "hash",
+                    StakingLedgers::Epoch { .. } => "epoch",
+                    StakingLedgers::Delegations { .. } => "delegations",
+                    StakingLedgers::PublicKey { .. } => "public-key",
+                    StakingLedgers::Payouts { .. } => "payouts",
+                },
+            ),
+            Self::Shutdown => return "shutdown".to_string(),
+            Self::Snarks(cmd) => (
+                "snarks",
+                match cmd {
+                    Snarks::StateHash { .. } => "state-hash",
+                    Snarks::PublicKey { .. } => "public-key",
+                    Snarks::Top { .. } => "top",
+                },
+            ),
+            Self::Summary { .. } => return "summary".to_string(),
+            Self::Transactions(cmd) => (
+                "transactions",
+                match cmd {
+                    Transactions::Hash { .. } => "hash",
+                    Transactions::PublicKey { .. } => "public-key",
+                    Transactions::StateHash { .. } => "state-hash",
+                },
+            ),
+            Self::InternalCommands(cmd) => (
+                "internal-commands",
+                match cmd {
+                    InternalCommands::StateHash { .. } => "state-hash",
+                    InternalCommands::PublicKey { .. } => "public-key",
+                },
+            ),
+            Self::Webhooks(cmd) => (
+                "webhooks",
+                match cmd {
+                    Webhooks::Add { .. } => "add",
+                    Webhooks::Remove { .. } => "remove",
+                    Webhooks::List => "list",
+                },
+            ),
+            Self::AddressBook(cmd) => (
+                "address-book",
+                match cmd {
+                    AddressBook::Add { .. } => "add",
+                    AddressBook::Remove { .. } => "remove",
+                    AddressBook::Load { .. } => "load",
+                    AddressBook::List { .. } => "list",
+                },
+            ),
+            Self::DbVersion => return "db-version".to_string(),
+        };
+
+        format!("{command} {subcommand}")
+    }
 }
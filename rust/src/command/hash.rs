@@ -0,0 +1,923 @@
+//! Canonical user command transaction hashes
+//!
+//! - v1 (pre-hardfork): Blake2b-256 digest of the base58check encoded,
+//!   version tagged bin_prot serialization of the signed command
+//! - v2 (post-hardfork): Blake2b-256 digest of the untagged bin_prot
+//!   serialization of the signed command, with a dummy signature
+//! - zkApp: Blake2b-256 digest of the bin_prot serialization of the zkApp
+//!   command, with dummy signatures & proofs. Account update digests, stack
+//!   hashes & verification key hashes are not part of the serialization.
+//!
+//! The dummy transaction proof is not available in this tree, so zkApp
+//! commands with proof authorizations are only hashed by the daemon.
+
+use super::{signed::TxnHash, UserCommandWithStatus};
+use crate::{
+    base::public_key::PublicKey,
+    mina_blocks::v2::{
+        protocol_state::SupplyAdjustmentSign,
+        staged_ledger_diff::{
+            AccountUpdateBody, Authorization, Elt, MayUseToken, NumericBoundsU32, NumericBoundsU64,
+            Precondition, Preconditions, ProofOrSignature, SignedCommandData,
+            SignedCommandPayloadBody, Update, UpdateKind, UpdatePermissions, UpdateTiming,
+            UpdateVerificationKey, UserCommand, UserCommandData, UserCommandKind, ZkappCommandData,
+        },
+        Permission, PermissionKind, Permissions,
+    },
+    proof_systems::{
+        signer::{
+            pubkey::CompressedPubKey,
+            signature::{BaseField, ScalarField},
+        },
+        FieldHelpers,
+    },
+    protocol::{
+        bin_prot::{self, ReadBinProtExt, WriteBinProtExt},
+        serialization_types::{
+            staged_ledger_diff::{self as mina_rs, UserCommand1, UserCommandJson},
+            version_bytes::{
+                EPOCH_SEED, LEDGER_HASH, RECEIPT_CHAIN_HASH, STATE_HASH, TOKEN_ID_KEY,
+                USER_COMMAND, USER_COMMAND_MEMO, V1_TXN_HASH, V2_TXN_HASH, VERIFICATION_KEY,
+            },
+        },
+    },
+};
+use anyhow::{anyhow, bail, Context, Result};
+use ark_ff::One;
+use blake2::digest::VariableOutput;
+use std::{
+    io::{Cursor, Read, Write},
+    str::FromStr,
+};
+
+/// Serialized field element length (bytes)
+const FIELD_LEN: usize = 32;
+
+/// Serialized signature length (bytes)
+const SIGNATURE_LEN: usize = 2 * FIELD_LEN;
+
+/// Payload body, `Since_genesis` global slot & `Global_slot_span` tags
+const PAYMENT_TAG: u8 = 0;
+const DELEGATION_TAG: u8 = 1;
+const SET_DELEGATE_TAG: u8 = 0;
+const SINCE_GENESIS_TAG: u8 = 0;
+const SLOT_SPAN_TAG: u8 = 0;
+
+/// zkApp update & precondition tags
+const SET_TAG: u8 = 0;
+const KEEP_TAG: u8 = 1;
+const CHECK_TAG: u8 = 0;
+const IGNORE_TAG: u8 = 1;
+
+/// Account update authorization kinds & controls, in constructor order
+const AUTHORIZATION_KINDS: [&str; 3] = ["Signature", "Proof", "None_given"];
+const CONTROLS: [&str; 3] = ["Proof", "Signature", "None_given"];
+
+/// v1 signed command hash
+pub fn hash_signed_command_v1(v1: &mina_rs::SignedCommandV1) -> Result<TxnHash> {
+    // convert versioned signed command to bin_prot bytes
+    let mut binprot_bytes = Vec::with_capacity(TxnHash::V1_LEN * 8); // max number of bits
+    bin_prot::to_writer(&mut binprot_bytes, v1)?;
+
+    // base58 encode + Blake2b hash
+    let binprot_bytes_bs58 = bs58::encode(&binprot_bytes[..])
+        .with_check_version(USER_COMMAND)
+        .into_string();
+    let mut hash = blake2b(binprot_bytes_bs58.as_bytes())?;
+
+    // add length + version bytes
+    const VERSION_BYTE: u8 = 1;
+    hash.insert(0, hash.len() as u8);
+    hash.insert(0, VERSION_BYTE);
+
+    // base58 encode txn hash
+    Ok(TxnHash::V1(
+        bs58::encode(hash)
+            .with_check_version(V1_TXN_HASH)
+            .into_string(),
+    ))
+}
+
+/// v2 signed command hash
+pub fn hash_signed_command_v2(data: &SignedCommandData) -> Result<TxnHash> {
+    hash_bin_prot_v2(&signed_command_v2_bin_prot(data)?)
+}
+
+/// v2 zkApp command hash
+pub fn hash_zkapp_command(data: &ZkappCommandData) -> Result<TxnHash> {
+    hash_bin_prot_v2(&zkapp_command_bin_prot(data)?)
+}
+
+/// v2 user command hash
+pub fn hash_user_command_v2(data: &UserCommandData) -> Result<TxnHash> {
+    match data {
+        UserCommandData::SignedCommandData(data) => hash_signed_command_v2(data),
+        UserCommandData::ZkappCommandData(data) => hash_zkapp_command(data),
+    }
+}
+
+/// bin_prot serialization of a v2 signed command, with a dummy signature
+pub fn signed_command_v2_bin_prot(data: &SignedCommandData) -> Result<Vec<u8>> {
+    let common = &data.payload.common;
+    let mut bytes = vec![];
+
+    // common
+    bytes.bin_write_integer(common.fee.0 as i64)?;
+    write_public_key(&mut bytes, &common.fee_payer_pk)?;
+    bytes.bin_write_integer(common.nonce.0 as i32)?;
+    bytes.bin_write_variant_index(SINCE_GENESIS_TAG)?;
+    bytes.bin_write_integer(common.valid_until.0 as u32 as i32)?;
+    write_memo(&mut bytes, &common.memo)?;
+
+    // body
+    match &data.payload.body.1 {
+        SignedCommandPayloadBody::Payment(payment) => {
+            bytes.bin_write_variant_index(PAYMENT_TAG)?;
+            write_public_key(&mut bytes, &payment.receiver_pk)?;
+            bytes.bin_write_integer(payment.amount.0 as i64)?;
+        }
+        SignedCommandPayloadBody::StakeDelegation((_, delegation)) => {
+            bytes.bin_write_variant_index(DELEGATION_TAG)?;
+            bytes.bin_write_variant_index(SET_DELEGATE_TAG)?;
+            write_public_key(&mut bytes, &delegation.new_delegate)?;
+        }
+    }
+
+    write_public_key(&mut bytes, &data.signer)?;
+    write_dummy_signature(&mut bytes)?;
+
+    Ok(bytes)
+}
+
+/// bin_prot serialization of a v2 zkApp command, with dummy signatures &
+/// proofs
+pub fn zkapp_command_bin_prot(data: &ZkappCommandData) -> Result<Vec<u8>> {
+    let fee_payer = &data.fee_payer.body;
+    let mut bytes = vec![];
+
+    // fee payer
+    write_public_key(&mut bytes, &fee_payer.public_key)?;
+    bytes.bin_write_integer(fee_payer.fee.0 as i64)?;
+    match &fee_payer.valid_until {
+        Some(valid_until) => {
+            bytes.bin_write_bool(true)?;
+            write_global_slot(&mut bytes, valid_until.0 as u32)?;
+        }
+        None => bytes.bin_write_bool(false)?,
+    }
+    bytes.bin_write_integer(fee_payer.nonce.0 as i32)?;
+    write_dummy_signature(&mut bytes)?;
+
+    // account updates & memo
+    write_call_forest(
+        &mut bytes,
+        data.account_updates
+            .iter()
+            .map(|update| &update.elt)
+            .collect(),
+    )?;
+    write_memo(&mut bytes, &data.memo)?;
+
+    Ok(bytes)
+}
+
+/// Hash of a user command in precomputed block JSON form, either the
+/// command with its status or only its `data`
+pub fn hash_user_command_json(bytes: &[u8]) -> Result<TxnHash> {
+    let mut json: serde_json::Value = serde_json::from_slice(bytes)?;
+    if let Some(data) = json.get_mut("data") {
+        json = data.take();
+    }
+
+    // v1 commands carry a fee token, v2 commands do not
+    if let Ok(v1) = serde_json::from_value::<UserCommandJson>(json.clone()) {
+        let UserCommand1::SignedCommand(v1) = UserCommand1::from(v1);
+        return hash_signed_command_v1(&v1);
+    }
+
+    let (_, data): (UserCommandKind, UserCommandData) =
+        serde_json::from_value(json).context("not a v1 or v2 user command")?;
+    hash_user_command_v2(&data)
+}
+
+/// Hash of a bin_prot serialized signed command, v1 (version tagged) or v2
+pub fn hash_user_command_bin_prot(bytes: &[u8]) -> Result<TxnHash> {
+    if let Ok(v1) = bin_prot::from_reader_strict::<_, mina_rs::SignedCommandV1>(bytes) {
+        return hash_signed_command_v1(&v1);
+    }
+
+    // the hashed serialization carries a dummy signature
+    let len = signed_command_v2_unsigned_len(bytes)?;
+    let mut bytes = bytes[..len].to_vec();
+    write_dummy_signature(&mut bytes)?;
+
+    hash_bin_prot_v2(&bytes)
+}
+
+impl UserCommand {
+    /// Computed hash, ignoring the block's `txn_hash`
+    pub fn compute_hash(&self) -> Result<TxnHash> {
+        hash_user_command_v2(&self.data.1)
+    }
+}
+
+impl UserCommandWithStatus {
+    /// Computed hash, ignoring the block's `txn_hash`
+    pub fn compute_hash(&self) -> Result<TxnHash> {
+        match self {
+            Self::V1(v1) => {
+                let UserCommand1::SignedCommand(ref signed_cmd) = v1.t.data.t.t;
+                hash_signed_command_v1(signed_cmd)
+            }
+            Self::V2(v2) => v2.compute_hash(),
+        }
+    }
+}
+
+fn hash_bin_prot_v2(bytes: &[u8]) -> Result<TxnHash> {
+    let mut hash = blake2b(bytes)?;
+    hash.insert(0, hash.len() as u8);
+
+    Ok(TxnHash::V2(
+        bs58::encode(hash)
+            .with_check_version(V2_TXN_HASH)
+            .into_string(),
+    ))
+}
+
+fn blake2b(bytes: &[u8]) -> Result<Vec<u8>> {
+    let mut hasher = blake2::Blake2bVar::new(32)?;
+    hasher.write_all(bytes)?;
+
+    Ok(hasher.finalize_boxed().to_vec())
+}
+
+fn write_public_key(bytes: &mut Vec<u8>, pk: &PublicKey) -> Result<()> {
+    let pk = CompressedPubKey::from_address(&pk.0).map_err(|e| anyhow!("{e}: {pk}"))?;
+
+    bytes.write_all(&pk.x.to_bytes())?;
+    bytes.bin_write_bool(pk.is_odd)?;
+    Ok(())
+}
+
+/// `(Field.one, Scalar.one)`
+fn write_dummy_signature(bytes: &mut Vec<u8>) -> Result<()> {
+    bytes.write_all(&BaseField::one().to_bytes())?;
+    bytes.write_all(&ScalarField::one().to_bytes())?;
+    Ok(())
+}
+
+fn write_memo(bytes: &mut Vec<u8>, memo: &str) -> Result<()> {
+    let memo =
+        decode_base58(memo, USER_COMMAND_MEMO).with_context(|| format!("invalid memo {memo}"))?;
+
+    bytes.bin_write_nat0(memo.len() as u64)?;
+    bytes.write_all(&memo)?;
+    Ok(())
+}
+
+/// Base58 check decoded bytes, without the version byte
+fn decode_base58(encoded: &str, version_byte: u8) -> Result<Vec<u8>> {
+    let mut bytes = bs58::decode(encoded)
+        .with_check(Some(version_byte))
+        .into_vec()
+        .with_context(|| format!("invalid base58 {encoded}"))?;
+
+    bytes.remove(0);
+    Ok(bytes)
+}
+
+/////////////////////////
+// zkApp serialization //
+/////////////////////////
+
+/// Account update digests & stack hashes are serialized as units
+fn write_call_forest(bytes: &mut Vec<u8>, forest: Vec<&Elt>) -> Result<()> {
+    bytes.bin_write_nat0(forest.len() as u64)?;
+
+    for elt in forest {
+        write_account_update_body(bytes, &elt.account_update.body)?;
+        write_control(bytes, &elt.account_update.authorization)?;
+        bytes.bin_write_unit()?;
+
+        write_call_forest(
+            bytes,
+            elt.calls.iter().map(|call| call.elt.as_ref()).collect(),
+        )?;
+        bytes.bin_write_unit()?;
+    }
+
+    Ok(())
+}
+
+fn write_account_update_body(bytes: &mut Vec<u8>, body: &AccountUpdateBody) -> Result<()> {
+    write_public_key(bytes, &body.public_key)?;
+
+    let token_id = decode_base58(&body.token_id.0, TOKEN_ID_KEY)?;
+    if token_id.len() != FIELD_LEN {
+        bail!("invalid token id {}", body.token_id.0)
+    }
+    bytes.write_all(&token_id)?;
+
+    write_update(bytes, &body.update)?;
+
+    // balance change
+    bytes.bin_write_integer(body.balance_change.magnitude.0 as i64)?;
+    bytes.bin_write_variant_index(match body.balance_change.sgn.0 {
+        SupplyAdjustmentSign::Pos => 0,
+        SupplyAdjustmentSign::Neg => 1,
+    })?;
+
+    bytes.bin_write_bool(body.increment_nonce)?;
+    write_field_lists(bytes, body.events.iter().map(|events| &events.0).collect())?;
+    write_field_lists(
+        bytes,
+        body.actions.iter().map(|actions| &actions.0).collect(),
+    )?;
+    write_field(bytes, &body.call_data)?;
+    write_preconditions(bytes, &body.preconditions)?;
+    bytes.bin_write_bool(body.use_full_commitment)?;
+    bytes.bin_write_bool(body.implicit_account_creation_fee)?;
+    bytes.bin_write_variant_index(match body.may_use_token.0 {
+        MayUseToken::No => 0,
+        MayUseToken::ParentsOwnToken => 1,
+        MayUseToken::InheritFromParent => 2,
+    })?;
+
+    // authorization kind, proofs carry the verification key hash
+    let (kind, vk_hash) = authorization_parts(&body.authorization_kind);
+    write_constructor(bytes, &AUTHORIZATION_KINDS, kind)?;
+    if kind == "Proof" {
+        let vk_hash = vk_hash.context("missing verification key hash")?;
+        let vk_hash = BaseField::from_str(vk_hash)
+            .map_err(|_| anyhow!("invalid verification key hash {vk_hash}"))?;
+        bytes.write_all(&vk_hash.to_bytes())?;
+    }
+
+    Ok(())
+}
+
+fn write_update(bytes: &mut Vec<u8>, update: &Update) -> Result<()> {
+    for app_state in &update.app_state {
+        write_set_or_keep(bytes, set_value(app_state), write_field)?;
+    }
+    bytes.bin_write_unit()?;
+
+    write_set_or_keep(bytes, set_value(&update.delegate), |bytes, pk| {
+        write_public_key(bytes, &pk.into())
+    })?;
+
+    // verification key hashes are recomputed when deserializing
+    let vk = match &update.verification_key {
+        UpdateVerificationKey::Set((_, vk)) => Some(vk),
+        UpdateVerificationKey::Keep(_) => None,
+    };
+    write_set_or_keep(bytes, vk, |bytes, vk| {
+        bytes.write_all(&decode_base58(&vk.data.0, VERIFICATION_KEY)?)?;
+        Ok(())
+    })?;
+
+    let permissions = match &update.permissions {
+        UpdatePermissions::Set((_, permissions)) => Some(permissions),
+        UpdatePermissions::Keep(_) => None,
+    };
+    write_set_or_keep(bytes, permissions, write_permissions)?;
+
+    for string in [&update.zkapp_uri, &update.token_symbol] {
+        write_set_or_keep(bytes, set_value(string), |bytes, string| {
+            bytes.bin_write_nat0(string.len() as u64)?;
+            bytes.write_all(string.as_bytes())?;
+            Ok(())
+        })?;
+    }
+
+    let timing = match &update.timing {
+        UpdateTiming::Set((_, timing)) => Some(timing),
+        UpdateTiming::Keep(_) => None,
+    };
+    write_set_or_keep(bytes, timing, |bytes, timing| {
+        bytes.bin_write_integer(timing.initial_minimum_balance.0 as i64)?;
+        write_global_slot(bytes, timing.cliff_time.0)?;
+        bytes.bin_write_integer(timing.cliff_amount.0 as i64)?;
+        bytes.bin_write_variant_index(SLOT_SPAN_TAG)?;
+        bytes.bin_write_integer(timing.vesting_period.0 as i32)?;
+        bytes.bin_write_integer(timing.vesting_increment.0 as i64)?;
+        Ok(())
+    })?;
+
+    write_set_or_keep(bytes, set_value(&update.voting_for), |bytes, state_hash| {
+        write_hash(bytes, state_hash, STATE_HASH)
+    })
+}
+
+fn write_permissions(bytes: &mut Vec<u8>, permissions: &Permissions) -> Result<()> {
+    let write_auth = |bytes: &mut Vec<u8>, auth: &Permission| {
+        bytes.bin_write_variant_index(match auth.0 {
+            PermissionKind::None => 0,
+            PermissionKind::Either => 1,
+            PermissionKind::Proof => 2,
+            PermissionKind::Signature => 3,
+            PermissionKind::Impossible => 4,
+        })
+    };
+
+    for auth in [
+        &permissions.edit_state,
+        &permissions.access,
+        &permissions.send,
+        &permissions.receive,
+        &permissions.set_delegate,
+        &permissions.set_permissions,
+    ] {
+        write_auth(bytes, auth)?;
+    }
+
+    let (auth, txn_version) = &permissions.set_verification_key;
+    write_auth(bytes, auth)?;
+    bytes.bin_write_integer(txn_version.parse::<u32>()? as i32)?;
+
+    for auth in [
+        &permissions.set_zkapp_uri,
+        &permissions.edit_action_state,
+        &permissions.set_token_symbol,
+        &permissions.increment_nonce,
+        &permissions.set_voting_for,
+        &permissions.set_timing,
+    ] {
+        write_auth(bytes, auth)?;
+    }
+
+    Ok(())
+}
+
+fn write_preconditions(bytes: &mut Vec<u8>, preconditions: &Preconditions) -> Result<()> {
+    // network
+    let network = &preconditions.network;
+    write_or_ignore(
+        bytes,
+        checked(&network.snarked_ledger_hash),
+        |bytes, hash| write_hash(bytes, &hash.0, LEDGER_HASH),
+    )?;
+    write_or_ignore(
+        bytes,
+        checked(&network.blockchain_length),
+        write_u32_interval,
+    )?;
+    write_or_ignore(
+        bytes,
+        checked(&network.min_window_density),
+        write_u32_interval,
+    )?;
+    write_or_ignore(bytes, checked(&network.total_currency), write_u64_interval)?;
+    write_or_ignore(
+        bytes,
+        checked(&network.global_slot_since_genesis),
+        write_global_slot_interval,
+    )?;
+
+    for epoch_data in [&network.staking_epoch_data, &network.next_epoch_data] {
+        write_or_ignore(bytes, checked(&epoch_data.ledger.hash), |bytes, hash| {
+            write_hash(bytes, hash, LEDGER_HASH)
+        })?;
+        write_or_ignore(
+            bytes,
+            checked(&epoch_data.ledger.total_currency),
+            write_u64_interval,
+        )?;
+        write_or_ignore(bytes, checked(&epoch_data.seed), |bytes, seed| {
+            write_hash(bytes, seed, EPOCH_SEED)
+        })?;
+
+        for checkpoint in [&epoch_data.start_checkpoint, &epoch_data.lock_checkpoint] {
+            write_or_ignore(bytes, checked(checkpoint), |bytes, state_hash| {
+                write_hash(bytes, state_hash, STATE_HASH)
+            })?;
+        }
+
+        write_or_ignore(bytes, checked(&epoch_data.epoch_length), write_u32_interval)?;
+    }
+
+    // account
+    let account = &preconditions.account;
+    write_or_ignore(bytes, checked(&account.balance), write_u64_interval)?;
+    write_or_ignore(bytes, checked(&account.nonce), write_u32_interval)?;
+    write_or_ignore(
+        bytes,
+        checked(&account.receipt_chain_hash),
+        |bytes, hash| write_hash(bytes, hash, RECEIPT_CHAIN_HASH),
+    )?;
+    write_or_ignore(bytes, checked(&account.delegate), write_public_key)?;
+
+    for state in &account.state {
+        write_or_ignore(bytes, checked(state), |bytes, state| {
+            write_field(bytes, &state.0)
+        })?;
+    }
+    bytes.bin_write_unit()?;
+
+    write_or_ignore(
+        bytes,
+        checked(&account.action_state),
+        |bytes, action_state| write_field(bytes, action_state),
+    )?;
+
+    for flag in [&account.proved_state, &account.is_new] {
+        write_or_ignore(bytes, checked(flag), |bytes, flag| {
+            bytes.bin_write_bool(*flag)?;
+            Ok(())
+        })?;
+    }
+
+    // valid while
+    write_or_ignore(
+        bytes,
+        checked(&preconditions.valid_while),
+        write_global_slot_interval,
+    )
+}
+
+/// Signatures are replaced by the dummy signature & proofs by the dummy
+/// transaction proof
+fn write_control(bytes: &mut Vec<u8>, authorization: &Authorization) -> Result<()> {
+    let (control, _) = authorization_parts(authorization);
+    write_constructor(bytes, &CONTROLS, control)?;
+
+    match control {
+        "Proof" => bail!("hashing proof authorizations requires the dummy transaction proof"),
+        "Signature" => write_dummy_signature(bytes),
+        _ => Ok(()),
+    }
+}
+
+/// Constructor name & argument of an authorization. Untagged deserialization
+/// picks the first variant of matching arity, so nullary constructors are
+/// named by their only field.
+fn authorization_parts(authorization: &Authorization) -> (&str, Option<&str>) {
+    match authorization {
+        Authorization::NoneGiven((name,))
+        | Authorization::Either((name,))
+        | Authorization::Proof((name,))
+        | Authorization::Signature((name,)) => (name.as_str(), None),
+        Authorization::Proof_((kind, arg)) | Authorization::Signature_((kind, arg)) => {
+            let name = match kind {
+                ProofOrSignature::Proof => "Proof",
+                ProofOrSignature::Signature => "Signature",
+            };
+            (name, Some(arg.as_str()))
+        }
+    }
+}
+
+fn write_constructor(bytes: &mut Vec<u8>, names: &[&str], name: &str) -> Result<()> {
+    match names.iter().position(|n| *n == name) {
+        Some(index) => {
+            bytes.bin_write_variant_index(index as u8)?;
+            Ok(())
+        }
+        None => bail!("invalid constructor {name}, expected one of {names:?}"),
+    }
+}
+
+fn set_value(update: &UpdateKind) -> Option<&str> {
+    match update {
+        UpdateKind::Set((_, value)) => Some(value.as_str()),
+        UpdateKind::Keep(_) => None,
+    }
+}
+
+fn write_set_or_keep<T: ?Sized>(
+    bytes: &mut Vec<u8>,
+    value: Option<&T>,
+    write: impl FnOnce(&mut Vec<u8>, &T) -> Result<()>,
+) -> Result<()> {
+    match value {
+        Some(value) => {
+            bytes.bin_write_variant_index(SET_TAG)?;
+            write(bytes, value)
+        }
+        None => {
+            bytes.bin_write_variant_index(KEEP_TAG)?;
+            Ok(())
+        }
+    }
+}
+
+fn checked<T>(precondition: &Precondition<T>) -> Option<&T> {
+    match precondition {
+        Precondition::Check((_, value)) => Some(value),
+        Precondition::Ignore(_) => None,
+    }
+}
+
+fn write_or_ignore<T>(
+    bytes: &mut Vec<u8>,
+    value: Option<&T>,
+    write: impl FnOnce(&mut Vec<u8>, &T) -> Result<()>,
+) -> Result<()> {
+    match value {
+        Some(value) => {
+            bytes.bin_write_variant_index(CHECK_TAG)?;
+            write(bytes, value)
+        }
+        None => {
+            bytes.bin_write_variant_index(IGNORE_TAG)?;
+            Ok(())
+        }
+    }
+}
+
+fn write_u32_interval(bytes: &mut Vec<u8>, bounds: &NumericBoundsU32) -> Result<()> {
+    bytes.bin_write_integer(bounds.lower.0 as i32)?;
+    bytes.bin_write_integer(bounds.upper.0 as i32)?;
+    Ok(())
+}
+
+fn write_u64_interval(bytes: &mut Vec<u8>, bounds: &NumericBoundsU64) -> Result<()> {
+    bytes.bin_write_integer(bounds.lower.0 as i64)?;
+    bytes.bin_write_integer(bounds.upper.0 as i64)?;
+    Ok(())
+}
+
+fn write_global_slot_interval(bytes: &mut Vec<u8>, bounds: &NumericBoundsU32) -> Result<()> {
+    write_global_slot(bytes, bounds.lower.0)?;
+    write_global_slot(bytes, bounds.upper.0)
+}
+
+fn write_global_slot(bytes: &mut Vec<u8>, slot: u32) -> Result<()> {
+    bytes.bin_write_variant_index(SINCE_GENESIS_TAG)?;
+    bytes.bin_write_integer(slot as i32)?;
+    Ok(())
+}
+
+fn write_field_lists(bytes: &mut Vec<u8>, lists: Vec<&Vec<String>>) -> Result<()> {
+    bytes.bin_write_nat0(lists.len() as u64)?;
+
+    for list in lists {
+        bytes.bin_write_nat0(list.len() as u64)?;
+        for field in list {
+            write_field(bytes, field)?;
+        }
+    }
+
+    Ok(())
+}
+
+/// Field elements are rendered big-endian & serialized little-endian
+fn write_field(bytes: &mut Vec<u8>, field: &str) -> Result<()> {
+    let mut le_bytes = hex::decode(field.trim_start_matches("0x"))
+        .with_context(|| format!("invalid field element {field}"))?;
+    if le_bytes.len() != FIELD_LEN {
+        bail!("invalid field element {field}")
+    }
+
+    le_bytes.reverse();
+    bytes.write_all(&le_bytes)?;
+    Ok(())
+}
+
+/// Base58 hashes carry a version tag before the field element
+fn write_hash(bytes: &mut Vec<u8>, hash: &str, version_byte: u8) -> Result<()> {
+    let decoded = decode_base58(hash, version_byte)?;
+    if decoded.len() != FIELD_LEN + 1 {
+        bail!("invalid hash {hash}")
+    }
+
+    bytes.write_all(&decoded[1..])?;
+    Ok(())
+}
+
+/// Length of a v2 signed command serialization without its signature
+fn signed_command_v2_unsigned_len(bytes: &[u8]) -> Result<usize> {
+    let mut rdr = Cursor::new(bytes);
+
+    // common
+    rdr.bin_read_integer::<i64>()?;
+    read_public_key(&mut rdr)?;
+    rdr.bin_read_integer::<i32>()?;
+    expect_tag(&mut rdr, "global slot", &[SINCE_GENESIS_TAG])?;
+    rdr.bin_read_integer::<i32>()?;
+    rdr.bin_read_bytes()?;
+
+    // body
+    match expect_tag(&mut rdr, "payload body", &[PAYMENT_TAG, DELEGATION_TAG])? {
+        PAYMENT_TAG => {
+            read_public_key(&mut rdr)?;
+            rdr.bin_read_integer::<i64>()?;
+        }
+        _ => {
+            expect_tag(&mut rdr, "delegation", &[SET_DELEGATE_TAG])?;
+            read_public_key(&mut rdr)?;
+        }
+    }
+
+    read_public_key(&mut rdr)?;
+
+    let len = rdr.position() as usize;
+    if bytes.len() != len + SIGNATURE_LEN {
+        bail!(
+            "expected a {SIGNATURE_LEN} byte signature, found {} bytes",
+            bytes.len() - len
+        )
+    }
+
+    Ok(len)
+}
+
+fn read_public_key<R: Read>(rdr: &mut R) -> Result<CompressedPubKey> {
+    let mut x = [0; FIELD_LEN];
+    rdr.read_exact(&mut x)?;
+
+    Ok(CompressedPubKey {
+        x: BaseField::from_bytes(&x).map_err(|e| anyhow!("invalid public key: {e}"))?,
+        is_odd: rdr.bin_read_bool()?,
+    })
+}
+
+fn expect_tag<R: Read>(rdr: &mut R, name: &str, tags: &[u8]) -> Result<u8> {
+    let tag = rdr.bin_read_variant_index()?;
+    if !tags.contains(&tag) {
+        bail!("invalid {name} tag {tag}")
+    }
+
+    Ok(tag)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        base::blockchain_length::BlockchainLength,
+        block::{
+            extract_block_height,
+            precomputed::{PcbVersion, PrecomputedBlock},
+        },
+        command::UserCommandWithStatusT,
+    };
+    use glob::glob;
+    use std::path::PathBuf;
+
+    /// Command of mainnet block 359606 without its hash
+    const V2_SIGNED_COMMAND: &str = r#"{
+      "data": [
+        "Signed_command",
+        {
+          "payload": {
+            "common": {
+              "fee": "0.0011",
+              "fee_payer_pk": "B62qpjxUpgdjzwQfd8q2gzxi99wN7SCgmofpvw27MBkfNHfHoY2VH32",
+              "nonce": "765",
+              "valid_until": "4294967295",
+              "memo": "E4YM2vTHhWEg66xpj52JErHUBU4pZ1yageL4TVDDpTTSsv8mK6YaH"
+            },
+            "body": [
+              "Payment",
+              {
+                "receiver_pk": "B62qpjxUpgdjzwQfd8q2gzxi99wN7SCgmofpvw27MBkfNHfHoY2VH32",
+                "amount": "1000000000"
+              }
+            ]
+          },
+          "signer": "B62qpjxUpgdjzwQfd8q2gzxi99wN7SCgmofpvw27MBkfNHfHoY2VH32",
+          "signature": "7mX5FyaaoRY5a3hKP3kqhm6A4gWo9NtoHMh7irbB3Dt326wm8gyfsEQeHKJgYqQeo7nBgFGNjCD9eC265VrECYZJqYsD5V5R"
+        }
+      ],
+      "status": ["Applied"]
+    }"#;
+
+    /// Mainnet v1 command hashes, as listed on Minascan
+    const V1_TXN_HASHES: [(&str, [&str; 2]); 2] = [
+        (
+            "./tests/data/sequential_blocks/mainnet-105489-3NK4huLvUDiL4XuCUcyrWCKynmvhqfKsx5h2MfBXVVUq2Qwzi5uT.json",
+            [
+                "CkpZZsSm9hQpGkGzMi8rcsQEWPZwGJXktiqGYADNwLoBeeamhzqnX",
+                "CkpZDcqGWQVpckXjcg99hh4EzmCrnPzMM8VzHaLAYxPU5tMubuLaj",
+            ],
+        ),
+        (
+            "./tests/data/misc_blocks/mainnet-2704-3NLgCqncc6Ct4dcuhaG3ANQbfWwQCxMXu4MJjwGgRKxs6p8vQsZf.json",
+            [
+                "CkpYgPbLYw83tNm1wnmfRZUssbKnoVixPFaB3hGVLpuZm9UeHtBFw",
+                "CkpYyMV4jDtgKfbz6hCUVB6J8jYfJd85A7mvtVw7ydKLuoCK5GS25",
+            ],
+        ),
+    ];
+
+    fn has_proof_authorization(forest: Vec<&Elt>) -> bool {
+        forest.into_iter().any(|elt| {
+            authorization_parts(&elt.account_update.authorization).0 == "Proof"
+                || has_proof_authorization(elt.calls.iter().map(|call| call.elt.as_ref()).collect())
+        })
+    }
+
+    #[test]
+    fn hash_precomputed_block_commands() -> Result<()> {
+        // v1 blocks do not record hashes
+        for (path, expect) in V1_TXN_HASHES {
+            let block = PrecomputedBlock::parse_file(&PathBuf::from(path), PcbVersion::V1)?;
+            let hashes = block
+                .commands()
+                .iter()
+                .map(UserCommandWithStatus::compute_hash)
+                .collect::<Result<Vec<_>>>()?;
+
+            assert_eq!(hashes, expect.map(|hash| TxnHash::V1(hash.to_string())));
+        }
+
+        // v2 blocks record the daemon's hashes
+        let mut zkapp_commands = 0;
+        for pattern in [
+            "./tests/data/hardfork/*.json",
+            "./tests/data/misc_blocks/*.json",
+        ] {
+            for path in glob(pattern)?.flatten() {
+                let height = BlockchainLength(extract_block_height(&path));
+                if PcbVersion::from(height) != PcbVersion::V2 {
+                    continue;
+                }
+
+                let block = PrecomputedBlock::parse_file(&path, PcbVersion::V2)?;
+
+                for command in block.commands() {
+                    let UserCommandWithStatus::V2(v2) = &command else {
+                        continue;
+                    };
+                    let Some(txn_hash) = &v2.txn_hash else {
+                        continue;
+                    };
+
+                    if let UserCommandData::ZkappCommandData(data) = &v2.data.1 {
+                        let forest = data.account_updates.iter().map(|update| &update.elt);
+                        if has_proof_authorization(forest.collect()) {
+                            assert!(command.compute_hash().is_err());
+                            continue;
+                        }
+
+                        zkapp_commands += 1;
+                    }
+
+                    assert_eq!(&command.compute_hash()?, txn_hash, "{path:?}");
+                }
+            }
+        }
+
+        assert!(zkapp_commands > 0);
+        Ok(())
+    }
+
+    #[test]
+    fn hash_v2_json_and_bin_prot() -> Result<()> {
+        let expect = PrecomputedBlock::parse_file(
+            &PathBuf::from("./tests/data/hardfork/mainnet-359606-3NKvvtFwjEtQLswWJzXBSxxiKuYVbLJrKXCnmhp6jctYMqAWcftg.json"),
+            PcbVersion::V2,
+        )?
+        .command_hashes();
+
+        // full command & data only
+        let json: serde_json::Value = serde_json::from_str(V2_SIGNED_COMMAND)?;
+        assert_eq!(
+            vec![hash_user_command_json(V2_SIGNED_COMMAND.as_bytes())?],
+            expect
+        );
+        assert_eq!(
+            vec![hash_user_command_json(&serde_json::to_vec(&json["data"])?)?],
+            expect
+        );
+
+        // the signature is replaced by the dummy signature
+        let (_, data): (UserCommandKind, UserCommandData) =
+            serde_json::from_value(json["data"].to_owned())?;
+        let UserCommandData::SignedCommandData(data) = data else {
+            panic!("signed command")
+        };
+
+        let mut bytes = signed_command_v2_bin_prot(&data)?;
+        let len = bytes.len();
+        bytes[len - SIGNATURE_LEN..].copy_from_slice(&[0xab; SIGNATURE_LEN]);
+
+        assert_eq!(vec![hash_user_command_bin_prot(&bytes)?], expect);
+        assert!(hash_user_command_bin_prot(&bytes[..len - 1]).is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn hash_v1_bin_prot() -> Result<()> {
+        let block = PrecomputedBlock::parse_file(
+            &PathBuf::from("./tests/data/sequential_blocks/mainnet-105489-3NK4huLvUDiL4XuCUcyrWCKynmvhqfKsx5h2MfBXVVUq2Qwzi5uT.json"),
+            PcbVersion::V1,
+        )?;
+
+        for command in block.commands() {
+            let UserCommandWithStatus::V1(v1) = &command else {
+                panic!("v1 command")
+            };
+            let UserCommand1::SignedCommand(ref signed_cmd) = v1.t.data.t.t;
+
+            let mut bytes = vec![];
+            bin_prot::to_writer(&mut bytes, signed_cmd)?;
+
+            assert_eq!(hash_user_command_bin_prot(&bytes)?, command.hash()?);
+        }
+
+        Ok(())
+    }
+}
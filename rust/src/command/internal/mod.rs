@@ -234,6 +234,13 @@ impl DbInternalCommandWithData {
         }
     }
 
+    pub fn state_hash(&self) -> StateHash {
+        match self {
+            DbInternalCommandWithData::Coinbase { state_hash, .. }
+            | DbInternalCommandWithData::FeeTransfer { state_hash, .. } => state_hash.clone(),
+        }
+    }
+
     pub fn kind(&self) -> u8 {
         match self {
             DbInternalCommandWithData::FeeTransfer { .. } => 0,
@@ -1,3 +1,4 @@
+pub mod hash;
 pub mod internal;
 pub mod signed;
 pub mod store;
@@ -15,24 +16,17 @@ use crate::{
             UserCommandData, ZkappCommandData,
         },
     },
-    protocol::{
-        bin_prot,
-        serialization_types::{
-            staged_ledger_diff::{
-                self as mina_rs, TransactionStatus1, TransactionStatusFailedType, UserCommand1,
-            },
-            version_bytes::{USER_COMMAND, V1_TXN_HASH, V2_TXN_HASH},
-        },
+    protocol::serialization_types::staged_ledger_diff::{
+        self as mina_rs, TransactionStatus1, TransactionStatusFailedType, UserCommand1,
     },
     utility::functions::nanomina_to_mina,
 };
 use anyhow::Result;
-use blake2::digest::VariableOutput;
 use log::trace;
 use mina_serialization_versioned::Versioned;
 use serde::{Deserialize, Serialize};
 use signed::{SignedCommandWithCreationData, SignedCommandWithKind};
-use std::{collections::BTreeSet, io::Write};
+use std::collections::BTreeSet;
 
 // re-export types
 pub type TxnHash = signed::TxnHash;
@@ -580,60 +574,19 @@ impl UserCommandWithStatusT for UserCommandWithStatus {
         match self {
             Self::V1(v1) => {
                 let UserCommand1::SignedCommand(ref signed_cmd) = v1.t.data.t.t;
-                hash_command_v1(signed_cmd)
+                hash::hash_signed_command_v1(signed_cmd)
             }
             Self::V2(v2) => {
                 if let Some(txn_hash) = v2.txn_hash.to_owned() {
                     return Ok(txn_hash);
                 }
 
-                hash_command_v2(&v2.data.1)
+                hash::hash_user_command_v2(&v2.data.1)
             }
         }
     }
 }
 
-fn hash_command_v1(v1: &mina_rs::SignedCommandV1) -> Result<TxnHash> {
-    // convert versioned signed command to bin_prot bytes
-    let mut binprot_bytes = Vec::with_capacity(TxnHash::V1_LEN * 8); // max number of bits
-    bin_prot::to_writer(&mut binprot_bytes, v1)?;
-
-    // base58 encode + Blake2b hash
-    let binprot_bytes_bs58 = bs58::encode(&binprot_bytes[..])
-        .with_check_version(USER_COMMAND)
-        .into_string();
-    let mut hasher = blake2::Blake2bVar::new(32)?;
-    hasher.write_all(binprot_bytes_bs58.as_bytes())?;
-
-    // add length + version bytes
-    let mut hash = hasher.finalize_boxed().to_vec();
-    const VERSION_BYTE: u8 = 1;
-    hash.insert(0, hash.len() as u8);
-    hash.insert(0, VERSION_BYTE);
-
-    // base58 encode txn hash
-    Ok(TxnHash::V1(
-        bs58::encode(hash)
-            .with_check_version(V1_TXN_HASH)
-            .into_string(),
-    ))
-}
-
-fn hash_command_v2(v2: &UserCommandData) -> Result<TxnHash> {
-    let bytes = serde_json::to_vec(v2)?;
-    let mut hasher = blake2::Blake2bVar::new(32)?;
-    hasher.write_all(&bytes[..])?;
-
-    let mut hash = hasher.finalize_boxed().to_vec();
-    hash.insert(0, hash.len() as u8);
-
-    Ok(TxnHash::V2(
-        bs58::encode(hash)
-            .with_check_version(V2_TXN_HASH)
-            .into_string(),
-    ))
-}
-
 pub const MEMO_LEN: usize = 32;
 
 /// Decode memo
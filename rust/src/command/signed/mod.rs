@@ -1,4 +1,6 @@
+pub mod store;
 mod txn_hash;
+pub mod verify;
 
 use crate::{
     command::*,
@@ -269,6 +271,10 @@ impl SignedCommand {
         }
     }
 
+    pub fn is_zkapp_command(&self) -> bool {
+        matches!(self, Self::V2(UserCommandData::ZkappCommandData(_)))
+    }
+
     pub fn is_delegation(&self) -> bool {
         matches!(self.kind(), CommandType::Delegation)
     }
@@ -0,0 +1,24 @@
+//! Invalid signature store trait
+
+use super::{verify::InvalidSignature, TxnHash};
+use crate::{base::state_hash::StateHash, store::Result};
+use speedb::WriteBatch;
+
+pub trait InvalidSignatureStore {
+    /// Record the user command's invalid signature
+    fn set_invalid_signature_batch(
+        &self,
+        invalid: &InvalidSignature,
+        batch: &mut WriteBatch,
+    ) -> Result<()>;
+
+    /// Get the reason the user command's signature in the block is invalid
+    fn get_invalid_signature(
+        &self,
+        txn_hash: &TxnHash,
+        state_hash: &StateHash,
+    ) -> Result<Option<InvalidSignature>>;
+
+    /// Get all invalid signatures, sorted by txn hash & state hash
+    fn get_invalid_signatures(&self) -> Result<Vec<InvalidSignature>>;
+}
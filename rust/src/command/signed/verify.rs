@@ -0,0 +1,618 @@
+//! User command signature verification
+//!
+//! Payments & delegations are signed over the legacy random oracle input of
+//! their payload, pre- & post-hardfork. Post-hardfork payloads no longer carry
+//! tokens or a separate source, the default token & fee payer take their
+//! place.
+//!
+//! zkApp fee payers sign the full transaction commitment over kimchi Poseidon:
+//! the hash of the memo, the fee payer's account update & the account update
+//! forest. The forest is hashed from the account update digests recorded in
+//! the block.
+
+use super::{SignedCommand, TxnHash};
+use crate::{
+    base::{public_key::PublicKey, state_hash::StateHash},
+    constants::{MINA_TOKEN_ID, ZKAPP_STATE_FIELD_ELEMENTS_NUM},
+    mina_blocks::v2::staged_ledger_diff::{Elt, FeePayerBody, UserCommandData, ZkappCommandData},
+    proof_systems::{
+        poseidon::{salt, PoseidonKind, Sponge, SpongeParams},
+        signer::{
+            pubkey::PubKey,
+            roinput::{ChunkedROInput, ROInput},
+            schnorr::{self, MAINNET_SIGNATURE_PREFIX, TESTNET_SIGNATURE_PREFIX},
+            signature::{BaseField, Signature},
+        },
+        FieldHelpers,
+    },
+    protocol::serialization_types::{
+        signatures::{SignatureJson, SignatureV1},
+        version_bytes,
+    },
+};
+use anyhow::{bail, Context};
+use ark_ff::{Field, One, Zero};
+use serde::{Deserialize, Serialize};
+use std::{path::Path, str::FromStr};
+
+/// Memo length (bytes)
+const MEMO_LEN: usize = 34;
+
+/// Legacy default token id
+const DEFAULT_TOKEN_ID: u64 = 1;
+
+/// Payment & delegation tags
+const PAYMENT_TAG: [bool; 3] = [false, false, false];
+const DELEGATION_TAG: [bool; 3] = [false, false, true];
+
+/// Kimchi hash prefixes
+const ZKAPP_MEMO_PREFIX: &str = "MinaZkappMemo*******";
+const ZKAPP_URI_PREFIX: &str = "MinaZkappUri********";
+const ZKAPP_EVENTS_EMPTY_PREFIX: &str = "MinaZkappEventsEmpty";
+const ZKAPP_ACTIONS_EMPTY_PREFIX: &str = "MinaZkappActionsEmpty";
+const ZKAPP_ACTION_STATE_EMPTY_PREFIX: &str = "MinaZkappActionStateEmptyElt";
+const ACCOUNT_UPDATE_CONS_PREFIX: &str = "MinaAcctUpdateCons**";
+const ACCOUNT_UPDATE_NODE_PREFIX: &str = "MinaAcctUpdateNode**";
+const MAINNET_ZKAPP_BODY_PREFIX: &str = "MainnetZkappBody****";
+const TESTNET_ZKAPP_BODY_PREFIX: &str = "TestnetZkappBody****";
+
+/// Verification key hash of signature authorized account updates
+const DUMMY_VK_HASH: &str =
+    "3392518251768960475377392625298437850623664973002200885669375116181514017494";
+
+/// Transaction version of the empty permissions
+const TXN_VERSION: u32 = 3;
+
+/// Authorization required by each of the empty permissions, encoded as
+/// constant, signature necessary & signature sufficient
+const AUTH_NONE: [bool; 3] = [true, false, true];
+
+/// Number of permissions
+const NUM_PERMISSIONS: usize = 13;
+
+/// Index of the permission followed by a transaction version
+const SET_VERIFICATION_KEY_PERMISSION: usize = 6;
+
+/// Bit length of the token symbol
+const TOKEN_SYMBOL_BITS: u32 = 48;
+
+#[derive(Debug, Clone)]
+pub struct SignatureVerifier {
+    legacy: SpongeParams,
+    kimchi: SpongeParams,
+    prefix: &'static str,
+    zkapp_body_prefix: &'static str,
+}
+
+/// User command whose signature failed verification
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct InvalidSignature {
+    pub txn_hash: TxnHash,
+    pub state_hash: StateHash,
+    pub reason: String,
+}
+
+impl SignatureVerifier {
+    /// Uses the Poseidon parameter tables in `params_dir`, if given, else the
+    /// compiled-in ones, which fail until they're vendored
+    pub fn new(params_dir: Option<&Path>, mainnet: bool) -> anyhow::Result<Self> {
+        Ok(Self {
+            legacy: SpongeParams::resolve(params_dir, PoseidonKind::Legacy)?,
+            kimchi: SpongeParams::resolve(params_dir, PoseidonKind::Kimchi)?,
+            prefix: if mainnet {
+                MAINNET_SIGNATURE_PREFIX
+            } else {
+                TESTNET_SIGNATURE_PREFIX
+            },
+            zkapp_body_prefix: if mainnet {
+                MAINNET_ZKAPP_BODY_PREFIX
+            } else {
+                TESTNET_ZKAPP_BODY_PREFIX
+            },
+        })
+    }
+
+    /// Reason the command's signature is invalid, if it is.
+    /// Only the fee payer's signature of zkApp commands is verified.
+    pub fn invalid_reason(&self, command: &SignedCommand) -> Option<String> {
+        match self.verify(command) {
+            Ok(true) => None,
+            Ok(false) => Some("signature does not match the payload & signer".to_string()),
+            Err(e) => Some(e.to_string()),
+        }
+    }
+
+    fn verify(&self, command: &SignedCommand) -> anyhow::Result<bool> {
+        let signer = pub_key(&command.signer())?;
+        let signature = command.signature()?;
+
+        match command {
+            SignedCommand::V2(UserCommandData::ZkappCommandData(data)) => {
+                let input = ChunkedROInput::new().append_field(self.full_commitment(data)?);
+                schnorr::verify(&self.kimchi, self.prefix, &signer, &signature, input)
+            }
+            _ => {
+                let input = command.to_roinput_legacy()?;
+                schnorr::verify(&self.legacy, self.prefix, &signer, &signature, input)
+            }
+        }
+    }
+
+    /// Commitment to the memo, fee payer & account updates, signed by the fee
+    /// payer
+    pub fn full_commitment(&self, data: &ZkappCommandData) -> anyhow::Result<BaseField> {
+        let commitment = self.forest_hash(
+            data.account_updates
+                .iter()
+                .map(|update| &update.elt)
+                .collect(),
+        )?;
+        let memo_hash = self.memo_hash(&decode_memo(&data.memo)?)?;
+        let fee_payer_hash = self.hash(
+            self.zkapp_body_prefix,
+            &self.fee_payer_input(&data.fee_payer.body)?.to_fields(),
+        )?;
+
+        self.hash(
+            ACCOUNT_UPDATE_CONS_PREFIX,
+            &[memo_hash, fee_payer_hash, commitment],
+        )
+    }
+
+    /// Hash of an account update forest, from its account update digests
+    fn forest_hash(&self, forest: Vec<&Elt>) -> anyhow::Result<BaseField> {
+        forest
+            .into_iter()
+            .rev()
+            .try_fold(BaseField::zero(), |stack_hash, elt| {
+                // bin_prot blocks do not carry digests
+                if elt.account_update_digest.is_empty() {
+                    bail!("missing account update digest")
+                }
+
+                let digest = field_from_hex(&elt.account_update_digest)?;
+                let calls =
+                    self.forest_hash(elt.calls.iter().map(|call| call.elt.as_ref()).collect())?;
+                let tree_hash = self.hash(ACCOUNT_UPDATE_NODE_PREFIX, &[digest, calls])?;
+
+                self.hash(ACCOUNT_UPDATE_CONS_PREFIX, &[tree_hash, stack_hash])
+            })
+    }
+
+    /// Memo bits, least significant first
+    fn memo_hash(&self, memo: &[u8]) -> anyhow::Result<BaseField> {
+        let input = memo
+            .iter()
+            .flat_map(|byte| (0..8).map(move |i| (byte >> i) & 1 == 1))
+            .fold(ChunkedROInput::new(), ChunkedROInput::append_bool);
+
+        self.hash(ZKAPP_MEMO_PREFIX, &input.to_fields())
+    }
+
+    /// Input of the fee payer's account update body, which pays the fee,
+    /// increments the nonce & checks the nonce & valid until slot
+    fn fee_payer_input(&self, body: &FeePayerBody) -> anyhow::Result<ChunkedROInput> {
+        let public_key = pub_key(&body.public_key)?.into_compressed();
+        let valid_until = body.valid_until.map_or(u32::MAX as u64, |slot| slot.0);
+
+        let input = ChunkedROInput::new()
+            .append_field(public_key.x)
+            .append_bool(public_key.is_odd)
+            .append(self.noop_update_input()?)
+            .append_field(BaseField::from(MINA_TOKEN_ID))
+            // balance change: negative fee
+            .append_u64(body.fee.0)
+            .append_bool(false)
+            // increment nonce
+            .append_bool(true)
+            .append_field(self.empty_hash(ZKAPP_EVENTS_EMPTY_PREFIX)?)
+            .append_field(self.empty_hash(ZKAPP_ACTIONS_EMPTY_PREFIX)?)
+            // call data
+            .append_field(BaseField::zero());
+
+        // network precondition: blockchain length, min window density, total
+        // currency & global slot since genesis
+        let mut input = ignore_range(ignore_field(input), 32);
+        input = ignore_range(input, 32);
+        input = ignore_range(input, 64);
+        input = check_range(input, 0, valid_until, 32);
+        for _epoch_data in 0..2 {
+            input = ignore_range(ignore_field(input), 64);
+            input = ignore_field(ignore_field(ignore_field(input)));
+            input = ignore_range(input, 32);
+        }
+
+        // account precondition: balance, nonce, receipt chain hash & delegate
+        input = ignore_range(input, 64);
+        input = check_range(input, body.nonce.0 as u64, body.nonce.0 as u64, 32);
+        input = ignore_field(input)
+            .append_bool(false)
+            .append_field(BaseField::zero())
+            .append_bool(false);
+        for _ in 0..ZKAPP_STATE_FIELD_ELEMENTS_NUM {
+            input = ignore_field(input);
+        }
+        input = input
+            .append_bool(false)
+            .append_field(self.empty_hash(ZKAPP_ACTION_STATE_EMPTY_PREFIX)?)
+            // proved state & is new
+            .append_bool(false)
+            .append_bool(false)
+            .append_bool(false)
+            .append_bool(false);
+
+        // valid while precondition
+        input = ignore_range(input, 32);
+
+        Ok(input
+            // use full commitment & implicit account creation fee
+            .append_bool(true)
+            .append_bool(true)
+            // may not use the token
+            .append_bool(false)
+            .append_bool(false)
+            // signature authorization
+            .append_bool(true)
+            .append_bool(false)
+            .append_field(BaseField::from_str(DUMMY_VK_HASH).expect("dummy vk hash")))
+    }
+
+    /// Input of an update which keeps every field
+    fn noop_update_input(&self) -> anyhow::Result<ChunkedROInput> {
+        let mut input = ChunkedROInput::new();
+
+        // app state
+        for _ in 0..ZKAPP_STATE_FIELD_ELEMENTS_NUM {
+            input = ignore_field(input);
+        }
+
+        // delegate & verification key
+        input = ignore_field(
+            input
+                .append_bool(false)
+                .append_field(BaseField::zero())
+                .append_bool(false),
+        );
+
+        // empty permissions
+        input = input.append_bool(false);
+        for permission in 0..NUM_PERMISSIONS {
+            for bit in AUTH_NONE {
+                input = input.append_bool(bit);
+            }
+
+            if permission == SET_VERIFICATION_KEY_PERMISSION {
+                input = input.append_u32(TXN_VERSION);
+            }
+        }
+
+        Ok(input
+            // zkApp uri
+            .append_bool(false)
+            .append_field(self.hash(ZKAPP_URI_PREFIX, &[])?)
+            // token symbol
+            .append_bool(false)
+            .append_packed(BaseField::zero(), TOKEN_SYMBOL_BITS)
+            // timing
+            .append_bool(false)
+            .append_u64(0)
+            .append_u32(0)
+            .append_u64(0)
+            .append_u32(0)
+            .append_u64(0)
+            // voting for
+            .append_bool(false)
+            .append_field(BaseField::zero()))
+    }
+
+    /// Hash of no input, the salted state's first element
+    fn empty_hash(&self, prefix: &str) -> anyhow::Result<BaseField> {
+        Ok(salt(&self.kimchi, prefix)?[0])
+    }
+
+    fn hash(&self, prefix: &str, input: &[BaseField]) -> anyhow::Result<BaseField> {
+        let init = salt(&self.kimchi, prefix)?;
+        Ok(Sponge::with_state(&self.kimchi, init).hash(input))
+    }
+}
+
+/// Ignored precondition on a field element
+fn ignore_field(input: ChunkedROInput) -> ChunkedROInput {
+    input.append_bool(false).append_field(BaseField::zero())
+}
+
+/// Ignored precondition on a `bits` bit integer, covering its whole range
+fn ignore_range(input: ChunkedROInput, bits: u32) -> ChunkedROInput {
+    let max = BaseField::from(2u64).pow([bits as u64]) - BaseField::one();
+    input
+        .append_bool(false)
+        .append_packed(BaseField::zero(), bits)
+        .append_packed(max, bits)
+}
+
+/// Precondition on a `bits` bit integer in `lower..=upper`
+fn check_range(input: ChunkedROInput, lower: u64, upper: u64, bits: u32) -> ChunkedROInput {
+    input
+        .append_bool(true)
+        .append_packed(BaseField::from(lower), bits)
+        .append_packed(BaseField::from(upper), bits)
+}
+
+impl SignedCommand {
+    /// Signature of a payment or delegation, or of a zkApp command's fee
+    /// payer
+    pub fn signature(&self) -> anyhow::Result<Signature> {
+        match self {
+            Self::V1(v1) => Ok(v1.t.t.signature.to_owned().into()),
+            Self::V2(UserCommandData::SignedCommandData(data)) => parse_signature(&data.signature),
+            Self::V2(UserCommandData::ZkappCommandData(data)) => {
+                match data.fee_payer.authorization.as_deref() {
+                    Some(signature) => parse_signature(signature),
+                    None => bail!("missing fee payer signature"),
+                }
+            }
+        }
+    }
+
+    /// Raw memo bytes
+    pub fn memo_bytes(&self) -> anyhow::Result<Vec<u8>> {
+        match self {
+            Self::V1(v1) => {
+                let bytes = &v1.t.t.payload.t.t.common.t.t.t.memo.t.0;
+                if bytes.len() < MEMO_LEN {
+                    bail!("memo too short: {} bytes", bytes.len())
+                }
+
+                Ok(bytes[..MEMO_LEN].to_vec())
+            }
+            Self::V2(UserCommandData::SignedCommandData(data)) => {
+                decode_memo(&data.payload.common.memo)
+            }
+            Self::V2(UserCommandData::ZkappCommandData(data)) => decode_memo(&data.memo),
+        }
+    }
+
+    /// Legacy random oracle input of the payment or delegation payload
+    pub fn to_roinput_legacy(&self) -> anyhow::Result<ROInput> {
+        let fee_payer = pub_key(&self.fee_payer_pk())?.into_compressed();
+        let source = pub_key(&self.source_pk())?.into_compressed();
+        let receiver = match self.receiver_pk().first() {
+            Some(pk) => pub_key(pk)?.into_compressed(),
+            None => bail!("missing receiver"),
+        };
+
+        let fee_token = self.fee_token().map_or(DEFAULT_TOKEN_ID, |token| token.0);
+        let tag = if self.is_delegation() {
+            DELEGATION_TAG
+        } else {
+            PAYMENT_TAG
+        };
+
+        let mut input = ROInput::new()
+            .append_field(fee_payer.x)
+            .append_field(source.x)
+            .append_field(receiver.x)
+            .append_u64(self.fee())
+            .append_u64(fee_token)
+            .append_bool(fee_payer.is_odd)
+            .append_u32(self.nonce().0)
+            .append_u32(self.valid_until() as u32)
+            .append_bytes(&self.memo_bytes()?);
+
+        for bit in tag {
+            input = input.append_bool(bit);
+        }
+
+        Ok(input
+            .append_bool(source.is_odd)
+            .append_bool(receiver.is_odd)
+            .append_u64(DEFAULT_TOKEN_ID)
+            .append_u64(self.amount())
+            .append_bool(false))
+    }
+}
+
+fn pub_key(pk: &PublicKey) -> anyhow::Result<PubKey> {
+    PubKey::from_address(&pk.0).map_err(|e| anyhow::anyhow!("{e}: {pk}"))
+}
+
+fn parse_signature(signature: &str) -> anyhow::Result<Signature> {
+    let json: SignatureJson =
+        serde_json::from_value(serde_json::Value::String(signature.to_owned()))
+            .with_context(|| format!("invalid signature {signature}"))?;
+    Ok(SignatureV1::from(json).into())
+}
+
+/// Raw bytes of a base58 encoded memo
+fn decode_memo(memo: &str) -> anyhow::Result<Vec<u8>> {
+    let bytes = bs58::decode(memo)
+        .with_check(Some(version_bytes::USER_COMMAND_MEMO))
+        .into_vec()
+        .with_context(|| format!("invalid memo {memo}"))?;
+
+    // version byte & memo
+    if bytes.len() != MEMO_LEN + 1 {
+        bail!("invalid memo length {}: {memo}", bytes.len() - 1)
+    }
+
+    Ok(bytes[1..].to_vec())
+}
+
+/// Field element of big-endian hex, as account update digests are rendered
+fn field_from_hex(hex: &str) -> anyhow::Result<BaseField> {
+    let mut bytes = hex::decode(hex.trim_start_matches("0x"))
+        .with_context(|| format!("invalid field element {hex}"))?;
+    bytes.reverse();
+
+    BaseField::from_bytes(&bytes).map_err(|e| anyhow::anyhow!("{e}: {hex}"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::block::{precomputed::PcbVersion, precomputed::PrecomputedBlock};
+    use std::path::PathBuf;
+
+    #[test]
+    fn payment_roinput() -> anyhow::Result<()> {
+        // 3 public key fields & the packed bits
+        let num_bits = 64 * 4 + 32 * 2 + MEMO_LEN * 8 + 3 + 4;
+        let num_fields = 3 + num_bits.div_ceil(ROInput::CHUNK_BITS);
+
+        for (path, version) in [
+            ("./tests/data/sequential_blocks/mainnet-105489-3NK4huLvUDiL4XuCUcyrWCKynmvhqfKsx5h2MfBXVVUq2Qwzi5uT.json", PcbVersion::V1),
+            ("./tests/data/hardfork/mainnet-359606-3NKvvtFwjEtQLswWJzXBSxxiKuYVbLJrKXCnmhp6jctYMqAWcftg.json", PcbVersion::V2),
+        ] {
+            let block = PrecomputedBlock::parse_file(&PathBuf::from(path), version)?;
+            let commands = block.commands();
+            assert!(!commands.is_empty());
+
+            for command in commands
+                .into_iter()
+                .map(SignedCommand::from)
+                .filter(|command| !command.is_zkapp_command())
+            {
+                let fields = command.to_roinput_legacy()?.to_fields();
+                let fee_payer = pub_key(&command.fee_payer_pk())?.into_compressed();
+
+                assert_eq!(fields.len(), num_fields);
+                assert_eq!(fields[0], fee_payer.x);
+                assert_eq!(command.memo_bytes()?.len(), MEMO_LEN);
+                assert!(command.signature().is_ok());
+            }
+        }
+
+        Ok(())
+    }
+
+    #[test]
+    fn zkapp_fee_payer_fields() -> anyhow::Result<()> {
+        let path = PathBuf::from("./tests/data/misc_blocks/mainnet-359763-3NKf6ocu98sTKEn1TdXP5ceRbd7s8kfg9bUkVA7wq5R4VmGyVA3m.json");
+        let block = PrecomputedBlock::parse_file(&path, PcbVersion::V2)?;
+        let zkapp_commands: Vec<_> = block
+            .commands()
+            .into_iter()
+            .map(SignedCommand::from)
+            .filter(SignedCommand::is_zkapp_command)
+            .collect();
+        assert!(!zkapp_commands.is_empty());
+
+        for command in zkapp_commands {
+            assert_eq!(command.memo_bytes()?.len(), MEMO_LEN);
+            assert!(command.signature().is_ok());
+
+            if let SignedCommand::V2(UserCommandData::ZkappCommandData(data)) = command {
+                for update in data.account_updates {
+                    field_from_hex(&update.elt.account_update_digest)?;
+                }
+            }
+        }
+
+        Ok(())
+    }
+
+    #[test]
+    fn tampered_payment_signatures() -> anyhow::Result<()> {
+        use crate::proof_systems::signer::{
+            schnorr::tests::{params, sign},
+            signature::ScalarField,
+        };
+
+        // stand-in parameters until the Poseidon constants are vendored
+        let verifier = SignatureVerifier {
+            legacy: params(),
+            kimchi: params(),
+            prefix: MAINNET_SIGNATURE_PREFIX,
+            zkapp_body_prefix: MAINNET_ZKAPP_BODY_PREFIX,
+        };
+
+        let path = PathBuf::from("./tests/data/sequential_blocks/mainnet-105489-3NK4huLvUDiL4XuCUcyrWCKynmvhqfKsx5h2MfBXVVUq2Qwzi5uT.json");
+        let block = PrecomputedBlock::parse_file(&path, PcbVersion::V1)?;
+        let payments: Vec<_> = block
+            .commands()
+            .into_iter()
+            .map(SignedCommand::from)
+            .filter(|command| !command.is_zkapp_command())
+            .collect();
+        let [payment, other, ..] = &payments[..] else {
+            panic!("expected at least 2 payments")
+        };
+
+        let (pub_key, signature) = sign(
+            &verifier.legacy,
+            ScalarField::from(123456789u64),
+            ScalarField::from(987654321u64),
+            payment.to_roinput_legacy()?,
+        );
+        let verify = |signature: &Signature, command: &SignedCommand| {
+            schnorr::verify(
+                &verifier.legacy,
+                verifier.prefix,
+                &pub_key,
+                signature,
+                command.to_roinput_legacy()?,
+            )
+        };
+
+        assert!(verify(&signature, payment)?);
+
+        // signed over another payload
+        assert!(!verify(&signature, other)?);
+
+        // tampered signature
+        let tampered = Signature::new(signature.rx, signature.s + ScalarField::one());
+        assert!(!verify(&tampered, payment)?);
+
+        Ok(())
+    }
+
+    #[test]
+    #[ignore = "requires the vendored Poseidon constants"]
+    fn mainnet_signatures() -> anyhow::Result<()> {
+        let verifier = SignatureVerifier::new(None, true)?;
+
+        for (path, version) in [
+            ("./tests/data/sequential_blocks/mainnet-105489-3NK4huLvUDiL4XuCUcyrWCKynmvhqfKsx5h2MfBXVVUq2Qwzi5uT.json", PcbVersion::V1),
+            ("./tests/data/hardfork/mainnet-359606-3NKvvtFwjEtQLswWJzXBSxxiKuYVbLJrKXCnmhp6jctYMqAWcftg.json", PcbVersion::V2),
+            ("./tests/data/misc_blocks/mainnet-359763-3NKf6ocu98sTKEn1TdXP5ceRbd7s8kfg9bUkVA7wq5R4VmGyVA3m.json", PcbVersion::V2),
+        ] {
+            let block = PrecomputedBlock::parse_file(&PathBuf::from(path), version)?;
+            let commands: Vec<_> = block.commands().into_iter().map(SignedCommand::from).collect();
+
+            for command in &commands {
+                assert_eq!(verifier.invalid_reason(command), None, "{path}");
+            }
+
+            // payment signed over another payload
+            let payments: Vec<_> = commands
+                .iter()
+                .filter(|command| !command.is_zkapp_command())
+                .collect();
+            if let [signed, other, ..] = payments[..] {
+                assert!(!schnorr::verify(
+                    &verifier.legacy,
+                    verifier.prefix,
+                    &pub_key(&signed.signer())?,
+                    &signed.signature()?,
+                    other.to_roinput_legacy()?,
+                )?);
+            }
+
+            // fee payer signed over another commitment
+            for command in &commands {
+                if let SignedCommand::V2(UserCommandData::ZkappCommandData(data)) = command {
+                    let tampered = verifier.full_commitment(data)? + BaseField::one();
+                    assert!(!schnorr::verify(
+                        &verifier.kimchi,
+                        verifier.prefix,
+                        &pub_key(&command.signer())?,
+                        &command.signature()?,
+                        ChunkedROInput::new().append_field(tampered),
+                    )?);
+                }
+            }
+        }
+
+        Ok(())
+    }
+}
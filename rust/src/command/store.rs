@@ -56,6 +56,17 @@ pub trait UserCommandStore {
         pk: &PublicKey,
     ) -> Result<Option<Vec<SignedCommandWithData>>>;
 
+    /// Get up to `limit` user commands involving the public key as a sender
+    /// or receiver, ascending by block height, starting after the `cursor`
+    /// command & skipping `offset` commands
+    fn get_user_commands_for_public_key_page(
+        &self,
+        pk: &PublicKey,
+        cursor: Option<(&TxnHash, &StateHash)>,
+        offset: usize,
+        limit: usize,
+    ) -> Result<Vec<SignedCommandWithData>>;
+
     /// Get user commands for the public key with number and/or state hash
     /// bounds
     fn get_user_commands_with_bounds(
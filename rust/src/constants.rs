@@ -48,6 +48,9 @@ pub const MAINNET_PROTOCOL_CONSTANTS: &[u32] = &[
 ];
 pub const MAINNET_EPOCH_SLOT_COUNT: u32 = 7140;
 pub const MAINNET_SLOTS_PER_SUB_WINDOW: u32 = 7;
+pub const MAINNET_SUB_WINDOWS_PER_WINDOW: u32 = 11;
+pub const MAINNET_GRACE_PERIOD_END: u32 = 1440;
+pub const MAINNET_ACTIVE_SLOT_COEFFICIENT: f64 = 0.75;
 pub const MAINNET_DELTA: u32 = 0;
 pub const MAINNET_TXPOOL_MAX_SIZE: u32 = 3000;
 
@@ -126,3 +129,4 @@ pub mod berkeley {
 
 pub const DEFAULT_WEB_HOSTNAME: &str = "0.0.0.0";
 pub const DEFAULT_WEB_PORT: u16 = 8080;
+pub const DEFAULT_REPLICA_CATCH_UP_INTERVAL_MS: u64 = 1000;
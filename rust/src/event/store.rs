@@ -2,6 +2,7 @@
 
 use crate::event::IndexerEvent;
 use speedb::{DBIterator, IteratorMode};
+use tokio::sync::broadcast;
 
 pub trait EventStore {
     /// Add event to db and return the next sequence number
@@ -20,4 +21,7 @@ pub trait EventStore {
 
     /// Returns the event log iterator
     fn event_log_iterator(&self, mode: IteratorMode) -> DBIterator<'_>;
+
+    /// Subscribe to events as they are added to the db
+    fn subscribe_events(&self) -> broadcast::Receiver<IndexerEvent>;
 }
@@ -121,16 +121,9 @@ impl Account {
     /// Time-locked balance (subtracted from circulating supply)
     /// as per https://docs.minaprotocol.com/mina-protocol/time-locked-accounts
     pub fn current_minimum_balance(&self, curr_global_slot: u32) -> u64 {
-        self.timing.as_ref().map_or(0, |t| {
-            if curr_global_slot < t.cliff_time.0 {
-                t.initial_minimum_balance.0
-            } else {
-                t.initial_minimum_balance.0.saturating_sub(
-                    ((curr_global_slot - t.cliff_time.0) / t.vesting_period.0) as u64
-                        * t.vesting_increment.0,
-                )
-            }
-        })
+        self.timing
+            .as_ref()
+            .map_or(0, |t| t.current_minimum_balance(curr_global_slot))
     }
 
     /// Creates a new empty account with the specified public key.
@@ -1307,4 +1300,39 @@ mod tests {
             }
         );
     }
+
+    #[test]
+    fn current_minimum_balance() {
+        use crate::base::numeric::Numeric;
+
+        let account = Account {
+            timing: Some(Timing {
+                cliff_time: Numeric(100),
+                vesting_period: Numeric(10),
+                cliff_amount: Numeric(1_000),
+                vesting_increment: Numeric(100),
+                initial_minimum_balance: Numeric(2_000),
+            }),
+            ..Default::default()
+        };
+
+        // before the cliff
+        assert_eq!(account.current_minimum_balance(0), 2_000);
+        assert_eq!(account.current_minimum_balance(99), 2_000);
+
+        // cliff amount vests at the cliff
+        assert_eq!(account.current_minimum_balance(100), 1_000);
+        assert_eq!(account.current_minimum_balance(109), 1_000);
+
+        // one increment per vesting period
+        assert_eq!(account.current_minimum_balance(110), 900);
+        assert_eq!(account.current_minimum_balance(195), 100);
+
+        // fully vested
+        assert_eq!(account.current_minimum_balance(200), 0);
+        assert_eq!(account.current_minimum_balance(u32::MAX), 0);
+
+        // untimed
+        assert_eq!(Account::default().current_minimum_balance(0), 0);
+    }
 }
@@ -19,6 +19,34 @@ pub struct Timing {
     pub initial_minimum_balance: Balance,
 }
 
+//////////
+// impl //
+//////////
+
+impl Timing {
+    /// Time-locked balance at the global slot
+    pub fn current_minimum_balance(&self, curr_global_slot: u32) -> u64 {
+        if curr_global_slot < self.cliff_time.0 {
+            return self.initial_minimum_balance.0;
+        }
+
+        // as per the protocol, everything vests at the cliff without a
+        // vesting period
+        if self.vesting_period.0 == 0 {
+            return 0;
+        }
+
+        // the cliff amount vests at the cliff, then one increment per period
+        let num_periods = (curr_global_slot - self.cliff_time.0) / self.vesting_period.0;
+        let vested = self
+            .cliff_amount
+            .0
+            .saturating_add((num_periods as u64).saturating_mul(self.vesting_increment.0));
+
+        self.initial_minimum_balance.0.saturating_sub(vested)
+    }
+}
+
 ///////////////
 // arbitrary //
 ///////////////
@@ -35,3 +63,37 @@ impl quickcheck::Arbitrary for Timing {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn timing(vesting_period: u32) -> Timing {
+        Timing {
+            cliff_time: Numeric(100),
+            vesting_period: Numeric(vesting_period),
+            cliff_amount: Numeric(10),
+            vesting_increment: Numeric(5),
+            initial_minimum_balance: Numeric(100),
+        }
+    }
+
+    #[test]
+    fn current_minimum_balance() {
+        let timing = timing(10);
+
+        assert_eq!(timing.current_minimum_balance(99), 100);
+        assert_eq!(timing.current_minimum_balance(100), 90);
+        assert_eq!(timing.current_minimum_balance(109), 90);
+        assert_eq!(timing.current_minimum_balance(110), 85);
+        assert_eq!(timing.current_minimum_balance(u32::MAX), 0);
+    }
+
+    #[test]
+    fn zero_vesting_period_vests_at_cliff() {
+        let timing = timing(0);
+
+        assert_eq!(timing.current_minimum_balance(99), 100);
+        assert_eq!(timing.current_minimum_balance(100), 0);
+    }
+}
@@ -0,0 +1,98 @@
+//! Ledger Merkle tree root computation
+//!
+//! Accounts are the leaves of a fixed depth binary tree, in ledger index
+//! order. Unoccupied leaves hash to the empty account hash & each internal
+//! node is a Poseidon hash of its children, salted by its height.
+
+use crate::proof_systems::{
+    curves::pasta::fields::fp::Fp,
+    poseidon::{salt, Sponge, SpongeParams},
+};
+
+/// Mainnet ledger depth
+pub const LEDGER_DEPTH: usize = 35;
+
+/// Domain separation prefix of internal nodes at `height` (leaves are at
+/// height 0)
+pub fn merkle_tree_prefix(height: usize) -> String {
+    format!("{:*<20}", format!("CodaMklTree{height:03}"))
+}
+
+/// Poseidon hash of a pair of children at `height - 1`
+pub fn merkle_hash(
+    params: &SpongeParams,
+    height: usize,
+    left: Fp,
+    right: Fp,
+) -> anyhow::Result<Fp> {
+    let init = salt(params, &merkle_tree_prefix(height - 1))?;
+    Ok(Sponge::with_state(params, init).hash(&[left, right]))
+}
+
+/// Root of the `depth` tree with `leaves` occupying the leftmost positions,
+/// all others `empty_leaf`
+///
+/// `hash(height, left, right)` computes the parent at `height` from its
+/// children
+pub fn merkle_root<H>(leaves: &[Fp], depth: usize, empty_leaf: Fp, hash: H) -> anyhow::Result<Fp>
+where
+    H: Fn(usize, Fp, Fp) -> anyhow::Result<Fp>,
+{
+    if depth < usize::BITS as usize && leaves.len() > 1 << depth {
+        anyhow::bail!("{} leaves exceed a depth {depth} tree", leaves.len())
+    }
+
+    let mut level = leaves.to_vec();
+    let mut empty = empty_leaf;
+
+    for height in 1..=depth {
+        if level.len() % 2 == 1 {
+            level.push(empty);
+        }
+
+        level = level
+            .chunks(2)
+            .map(|pair| hash(height, pair[0], pair[1]))
+            .collect::<anyhow::Result<_>>()?;
+        empty = hash(height, empty, empty)?;
+    }
+
+    Ok(level.first().copied().unwrap_or(empty))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn prefix() {
+        assert_eq!(merkle_tree_prefix(0), "CodaMklTree000******");
+        assert_eq!(merkle_tree_prefix(34), "CodaMklTree034******");
+        assert_eq!(merkle_tree_prefix(0).len(), 20);
+    }
+
+    #[test]
+    fn root_with_empty_subtrees() -> anyhow::Result<()> {
+        // non-commutative & height dependent
+        let hash = |height: usize, l: Fp, r: Fp| -> anyhow::Result<Fp> {
+            Ok(l + r + r + Fp::from(height as u64))
+        };
+        let [a, b, c, e] = [1u64, 10, 100, 7].map(Fp::from);
+
+        // height 1
+        let ab = hash(1, a, b)?;
+        let ce = hash(1, c, e)?;
+        let ee = hash(1, e, e)?;
+
+        // height 2
+        let abce = hash(2, ab, ce)?;
+        let eeee = hash(2, ee, ee)?;
+
+        assert_eq!(merkle_root(&[a, b, c], 2, e, hash)?, abce);
+        assert_eq!(merkle_root(&[a, b, c], 3, e, hash)?, hash(3, abce, eeee)?);
+        assert_eq!(merkle_root(&[], 2, e, hash)?, eeee);
+        assert!(merkle_root(&[a, b, c], 1, e, hash).is_err());
+
+        Ok(())
+    }
+}
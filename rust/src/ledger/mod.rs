@@ -5,8 +5,10 @@ pub mod coinbase;
 pub mod diff;
 pub mod genesis;
 pub mod hash;
+pub mod merkle;
 pub mod staking;
 pub mod store;
+pub mod supply;
 pub mod token;
 
 use crate::{
@@ -25,6 +27,9 @@ use token::ledger::TokenLedger;
 // re-export [hash::LedgerHash]
 pub type LedgerHash = hash::LedgerHash;
 
+/// Accounts keyed by token & public key
+pub type LedgerAccounts = HashMap<String, HashMap<String, Account>>;
+
 #[derive(Default, Clone, Serialize, Deserialize)]
 pub struct Ledger {
     pub tokens: HashMap<TokenAddress, TokenLedger>,
@@ -238,6 +243,11 @@ impl Ledger {
     }
 
     pub fn to_string_pretty(&self) -> String {
+        serde_json::to_string_pretty(&self.accounts_by_token()).unwrap()
+    }
+
+    /// Accounts, less the account creation fee, keyed by token & public key
+    pub fn accounts_by_token(&self) -> LedgerAccounts {
         let mut tokens = HashMap::new();
 
         for (token, token_ledger) in self.tokens.iter() {
@@ -253,7 +263,7 @@ impl Ledger {
             tokens.insert(token.to_string(), accounts);
         }
 
-        serde_json::to_string_pretty(&tokens).unwrap()
+        tokens
     }
 }
 
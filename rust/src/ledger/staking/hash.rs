@@ -0,0 +1,308 @@
+//! Legacy (pre-hardfork) account hashing & staking ledger hashes
+//!
+//! Account leaves follow the v1 `Account.to_input` layout. The record fields
+//! are folded last to first, so the snapp digest leads & the public key
+//! trails both the field elements & the bit string.
+
+use super::{permissions::StakingPermissions, StakingAccount, StakingLedger};
+use crate::{
+    base::state_hash::StateHash,
+    ledger::{
+        account::Timing,
+        merkle::{merkle_root, merkle_tree_prefix, LEDGER_DEPTH},
+        LedgerHash,
+    },
+    proof_systems::{
+        curves::pasta::fields::fp::Fp,
+        poseidon::{salt, Sponge, SpongeParams},
+        signer::{pubkey::CompressedPubKey, roinput::ROInput},
+        FieldHelpers,
+    },
+    protocol::serialization_types::{common::HashV1, version_bytes},
+};
+use anyhow::{bail, Context};
+use ark_ff::Zero;
+
+/// Account domain separation prefix
+const ACCOUNT_PREFIX: &str = "CodaAccount*********";
+
+/// Snapp account domain separation prefix
+const SNAPP_ACCOUNT_PREFIX: &str = "CodaSnappAccount****";
+
+/// Number of snapp app state field elements
+const SNAPP_APP_STATE_LEN: usize = 8;
+
+/// Receipt chain hash of accounts without any sent transactions
+pub const EMPTY_RECEIPT_CHAIN_HASH: &str = "2mzbV7WevxLuchs2dAMY4vQBS6XttnCUF8Hvks4XNBQ5qiSGGBQe";
+
+/// Legacy default token id
+const DEFAULT_TOKEN_ID: u64 = 1;
+
+/// Account record, as hashed
+struct LegacyAccountInput {
+    public_key: CompressedPubKey,
+    balance: u64,
+    nonce: u32,
+    receipt_chain_hash: Fp,
+    delegate: CompressedPubKey,
+    voting_for: Fp,
+    timing: Option<Timing>,
+    permissions: Vec<bool>,
+}
+
+impl StakingAccount {
+    /// Legacy random oracle input of the account
+    pub fn to_roinput_legacy(&self, params: &SpongeParams) -> anyhow::Result<ROInput> {
+        self.to_roinput_legacy_with(empty_snapp_hash(params)?)
+    }
+
+    /// Legacy Merkle leaf of the account
+    pub fn hash_legacy(&self, params: &SpongeParams) -> anyhow::Result<Fp> {
+        hash_with_prefix(params, ACCOUNT_PREFIX, &self.to_roinput_legacy(params)?)
+    }
+
+    fn to_roinput_legacy_with(&self, snapp_hash: Fp) -> anyhow::Result<ROInput> {
+        if self
+            .token
+            .as_ref()
+            .is_some_and(|token| *token != Default::default())
+        {
+            bail!("legacy account {} has a non-default token", self.pk)
+        }
+
+        let receipt_chain_hash = if self.receipt_chain_hash.0.is_empty() {
+            EMPTY_RECEIPT_CHAIN_HASH
+        } else {
+            &self.receipt_chain_hash.0
+        };
+
+        Ok(LegacyAccountInput {
+            public_key: compressed(&self.pk.0)?,
+            balance: self.balance,
+            nonce: self.nonce.map_or(0, |nonce| nonce.0),
+            receipt_chain_hash: hash_to_field(
+                receipt_chain_hash,
+                version_bytes::RECEIPT_CHAIN_HASH,
+            )?,
+            delegate: compressed(&self.delegate.0)?,
+            voting_for: state_hash_to_field(&self.voting_for)?,
+            timing: self.timing.clone(),
+            permissions: self.permissions.to_bits_legacy(),
+        }
+        .to_roinput(snapp_hash))
+    }
+}
+
+impl StakingLedger {
+    /// Legacy ledger hash of `accounts`, in ledger index order
+    pub fn ledger_hash_legacy<'a, I>(
+        accounts: I,
+        params: &SpongeParams,
+    ) -> anyhow::Result<LedgerHash>
+    where
+        I: IntoIterator<Item = &'a StakingAccount>,
+    {
+        let snapp_hash = empty_snapp_hash(params)?;
+        let leaves = accounts
+            .into_iter()
+            .map(|account| {
+                let input = account.to_roinput_legacy_with(snapp_hash)?;
+                hash_with_prefix(params, ACCOUNT_PREFIX, &input)
+            })
+            .collect::<anyhow::Result<Vec<_>>>()?;
+
+        // one salted sponge state per height
+        let inits = (0..LEDGER_DEPTH)
+            .map(|height| salt(params, &merkle_tree_prefix(height)))
+            .collect::<anyhow::Result<Vec<_>>>()?;
+
+        let root = merkle_root(
+            &leaves,
+            LEDGER_DEPTH,
+            empty_account_hash_legacy(params)?,
+            |height, left, right| {
+                Ok(Sponge::with_state(params, inits[height - 1].clone()).hash(&[left, right]))
+            },
+        )?;
+
+        Ok(field_to_ledger_hash(&root))
+    }
+}
+
+impl LegacyAccountInput {
+    fn to_roinput(&self, snapp_hash: Fp) -> ROInput {
+        let timing = self.timing.clone().unwrap_or(Timing {
+            // untimed accounts vest every slot
+            vesting_period: 1.into(),
+            ..Default::default()
+        });
+
+        ROInput::new()
+            // snapp
+            .append_field(snapp_hash)
+            // permissions
+            .append_bools(&self.permissions)
+            // timing
+            .append_bool(self.timing.is_some())
+            .append_u64(timing.initial_minimum_balance.0)
+            .append_u32(timing.cliff_time.0)
+            .append_u64(timing.cliff_amount.0)
+            .append_u32(timing.vesting_period.0)
+            .append_u64(timing.vesting_increment.0)
+            // voting for
+            .append_field(self.voting_for)
+            // delegate
+            .append_field(self.delegate.x)
+            .append_bool(self.delegate.is_odd)
+            // receipt chain hash
+            .append_field(self.receipt_chain_hash)
+            // nonce & balance
+            .append_u32(self.nonce)
+            .append_u64(self.balance)
+            // token permissions: not owned, not disabled
+            .append_bool(false)
+            .append_bool(false)
+            // token id
+            .append_u64(DEFAULT_TOKEN_ID)
+            // public key
+            .append_field(self.public_key.x)
+            .append_bool(self.public_key.is_odd)
+    }
+}
+
+/// Leaf of unoccupied ledger positions
+pub fn empty_account_hash_legacy(params: &SpongeParams) -> anyhow::Result<Fp> {
+    let empty_pk = CompressedPubKey {
+        x: Fp::zero(),
+        is_odd: false,
+    };
+    let input = LegacyAccountInput {
+        public_key: empty_pk.clone(),
+        balance: 0,
+        nonce: 0,
+        receipt_chain_hash: hash_to_field(
+            EMPTY_RECEIPT_CHAIN_HASH,
+            version_bytes::RECEIPT_CHAIN_HASH,
+        )?,
+        delegate: empty_pk,
+        voting_for: Fp::zero(),
+        timing: None,
+        permissions: StakingPermissions::default().to_bits_legacy(),
+    };
+
+    hash_with_prefix(
+        params,
+        ACCOUNT_PREFIX,
+        &input.to_roinput(empty_snapp_hash(params)?),
+    )
+}
+
+/// Digest of the default snapp account, shared by all legacy accounts
+fn empty_snapp_hash(params: &SpongeParams) -> anyhow::Result<Fp> {
+    // zero app state & no verification key
+    let input =
+        (0..=SNAPP_APP_STATE_LEN).fold(ROInput::new(), |input, _| input.append_field(Fp::zero()));
+
+    hash_with_prefix(params, SNAPP_ACCOUNT_PREFIX, &input)
+}
+
+fn hash_with_prefix(params: &SpongeParams, prefix: &str, input: &ROInput) -> anyhow::Result<Fp> {
+    let init = salt(params, prefix)?;
+    Ok(Sponge::with_state(params, init).hash(&input.to_fields()))
+}
+
+fn compressed(address: &str) -> anyhow::Result<CompressedPubKey> {
+    CompressedPubKey::from_address(address).map_err(|e| anyhow::anyhow!("{e}: {address}"))
+}
+
+fn state_hash_to_field(state_hash: &StateHash) -> anyhow::Result<Fp> {
+    if state_hash.0.is_empty() {
+        return Ok(Fp::zero());
+    }
+
+    hash_to_field(&state_hash.0, version_bytes::STATE_HASH)
+}
+
+/// Field element of a base58 versioned hash
+fn hash_to_field(hash: &str, version_byte: u8) -> anyhow::Result<Fp> {
+    let bytes = bs58::decode(hash)
+        .with_check(Some(version_byte))
+        .into_vec()
+        .with_context(|| format!("invalid hash {hash}"))?;
+
+    // version check byte, versioned type byte & 32 little-endian bytes
+    if bytes.len() != 34 {
+        bail!("invalid hash length {}: {hash}", bytes.len())
+    }
+
+    Fp::from_bytes(&bytes[2..]).map_err(|e| anyhow::anyhow!("{e}: {hash}"))
+}
+
+fn field_to_ledger_hash(x: &Fp) -> LedgerHash {
+    let mut bytes = [0u8; 32];
+    bytes.copy_from_slice(&x.to_bytes());
+
+    LedgerHash::from_hashv1(HashV1::from(bytes))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::proof_systems::poseidon::PoseidonKind;
+    use std::path::PathBuf;
+
+    #[test]
+    fn hash_field_roundtrip() -> anyhow::Result<()> {
+        let hash = "jx7buQVWFLsXTtzRgSxbYcT8EYLS8KCZbLrfDcJxMtyy4thw2Ee";
+        let x = hash_to_field(hash, version_bytes::LEDGER_HASH)?;
+
+        assert_eq!(field_to_ledger_hash(&x).0, hash);
+        assert_eq!(
+            hash_to_field(
+                "3NK2tkzqqK5spR2sZ7tujjqPksL45M3UUrcA4WhCkeiPtnugyE2x",
+                version_bytes::STATE_HASH
+            )?,
+            Fp::zero()
+        );
+        assert!(hash_to_field(hash, version_bytes::STATE_HASH).is_err());
+
+        Ok(())
+    }
+
+    #[test]
+    fn legacy_account_input() -> anyhow::Result<()> {
+        let pk = "B62qrecVjpoZ4Re3a5arN6gXZ6orhmj1enUtA887XdG5mtZfdUbBUh4";
+        let account = StakingAccount {
+            pk: pk.into(),
+            delegate: pk.into(),
+            balance: 1000,
+            ..Default::default()
+        };
+
+        // snapp, voting for, delegate, receipt chain hash & public key fields,
+        // 19 permission + 257 timing + 164 remaining bits in 2 chunks
+        let fields = account.to_roinput_legacy_with(Fp::zero())?.to_fields();
+        assert_eq!(fields.len(), 7);
+        assert_eq!(fields[0], Fp::zero());
+        assert_eq!(fields[4], compressed(pk)?.x);
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    #[ignore = "requires the vendored legacy Poseidon constants"]
+    async fn genesis_staking_ledger_hash() -> anyhow::Result<()> {
+        let path: PathBuf = "../tests/data/staking_ledgers/mainnet-0-jx7buQVWFLsXTtzRgSxbYcT8EYLS8KCZbLrfDcJxMtyy4thw2Ee.json".into();
+        let params = SpongeParams::vendored(PoseidonKind::Legacy)?;
+
+        // verified while parsing
+        let staking_ledger = StakingLedger::parse_file_verified(&path, params).await?;
+
+        assert_eq!(
+            staking_ledger.ledger_hash.0,
+            "jx7buQVWFLsXTtzRgSxbYcT8EYLS8KCZbLrfDcJxMtyy4thw2Ee"
+        );
+
+        Ok(())
+    }
+}
@@ -1,4 +1,6 @@
+pub mod hash;
 pub mod parser;
+pub mod payout;
 pub mod permissions;
 
 use super::token::TokenAddress;
@@ -8,14 +10,15 @@ use crate::{
     chain::Network,
     constants::{HARDFORK_GENESIS_HASH, MAINNET_GENESIS_HASH, MINA_TOKEN_ADDRESS},
     ledger::{
-        account::{ReceiptChainHash, Timing},
-        LedgerHash,
+        account::{Account, ReceiptChainHash, Timing},
+        Ledger, LedgerHash,
     },
     mina_blocks::v2::ZkappAccount,
+    proof_systems::poseidon::SpongeParams,
     utility::{compression::decompress_gzip, functions::extract_height_and_hash},
 };
-use anyhow::Context;
-use log::trace;
+use anyhow::{bail, Context};
+use log::{trace, warn};
 use permissions::StakingPermissions;
 use serde::{Deserialize, Serialize};
 use std::{
@@ -161,6 +164,27 @@ impl From<StakingAccountJson> for StakingAccount {
     }
 }
 
+impl From<Account> for StakingAccount {
+    /// Staged ledgers don't track staking permissions
+    fn from(value: Account) -> Self {
+        let value = value.deduct_mina_account_creation_fee();
+
+        Self {
+            pk: value.public_key,
+            balance: value.balance.0,
+            delegate: value.delegate,
+            username: value.username.map(|username| username.0),
+            token: value.token,
+            permissions: StakingPermissions::default(),
+            receipt_chain_hash: value.receipt_chain_hash.unwrap_or_default(),
+            voting_for: StateHash(value.voting_for.unwrap_or_default().0),
+            nonce: value.nonce,
+            timing: value.timing,
+            zkapp: value.zkapp,
+        }
+    }
+}
+
 impl StakingLedger {
     const V1_STAKING_LEDGER_HASHES: [&str; 79] = [
         "jx7buQVWFLsXTtzRgSxbYcT8EYLS8KCZbLrfDcJxMtyy4thw2Ee",
@@ -261,6 +285,23 @@ impl StakingLedger {
 
     /// Parse a valid (compressed) ledger file
     pub async fn parse_file(path: &Path) -> anyhow::Result<Self> {
+        Self::parse_file_with(path, None).await
+    }
+
+    /// Parse a valid (compressed) ledger file, checking its accounts hash to
+    /// the file's ledger hash
+    ///
+    /// Only legacy (pre-hardfork) ledgers are verified
+    pub async fn parse_file_verified(path: &Path, params: &SpongeParams) -> anyhow::Result<Self> {
+        Self::parse_file_with(path, Some(params)).await
+    }
+
+    /// Parse a valid (compressed) ledger file, verifying it if `params` are
+    /// given
+    pub async fn parse_file_with(
+        path: &Path,
+        params: Option<&SpongeParams>,
+    ) -> anyhow::Result<Self> {
         let mut bytes = std::fs::read(path)?;
         let is_compressed = path.extension().is_some_and(|ext| ext == "gz");
 
@@ -291,17 +332,36 @@ impl StakingLedger {
         let staking_ledger: Vec<StakingAccountJson> = serde_json::from_slice(&bytes)
             .with_context(|| format!("Failed reading staking ledger {}", path.display()))?;
 
-        let staking_ledger: HashMap<PublicKey, StakingAccount> = staking_ledger
+        let staking_ledger: Vec<(bool, StakingAccount)> = staking_ledger
             .into_iter()
-            .filter_map(|acct| {
-                if is_pre_hardfork || acct.token.as_ref().is_some_and(|t| t == MINA_TOKEN_ADDRESS) {
-                    Some((acct.pk.clone(), acct.into()))
-                } else {
-                    None
-                }
+            .map(|acct| {
+                let is_mina = acct.token.as_ref().is_some_and(|t| t == MINA_TOKEN_ADDRESS);
+                (is_pre_hardfork || is_mina, acct.into())
             })
             .collect();
 
+        // accounts are listed in ledger index order
+        if let Some(params) = params {
+            if is_pre_hardfork {
+                let accounts = staking_ledger.iter().map(|(_, acct)| acct);
+                let computed = Self::ledger_hash_legacy(accounts, params)?;
+
+                if computed != ledger_hash {
+                    bail!("Staking ledger {} hashes to {}", ledger_hash.0, computed.0)
+                }
+            } else {
+                warn!(
+                    "Post-hardfork staking ledger {} is not verified",
+                    ledger_hash.0
+                );
+            }
+        }
+
+        let staking_ledger: HashMap<PublicKey, StakingAccount> = staking_ledger
+            .into_iter()
+            .filter_map(|(keep, acct)| keep.then(|| (acct.pk.clone(), acct)))
+            .collect();
+
         let total_currency: u64 = staking_ledger.values().map(|account| account.balance).sum();
         let genesis_state_hash = Self::genesis_state_hash(&ledger_hash);
 
@@ -315,6 +375,85 @@ impl StakingLedger {
         })
     }
 
+    /// Staking ledger of the staged ledger's MINA accounts
+    pub fn from_staged_ledger(
+        ledger: &Ledger,
+        epoch: u32,
+        network: Network,
+        ledger_hash: LedgerHash,
+        genesis_state_hash: StateHash,
+    ) -> Self {
+        let staking_ledger: HashMap<PublicKey, StakingAccount> = ledger
+            .tokens
+            .get(&TokenAddress::default())
+            .map(|mina_ledger| {
+                mina_ledger
+                    .accounts
+                    .iter()
+                    .map(|(pk, account)| (pk.clone(), account.clone().into()))
+                    .collect()
+            })
+            .unwrap_or_default();
+        let total_currency = staking_ledger.values().map(|account| account.balance).sum();
+
+        Self {
+            epoch,
+            network,
+            ledger_hash,
+            total_currency,
+            genesis_state_hash,
+            staking_ledger,
+        }
+    }
+
+    /// Accounts whose balance, delegate, nonce, or timing differ between the
+    /// staking ledgers, including accounts missing from either
+    pub fn mismatches(&self, other: &Self) -> Vec<String> {
+        let mut mismatches = vec![];
+
+        for (pk, account) in &self.staking_ledger {
+            match other.staking_ledger.get(pk) {
+                None => mismatches.push(format!("{pk} missing from {}", other.summary())),
+                Some(other_account) => {
+                    if account.balance != other_account.balance {
+                        mismatches.push(format!(
+                            "{pk} balance {} != {}",
+                            account.balance, other_account.balance
+                        ));
+                    }
+
+                    if account.delegate != other_account.delegate {
+                        mismatches.push(format!(
+                            "{pk} delegate {} != {}",
+                            account.delegate, other_account.delegate
+                        ));
+                    }
+
+                    if account.nonce.unwrap_or_default() != other_account.nonce.unwrap_or_default()
+                    {
+                        mismatches.push(format!(
+                            "{pk} nonce {} != {}",
+                            account.nonce.unwrap_or_default(),
+                            other_account.nonce.unwrap_or_default()
+                        ));
+                    }
+
+                    if account.timing != other_account.timing {
+                        mismatches.push(format!("{pk} timing differs"));
+                    }
+                }
+            }
+        }
+
+        for pk in other.staking_ledger.keys() {
+            if !self.staking_ledger.contains_key(pk) {
+                mismatches.push(format!("{pk} missing from {}", self.summary()));
+            }
+        }
+
+        mismatches
+    }
+
     pub fn genesis_state_hash(ledger_hash: &LedgerHash) -> StateHash {
         if Self::V1_STAKING_LEDGER_HASHES.contains(&(&ledger_hash.0 as &str)) {
             MAINNET_GENESIS_HASH.into()
@@ -407,8 +546,10 @@ impl std::str::FromStr for ReceiptChainHash {
 mod tests {
     use super::StakingLedger;
     use crate::{
-        chain::Network, constants::MAINNET_GENESIS_HASH,
-        ledger::staking::AggregatedEpochStakeDelegations,
+        base::public_key::PublicKey,
+        chain::Network,
+        constants::MAINNET_GENESIS_HASH,
+        ledger::{staking::AggregatedEpochStakeDelegations, Ledger, LedgerHash},
     };
     use std::{collections::HashSet, path::PathBuf};
 
@@ -429,8 +570,6 @@ mod tests {
 
     #[tokio::test]
     async fn calculate_delegations() -> anyhow::Result<()> {
-        use crate::base::public_key::PublicKey;
-
         let path: PathBuf = "../tests/data/staking_ledgers/mainnet-0-jx7buQVWFLsXTtzRgSxbYcT8EYLS8KCZbLrfDcJxMtyy4thw2Ee.json".into();
         let staking_ledger = StakingLedger::parse_file(&path).await?;
 
@@ -493,4 +632,49 @@ mod tests {
 
         Ok(())
     }
+
+    #[test]
+    fn derived_staking_ledger_mismatches() -> anyhow::Result<()> {
+        let pk0 = "B62qmCwouxG2UzH6zEYGFWFFzUuSv9sbLnr96VJWDX3paSSucX7jAJN";
+        let pk1 = "B62qpz34iGX2eaRDyHmHbq3v1SnUgzounhudGZRfNUDh79JuTstPNy1";
+        let ledger_hash =
+            LedgerHash::new_or_panic("jx7buQVWFLsXTtzRgSxbYcT8EYLS8KCZbLrfDcJxMtyy4thw2Ee".into());
+
+        let derive = |ledger: &Ledger| {
+            StakingLedger::from_staged_ledger(
+                ledger,
+                2,
+                Network::Mainnet,
+                ledger_hash.clone(),
+                MAINNET_GENESIS_HASH.into(),
+            )
+        };
+
+        let derived = derive(&Ledger::from(vec![
+            (pk0, 2_000_000_000, None, None),
+            (pk1, 3_000_000_000, None, Some(pk0)),
+        ])?);
+
+        // account creation fees are deducted
+        assert_eq!(derived.total_currency, 3_000_000_000);
+        assert_eq!(
+            derived.staking_ledger[&PublicKey::from(pk1)].delegate.0,
+            pk0
+        );
+        assert!(derived.mismatches(&derived).is_empty());
+
+        let other = derive(&Ledger::from(vec![(pk0, 3_000_000_000, None, None)])?);
+        let mut mismatches = derived.mismatches(&other);
+        mismatches.sort();
+
+        assert_eq!(
+            mismatches,
+            vec![
+                format!("{pk0} balance 1000000000 != 2000000000"),
+                format!("{pk1} missing from {}", other.summary()),
+            ]
+        );
+
+        Ok(())
+    }
 }
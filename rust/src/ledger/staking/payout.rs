@@ -0,0 +1,428 @@
+//! Staking pool delegator payouts
+
+use crate::{
+    base::{public_key::PublicKey, state_hash::StateHash},
+    block::store::BlockStore,
+    canonicity::{store::CanonicityStore, Canonicity},
+    ledger::{account::Timing, coinbase::Coinbase, store::staking::StakingLedgerStore},
+    store::IndexerStore,
+    utility::store::common::state_hash_suffix,
+};
+use anyhow::bail;
+use bincode::{Decode, Encode};
+use serde::{Deserialize, Serialize};
+use speedb::{Direction, IteratorMode};
+
+#[derive(
+    Debug,
+    Default,
+    Clone,
+    Copy,
+    PartialEq,
+    Eq,
+    Serialize,
+    Deserialize,
+    clap::ValueEnum,
+    Encode,
+    Decode,
+)]
+#[serde(rename_all = "snake_case")]
+pub enum PayoutScheme {
+    /// Block rewards are shared in proportion to stake
+    #[default]
+    Proportional,
+
+    /// Supercharged coinbase bonuses are only shared among the delegators
+    /// whose stake was unlocked when the block was produced
+    Supercharged,
+
+    /// Coinbase rewards are shared in proportion to stake, the pool keeps
+    /// the transaction fees
+    FeeSplit,
+}
+
+/// Canonical block produced by the pool
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PoolBlock {
+    pub state_hash: StateHash,
+    pub global_slot: u32,
+    pub coinbase: u64,
+    pub supercharged: bool,
+
+    /// Transaction fees net of SNARK fees
+    pub fees: u64,
+}
+
+/// Staking ledger account delegating to the pool
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PoolDelegator {
+    pub public_key: PublicKey,
+    pub stake: u64,
+    pub timing: Option<Timing>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct DelegatorPayout {
+    pub public_key: PublicKey,
+
+    /// Staking ledger balance (nanomina)
+    pub stake: u64,
+
+    /// Whether the stake was time-locked when any of the pool's blocks were
+    /// produced
+    pub locked: bool,
+
+    /// Payout (nanomina)
+    pub payout: u64,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct PoolPayouts {
+    pub delegate: PublicKey,
+    pub epoch: u32,
+    pub genesis_state_hash: StateHash,
+    pub scheme: PayoutScheme,
+    pub fee_percent: f64,
+
+    /// Total delegated stake (nanomina)
+    pub total_stake: u64,
+    pub num_blocks: u32,
+    pub num_supercharged_blocks: u32,
+
+    /// Coinbases & transaction fees net of SNARK fees (nanomina)
+    pub total_rewards: u64,
+
+    /// Pool fee, retained fees & rounding remainders (nanomina)
+    pub operator_reward: u64,
+    pub payouts: Vec<DelegatorPayout>,
+}
+
+//////////
+// impl //
+//////////
+
+impl PoolPayouts {
+    const PPM: u128 = 1_000_000;
+
+    /// Payouts for the delegate's canonical blocks in the epoch, `None` if
+    /// the epoch's staking ledger is missing
+    pub fn from_store(
+        db: &IndexerStore,
+        delegate: &PublicKey,
+        epoch: u32,
+        genesis_state_hash: &StateHash,
+        scheme: PayoutScheme,
+        fee_percent: f64,
+    ) -> anyhow::Result<Option<Self>> {
+        if db
+            .get_staking_ledger_hash_by_epoch(epoch, genesis_state_hash)?
+            .is_none()
+        {
+            return Ok(None);
+        }
+
+        let mut delegators = vec![];
+        if let Some(delegation) = db.get_epoch_delegations(delegate, epoch, genesis_state_hash)? {
+            for pk in delegation.delegates {
+                if let Some(account) = db.get_staking_account(&pk, epoch, genesis_state_hash)? {
+                    delegators.push(PoolDelegator {
+                        public_key: pk,
+                        stake: account.balance,
+                        timing: account.timing,
+                    });
+                }
+            }
+        }
+
+        // the delegate's canonical blocks in the epoch
+        let mut blocks = vec![];
+        for (key, _) in db
+            .block_creator_global_slot_iterator(IteratorMode::From(
+                delegate.0.as_bytes(),
+                Direction::Forward,
+            ))
+            .flatten()
+        {
+            if !key.starts_with(delegate.0.as_bytes()) {
+                break;
+            }
+
+            let state_hash = state_hash_suffix(&key)?;
+            if db.get_block_epoch(&state_hash)? != Some(epoch)
+                || db.get_block_genesis_state_hash(&state_hash)?.as_ref()
+                    != Some(genesis_state_hash)
+                || db.get_block_canonicity(&state_hash)? != Some(Canonicity::Canonical)
+            {
+                continue;
+            }
+
+            if let Some((block, _)) = db.get_block(&state_hash)? {
+                let coinbase = Coinbase::from_precomputed(&block);
+
+                blocks.push(PoolBlock {
+                    state_hash,
+                    global_slot: block.global_slot_since_genesis(),
+                    coinbase: coinbase.amount(),
+                    supercharged: coinbase.supercharge,
+                    fees: block.tx_fees().saturating_sub(block.snark_fees()),
+                });
+            }
+        }
+
+        Self::compute(
+            delegate.clone(),
+            epoch,
+            genesis_state_hash.clone(),
+            scheme,
+            fee_percent,
+            &blocks,
+            delegators,
+        )
+        .map(Some)
+    }
+
+    /// Shares each block's rewards, less the pool fee, among the delegators
+    pub fn compute(
+        delegate: PublicKey,
+        epoch: u32,
+        genesis_state_hash: StateHash,
+        scheme: PayoutScheme,
+        fee_percent: f64,
+        blocks: &[PoolBlock],
+        delegators: Vec<PoolDelegator>,
+    ) -> anyhow::Result<Self> {
+        if !(0.0..=100.0).contains(&fee_percent) {
+            bail!("Invalid pool fee percent: {fee_percent}");
+        }
+
+        // share of rewards paid out, in parts per million
+        let payout_ppm = Self::PPM - (fee_percent * 10_000.0).round() as u128;
+        let total_stake: u64 = delegators.iter().map(|d| d.stake).sum();
+
+        let mut payouts: Vec<_> = delegators
+            .iter()
+            .map(|delegator| DelegatorPayout {
+                public_key: delegator.public_key.clone(),
+                stake: delegator.stake,
+                locked: false,
+                payout: 0,
+            })
+            .collect();
+        let mut total_rewards = 0;
+
+        for block in blocks {
+            total_rewards += block.coinbase + block.fees;
+
+            // rewards shared among all delegators & only unlocked delegators
+            let (mut shared, mut bonus) = match scheme {
+                PayoutScheme::Proportional => (block.coinbase + block.fees, 0),
+                PayoutScheme::Supercharged if block.supercharged => {
+                    let base = block.coinbase / 2;
+                    (base + block.fees, block.coinbase - base)
+                }
+                PayoutScheme::Supercharged => (block.coinbase + block.fees, 0),
+                PayoutScheme::FeeSplit => (block.coinbase, 0),
+            };
+
+            let locked: Vec<_> = delegators
+                .iter()
+                .map(|d| {
+                    d.timing
+                        .as_ref()
+                        .is_some_and(|t| t.current_minimum_balance(block.global_slot) > 0)
+                })
+                .collect();
+            let unlocked_stake: u64 = delegators
+                .iter()
+                .zip(locked.iter())
+                .filter_map(|(d, locked)| (!locked).then_some(d.stake))
+                .sum();
+
+            // without unlocked delegators, the bonus is shared
+            if unlocked_stake == 0 {
+                shared += bonus;
+                bonus = 0;
+            }
+
+            let shared = shared as u128 * payout_ppm / Self::PPM;
+            let bonus = bonus as u128 * payout_ppm / Self::PPM;
+
+            for (payout, locked) in payouts.iter_mut().zip(locked) {
+                payout.locked |= locked;
+
+                if total_stake > 0 {
+                    payout.payout += (shared * payout.stake as u128 / total_stake as u128) as u64;
+                }
+
+                if !locked && bonus > 0 {
+                    payout.payout += (bonus * payout.stake as u128 / unlocked_stake as u128) as u64;
+                }
+            }
+        }
+
+        // largest payouts first
+        payouts.sort_by(|a, b| {
+            b.payout
+                .cmp(&a.payout)
+                .then(b.stake.cmp(&a.stake))
+                .then(a.public_key.cmp(&b.public_key))
+        });
+
+        let total_payouts: u64 = payouts.iter().map(|p| p.payout).sum();
+        Ok(Self {
+            delegate,
+            epoch,
+            genesis_state_hash,
+            scheme,
+            fee_percent,
+            total_stake,
+            num_blocks: blocks.len() as u32,
+            num_supercharged_blocks: blocks.iter().filter(|b| b.supercharged).count() as u32,
+            total_rewards,
+            operator_reward: total_rewards - total_payouts,
+            payouts,
+        })
+    }
+}
+
+///////////////////
+// display/debug //
+///////////////////
+
+impl std::fmt::Display for PayoutScheme {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "{}",
+            match self {
+                Self::Proportional => "proportional",
+                Self::Supercharged => "supercharged",
+                Self::FeeSplit => "fee-split",
+            }
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{base::numeric::Numeric, constants::MAINNET_COINBASE_REWARD};
+    use quickcheck::{Arbitrary, Gen};
+
+    const MINA: u64 = 1_000_000_000;
+
+    fn block(global_slot: u32, supercharged: bool, fees: u64) -> PoolBlock {
+        PoolBlock {
+            state_hash: StateHash::default(),
+            global_slot,
+            coinbase: if supercharged {
+                2 * MAINNET_COINBASE_REWARD
+            } else {
+                MAINNET_COINBASE_REWARD
+            },
+            supercharged,
+            fees,
+        }
+    }
+
+    /// Unlocked & locked (until slot 100) delegators with 3:1 stake
+    fn delegators() -> Vec<PoolDelegator> {
+        let g = &mut Gen::new(1000);
+        let unlocked = PublicKey::arbitrary(g);
+        let locked = PublicKey::arbitrary_not(g, &Some(unlocked.clone()));
+
+        vec![
+            PoolDelegator {
+                public_key: unlocked,
+                stake: 3_000 * MINA,
+                timing: None,
+            },
+            PoolDelegator {
+                public_key: locked,
+                stake: 1_000 * MINA,
+                timing: Some(Timing {
+                    cliff_time: Numeric(100),
+                    vesting_period: Numeric(1),
+                    cliff_amount: Numeric(1_000 * MINA),
+                    vesting_increment: Numeric(0),
+                    initial_minimum_balance: Numeric(1_000 * MINA),
+                }),
+            },
+        ]
+    }
+
+    fn payouts(
+        scheme: PayoutScheme,
+        fee_percent: f64,
+        blocks: &[PoolBlock],
+    ) -> anyhow::Result<PoolPayouts> {
+        PoolPayouts::compute(
+            PublicKey::default(),
+            0,
+            StateHash::default(),
+            scheme,
+            fee_percent,
+            blocks,
+            delegators(),
+        )
+    }
+
+    #[test]
+    fn proportional() -> anyhow::Result<()> {
+        let fees = 4 * MINA;
+        let res = payouts(PayoutScheme::Proportional, 5.0, &[block(10, false, fees)])?;
+        let rewards = MAINNET_COINBASE_REWARD + fees;
+        let paid = rewards / 100 * 95;
+
+        assert_eq!(res.total_rewards, rewards);
+        assert_eq!(res.payouts[0].payout, paid / 4 * 3);
+        assert_eq!(res.payouts[1].payout, paid / 4);
+        assert!(!res.payouts[0].locked);
+        assert!(res.payouts[1].locked);
+        assert_eq!(res.operator_reward, rewards - paid);
+        Ok(())
+    }
+
+    #[test]
+    fn supercharged_bonus_to_unlocked() -> anyhow::Result<()> {
+        // locked at slot 10, unlocked at slot 200
+        let blocks = [block(10, true, 0), block(200, true, 0)];
+        let res = payouts(PayoutScheme::Supercharged, 0.0, &blocks)?;
+
+        // base coinbases are shared, the first bonus only with the unlocked
+        let base = 2 * MAINNET_COINBASE_REWARD;
+        assert_eq!(res.num_supercharged_blocks, 2);
+        assert_eq!(
+            res.payouts[0].payout,
+            base / 4 * 3 + MAINNET_COINBASE_REWARD + MAINNET_COINBASE_REWARD / 4 * 3
+        );
+        assert_eq!(
+            res.payouts[1].payout,
+            base / 4 + MAINNET_COINBASE_REWARD / 4
+        );
+        assert_eq!(res.operator_reward, 0);
+
+        // proportional shares the bonuses
+        let res = payouts(PayoutScheme::Proportional, 0.0, &blocks)?;
+        assert_eq!(res.payouts[1].payout, 4 * MAINNET_COINBASE_REWARD / 4);
+        Ok(())
+    }
+
+    #[test]
+    fn fee_split_keeps_fees() -> anyhow::Result<()> {
+        let fees = 10 * MINA;
+        let res = payouts(PayoutScheme::FeeSplit, 0.0, &[block(10, false, fees)])?;
+
+        assert_eq!(res.payouts[0].payout, MAINNET_COINBASE_REWARD / 4 * 3);
+        assert_eq!(res.payouts[1].payout, MAINNET_COINBASE_REWARD / 4);
+        assert_eq!(res.operator_reward, fees);
+        Ok(())
+    }
+
+    #[test]
+    fn invalid_fee_percent() {
+        assert!(payouts(PayoutScheme::Proportional, 100.5, &[]).is_err());
+        assert!(payouts(PayoutScheme::Proportional, -1.0, &[]).is_err());
+        assert!(payouts(PayoutScheme::Proportional, f64::NAN, &[]).is_err());
+    }
+}
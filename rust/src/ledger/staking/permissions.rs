@@ -100,6 +100,51 @@ pub enum PermissionPermission {
     Impossible,
 }
 
+impl StakingPermissions {
+    /// Legacy random oracle input bits, fields folded last to first
+    pub fn to_bits_legacy(&self) -> Vec<bool> {
+        let receive = self
+            .receive
+            .clone()
+            .unwrap_or(Permission::None(PermissionNone::None));
+
+        [
+            &self.set_verification_key,
+            &self.set_permissions,
+            &self.set_delegate,
+            &receive,
+            &self.send,
+            &self.edit_state,
+        ]
+        .into_iter()
+        .flat_map(Permission::to_bits_legacy)
+        .chain(std::iter::once(self.stake.unwrap_or(true)))
+        .collect()
+    }
+}
+
+impl Permission {
+    /// `[constant, signature_necessary, signature_sufficient]`
+    fn to_bits_legacy(&self) -> [bool; 3] {
+        let auth = match self {
+            Self::Auth(PermissionAuth { auth, .. }) => auth.clone(),
+            Self::Signature(_) => PermissionPermission::Signature,
+            Self::Proof(_) => PermissionPermission::Proof,
+            Self::None(_) => PermissionPermission::None,
+            Self::Either(_) => PermissionPermission::Either,
+            Self::Impossible(_) => PermissionPermission::Impossible,
+        };
+
+        match auth {
+            PermissionPermission::None => [true, false, true],
+            PermissionPermission::Either => [false, false, true],
+            PermissionPermission::Proof => [false, false, false],
+            PermissionPermission::Signature => [false, true, true],
+            PermissionPermission::Impossible => [true, true, false],
+        }
+    }
+}
+
 impl std::default::Default for Permission {
     fn default() -> Self {
         Self::Signature(PermissionSignature::Signature)
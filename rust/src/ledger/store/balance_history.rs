@@ -0,0 +1,44 @@
+//! Balance history store trait
+
+use crate::{
+    base::{public_key::PublicKey, state_hash::StateHash},
+    ledger::token::TokenAddress,
+    store::Result,
+};
+use serde::{Deserialize, Serialize};
+
+/// Token account balance after a best chain block
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct BalanceHistoryEntry {
+    pub state_hash: StateHash,
+    pub block_height: u32,
+    pub balance: u64,
+}
+
+pub trait BalanceHistoryStore {
+    /// Record the token account's balance after the block
+    fn set_balance_history(
+        &self,
+        pk: &PublicKey,
+        token: &TokenAddress,
+        entry: &BalanceHistoryEntry,
+    ) -> Result<()>;
+
+    /// Remove the token account's balance after the block at `block_height`
+    fn remove_balance_history(
+        &self,
+        pk: &PublicKey,
+        token: &TokenAddress,
+        block_height: u32,
+    ) -> Result<()>;
+
+    /// Get the token account's balance history between the (inclusive)
+    /// heights, in ascending height order
+    fn get_balance_history(
+        &self,
+        pk: &PublicKey,
+        token: &TokenAddress,
+        from_height: Option<u32>,
+        to_height: Option<u32>,
+    ) -> Result<Vec<BalanceHistoryEntry>>;
+}
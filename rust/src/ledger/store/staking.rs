@@ -34,6 +34,25 @@ pub trait StakingLedgerStore {
     /// Add a staking ledger
     fn add_staking_ledger(&self, staking_ledger: StakingLedger) -> Result<()>;
 
+    /// If the canonical block's canonical parent is in an earlier epoch,
+    /// derive the following epoch's staking ledger from the snarked ledger
+    /// as of the end of the previous epoch, i.e. the parent's snarked ledger
+    /// (see [Self::derive_staking_ledger])
+    fn add_derived_staking_ledger(&self, state_hash: &StateHash) -> Result<()>;
+
+    /// Derive the `epoch` staking ledger from the staged ledger of the latest
+    /// canonical block, up to the canonical block `state_hash`, whose staged
+    /// ledger hash is `ledger_hash`. It's persisted unless a staking ledger
+    /// was supplied for the epoch, which is cross-checked against the derived
+    /// one & mismatches are reported.
+    fn derive_staking_ledger(
+        &self,
+        state_hash: &StateHash,
+        epoch: u32,
+        ledger_hash: &LedgerHash,
+        genesis_state_hash: &StateHash,
+    ) -> Result<()>;
+
     /// Get the staking ledger with the given hash & epoch
     fn get_staking_ledger(
         &self,
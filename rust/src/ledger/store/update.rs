@@ -3,6 +3,7 @@
 use crate::{
     base::{public_key::PublicKey, state_hash::StateHash},
     block::store::BlockStore,
+    command::{TxnHash, UserCommandWithStatusT},
     ledger::{
         account::Account,
         diff::{
@@ -13,15 +14,21 @@ use crate::{
     },
     store::{
         zkapp::{
-            actions::ZkappActionStore, events::ZkappEventStore, tokens::ZkappTokenStore, ZkappStore,
+            actions::{ZkappActionStore, ZkappActionWithMeta},
+            events::{ZkappEventStore, ZkappEventWithMeta},
+            tokens::ZkappTokenStore,
+            ZkappStore,
         },
         DbUpdate, IndexerStore, Result,
     },
 };
-use std::collections::HashSet;
+use anyhow::Context;
+use std::collections::{HashSet, VecDeque};
 
 #[derive(Debug, Clone)]
 pub struct AccountUpdate {
+    pub block_state_hash: StateHash,
+    pub blockchain_length: u32,
     pub account_diffs: Vec<AccountDiff>,
     pub token_diffs: Vec<TokenDiff>,
     pub new_accounts: HashSet<(PublicKey, TokenAddress)>,
@@ -42,6 +49,8 @@ impl DbAccountUpdate {
         block_height: u32,
     ) -> Result<()> {
         for AccountUpdate {
+            block_state_hash,
+            blockchain_length,
             account_diffs,
             token_diffs,
             new_accounts,
@@ -49,6 +58,7 @@ impl DbAccountUpdate {
         } in apply.into_iter()
         {
             let token_account_diffs = aggregate_token_account_diffs(account_diffs);
+            let mut zkapp_txn_hashes = None;
 
             // apply account diffs
             for ((pk, token), diffs) in token_account_diffs {
@@ -128,11 +138,45 @@ impl DbAccountUpdate {
 
                         // these diffs do not modify the account
                         ZkappActions(diff) => {
-                            db.add_actions(&diff.public_key, &diff.token, &diff.actions)?;
+                            let txn_hash = ZkappTxnHashes::get_or_init(
+                                &mut zkapp_txn_hashes,
+                                db,
+                                &block_state_hash,
+                            )?
+                            .next_action_txn_hash(&diff.public_key, &diff.token)?;
+                            let actions: Vec<_> = diff
+                                .actions
+                                .iter()
+                                .map(|action| ZkappActionWithMeta {
+                                    action: action.to_owned(),
+                                    state_hash: block_state_hash.to_owned(),
+                                    block_height: blockchain_length,
+                                    txn_hash: txn_hash.to_owned(),
+                                })
+                                .collect();
+
+                            db.add_actions(&diff.public_key, &diff.token, &actions)?;
                             after
                         }
                         ZkappEvents(diff) => {
-                            db.add_events(&diff.public_key, &diff.token, &diff.events)?;
+                            let txn_hash = ZkappTxnHashes::get_or_init(
+                                &mut zkapp_txn_hashes,
+                                db,
+                                &block_state_hash,
+                            )?
+                            .next_event_txn_hash(&diff.public_key, &diff.token)?;
+                            let events: Vec<_> = diff
+                                .events
+                                .iter()
+                                .map(|event| ZkappEventWithMeta {
+                                    event: event.to_owned(),
+                                    state_hash: block_state_hash.to_owned(),
+                                    block_height: blockchain_length,
+                                    txn_hash: txn_hash.to_owned(),
+                                })
+                                .collect();
+
+                            db.add_events(&diff.public_key, &diff.token, &events)?;
                             after
                         }
                         // zkapp account diffs should be expanded
@@ -140,6 +184,19 @@ impl DbAccountUpdate {
                     };
                 }
 
+                // record post-block balance
+                if diffs.iter().any(changes_balance) {
+                    db.set_balance_history(
+                        &pk,
+                        &token,
+                        &BalanceHistoryEntry {
+                            state_hash: block_state_hash.to_owned(),
+                            block_height: blockchain_length,
+                            balance: after.clone().deduct_mina_account_creation_fee().balance.0,
+                        },
+                    )?;
+                }
+
                 // update staged ledger account
                 db.set_staged_account(&pk, &token, state_hash, block_height, &after)?;
 
@@ -176,6 +233,7 @@ impl DbAccountUpdate {
     ) -> Result<()> {
         // unapply account & token diffs, remove accounts
         for AccountUpdate {
+            blockchain_length,
             account_diffs,
             token_diffs,
             new_accounts,
@@ -185,6 +243,10 @@ impl DbAccountUpdate {
             let token_account_diffs = aggregate_token_account_diffs(account_diffs);
 
             for ((pk, token), diffs) in token_account_diffs {
+                if diffs.iter().any(changes_balance) {
+                    db.remove_balance_history(&pk, &token, blockchain_length)?;
+                }
+
                 let before = db.get_best_account(&pk, &token)?;
                 let (before_values, mut after) = (
                     before.as_ref().map(|a| (a.is_zkapp_account(), a.balance.0)),
@@ -348,9 +410,27 @@ impl DbAccountUpdate {
     }
 }
 
-use super::{best::BestLedgerStore, staged::StagedLedgerStore};
+use super::{
+    balance_history::{BalanceHistoryEntry, BalanceHistoryStore},
+    best::BestLedgerStore,
+    staged::StagedLedgerStore,
+};
 use std::collections::HashMap;
 
+/// Whether the diff modifies the account's balance
+fn changes_balance(diff: &AccountDiff) -> bool {
+    use AccountDiff::*;
+
+    matches!(
+        diff,
+        Payment(_)
+            | FeeTransfer(_)
+            | FeeTransferViaCoinbase(_)
+            | Coinbase(_)
+            | ZkappPayment(ZkappPaymentDiff::Payment { .. })
+    )
+}
+
 /// Aggregate diffs per token account
 fn aggregate_token_account_diffs(
     account_diffs: Vec<AccountDiff>,
@@ -372,6 +452,103 @@ fn aggregate_token_account_diffs(
     token_account_diffs
 }
 
+/// The block's zkapp actions & events account diffs, in block order, with
+/// the hash of the applied zkapp command producing each
+pub(crate) fn zkapp_action_event_diffs(
+    db: &IndexerStore,
+    state_hash: &StateHash,
+) -> Result<Vec<(TxnHash, AccountDiff)>> {
+    let (block, _) = db
+        .get_block(state_hash)?
+        .with_context(|| format!("block missing from store {state_hash}"))?;
+    let mut action_event_diffs = vec![];
+
+    for command in block.zkapp_commands() {
+        if !command.is_applied() {
+            continue;
+        }
+
+        let txn_hash = command.hash()?;
+        let diffs = AccountDiff::expand(AccountDiff::from_command(
+            command.to_command(state_hash.to_owned()),
+            block.global_slot_since_genesis(),
+        ));
+
+        for diff in diffs.into_iter().flatten() {
+            if matches!(
+                diff,
+                AccountDiff::ZkappActions(_) | AccountDiff::ZkappEvents(_)
+            ) {
+                action_event_diffs.push((txn_hash.to_owned(), diff));
+            }
+        }
+    }
+
+    Ok(action_event_diffs)
+}
+
+/// Hashes of the zkapp commands producing actions & events in a block
+///
+/// Each token account's hashes are queued in block order, matching the
+/// order of the block's zkapp actions & events account diffs
+#[derive(Debug, Default)]
+struct ZkappTxnHashes {
+    actions: HashMap<(PublicKey, TokenAddress), VecDeque<TxnHash>>,
+    events: HashMap<(PublicKey, TokenAddress), VecDeque<TxnHash>>,
+}
+
+impl ZkappTxnHashes {
+    /// Lazily computes the block's zkapp txn hashes, only needed for blocks
+    /// containing actions or events
+    fn get_or_init<'a>(
+        txn_hashes: &'a mut Option<Self>,
+        db: &IndexerStore,
+        state_hash: &StateHash,
+    ) -> Result<&'a mut Self> {
+        if txn_hashes.is_none() {
+            *txn_hashes = Some(Self::new(db, state_hash)?);
+        }
+
+        Ok(txn_hashes.as_mut().expect("zkapp txn hashes"))
+    }
+
+    fn new(db: &IndexerStore, state_hash: &StateHash) -> Result<Self> {
+        let mut txn_hashes = Self::default();
+
+        for (txn_hash, diff) in zkapp_action_event_diffs(db, state_hash)? {
+            match diff {
+                AccountDiff::ZkappActions(diff) => txn_hashes
+                    .actions
+                    .entry((diff.public_key, diff.token))
+                    .or_default()
+                    .push_back(txn_hash),
+                AccountDiff::ZkappEvents(diff) => txn_hashes
+                    .events
+                    .entry((diff.public_key, diff.token))
+                    .or_default()
+                    .push_back(txn_hash),
+                _ => (),
+            }
+        }
+
+        Ok(txn_hashes)
+    }
+
+    fn next_action_txn_hash(&mut self, pk: &PublicKey, token: &TokenAddress) -> Result<TxnHash> {
+        self.actions
+            .get_mut(&(pk.to_owned(), token.to_owned()))
+            .and_then(VecDeque::pop_front)
+            .with_context(|| format!("missing actions txn hash for ({pk}, {token})"))
+    }
+
+    fn next_event_txn_hash(&mut self, pk: &PublicKey, token: &TokenAddress) -> Result<TxnHash> {
+        self.events
+            .get_mut(&(pk.to_owned(), token.to_owned()))
+            .and_then(VecDeque::pop_front)
+            .with_context(|| format!("missing events txn hash for ({pk}, {token})"))
+    }
+}
+
 /// Aggregate token diffs per token
 fn aggregate_token_diffs(token_diffs: Vec<TokenDiff>) -> HashMap<TokenAddress, Vec<TokenDiff>> {
     let mut acc = <HashMap<TokenAddress, Vec<TokenDiff>>>::with_capacity(token_diffs.len());
@@ -0,0 +1,164 @@
+//! MINA supply computed from ledger vesting schedules
+
+use super::{
+    store::{best::BestLedgerStore, staged::StagedLedgerStore},
+    token::TokenAddress,
+    Ledger,
+};
+use crate::{
+    base::state_hash::StateHash,
+    block::store::BlockStore,
+    canonicity::{store::CanonicityStore, Canonicity},
+    store::IndexerStore,
+};
+use anyhow::Context;
+use serde::{Deserialize, Serialize};
+
+/// MINA supply (nanomina) at a global slot
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Supply {
+    /// Block whose ledger the supply is computed from
+    pub state_hash: StateHash,
+    pub block_height: u32,
+    pub global_slot: u32,
+
+    /// Protocol state total currency
+    pub total_currency: u64,
+
+    /// Sum of timed accounts' current minimum balances
+    pub locked: u64,
+
+    /// Ledger balances minus the locked supply
+    pub liquid: u64,
+
+    /// Total currency minus the locked supply
+    pub circulating: u64,
+}
+
+impl Supply {
+    /// Supply of the `ledger` at `global_slot`
+    pub fn new(
+        ledger: &Ledger,
+        state_hash: StateHash,
+        block_height: u32,
+        global_slot: u32,
+        total_currency: u64,
+    ) -> Self {
+        let locked = ledger.time_locked_amount(global_slot).0;
+        let balances: u64 = ledger
+            .tokens
+            .get(&TokenAddress::default())
+            .map(|mina_ledger| {
+                mina_ledger
+                    .accounts
+                    .values()
+                    .map(|account| account.clone().deduct_mina_account_creation_fee().balance.0)
+                    .sum()
+            })
+            .unwrap_or_default();
+
+        Self {
+            state_hash,
+            block_height,
+            global_slot,
+            total_currency,
+            locked,
+            liquid: balances.saturating_sub(locked),
+            circulating: total_currency.saturating_sub(locked),
+        }
+    }
+
+    /// Best tip supply, cached until the best tip changes
+    pub fn at_best_tip(db: &IndexerStore) -> anyhow::Result<Option<Self>> {
+        let Some(best_hash) = db.get_best_block_hash()? else {
+            return Ok(None);
+        };
+
+        if let Some(supply) = db.best_supply.read().expect("best supply lock").as_ref() {
+            if supply.state_hash == best_hash {
+                return Ok(Some(supply.clone()));
+            }
+        }
+
+        let supply = Self::at_slot(db, None)?;
+        if let Some(supply) = supply.as_ref() {
+            *db.best_supply.write().expect("best supply lock") = Some(supply.clone());
+        }
+
+        Ok(supply)
+    }
+
+    /// Supply at `global_slot` computed from the ledger of the last best chain
+    /// block at or before it. Defaults to the best tip's slot.
+    ///
+    /// Slots past the best tip use the best ledger, i.e. vesting is projected
+    pub fn at_slot(db: &IndexerStore, global_slot: Option<u32>) -> anyhow::Result<Option<Self>> {
+        let (Some(best_hash), Some(best_slot)) =
+            (db.get_best_block_hash()?, db.get_best_block_global_slot()?)
+        else {
+            return Ok(None);
+        };
+
+        let global_slot = global_slot.unwrap_or(best_slot);
+        let state_hash = if global_slot >= best_slot {
+            Some(best_hash.clone())
+        } else {
+            supply_block(db, best_hash.clone(), global_slot)?
+        };
+
+        let Some(state_hash) = state_hash else {
+            return Ok(None);
+        };
+
+        let ledger = if state_hash == best_hash {
+            db.get_best_ledger(false)?
+        } else {
+            db.get_staged_ledger_at_state_hash(&state_hash, false)?
+        };
+
+        let Some(ledger) = ledger else {
+            return Ok(None);
+        };
+
+        let (block, _) = db
+            .get_block(&state_hash)?
+            .with_context(|| format!("block missing from store {state_hash}"))?;
+
+        Ok(Some(Self::new(
+            &ledger,
+            state_hash,
+            block.blockchain_length(),
+            global_slot,
+            block.total_currency(),
+        )))
+    }
+}
+
+/// Last best chain block at or before `global_slot`
+///
+/// Walks back from the best tip through the non-canonical blocks, then looks
+/// up the canonical block by slot
+fn supply_block(
+    db: &IndexerStore,
+    best_hash: StateHash,
+    global_slot: u32,
+) -> anyhow::Result<Option<StateHash>> {
+    let mut curr = Some(best_hash);
+
+    while let Some(state_hash) = curr {
+        if let Some(Canonicity::Canonical) = db.get_block_canonicity(&state_hash)? {
+            break;
+        }
+
+        if db
+            .get_block_global_slot(&state_hash)?
+            .is_some_and(|slot| slot <= global_slot)
+        {
+            return Ok(Some(state_hash));
+        }
+
+        curr = db.get_block_parent_hash(&state_hash)?;
+    }
+
+    db.get_canonical_hash_at_or_before_slot(global_slot)
+}
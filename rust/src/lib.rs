@@ -1,5 +1,6 @@
 extern crate core;
 
+pub mod address_book;
 pub mod base;
 pub mod block;
 pub mod canonicity;
@@ -10,6 +11,7 @@ pub mod command;
 pub mod constants;
 pub mod event;
 pub mod ledger;
+pub mod metrics;
 pub mod mina_blocks;
 pub mod proof_systems;
 pub mod protocol;
@@ -20,6 +22,7 @@ pub mod store;
 pub mod unix_socket_server;
 pub mod utility;
 pub mod web;
+pub mod webhook;
 
 #[cfg(target_family = "unix")]
 pub mod platform {
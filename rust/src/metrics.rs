@@ -0,0 +1,320 @@
+//! Prometheus metrics for ingestion, store & API health
+
+use crate::{
+    block::store::BlockStore, canonicity::store::CanonicityStore,
+    constants::MAINNET_TRANSITION_FRONTIER_K, event::store::EventStore, store::IndexerStore,
+};
+use std::{
+    collections::BTreeMap,
+    fmt::Write,
+    sync::{
+        atomic::{AtomicU64, Ordering},
+        Mutex,
+    },
+    time::{Duration, SystemTime, UNIX_EPOCH},
+};
+
+/// Latency histogram bucket upper bounds (sec)
+pub const LATENCY_BUCKETS: [f64; 10] = [0.001, 0.005, 0.01, 0.025, 0.05, 0.1, 0.25, 0.5, 1.0, 5.0];
+
+/// Indexer metrics, updated by the indexer state & API handlers and rendered
+/// in the Prometheus text exposition format
+#[derive(Debug, Default)]
+pub struct Metrics {
+    /// Blocks added to the witness tree, blocks in the store on replicas
+    pub blocks_processed: AtomicU64,
+
+    /// Block bytes added to the witness tree
+    pub bytes_processed: AtomicU64,
+
+    pub best_tip_height: AtomicU64,
+    pub canonical_root_height: AtomicU64,
+
+    /// Number of blocks in the witness tree
+    pub witness_tree_size: AtomicU64,
+    pub num_dangling_branches: AtomicU64,
+
+    /// Unix timestamp (sec) of the last witness tree update, stalls when
+    /// ingestion stalls
+    pub last_update_timestamp: AtomicU64,
+
+    /// Latency per top-level GraphQL resolver
+    graphql_latency: Mutex<BTreeMap<String, Histogram>>,
+
+    /// Latency per Unix domain socket command
+    socket_latency: Mutex<BTreeMap<String, Histogram>>,
+}
+
+#[derive(Debug, Default, Clone, PartialEq)]
+pub struct Histogram {
+    /// Non-cumulative counts per [LATENCY_BUCKETS] bucket
+    buckets: [u64; LATENCY_BUCKETS.len()],
+    sum: f64,
+    count: u64,
+}
+
+///////////
+// impls //
+///////////
+
+impl Histogram {
+    pub fn observe(&mut self, secs: f64) {
+        if let Some(idx) = LATENCY_BUCKETS.iter().position(|le| secs <= *le) {
+            self.buckets[idx] += 1;
+        }
+
+        self.sum += secs;
+        self.count += 1;
+    }
+
+    fn render(&self, out: &mut String, name: &str, label: &str, value: &str) {
+        let mut cumulative = 0;
+        for (le, count) in LATENCY_BUCKETS.iter().zip(self.buckets) {
+            cumulative += count;
+            writeln!(
+                out,
+                "{name}_bucket{{{label}=\"{value}\",le=\"{le}\"}} {cumulative}"
+            )
+            .ok();
+        }
+
+        writeln!(
+            out,
+            "{name}_bucket{{{label}=\"{value}\",le=\"+Inf\"}} {}",
+            self.count
+        )
+        .ok();
+        writeln!(out, "{name}_sum{{{label}=\"{value}\"}} {}", self.sum).ok();
+        writeln!(out, "{name}_count{{{label}=\"{value}\"}} {}", self.count).ok();
+    }
+}
+
+impl Metrics {
+    pub fn set(gauge: &AtomicU64, value: u64) {
+        gauge.store(value, Ordering::Relaxed);
+    }
+
+    /// Records the current time as the last witness tree update
+    pub fn touch(&self) {
+        let now = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .unwrap_or_default()
+            .as_secs();
+        Self::set(&self.last_update_timestamp, now);
+    }
+
+    /// Exports the store's best tip & canonical root, for read replicas
+    /// which have no witness tree
+    pub fn update_from_store(&self, store: &IndexerStore) -> anyhow::Result<()> {
+        let Some(best_tip_height) = store.get_best_block_height()? else {
+            return Ok(());
+        };
+
+        // the canonical root is the highest canonical block
+        let mut canonical_root_height = 0;
+        for height in
+            (best_tip_height.saturating_sub(MAINNET_TRANSITION_FRONTIER_K)..=best_tip_height).rev()
+        {
+            if store.get_canonical_hash_at_height(height)?.is_some() {
+                canonical_root_height = height;
+                break;
+            }
+        }
+
+        if self.best_tip_height.load(Ordering::Relaxed) != best_tip_height as u64 {
+            self.touch();
+        }
+
+        Self::set(&self.best_tip_height, best_tip_height as u64);
+        Self::set(&self.canonical_root_height, canonical_root_height as u64);
+        Self::set(
+            &self.blocks_processed,
+            store.get_block_production_total_count()? as u64,
+        );
+        Ok(())
+    }
+
+    pub fn observe_graphql(&self, resolver: &str, elapsed: Duration) {
+        observe(&self.graphql_latency, resolver, elapsed);
+    }
+
+    pub fn observe_socket(&self, command: &str, elapsed: Duration) {
+        observe(&self.socket_latency, command, elapsed);
+    }
+
+    /// Prometheus text exposition of the indexer & store metrics
+    pub fn render(&self, store: &IndexerStore) -> String {
+        let mut out = String::new();
+        let load = |gauge: &AtomicU64| gauge.load(Ordering::Relaxed);
+
+        for (name, kind, help, value) in [
+            (
+                "mina_indexer_blocks_processed_total",
+                "counter",
+                "Blocks added to the witness tree",
+                load(&self.blocks_processed),
+            ),
+            (
+                "mina_indexer_bytes_processed_total",
+                "counter",
+                "Block bytes added to the witness tree",
+                load(&self.bytes_processed),
+            ),
+            (
+                "mina_indexer_best_tip_height",
+                "gauge",
+                "Best tip blockchain length",
+                load(&self.best_tip_height),
+            ),
+            (
+                "mina_indexer_canonical_root_height",
+                "gauge",
+                "Canonical root blockchain length",
+                load(&self.canonical_root_height),
+            ),
+            (
+                "mina_indexer_witness_tree_size",
+                "gauge",
+                "Blocks in the witness tree",
+                load(&self.witness_tree_size),
+            ),
+            (
+                "mina_indexer_dangling_branches",
+                "gauge",
+                "Dangling branches in the witness tree",
+                load(&self.num_dangling_branches),
+            ),
+            (
+                "mina_indexer_last_update_timestamp_seconds",
+                "gauge",
+                "Unix time of the last witness tree update",
+                load(&self.last_update_timestamp),
+            ),
+            (
+                "mina_indexer_event_sequence_number",
+                "gauge",
+                "Next event log sequence number",
+                store.get_next_seq_num().unwrap_or_default() as u64,
+            ),
+            (
+                "mina_indexer_db_live_data_bytes",
+                "gauge",
+                "Estimated live data size",
+                store.estimate_live_data_size(),
+            ),
+            (
+                "mina_indexer_db_memtable_bytes",
+                "gauge",
+                "Size of all memtables",
+                store.cur_size_all_mem_tables(),
+            ),
+            (
+                "mina_indexer_db_estimated_keys",
+                "gauge",
+                "Estimated number of keys",
+                store.estimate_num_keys(),
+            ),
+        ] {
+            writeln!(out, "# HELP {name} {help}").ok();
+            writeln!(out, "# TYPE {name} {kind}").ok();
+            writeln!(out, "{name} {value}").ok();
+        }
+
+        for (name, help, label, histograms) in [
+            (
+                "mina_indexer_graphql_resolver_seconds",
+                "GraphQL resolver latency",
+                "resolver",
+                &self.graphql_latency,
+            ),
+            (
+                "mina_indexer_socket_command_seconds",
+                "Unix domain socket command latency",
+                "command",
+                &self.socket_latency,
+            ),
+        ] {
+            writeln!(out, "# HELP {name} {help}").ok();
+            writeln!(out, "# TYPE {name} histogram").ok();
+
+            for (value, histogram) in histograms.lock().expect("metrics lock").iter() {
+                histogram.render(&mut out, name, label, value);
+            }
+        }
+
+        out
+    }
+}
+
+fn observe(histograms: &Mutex<BTreeMap<String, Histogram>>, key: &str, elapsed: Duration) {
+    histograms
+        .lock()
+        .expect("metrics lock")
+        .entry(key.to_string())
+        .or_default()
+        .observe(elapsed.as_secs_f64());
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::block::precomputed::{PcbVersion, PrecomputedBlock};
+    use std::path::PathBuf;
+    use tempfile::TempDir;
+
+    #[test]
+    fn histogram_buckets() {
+        let mut histogram = Histogram::default();
+        histogram.observe(0.002);
+        histogram.observe(0.2);
+        histogram.observe(10.0);
+
+        let mut out = String::new();
+        histogram.render(&mut out, "latency", "command", "summary");
+
+        assert!(out.contains("latency_bucket{command=\"summary\",le=\"0.001\"} 0\n"));
+        assert!(out.contains("latency_bucket{command=\"summary\",le=\"0.005\"} 1\n"));
+        assert!(out.contains("latency_bucket{command=\"summary\",le=\"0.25\"} 2\n"));
+        assert!(out.contains("latency_bucket{command=\"summary\",le=\"5\"} 2\n"));
+        assert!(out.contains("latency_bucket{command=\"summary\",le=\"+Inf\"} 3\n"));
+        assert!(out.contains("latency_count{command=\"summary\"} 3\n"));
+    }
+
+    #[test]
+    fn replica_gauges_from_store() -> anyhow::Result<()> {
+        let store_dir = TempDir::with_prefix(std::env::current_dir()?)?;
+        let store = IndexerStore::new(store_dir.path(), true)?;
+        let metrics = Metrics::default();
+
+        // nothing to export from an empty store
+        metrics.update_from_store(&store)?;
+        assert_eq!(metrics.best_tip_height.load(Ordering::Relaxed), 0);
+        assert_eq!(metrics.last_update_timestamp.load(Ordering::Relaxed), 0);
+
+        let path = PathBuf::from("./tests/data/misc_blocks/mainnet-128743-3NLmYZD9eaV58opgC5RzQXaoPbyC15McNxw1CuCNatj7F9vGBbNz.json");
+        let block = PrecomputedBlock::parse_file(&path, PcbVersion::V1)?;
+        let height = block.blockchain_length();
+
+        store.add_block(&block, 0)?;
+        store.set_best_block(&block.state_hash(), height)?;
+        store.add_canonical_block(
+            height,
+            block.global_slot_since_genesis(),
+            &block.state_hash(),
+            &block.genesis_state_hash(),
+            None,
+        )?;
+
+        metrics.update_from_store(&store)?;
+        assert_eq!(
+            metrics.best_tip_height.load(Ordering::Relaxed),
+            height as u64
+        );
+        assert_eq!(
+            metrics.canonical_root_height.load(Ordering::Relaxed),
+            height as u64
+        );
+        assert!(metrics.last_update_timestamp.load(Ordering::Relaxed) > 0);
+        Ok(())
+    }
+}
@@ -413,15 +413,15 @@ pub enum UpdateTiming {
 pub struct Preconditions {
     pub network: NetworkPreconditions,
     pub account: AccountPreconditions,
-    pub valid_while: Precondition<String>,
+    pub valid_while: Precondition<NumericBoundsU32>,
 }
 
 #[derive(Debug, Clone, PartialEq, PartialOrd, Serialize, Deserialize)]
 pub struct NetworkPreconditions {
-    pub snarked_ledger_hash: Precondition<LedgerHashBounds>,
+    pub snarked_ledger_hash: Precondition<LedgerHash>,
     pub blockchain_length: Precondition<NumericBoundsU32>,
     pub min_window_density: Precondition<NumericBoundsU32>,
-    pub total_currency: Precondition<NumericBoundsU32>,
+    pub total_currency: Precondition<NumericBoundsU64>,
     pub global_slot_since_genesis: Precondition<NumericBoundsU32>,
     pub staking_epoch_data: StakingEpochDataPreconditions,
     pub next_epoch_data: StakingEpochDataPreconditions,
@@ -433,13 +433,13 @@ pub struct StakingEpochDataPreconditions {
     pub seed: Precondition<String>,
     pub start_checkpoint: Precondition<String>,
     pub lock_checkpoint: Precondition<String>,
-    pub epoch_length: Precondition<String>,
+    pub epoch_length: Precondition<NumericBoundsU32>,
 }
 
 #[derive(Debug, Clone, PartialEq, PartialOrd, Serialize, Deserialize)]
 pub struct LedgerPreconditions {
     pub hash: Precondition<String>,
-    pub total_currency: Precondition<String>,
+    pub total_currency: Precondition<NumericBoundsU64>,
 }
 
 #[derive(Debug, Clone, PartialEq, PartialOrd, Serialize, Deserialize)]
@@ -463,20 +463,14 @@ pub enum Precondition<T> {
 
 #[derive(Debug, Clone, PartialEq, PartialOrd, Serialize, Deserialize)]
 pub struct NumericBoundsU32 {
-    lower: Numeric<u32>,
-    upper: Numeric<u32>,
+    pub lower: Numeric<u32>,
+    pub upper: Numeric<u32>,
 }
 
 #[derive(Debug, Clone, PartialEq, PartialOrd, Serialize, Deserialize)]
 pub struct NumericBoundsU64 {
-    lower: Numeric<u64>,
-    upper: Numeric<u64>,
-}
-
-#[derive(Debug, Clone, PartialEq, PartialOrd, Serialize, Deserialize)]
-pub struct LedgerHashBounds {
-    lower: LedgerHash,
-    upper: LedgerHash,
+    pub lower: Numeric<u64>,
+    pub upper: Numeric<u64>,
 }
 
 #[derive(Debug, Clone, PartialEq, PartialOrd, Serialize, Deserialize)]
@@ -613,9 +607,14 @@ impl From<UpdateTiming> for Option<Timing> {
 
 #[cfg(test)]
 mod tests {
+    use super::{NumericBoundsU32, Precondition, Preconditions};
     use crate::{
+        base::numeric::Numeric,
         block::precomputed::{PcbVersion, PrecomputedBlock},
-        command::{signed::SignedCommand, to_mina_json, to_zkapp_json, UserCommandWithStatusT},
+        command::{
+            signed::SignedCommand, to_mina_json, to_zkapp_json, UserCommandWithStatus,
+            UserCommandWithStatusT,
+        },
         mina_blocks::v2::staged_ledger_diff::UserCommandData,
     };
     use std::path::PathBuf;
@@ -1017,4 +1016,51 @@ mod tests {
 
         Ok(())
     }
+
+    fn top_level_preconditions(block_file: &str) -> anyhow::Result<Vec<Preconditions>> {
+        let block = PrecomputedBlock::parse_file(&PathBuf::from(block_file), PcbVersion::V2)?;
+
+        Ok(block
+            .commands()
+            .into_iter()
+            .filter_map(|cmd| match cmd {
+                UserCommandWithStatus::V2(v2) => match v2.data.1 {
+                    UserCommandData::ZkappCommandData(data) => Some(data.account_updates),
+                    _ => None,
+                },
+                _ => None,
+            })
+            .flatten()
+            .map(|update| update.elt.account_update.body.preconditions)
+            .collect())
+    }
+
+    #[test]
+    fn zkapp_check_preconditions() -> anyhow::Result<()> {
+        // account preconditions
+        let preconditions = top_level_preconditions("./tests/data/hardfork/mainnet-359611-3NKybkb8C3R5PjwkxNUVCL6tb5qVf5i4jPWkDCcyJbka9Qgvr8CG.json")?;
+        let zero = NumericBoundsU32 {
+            lower: Numeric(0),
+            upper: Numeric(0),
+        };
+
+        assert!(preconditions.iter().any(|pre| {
+            pre.account.nonce == Precondition::Check(("Check".to_string(), zero.clone()))
+                && pre.account.proved_state == Precondition::Check(("Check".to_string(), false))
+        }));
+
+        // network preconditions
+        let preconditions = top_level_preconditions("./tests/data/misc_blocks/mainnet-367464-3NL4rfMMoM5soFpmwcGvafrFSQgbtDWnLUbKFAqPPiSCrEqdfqrF.json")?;
+        let slots = NumericBoundsU32 {
+            lower: Numeric(0),
+            upper: Numeric(578236),
+        };
+
+        assert!(preconditions.iter().any(|pre| {
+            pre.network.global_slot_since_genesis
+                == Precondition::Check(("Check".to_string(), slots.clone()))
+        }));
+
+        Ok(())
+    }
 }
@@ -2,4 +2,4 @@ pub mod curves;
 
 pub use curves::pallas::Pallas;
 
-mod fields;
+pub mod fields;
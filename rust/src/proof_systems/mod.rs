@@ -3,6 +3,7 @@ use num::BigUint;
 use thiserror::Error;
 
 pub mod curves;
+pub mod poseidon;
 pub mod signer;
 
 /// Field helpers error
@@ -0,0 +1,418 @@
+//! Poseidon permutation & arithmetic sponge over the Pasta base field
+//!
+//! Mina uses two parameter sets:
+//! - legacy (pre-Berkeley): width 3, rate 2, x^5 sbox, 63 full rounds with an
+//!   initial round constant addition
+//! - kimchi (Berkeley): width 3, rate 2, x^7 sbox, 55 full rounds
+//!
+//! The round constants & MDS matrices are compiled in ([SpongeParams::vendored]),
+//! o1-labs JSON parameter tables can override them ([SpongeParams::load])
+
+mod params;
+
+use super::{curves::pasta::fields::fp::Fp, FieldHelpers};
+use anyhow::{bail, Context};
+use ark_ff::{Field, Zero};
+use serde::Deserialize;
+use std::{path::Path, sync::OnceLock};
+
+/// Poseidon parameter set
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PoseidonKind {
+    Legacy,
+    Kimchi,
+}
+
+/// Poseidon sponge parameters
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SpongeParams {
+    pub kind: PoseidonKind,
+
+    /// Sponge rate, the capacity is the remaining state width
+    pub rate: usize,
+
+    /// sbox exponent
+    pub alpha: u64,
+
+    /// Number of full rounds
+    pub full_rounds: usize,
+
+    /// `width x width` MDS matrix
+    pub mds: Vec<Vec<Fp>>,
+
+    /// One row of `width` constants per round (plus one for the initial
+    /// legacy round constant addition)
+    pub round_constants: Vec<Vec<Fp>>,
+}
+
+/// Arithmetic sponge over [Fp]
+#[derive(Debug, Clone)]
+pub struct Sponge<'a> {
+    params: &'a SpongeParams,
+    state: Vec<Fp>,
+    offset: usize,
+    squeezing: bool,
+}
+
+/// o1-labs parameter table: little-endian hex field elements
+#[derive(Debug, Deserialize)]
+struct ParamsTable {
+    mds: Vec<Vec<String>>,
+    round_constants: Vec<Vec<String>>,
+}
+
+///////////
+// impls //
+///////////
+
+impl PoseidonKind {
+    fn file_name(&self) -> &'static str {
+        match self {
+            Self::Legacy => "legacy.json",
+            Self::Kimchi => "kimchi.json",
+        }
+    }
+
+    fn alpha(&self) -> u64 {
+        match self {
+            Self::Legacy => 5,
+            Self::Kimchi => 7,
+        }
+    }
+
+    fn full_rounds(&self) -> usize {
+        match self {
+            Self::Legacy => 63,
+            Self::Kimchi => 55,
+        }
+    }
+
+    fn initial_ark(&self) -> bool {
+        matches!(self, Self::Legacy)
+    }
+
+    fn vendored_table(&self) -> (&'static [[&'static str; 3]], &'static [[&'static str; 3]]) {
+        match self {
+            Self::Legacy => (params::LEGACY_MDS, params::LEGACY_ROUND_CONSTANTS),
+            Self::Kimchi => (params::KIMCHI_MDS, params::KIMCHI_ROUND_CONSTANTS),
+        }
+    }
+}
+
+impl SpongeParams {
+    pub const WIDTH: usize = 3;
+    pub const RATE: usize = 2;
+
+    /// Compiled-in `kind` parameters
+    pub fn vendored(kind: PoseidonKind) -> anyhow::Result<&'static Self> {
+        static LEGACY: OnceLock<Result<SpongeParams, String>> = OnceLock::new();
+        static KIMCHI: OnceLock<Result<SpongeParams, String>> = OnceLock::new();
+
+        let cell = match kind {
+            PoseidonKind::Legacy => &LEGACY,
+            PoseidonKind::Kimchi => &KIMCHI,
+        };
+
+        cell.get_or_init(|| {
+            let (mds, round_constants) = kind.vendored_table();
+            let rows = |table: &'static [[&'static str; 3]]| -> Vec<Vec<&'static str>> {
+                table.iter().map(|row| row.to_vec()).collect()
+            };
+
+            Self::from_rows(kind, rows(mds), rows(round_constants))
+                .map_err(|e| format!("vendored {kind:?} Poseidon constants are missing: {e}"))
+        })
+        .as_ref()
+        .map_err(|e| anyhow::anyhow!("{e}"))
+    }
+
+    /// Parameters from the `params_dir` override, if given, else the
+    /// compiled-in ones
+    pub fn resolve(params_dir: Option<&Path>, kind: PoseidonKind) -> anyhow::Result<Self> {
+        match params_dir {
+            Some(params_dir) => Self::load(params_dir, kind),
+            None => Self::vendored(kind).cloned(),
+        }
+    }
+
+    /// Loads the `kind` parameter table from `params_dir`
+    pub fn load(params_dir: &Path, kind: PoseidonKind) -> anyhow::Result<Self> {
+        let path = params_dir.join(kind.file_name());
+        let contents = std::fs::read(&path)
+            .with_context(|| format!("missing Poseidon parameters {}", path.display()))?;
+
+        Self::from_json(&contents, kind)
+    }
+
+    /// Parses a parameter table in the o1-labs JSON format
+    pub fn from_json(contents: &[u8], kind: PoseidonKind) -> anyhow::Result<Self> {
+        let table: ParamsTable = serde_json::from_slice(contents)?;
+        Self::from_rows(kind, table.mds, table.round_constants)
+    }
+
+    /// Parses rows of little-endian hex field elements
+    fn from_rows<S>(
+        kind: PoseidonKind,
+        mds: Vec<Vec<S>>,
+        round_constants: Vec<Vec<S>>,
+    ) -> anyhow::Result<Self>
+    where
+        S: AsRef<str>,
+    {
+        let parse = |rows: Vec<Vec<S>>| -> anyhow::Result<Vec<Vec<Fp>>> {
+            rows.iter()
+                .map(|row| {
+                    row.iter()
+                        .map(|hex| {
+                            let hex = hex.as_ref();
+                            Fp::from_hex(hex).map_err(|e| anyhow::anyhow!("{e}: {hex}"))
+                        })
+                        .collect()
+                })
+                .collect()
+        };
+
+        Self::new(kind, parse(mds)?, parse(round_constants)?)
+    }
+
+    pub fn new(
+        kind: PoseidonKind,
+        mds: Vec<Vec<Fp>>,
+        round_constants: Vec<Vec<Fp>>,
+    ) -> anyhow::Result<Self> {
+        let full_rounds = kind.full_rounds();
+        let num_constants = full_rounds + kind.initial_ark() as usize;
+
+        if mds.len() != Self::WIDTH || mds.iter().any(|row| row.len() != Self::WIDTH) {
+            bail!("Poseidon MDS matrix must be {0}x{0}", Self::WIDTH)
+        }
+
+        if round_constants.len() != num_constants
+            || round_constants.iter().any(|row| row.len() != Self::WIDTH)
+        {
+            bail!(
+                "{kind:?} Poseidon expects {num_constants} rows of {} round constants",
+                Self::WIDTH
+            )
+        }
+
+        Ok(Self {
+            kind,
+            rate: Self::RATE,
+            alpha: kind.alpha(),
+            full_rounds,
+            mds,
+            round_constants,
+        })
+    }
+
+    /// Poseidon permutation of `state`
+    pub fn permute(&self, state: &mut [Fp]) {
+        let mut round_constants = self.round_constants.iter();
+
+        if self.kind.initial_ark() {
+            add_round_constants(state, round_constants.next().expect("initial ark"));
+        }
+
+        for constants in round_constants.take(self.full_rounds) {
+            for x in state.iter_mut() {
+                *x = x.pow([self.alpha]);
+            }
+
+            let mixed: Vec<Fp> = self
+                .mds
+                .iter()
+                .map(|row| row.iter().zip(state.iter()).map(|(m, x)| *m * x).sum())
+                .collect();
+
+            state.copy_from_slice(&mixed);
+            add_round_constants(state, constants);
+        }
+    }
+}
+
+fn add_round_constants(state: &mut [Fp], constants: &[Fp]) {
+    for (x, c) in state.iter_mut().zip(constants) {
+        *x += c;
+    }
+}
+
+impl<'a> Sponge<'a> {
+    pub fn new(params: &'a SpongeParams) -> Self {
+        Self {
+            params,
+            state: vec![Fp::zero(); SpongeParams::WIDTH],
+            offset: 0,
+            squeezing: false,
+        }
+    }
+
+    /// Sponge initialized with the given state, e.g. a salted prefix state
+    pub fn with_state(params: &'a SpongeParams, state: Vec<Fp>) -> Self {
+        Self {
+            params,
+            state,
+            offset: 0,
+            squeezing: false,
+        }
+    }
+
+    pub fn state(&self) -> &[Fp] {
+        &self.state
+    }
+
+    pub fn absorb(&mut self, input: &[Fp]) {
+        for x in input {
+            if self.squeezing || self.offset == self.params.rate {
+                self.params.permute(&mut self.state);
+                self.offset = 0;
+                self.squeezing = false;
+            }
+
+            self.state[self.offset] += x;
+            self.offset += 1;
+        }
+    }
+
+    pub fn squeeze(&mut self) -> Fp {
+        if !self.squeezing || self.offset == self.params.rate {
+            self.params.permute(&mut self.state);
+            self.offset = 0;
+            self.squeezing = true;
+        }
+
+        let out = self.state[self.offset];
+        self.offset += 1;
+        out
+    }
+
+    /// Absorbs `input` & squeezes a single field element
+    pub fn hash(mut self, input: &[Fp]) -> Fp {
+        self.absorb(input);
+        self.squeeze()
+    }
+}
+
+/// Packs an ASCII domain separation `prefix` (at most 31 bytes) into a field
+/// element, little-endian bits per byte
+pub fn prefix_to_field(prefix: &str) -> anyhow::Result<Fp> {
+    if prefix.len() > 31 {
+        bail!("Poseidon prefix too long: {prefix}")
+    }
+
+    let bits: Vec<bool> = prefix
+        .bytes()
+        .flat_map(|byte| (0..8).map(move |i| (byte >> i) & 1 == 1))
+        .collect();
+
+    Fp::from_bits(&bits).map_err(|e| anyhow::anyhow!("{e}"))
+}
+
+/// Sponge state after absorbing the domain separation `prefix`
+pub fn salt(params: &SpongeParams, prefix: &str) -> anyhow::Result<Vec<Fp>> {
+    let mut sponge = Sponge::new(params);
+    sponge.absorb(&[prefix_to_field(prefix)?]);
+    sponge.squeeze();
+
+    Ok(sponge.state)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ark_ff::One;
+
+    /// Identity MDS & zero constants, so each round is the sbox alone
+    fn sbox_only(kind: PoseidonKind) -> SpongeParams {
+        let mds = (0..SpongeParams::WIDTH)
+            .map(|i| {
+                (0..SpongeParams::WIDTH)
+                    .map(|j| if i == j { Fp::one() } else { Fp::zero() })
+                    .collect()
+            })
+            .collect();
+        let rows = kind.full_rounds() + kind.initial_ark() as usize;
+        let round_constants = vec![vec![Fp::zero(); SpongeParams::WIDTH]; rows];
+
+        SpongeParams::new(kind, mds, round_constants).unwrap()
+    }
+
+    #[test]
+    fn permutation_rounds() {
+        for kind in [PoseidonKind::Legacy, PoseidonKind::Kimchi] {
+            let params = sbox_only(kind);
+            let two = Fp::from(2u64);
+            let mut state = vec![Fp::zero(), Fp::one(), two];
+            params.permute(&mut state);
+
+            // 2^(alpha^rounds)
+            let mut expect = two;
+            for _ in 0..params.full_rounds {
+                expect = expect.pow([params.alpha]);
+            }
+
+            assert_eq!(state, vec![Fp::zero(), Fp::one(), expect]);
+        }
+    }
+
+    #[test]
+    fn sponge_absorbs_rate_before_permuting() {
+        let params = sbox_only(PoseidonKind::Kimchi);
+        let mut sponge = Sponge::new(&params);
+        sponge.absorb(&[Fp::from(3u64), Fp::from(4u64)]);
+
+        // nothing permuted until the rate is exceeded or squeezed
+        assert_eq!(
+            sponge.state(),
+            &[Fp::from(3u64), Fp::from(4u64), Fp::zero()]
+        );
+
+        let out = sponge.squeeze();
+        let mut expect = Fp::from(3u64);
+        for _ in 0..params.full_rounds {
+            expect = expect.pow([params.alpha]);
+        }
+
+        assert_eq!(out, expect);
+    }
+
+    #[test]
+    fn invalid_params() {
+        let params = sbox_only(PoseidonKind::Kimchi);
+        assert!(SpongeParams::new(
+            PoseidonKind::Legacy,
+            params.mds.clone(),
+            params.round_constants.clone()
+        )
+        .is_err());
+        assert!(SpongeParams::from_json(
+            br#"{"mds":[],"round_constants":[]}"#,
+            PoseidonKind::Kimchi
+        )
+        .is_err());
+    }
+
+    #[test]
+    fn vendored_params() {
+        for kind in [PoseidonKind::Legacy, PoseidonKind::Kimchi] {
+            let (mds, round_constants) = kind.vendored_table();
+
+            // the tables are complete or absent, never partial
+            match SpongeParams::vendored(kind) {
+                Ok(params) => assert_eq!(params.kind, kind),
+                Err(e) => {
+                    assert!(mds.is_empty() && round_constants.is_empty(), "{e}");
+                    assert!(e.to_string().contains("constants are missing"));
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn prefix_packing() {
+        assert_eq!(prefix_to_field("").unwrap(), Fp::zero());
+        assert_eq!(prefix_to_field("A").unwrap(), Fp::from(65u64));
+        assert_eq!(prefix_to_field("AB").unwrap(), Fp::from(65u64 + 66 * 256));
+        assert!(prefix_to_field(&"*".repeat(32)).is_err());
+    }
+}
@@ -0,0 +1,19 @@
+//! Compiled-in Poseidon parameter tables
+//!
+//! Little-endian hex field elements, laid out like the o1-labs
+//! `poseidon/src/pasta/fp_legacy.rs` & `fp_kimchi.rs` tables: a 3x3 MDS
+//! matrix & one row of 3 round constants per round (64 legacy rows including
+//! the initial round constant addition, 55 kimchi rows).
+//!
+//! The tables aren't vendored yet: they must be transcribed from
+//! o1-labs/proof-systems byte for byte. Until then they're empty,
+//! [super::SpongeParams::vendored] reports them as missing & the
+//! `--poseidon-params` tables are required.
+
+pub(super) const LEGACY_MDS: &[[&str; 3]] = &[];
+
+pub(super) const LEGACY_ROUND_CONSTANTS: &[[&str; 3]] = &[];
+
+pub(super) const KIMCHI_MDS: &[[&str; 3]] = &[];
+
+pub(super) const KIMCHI_ROUND_CONSTANTS: &[[&str; 3]] = &[];
@@ -1,2 +1,4 @@
 pub mod pubkey;
+pub mod roinput;
+pub mod schnorr;
 pub mod signature;
@@ -0,0 +1,222 @@
+//! Random oracle input
+//!
+//! Legacy hash input ([ROInput]) is made of whole field elements followed by a
+//! bit string, which is packed into field elements of [ROInput::CHUNK_BITS]
+//! bits each. Kimchi hash input ([ChunkedROInput]) packs values of known bit
+//! lengths instead.
+
+use crate::proof_systems::{signer::signature::BaseField, FieldHelpers};
+use ark_ff::{Field, Zero};
+
+/// Input a signed message is hashed as
+pub trait MessageInput {
+    fn append_field(self, x: BaseField) -> Self;
+
+    fn to_fields(&self) -> Vec<BaseField>;
+}
+
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct ROInput {
+    fields: Vec<BaseField>,
+    bits: Vec<bool>,
+}
+
+impl ROInput {
+    /// Bits per packed field element, one less than the field size
+    pub const CHUNK_BITS: usize = 254;
+
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn append_field(mut self, x: BaseField) -> Self {
+        self.fields.push(x);
+        self
+    }
+
+    pub fn append_bool(mut self, b: bool) -> Self {
+        self.bits.push(b);
+        self
+    }
+
+    pub fn append_bools(mut self, bits: &[bool]) -> Self {
+        self.bits.extend_from_slice(bits);
+        self
+    }
+
+    /// Appends little-endian bits
+    pub fn append_u32(self, x: u32) -> Self {
+        self.append_bytes(&x.to_le_bytes())
+    }
+
+    /// Appends little-endian bits
+    pub fn append_u64(self, x: u64) -> Self {
+        self.append_bytes(&x.to_le_bytes())
+    }
+
+    /// Appends the bits of each byte, least significant first
+    pub fn append_bytes(mut self, bytes: &[u8]) -> Self {
+        self.bits.extend(
+            bytes
+                .iter()
+                .flat_map(|byte| (0..8).map(move |i| (byte >> i) & 1 == 1)),
+        );
+        self
+    }
+
+    /// Fields followed by the packed bits
+    pub fn to_fields(&self) -> Vec<BaseField> {
+        let mut fields = self.fields.clone();
+        fields.extend(self.bits.chunks(Self::CHUNK_BITS).map(|chunk| {
+            BaseField::from_bits(chunk).expect("chunk is smaller than the field size")
+        }));
+
+        fields
+    }
+}
+
+/// Kimchi random oracle input
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct ChunkedROInput {
+    fields: Vec<BaseField>,
+    packed: Vec<(BaseField, u32)>,
+}
+
+impl ChunkedROInput {
+    /// Packed values fill field elements of fewer bits
+    pub const FIELD_BITS: u32 = 255;
+
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn append_field(mut self, x: BaseField) -> Self {
+        self.fields.push(x);
+        self
+    }
+
+    /// Appends `x`, which fits in `bits` bits
+    pub fn append_packed(mut self, x: BaseField, bits: u32) -> Self {
+        self.packed.push((x, bits));
+        self
+    }
+
+    pub fn append_bool(self, b: bool) -> Self {
+        self.append_packed(BaseField::from(b as u64), 1)
+    }
+
+    pub fn append_u32(self, x: u32) -> Self {
+        self.append_packed(BaseField::from(x as u64), 32)
+    }
+
+    pub fn append_u64(self, x: u64) -> Self {
+        self.append_packed(BaseField::from(x), 64)
+    }
+
+    /// Appends the fields & packed values of `other`
+    pub fn append(mut self, other: Self) -> Self {
+        self.fields.extend(other.fields);
+        self.packed.extend(other.packed);
+        self
+    }
+
+    /// Fields followed by the packed values, accumulated most significant
+    /// first
+    pub fn to_fields(&self) -> Vec<BaseField> {
+        let mut fields = self.fields.clone();
+        if self.packed.is_empty() {
+            return fields;
+        }
+
+        let mut acc = BaseField::zero();
+        let mut acc_bits = 0;
+        for (x, bits) in &self.packed {
+            acc_bits += bits;
+
+            if acc_bits < Self::FIELD_BITS {
+                acc = acc * BaseField::from(2u64).pow([*bits as u64]) + x;
+            } else {
+                fields.push(acc);
+                acc = *x;
+                acc_bits = *bits;
+            }
+        }
+
+        fields.push(acc);
+        fields
+    }
+}
+
+impl MessageInput for ROInput {
+    fn append_field(self, x: BaseField) -> Self {
+        ROInput::append_field(self, x)
+    }
+
+    fn to_fields(&self) -> Vec<BaseField> {
+        ROInput::to_fields(self)
+    }
+}
+
+impl MessageInput for ChunkedROInput {
+    fn append_field(self, x: BaseField) -> Self {
+        ChunkedROInput::append_field(self, x)
+    }
+
+    fn to_fields(&self) -> Vec<BaseField> {
+        ChunkedROInput::to_fields(self)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn packing() {
+        let input = ROInput::new()
+            .append_field(BaseField::from(42u64))
+            .append_bool(true)
+            .append_u32(2)
+            .append_bytes(&[1]);
+
+        // 1 + (2 << 1) + (1 << 33)
+        assert_eq!(
+            input.to_fields(),
+            vec![BaseField::from(42u64), BaseField::from(5u64 + (1 << 33))]
+        );
+
+        let input = ROInput::new().append_bytes(&[0xff; 32]);
+        let fields = input.to_fields();
+
+        assert_eq!(fields.len(), 2);
+        assert_eq!(fields[1], BaseField::from(3u64));
+    }
+
+    #[test]
+    fn chunked_packing() {
+        let input = ChunkedROInput::new()
+            .append_bool(true)
+            .append_field(BaseField::from(42u64))
+            .append_bool(false)
+            .append_u32(3);
+
+        // fields first, then 1 || 0 || 3
+        assert_eq!(
+            input.to_fields(),
+            vec![BaseField::from(42u64), BaseField::from((1u64 << 33) + 3)]
+        );
+
+        // 254 bits fit in one field element
+        let input = (0..255).fold(ChunkedROInput::new(), |input, _| input.append_bool(true));
+        let fields = input.to_fields();
+
+        assert_eq!(fields.len(), 2);
+        assert_eq!(fields[1], BaseField::from(1u64));
+        assert_eq!(
+            fields[0] + BaseField::from(1u64),
+            BaseField::from(2u64).pow([254])
+        );
+
+        assert!(ChunkedROInput::new().to_fields().is_empty());
+    }
+}
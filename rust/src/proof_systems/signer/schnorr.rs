@@ -0,0 +1,201 @@
+//! Schnorr signature verification over Pallas
+//!
+//! The challenge is the Poseidon hash of the message input followed by the
+//! signer's public key coordinates & the signature's `rx`, salted by the
+//! network's signature domain. Legacy & kimchi signatures differ in the
+//! sponge parameters & in how the message input is packed.
+
+use crate::proof_systems::{
+    poseidon::{salt, Sponge, SpongeParams},
+    signer::{
+        pubkey::{CurvePoint, PubKey},
+        roinput::MessageInput,
+        signature::{BaseField, ScalarField, Signature},
+    },
+};
+use ark_ec::{AffineCurve, ProjectiveCurve};
+use ark_ff::{BigInteger, PrimeField};
+
+/// Mainnet signature domain
+pub const MAINNET_SIGNATURE_PREFIX: &str = "MinaSignatureMainnet";
+
+/// Testnet signature domain
+pub const TESTNET_SIGNATURE_PREFIX: &str = "CodaSignature*******";
+
+/// Signature challenge `e`
+pub fn message_hash<I: MessageInput>(
+    params: &SpongeParams,
+    prefix: &str,
+    pub_key: &PubKey,
+    rx: BaseField,
+    input: I,
+) -> anyhow::Result<ScalarField> {
+    let point = pub_key.point();
+    let input = input
+        .append_field(point.x)
+        .append_field(point.y)
+        .append_field(rx);
+    let hash = Sponge::with_state(params, salt(params, prefix)?).hash(&input.to_fields());
+
+    // the base field is smaller than the scalar field
+    Ok(ScalarField::from_repr(hash.into_repr()).expect("base field element fits"))
+}
+
+/// Checks `s * G - e * pk` has even `y` & `x = rx`
+pub fn verify<I: MessageInput>(
+    params: &SpongeParams,
+    prefix: &str,
+    pub_key: &PubKey,
+    signature: &Signature,
+    input: I,
+) -> anyhow::Result<bool> {
+    let e = message_hash(params, prefix, pub_key, signature.rx, input)?;
+    let r = (CurvePoint::prime_subgroup_generator().mul(signature.s.into_repr())
+        - pub_key.point().mul(e.into_repr()))
+    .into_affine();
+
+    Ok(!r.infinity && r.y.into_repr().is_even() && r.x == signature.rx)
+}
+
+#[cfg(test)]
+pub(crate) mod tests {
+    use super::*;
+    use crate::proof_systems::{
+        poseidon::PoseidonKind,
+        signer::roinput::{ChunkedROInput, ROInput},
+    };
+    use ark_ff::{One, Zero};
+    use std::ops::Neg;
+
+    /// Arbitrary but full parameters so every input affects the challenge
+    pub(crate) fn params() -> SpongeParams {
+        let mds = vec![
+            vec![BaseField::from(2u64), BaseField::one(), BaseField::one()],
+            vec![BaseField::one(), BaseField::from(2u64), BaseField::one()],
+            vec![BaseField::one(), BaseField::one(), BaseField::from(3u64)],
+        ];
+        let round_constants = (0..55u64)
+            .map(|r| (0..3u64).map(|i| BaseField::from(r * 3 + i)).collect())
+            .collect();
+
+        SpongeParams::new(PoseidonKind::Kimchi, mds, round_constants).unwrap()
+    }
+
+    pub(crate) fn sign<I: MessageInput>(
+        params: &SpongeParams,
+        secret: ScalarField,
+        nonce: ScalarField,
+        input: I,
+    ) -> (PubKey, Signature) {
+        let generator = CurvePoint::prime_subgroup_generator();
+        let pub_key = PubKey::from_point_unsafe(generator.mul(secret.into_repr()).into_affine());
+
+        let r = generator.mul(nonce.into_repr()).into_affine();
+        let k = if r.y.into_repr().is_even() {
+            nonce
+        } else {
+            nonce.neg()
+        };
+
+        let e = message_hash(params, MAINNET_SIGNATURE_PREFIX, &pub_key, r.x, input).unwrap();
+        (pub_key, Signature::new(r.x, k + e * secret))
+    }
+
+    #[test]
+    fn sign_and_verify() -> anyhow::Result<()> {
+        let params = params();
+        let input = ROInput::new().append_u64(1_000_000).append_bool(true);
+        let (pub_key, signature) = sign(
+            &params,
+            ScalarField::from(123456789u64),
+            ScalarField::from(987654321u64),
+            input.clone(),
+        );
+
+        assert!(verify(
+            &params,
+            MAINNET_SIGNATURE_PREFIX,
+            &pub_key,
+            &signature,
+            input.clone()
+        )?);
+
+        // wrong network
+        assert!(!verify(
+            &params,
+            TESTNET_SIGNATURE_PREFIX,
+            &pub_key,
+            &signature,
+            input.clone()
+        )?);
+
+        // tampered message
+        let tampered = ROInput::new().append_u64(1_000_001).append_bool(true);
+        assert!(!verify(
+            &params,
+            MAINNET_SIGNATURE_PREFIX,
+            &pub_key,
+            &signature,
+            tampered
+        )?);
+
+        // tampered signature
+        let bad_sig = Signature::new(signature.rx, signature.s + ScalarField::one());
+        assert!(!verify(
+            &params,
+            MAINNET_SIGNATURE_PREFIX,
+            &pub_key,
+            &bad_sig,
+            input.clone()
+        )?);
+
+        let zero_sig = Signature::new(BaseField::zero(), ScalarField::zero());
+        assert!(!verify(
+            &params,
+            MAINNET_SIGNATURE_PREFIX,
+            &pub_key,
+            &zero_sig,
+            input
+        )?);
+
+        Ok(())
+    }
+
+    #[test]
+    fn chunked_sign_and_verify() -> anyhow::Result<()> {
+        let params = params();
+        let input = ChunkedROInput::new()
+            .append_field(BaseField::from(42u64))
+            .append_u64(1_000_000)
+            .append_bool(true);
+        let (pub_key, signature) = sign(
+            &params,
+            ScalarField::from(123456789u64),
+            ScalarField::from(987654321u64),
+            input.clone(),
+        );
+
+        assert!(verify(
+            &params,
+            MAINNET_SIGNATURE_PREFIX,
+            &pub_key,
+            &signature,
+            input
+        )?);
+
+        // same message, legacy packing
+        let legacy = ROInput::new()
+            .append_field(BaseField::from(42u64))
+            .append_u64(1_000_000)
+            .append_bool(true);
+        assert!(!verify(
+            &params,
+            MAINNET_SIGNATURE_PREFIX,
+            &pub_key,
+            &signature,
+            legacy
+        )?);
+
+        Ok(())
+    }
+}
@@ -11,24 +11,27 @@ use crate::{
     chain::{ChainId, Network},
     cli::server::ServerArgsJson,
     constants::*,
+    event::store::EventStore,
     ledger::{
         genesis::GenesisLedger, staking::StakingLedger, store::staking::StakingLedgerStore,
         LedgerHash,
     },
-    state::{IndexerState, IndexerStateConfig},
+    proof_systems::poseidon::SpongeParams,
+    state::{missing::SharedMissingBlocks, IndexerState, IndexerStateConfig},
     store::IndexerStore,
-    unix_socket_server::{create_socket_listener, handle_connection},
+    unix_socket_server::{create_socket_listener, handle_connection, SocketState},
     utility::functions::extract_network_height_hash,
+    webhook::notifier::WebhookNotifier,
 };
+use anyhow::Context;
 use log::{debug, error, info, trace, warn};
 use notify::{Config, Event, EventKind, RecommendedWatcher, RecursiveMode, Watcher};
 use serde::{Deserialize, Serialize};
 use speedb::checkpoint::Checkpoint;
 use std::{
-    collections::HashSet,
     fs,
     path::{Path, PathBuf},
-    process,
+    process::{self, Stdio},
     sync::Arc,
     time::Duration,
 };
@@ -36,7 +39,10 @@ use tokio::{
     runtime::Handle,
     sync::{mpsc, RwLock},
 };
-use tokio_graceful_shutdown::{SubsystemBuilder, SubsystemHandle};
+use tokio_graceful_shutdown::{FutureExt, SubsystemBuilder, SubsystemHandle};
+
+/// Blocks read ahead of the block pipeline from a block source
+const BLOCK_SOURCE_CAPACITY: usize = 16;
 
 #[derive(Clone, Debug, Deserialize, Serialize)]
 pub struct IndexerVersion {
@@ -60,6 +66,7 @@ pub struct IndexerConfiguration {
     pub genesis_ledger: GenesisLedger,
     pub version: IndexerVersion,
     pub blocks_dir: Option<PathBuf>,
+    pub blocks_source: Option<PathBuf>,
     pub staking_ledgers_dir: Option<PathBuf>,
     pub prune_interval: u32,
     pub canonical_threshold: u32,
@@ -97,12 +104,18 @@ impl IndexerConfiguration {
         self,
         store: &Arc<IndexerStore>,
     ) -> anyhow::Result<()> {
+        let blocks_source = self.blocks_source.clone();
         let state = self.initialize(store).await.unwrap_or_else(|e| {
             error!("Failed to initialize mina indexer store: {e}");
             std::process::exit(1);
         });
 
-        if let Some(indexer_store) = state.indexer_store.as_ref() {
+        let state = Arc::new(RwLock::new(state));
+        if let Some(blocks_source) = blocks_source {
+            ingest_block_source(blocks_source, state.clone()).await?;
+        }
+
+        if let Some(indexer_store) = state.read().await.indexer_store.as_ref() {
             indexer_store.database.cancel_all_background_work(true);
         }
 
@@ -263,6 +276,7 @@ impl IndexerConfiguration {
         Checkpoint::new(&store.database)?.create_checkpoint(&temp_checkpoint_dir)?;
         fs::remove_dir_all(&temp_checkpoint_dir)?;
 
+        state.update_metrics();
         Ok(state)
     }
 
@@ -272,8 +286,10 @@ impl IndexerConfiguration {
         self,
         subsys: SubsystemHandle,
         store: Arc<IndexerStore>,
+        missing_blocks: SharedMissingBlocks,
     ) -> anyhow::Result<()> {
         let blocks_dir = self.blocks_dir.clone();
+        let blocks_source = self.blocks_source.clone();
         let staking_ledgers_dir = self.staking_ledgers_dir.clone();
         let domain_socket_path = self.domain_socket_path.clone();
 
@@ -285,15 +301,40 @@ impl IndexerConfiguration {
         let missing_block_recovery_batch = self.missing_block_recovery_batch;
 
         // initialize witness tree & connect database
-        let state = Arc::new(RwLock::new(self.initialize(&store).await.unwrap_or_else(
-            |e| {
-                error!("Failed to initialize mina indexer state: {e}");
-                std::process::exit(1);
-            },
-        )));
+        let mut state = self.initialize(&store).await.unwrap_or_else(|e| {
+            error!("Failed to initialize mina indexer state: {e}");
+            std::process::exit(1);
+        });
+
+        state.share_missing_blocks(missing_blocks);
+        let state = Arc::new(RwLock::new(state));
 
         // read-only state
-        start_uds_server(&subsys, state.clone(), &domain_socket_path).await?;
+        start_uds_server(
+            &subsys,
+            SocketState::Indexer(state.clone()),
+            &domain_socket_path,
+        )
+        .await?;
+
+        // feeds the additional source's blocks to the block pipeline
+        if let Some(blocks_source) = blocks_source {
+            subsys.start(SubsystemBuilder::new("Block Source", {
+                let state = state.clone();
+                move |subsys| async move {
+                    ingest_block_source(blocks_source, state)
+                        .cancel_on_shutdown(&subsys)
+                        .await
+                        .unwrap_or(Ok(()))
+                }
+            }));
+        }
+
+        // notifies webhooks of watched public key activity
+        subsys.start(SubsystemBuilder::new("Webhook Notifier", {
+            let notifier = WebhookNotifier::new(store.clone());
+            move |subsys| notifier.run(subsys)
+        }));
 
         // modifies the state
         let missing_block_recovery =
@@ -324,7 +365,7 @@ impl IndexerConfiguration {
 /// Starts UDS server with read-only state for summary
 async fn start_uds_server(
     subsys: &SubsystemHandle,
-    state: Arc<RwLock<IndexerState>>,
+    state: SocketState,
     domain_socket_path: &Path,
 ) -> anyhow::Result<()> {
     let listener = create_socket_listener(domain_socket_path);
@@ -336,6 +377,112 @@ async fn start_uds_server(
     Ok(())
 }
 
+/// Feeds the blocks of the source at `path` to the block pipeline
+///
+/// The source is read on a blocking thread, the state is only locked while
+/// adding each block
+async fn ingest_block_source(
+    path: PathBuf,
+    state: Arc<RwLock<IndexerState>>,
+) -> anyhow::Result<()> {
+    info!("Adding blocks from source {path:#?}");
+    let mut block_parser = BlockParser::new_with_source(&path)?;
+
+    if block_parser.total_num_blocks > 0 {
+        info!(
+            "Adding {} blocks from source",
+            block_parser.total_num_blocks
+        );
+    }
+
+    let (tx, mut rx) = mpsc::channel(BLOCK_SOURCE_CAPACITY);
+    tokio::task::spawn_blocking(move || loop {
+        let next = block_parser.next_source_block();
+        let done = !matches!(next, Ok(Some(_)));
+
+        // the receiver is dropped on pipeline errors
+        if tx.blocking_send(next).is_err() || done {
+            break;
+        }
+    });
+
+    let mut num_blocks = 0;
+    while let Some(next) = rx.recv().await {
+        let next = next.with_context(|| format!("block source {}", path.display()))?;
+        let Some((parsed_block, block_bytes)) = next else {
+            break;
+        };
+
+        state
+            .write()
+            .await
+            .add_source_block(&parsed_block.into(), block_bytes)?;
+        num_blocks += 1;
+    }
+
+    info!("Finished adding {num_blocks} blocks from source {path:#?}");
+    Ok(())
+}
+
+/// Serves Unix domain socket queries from a read replica, catching up with
+/// the primary every `catch_up_interval`
+///
+/// There is no witness tree or filesystem watching on replicas
+pub async fn start_replica(
+    subsys: SubsystemHandle,
+    store: Arc<IndexerStore>,
+    domain_socket_path: PathBuf,
+    catch_up_interval: Duration,
+) -> anyhow::Result<()> {
+    start_uds_server(
+        &subsys,
+        SocketState::Replica(store.clone()),
+        &domain_socket_path,
+    )
+    .await?;
+
+    info!("Catching up with the primary every {catch_up_interval:?}");
+    match catch_up_with_primary(&store, catch_up_interval)
+        .cancel_on_shutdown(&subsys)
+        .await
+    {
+        Ok(res) => res,
+        Err(_) => {
+            info!("Shutting down read replica");
+            Ok(())
+        }
+    }
+}
+
+/// Replays the primary's writes, rebroadcasts its new events to the
+/// replica's subscribers & updates the replica's metrics
+async fn catch_up_with_primary(store: &IndexerStore, interval: Duration) -> anyhow::Result<()> {
+    let mut next_seq_num = store.get_next_seq_num()?;
+
+    loop {
+        tokio::time::sleep(interval).await;
+
+        if let Err(e) = store.database.try_catch_up_with_primary() {
+            warn!("Failed to catch up with the primary: {e}");
+            continue;
+        }
+
+        let primary_next_seq_num = store.get_next_seq_num()?;
+        for seq_num in next_seq_num..primary_next_seq_num {
+            if let Some(event) = store.get_event(seq_num)? {
+                // no receivers is not an error
+                let _ = store.event_sender.send(event);
+            }
+        }
+
+        next_seq_num = primary_next_seq_num;
+
+        if let Err(e) = store.metrics.update_from_store(store) {
+            warn!("Failed to update replica metrics: {e}");
+        }
+    }
+}
+
 fn matches_event_kind(kind: EventKind) -> bool {
     use notify::event::{AccessKind, AccessMode, CreateKind, ModifyKind};
 
@@ -472,10 +619,13 @@ async fn retry_parse_precomputed_block(path: &Path) -> anyhow::Result<Precompute
     )
 }
 
-async fn retry_parse_staking_ledger(path: &Path) -> anyhow::Result<StakingLedger> {
+async fn retry_parse_staking_ledger(
+    path: &Path,
+    params: Option<&SpongeParams>,
+) -> anyhow::Result<StakingLedger> {
     let num_attempts = 5;
     for attempt in 1..num_attempts {
-        match StakingLedger::parse_file(path).await {
+        match StakingLedger::parse_file_with(path, params).await {
             Ok(ledger) => return Ok(ledger),
             Err(e) => {
                 warn!("Attempt {attempt}: {e}. Retrying in 1s...");
@@ -532,7 +682,7 @@ async fn process_event(event: Event, state: &Arc<RwLock<IndexerState>>) -> anyho
                 // if staking ledger is not in the witness tree, parse & add it
                 let mut state = state.write().await;
                 if let Some(store) = state.indexer_store.as_ref() {
-                    match retry_parse_staking_ledger(&path).await {
+                    match retry_parse_staking_ledger(&path, store.ledger_hash_params.get()).await {
                         Ok(staking_ledger) => {
                             let epoch = staking_ledger.epoch;
                             let ledger_hash = staking_ledger.ledger_hash.clone();
@@ -651,6 +801,10 @@ async fn fetch_new_blocks(
 }
 
 /// Recovers missing blocks
+///
+/// The recovery exe is run once per missing height with args `network`,
+/// `height` & `blocks_dir` & a JSON
+/// [MissingBlockRequest](crate::state::missing::MissingBlockRequest) on stdin
 async fn recover_missing_blocks(
     state: &Arc<RwLock<IndexerState>>,
     blocks_dir: impl AsRef<Path>,
@@ -661,28 +815,38 @@ async fn recover_missing_blocks(
 
     let state = state.read().await;
     let network = state.version.network.clone();
-    let missing_parent_lengths = state
-        .dangling_branches
-        .iter()
-        .map(|b| b.root_block().blockchain_length.saturating_sub(1))
-        .collect::<HashSet<_>>();
+    let missing_blocks = state.missing_blocks();
 
     // exit early if no missing blocks
-    if missing_parent_lengths.is_empty() {
+    if missing_blocks.is_empty() {
         debug!("No missing blocks found");
         return Ok(());
     }
 
+    let blocks_dir = blocks_dir.as_ref().display().to_string();
     let run_missing_blocks_recovery = |blockchain_length: u32| {
+        let request =
+            missing_blocks.request(network.clone(), blockchain_length, blocks_dir.clone());
         let mut cmd =
             std::process::Command::new(missing_block_recovery_exe.as_ref().display().to_string());
-        let cmd = cmd.args([
-            &network.to_string(),
-            &blockchain_length.to_string(),
-            &blocks_dir.as_ref().display().to_string(),
-        ]);
+        let cmd = cmd
+            .args([
+                &network.to_string(),
+                &blockchain_length.to_string(),
+                &blocks_dir,
+            ])
+            .stdin(Stdio::piped())
+            .stdout(Stdio::piped())
+            .stderr(Stdio::piped());
+
+        match cmd.spawn().and_then(|mut child| {
+            if let Some(mut stdin) = child.stdin.take() {
+                // the exe may exit without reading its stdin
+                serde_json::to_writer(&mut stdin, &request).ok();
+            }
 
-        match cmd.output() {
+            child.wait_with_output()
+        }) {
             Ok(output) => {
                 let stdout = String::from_utf8(output.stdout).expect("stdout");
                 let stdout = stdout.trim_end();
@@ -709,17 +873,18 @@ async fn recover_missing_blocks(
         }
     };
 
-    debug!("Getting missing parent blocks of dangling roots");
+    debug!("Getting missing blocks: {missing_blocks:?}");
+    let missing_heights = missing_blocks.heights();
 
     if batch_recovery {
-        let min_missing_length = missing_parent_lengths.iter().min().cloned();
-        let max_missing_length = missing_parent_lengths.iter().max().cloned();
+        let min_missing_length = missing_heights.first().cloned();
+        let max_missing_length = missing_heights.last().cloned();
 
         if let (Some(min), Some(max)) = (min_missing_length, max_missing_length) {
             (min..=max).for_each(run_missing_blocks_recovery)
         }
     } else {
-        missing_parent_lengths
+        missing_heights
             .into_iter()
             .for_each(run_missing_blocks_recovery);
     }
@@ -795,6 +960,7 @@ impl From<(ServerArgsJson, PathBuf)> for IndexerConfiguration {
             genesis_ledger,
             domain_socket_path: value.1,
             blocks_dir: value.0.blocks_dir.map(Into::into),
+            blocks_source: value.0.blocks_source.map(Into::into),
             staking_ledgers_dir: value.0.staking_ledgers_dir.map(Into::into),
             prune_interval: value.0.prune_interval,
             canonical_threshold: value.0.canonical_threshold,
@@ -16,7 +16,7 @@ pub struct SnarkWorkSummary {
     pub prover: PublicKey,
 }
 
-#[derive(Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
 pub struct SnarkWorkTotal {
     pub total_fees: Amount,
     pub prover: PublicKey,
@@ -53,6 +53,7 @@ impl Branch {
             blockchain_length,
             global_slot_since_genesis,
             hash_last_vrf_output: genesis_last_vrf_output,
+            fork_choice: None,
         };
 
         Self::new_genesis_block(genesis_block)
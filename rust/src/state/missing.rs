@@ -0,0 +1,216 @@
+//! Missing block detection
+//!
+//! Blocks at or below the canonical root form a contiguous chain, so missing
+//! blocks are confined to the witness tree:
+//! - the parents of dangling branch roots
+//! - heights between the canonical root & the highest known block which no
+//!   block in the witness tree occupies
+
+use super::IndexerState;
+use crate::{base::state_hash::StateHash, chain::Network};
+use serde::{Deserialize, Serialize};
+use std::{
+    collections::{BTreeMap, BTreeSet},
+    sync::{Arc, RwLock},
+};
+
+/// Missing blocks maintained by the indexer state & read by the web server
+pub type SharedMissingBlocks = Arc<RwLock<MissingBlocks>>;
+
+#[derive(Debug, Default, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct MissingBlocks {
+    /// Missing parents of dangling branch roots, sorted by blockchain length
+    pub missing_parents: Vec<MissingParent>,
+
+    /// Heights above the canonical root without any known block
+    pub missing_heights: Vec<u32>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct MissingParent {
+    pub state_hash: StateHash,
+    pub blockchain_length: u32,
+
+    /// Dangling branch roots whose parent is missing
+    pub children: Vec<StateHash>,
+}
+
+/// Missing block recovery hook request, written as JSON to the hook's stdin
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct MissingBlockRequest {
+    pub network: Network,
+    pub blockchain_length: u32,
+    pub blocks_dir: String,
+
+    /// Known missing blocks at this height, empty if only the height is known
+    /// to be missing
+    pub state_hashes: Vec<StateHash>,
+}
+
+impl MissingBlocks {
+    pub fn is_empty(&self) -> bool {
+        self.missing_parents.is_empty() && self.missing_heights.is_empty()
+    }
+
+    /// Heights of all missing blocks, ascending
+    pub fn heights(&self) -> BTreeSet<u32> {
+        self.missing_parents
+            .iter()
+            .map(|parent| parent.blockchain_length)
+            .chain(self.missing_heights.iter().copied())
+            .collect()
+    }
+
+    /// Recovery hook request for the missing block(s) at `blockchain_length`
+    pub fn request(
+        &self,
+        network: Network,
+        blockchain_length: u32,
+        blocks_dir: String,
+    ) -> MissingBlockRequest {
+        MissingBlockRequest {
+            network,
+            blockchain_length,
+            blocks_dir,
+            state_hashes: self
+                .missing_parents
+                .iter()
+                .filter(|parent| parent.blockchain_length == blockchain_length)
+                .map(|parent| parent.state_hash.clone())
+                .collect(),
+        }
+    }
+}
+
+impl IndexerState {
+    /// Missing parents of dangling branches & witness tree height gaps
+    pub fn missing_blocks(&self) -> MissingBlocks {
+        self.missing_blocks
+            .read()
+            .expect("missing blocks lock")
+            .clone()
+    }
+
+    /// Shares the missing blocks with e.g. the web server
+    pub fn share_missing_blocks(&mut self, missing_blocks: SharedMissingBlocks) {
+        self.missing_blocks = missing_blocks;
+        self.compute_missing_blocks();
+    }
+
+    /// Updates the missing blocks once a block of `blockchain_length` is
+    /// added to the witness tree
+    ///
+    /// The witness tree is only traversed the first time, afterwards only the
+    /// added height, the canonical root & the dangling branch roots are
+    /// considered
+    pub(crate) fn update_missing_blocks(&mut self, blockchain_length: u32) {
+        let Some(max_length) = self.max_witness_tree_length else {
+            return self.compute_missing_blocks();
+        };
+
+        let canonical_root_length = self.canonical_root_block().blockchain_length;
+        let missing_parents = self.missing_parents();
+        let mut missing_blocks = self.missing_blocks.write().expect("missing blocks lock");
+        missing_blocks.missing_parents = missing_parents;
+
+        // heights skipped by a new highest block are missing
+        if blockchain_length > max_length {
+            missing_blocks
+                .missing_heights
+                .extend((max_length + 1).max(canonical_root_length + 1)..blockchain_length);
+            self.max_witness_tree_length = Some(blockchain_length);
+        }
+
+        missing_blocks
+            .missing_heights
+            .retain(|length| *length > canonical_root_length && *length != blockchain_length);
+    }
+
+    /// Computes the missing blocks from the whole witness tree
+    fn compute_missing_blocks(&mut self) {
+        let canonical_root_length = self.canonical_root_block().blockchain_length;
+        let known_lengths = self.witness_tree_lengths();
+        let max_length = known_lengths
+            .last()
+            .copied()
+            .unwrap_or(canonical_root_length);
+
+        self.max_witness_tree_length = Some(max_length);
+        *self.missing_blocks.write().expect("missing blocks lock") = MissingBlocks {
+            missing_parents: self.missing_parents(),
+            missing_heights: (canonical_root_length + 1..max_length)
+                .filter(|length| !known_lengths.contains(length))
+                .collect(),
+        };
+    }
+
+    /// Missing parents of the dangling branch roots
+    fn missing_parents(&self) -> Vec<MissingParent> {
+        let mut parents: BTreeMap<(u32, StateHash), Vec<StateHash>> = BTreeMap::new();
+        for dangling in &self.dangling_branches {
+            let root = dangling.root_block();
+
+            // genesis blocks have no parent
+            if root.blockchain_length > 1 {
+                parents
+                    .entry((root.blockchain_length - 1, root.parent_hash.clone()))
+                    .or_default()
+                    .push(root.state_hash.clone());
+            }
+        }
+
+        parents
+            .into_iter()
+            .map(
+                |((blockchain_length, state_hash), children)| MissingParent {
+                    state_hash,
+                    blockchain_length,
+                    children,
+                },
+            )
+            .collect()
+    }
+
+    /// Blockchain lengths of all witness tree blocks
+    fn witness_tree_lengths(&self) -> BTreeSet<u32> {
+        let mut known_lengths = BTreeSet::new();
+        for branch in std::iter::once(&self.root_branch).chain(&self.dangling_branches) {
+            if let Ok(blocks) = branch.branches.traverse_pre_order(&branch.root) {
+                known_lengths.extend(blocks.map(|node| node.data().blockchain_length));
+            }
+        }
+
+        known_lengths
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn missing_parent(blockchain_length: u32) -> MissingParent {
+        MissingParent {
+            state_hash: StateHash::default(),
+            blockchain_length,
+            children: vec![],
+        }
+    }
+
+    #[test]
+    fn heights_and_requests() {
+        let missing = MissingBlocks {
+            missing_parents: vec![missing_parent(11), missing_parent(21)],
+            missing_heights: vec![11, 12, 15],
+        };
+
+        assert!(!missing.is_empty());
+        assert!(MissingBlocks::default().is_empty());
+        assert_eq!(missing.heights(), BTreeSet::from([11, 12, 15, 21]));
+
+        let request = missing.request(Network::Mainnet, 21, "./blocks".into());
+        assert_eq!(request.state_hashes, vec![StateHash::default()]);
+
+        let request = missing.request(Network::Mainnet, 12, "./blocks".into());
+        assert!(request.state_hashes.is_empty());
+    }
+}
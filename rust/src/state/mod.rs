@@ -1,4 +1,5 @@
 pub mod branch;
+pub mod missing;
 pub mod summary;
 
 use crate::{
@@ -23,9 +24,11 @@ use crate::{
         token::{Token, TokenAddress},
         Ledger, LedgerHash,
     },
+    metrics::Metrics,
     server::IndexerVersion,
     state::{
         branch::Branch,
+        missing::SharedMissingBlocks,
         summary::{
             DbStats, SummaryShort, SummaryVerbose, WitnessTreeSummaryShort,
             WitnessTreeSummaryVerbose,
@@ -117,6 +120,14 @@ pub struct IndexerState {
 
     /// PCB versions & chain ids for various networks
     pub chain_data: ChainData,
+
+    /// Blocks missing from the witness tree, updated as blocks are added &
+    /// shared with the web server
+    pub missing_blocks: SharedMissingBlocks,
+
+    /// Highest blockchain length in the witness tree, `None` until the
+    /// missing blocks are first computed
+    max_witness_tree_length: Option<u32>,
 }
 
 #[derive(Debug, Clone)]
@@ -356,6 +367,8 @@ impl IndexerState {
             reporting_freq: config.reporting_freq,
             staking_ledgers: HashSet::new(),
             chain_data: ChainData::default(),
+            missing_blocks: SharedMissingBlocks::default(),
+            max_witness_tree_length: None,
         })
     }
 
@@ -394,6 +407,8 @@ impl IndexerState {
             reporting_freq: config.reporting_freq,
             staking_ledgers: HashSet::new(),
             chain_data: ChainData::default(),
+            missing_blocks: SharedMissingBlocks::default(),
+            max_witness_tree_length: None,
         })
     }
 
@@ -461,6 +476,8 @@ impl IndexerState {
             staking_ledgers: HashSet::new(),
             version: IndexerVersion::default(),
             chain_data: ChainData::default(),
+            missing_blocks: SharedMissingBlocks::default(),
+            max_witness_tree_length: None,
         })
     }
 
@@ -566,6 +583,15 @@ impl IndexerState {
         self.add_blocks_with_time(block_parser, None).await
     }
 
+    /// Adds a block read from a [crate::block::source::BlockSource]
+    pub fn add_source_block(&mut self, block: &PrecomputedBlock, block_bytes: u64) -> Result<()> {
+        self.compact_db_every_n_blocks(100_000)?;
+
+        info!("Adding block to witness tree {}", block.summary());
+        self.block_pipeline(block, block_bytes)?;
+        Ok(())
+    }
+
     async fn add_blocks_with_time(
         &mut self,
         block_parser: &mut BlockParser,
@@ -671,6 +697,8 @@ impl IndexerState {
                 self.add_canonical_block_to_store(block, &block.genesis_state_hash, None)
                     .unwrap()
             });
+
+            self.update_metrics();
         }
 
         Ok(true)
@@ -682,6 +710,23 @@ impl IndexerState {
         precomputed_block: &PrecomputedBlock,
         increment_blocks: bool,
         insert_diff: bool,
+    ) -> Result<(ExtensionType, Option<WitnessTreeEvent>)> {
+        let res = self.extend_witness_tree(precomputed_block, increment_blocks, insert_diff)?;
+
+        if res.0 != ExtensionType::BlockNotAdded {
+            self.update_missing_blocks(precomputed_block.blockchain_length());
+        }
+
+        Ok(res)
+    }
+
+    /// Extends the root branch or a dangling branch with the block, or spawns
+    /// a new dangling branch
+    fn extend_witness_tree(
+        &mut self,
+        precomputed_block: &PrecomputedBlock,
+        increment_blocks: bool,
+        insert_diff: bool,
     ) -> Result<(ExtensionType, Option<WitnessTreeEvent>)> {
         let incoming_length = precomputed_block.blockchain_length();
         if self.root_branch.root_block().blockchain_length > incoming_length {
@@ -1112,7 +1157,8 @@ impl IndexerState {
             .get_staking_ledger_hash_by_epoch(epoch, &StakingLedger::genesis_state_hash(&hash))?
             .is_none()
         {
-            let staking_ledger = StakingLedger::parse_file(path).await?;
+            let staking_ledger =
+                StakingLedger::parse_file_with(path, store.ledger_hash_params.get()).await?;
             let summary = staking_ledger.summary();
 
             staking_ledgers.insert((staking_ledger.epoch, staking_ledger.ledger_hash.to_owned()));
@@ -1643,6 +1689,30 @@ impl IndexerState {
         Ok(())
     }
 
+    /// Exports ingestion & witness tree metrics
+    pub fn update_metrics(&self) {
+        if let Some(indexer_store) = self.indexer_store.as_ref() {
+            let metrics = &indexer_store.metrics;
+
+            Metrics::set(&metrics.blocks_processed, self.blocks_processed as u64);
+            Metrics::set(&metrics.bytes_processed, self.bytes_processed);
+            Metrics::set(
+                &metrics.best_tip_height,
+                self.best_tip_block().blockchain_length as u64,
+            );
+            Metrics::set(
+                &metrics.canonical_root_height,
+                self.canonical_root_block().blockchain_length as u64,
+            );
+            Metrics::set(&metrics.witness_tree_size, self.len() as u64);
+            Metrics::set(
+                &metrics.num_dangling_branches,
+                self.dangling_branches.len() as u64,
+            );
+            metrics.touch();
+        }
+    }
+
     pub fn summary_short(&self) -> SummaryShort {
         let mut max_dangling_height = 0;
         let mut max_dangling_length = 0;
@@ -1773,6 +1843,8 @@ impl IndexerState {
 
     fn report_from_block_count(&self, block_parser: &mut BlockParser, total_time: Instant) {
         if self.should_report_from_block_count(block_parser) {
+            self.update_metrics();
+
             let elapsed = total_time.elapsed().as_secs();
             let block_rate = self.blocks_processed as f64 / elapsed as f64;
             let bytes_rate = if elapsed != 0 {
@@ -1816,6 +1888,8 @@ impl IndexerState {
         if self.should_report_from_block_count(block_parser)
             || step_time.elapsed().as_secs() > BLOCK_REPORTING_FREQ_SEC
         {
+            self.update_metrics();
+
             let elapsed = total_time.elapsed().as_secs();
             let best_tip: BlockWithoutHeight = self.best_tip_block().clone().into();
             let block_rate = self.blocks_processed as f64 / elapsed as f64;
@@ -0,0 +1,201 @@
+//! Address book store impl
+
+use super::{column_families::ColumnFamilyHelpers, username::UsernameStore, IndexerStore};
+use crate::{
+    address_book::{store::AddressBookStore, Category, Label},
+    base::{public_key::PublicKey, username::Username},
+    store::Result,
+    utility::store::address_book::{label_category_key, split_label_category_key},
+};
+use log::trace;
+use speedb::{Direction, IteratorMode, WriteBatch};
+
+impl AddressBookStore for IndexerStore {
+    fn add_label(&self, label: &Label) -> Result<()> {
+        let pk = &label.public_key;
+        trace!("Adding {} label {} for {pk}", label.category, label.label);
+
+        let mut batch = WriteBatch::default();
+
+        // drop the previous label's category
+        if let Some(prev) = self.get_label(pk)? {
+            batch.delete_cf(
+                self.address_book_category_sort_cf(),
+                label_category_key(prev.category, pk),
+            );
+        }
+
+        batch.put_cf(
+            self.address_book_cf(),
+            pk.0.as_bytes(),
+            serde_json::to_vec(label)?,
+        );
+        batch.put_cf(
+            self.address_book_category_sort_cf(),
+            label_category_key(label.category, pk),
+            b"",
+        );
+
+        Ok(self.database.write(batch)?)
+    }
+
+    fn remove_label(&self, pk: &PublicKey) -> Result<bool> {
+        trace!("Removing label for {pk}");
+
+        let Some(label) = self.get_label(pk)? else {
+            return Ok(false);
+        };
+
+        let mut batch = WriteBatch::default();
+        batch.delete_cf(self.address_book_cf(), pk.0.as_bytes());
+        batch.delete_cf(
+            self.address_book_category_sort_cf(),
+            label_category_key(label.category, pk),
+        );
+
+        self.database.write(batch)?;
+        Ok(true)
+    }
+
+    fn get_label(&self, pk: &PublicKey) -> Result<Option<Label>> {
+        trace!("Getting label for {pk}");
+
+        Ok(self
+            .database
+            .get_cf(self.address_book_cf(), pk.0.as_bytes())?
+            .map(|bytes| serde_json::from_slice(&bytes))
+            .transpose()?)
+    }
+
+    fn get_labels(&self, category: Option<Category>) -> Result<Vec<Label>> {
+        trace!("Getting {category:?} labels");
+
+        let mut labels = vec![];
+        match category {
+            None => {
+                for (_, value) in self
+                    .database
+                    .iterator_cf(self.address_book_cf(), IteratorMode::Start)
+                    .flatten()
+                {
+                    labels.push(serde_json::from_slice(&value)?);
+                }
+            }
+            Some(category) => {
+                let prefix = [category.to_byte()];
+                for (key, _) in self
+                    .database
+                    .iterator_cf(
+                        self.address_book_category_sort_cf(),
+                        IteratorMode::From(&prefix, Direction::Forward),
+                    )
+                    .flatten()
+                {
+                    if key[0] != category.to_byte() {
+                        break;
+                    }
+
+                    let (_, pk) = split_label_category_key(&key)?;
+                    if let Some(label) = self.get_label(&pk)? {
+                        labels.push(label);
+                    }
+                }
+            }
+        }
+
+        Ok(labels)
+    }
+
+    fn get_label_pks(&self, label: &str) -> Result<Vec<PublicKey>> {
+        trace!("Getting public keys labeled {label}");
+
+        Ok(self
+            .get_labels(None)?
+            .into_iter()
+            .filter_map(|l| (l.label == label).then_some(l.public_key))
+            .collect())
+    }
+
+    fn resolve_username(&self, pk: &PublicKey) -> Result<Option<Username>> {
+        match self.get_label(pk)?.map(|label| Username::new(label.label)) {
+            Some(Ok(username)) => Ok(Some(username)),
+            Some(Err(e)) => {
+                trace!("Label of {pk} is not a username: {e}");
+                self.get_username(pk)
+            }
+            None => self.get_username(pk),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use quickcheck::{Arbitrary, Gen};
+    use tempfile::TempDir;
+
+    fn label(pk: &PublicKey, label: &str, category: Category) -> Label {
+        Label {
+            public_key: pk.clone(),
+            label: label.to_string(),
+            category,
+            source: "test".to_string(),
+        }
+    }
+
+    #[test]
+    fn add_replace_remove_labels() -> anyhow::Result<()> {
+        let store_dir = TempDir::with_prefix(std::env::current_dir()?)?;
+        let store = IndexerStore::new(store_dir.path(), true)?;
+
+        let g = &mut Gen::new(1000);
+        let pk0 = PublicKey::arbitrary(g);
+        let pk1 = PublicKey::arbitrary_not(g, &Some(pk0.clone()));
+
+        // labels take precedence over usernames
+        store.add_username(pk0.clone(), &Username::new("on-chain")?)?;
+        assert_eq!(
+            store.resolve_username(&pk0)?,
+            Some(Username::new("on-chain")?)
+        );
+
+        store.add_label(&label(&pk0, "Binance", Category::Exchange))?;
+        store.add_label(&label(&pk1, "Foundation", Category::Foundation))?;
+        assert_eq!(
+            store.resolve_username(&pk0)?,
+            Some(Username::new("Binance")?)
+        );
+        assert_eq!(store.get_labels(None)?.len(), 2);
+        assert_eq!(store.get_label_pks("Binance")?, vec![pk0.clone()]);
+        assert_eq!(
+            store.get_labels(Some(Category::Exchange))?,
+            vec![label(&pk0, "Binance", Category::Exchange)]
+        );
+
+        // re-categorize
+        store.add_label(&label(&pk0, "Pool", Category::Pool))?;
+        assert!(store.get_labels(Some(Category::Exchange))?.is_empty());
+        assert_eq!(
+            store.get_labels(Some(Category::Pool))?,
+            vec![label(&pk0, "Pool", Category::Pool)]
+        );
+
+        // labels which aren't valid usernames fall back to the username
+        let long = "x".repeat(Username::MAX_LEN + 1);
+        store.add_label(&label(&pk0, &long, Category::Other))?;
+        assert_eq!(
+            store.resolve_username(&pk0)?,
+            Some(Username::new("on-chain")?)
+        );
+
+        // remove falls back to the username
+        assert!(store.remove_label(&pk0)?);
+        assert!(!store.remove_label(&pk0)?);
+        assert!(store.get_labels(Some(Category::Pool))?.is_empty());
+        assert_eq!(
+            store.resolve_username(&pk0)?,
+            Some(Username::new("on-chain")?)
+        );
+        Ok(())
+    }
+}
@@ -0,0 +1,84 @@
+//! Balance history store impl
+
+use super::{column_families::ColumnFamilyHelpers, IndexerStore};
+use crate::{
+    base::public_key::PublicKey,
+    ledger::{
+        store::balance_history::{BalanceHistoryEntry, BalanceHistoryStore},
+        token::TokenAddress,
+    },
+    store::Result,
+    utility::store::ledger::balance_history::balance_history_key,
+};
+use log::trace;
+use speedb::{Direction, IteratorMode};
+
+impl BalanceHistoryStore for IndexerStore {
+    fn set_balance_history(
+        &self,
+        pk: &PublicKey,
+        token: &TokenAddress,
+        entry: &BalanceHistoryEntry,
+    ) -> Result<()> {
+        trace!(
+            "Setting ({pk}, {token}) balance history at height {}",
+            entry.block_height
+        );
+
+        Ok(self.database.put_cf(
+            self.balance_history_cf(),
+            balance_history_key(pk, token, entry.block_height),
+            serde_json::to_vec(entry)?,
+        )?)
+    }
+
+    fn remove_balance_history(
+        &self,
+        pk: &PublicKey,
+        token: &TokenAddress,
+        block_height: u32,
+    ) -> Result<()> {
+        trace!("Removing ({pk}, {token}) balance history at height {block_height}");
+
+        Ok(self.database.delete_cf(
+            self.balance_history_cf(),
+            balance_history_key(pk, token, block_height),
+        )?)
+    }
+
+    fn get_balance_history(
+        &self,
+        pk: &PublicKey,
+        token: &TokenAddress,
+        from_height: Option<u32>,
+        to_height: Option<u32>,
+    ) -> Result<Vec<BalanceHistoryEntry>> {
+        trace!("Getting ({pk}, {token}) balance history");
+
+        let start = balance_history_key(pk, token, from_height.unwrap_or_default());
+        let prefix = &start[..PublicKey::LEN + TokenAddress::LEN];
+        let mut history = vec![];
+
+        for (key, value) in self
+            .database
+            .iterator_cf(
+                self.balance_history_cf(),
+                IteratorMode::From(&start, Direction::Forward),
+            )
+            .flatten()
+        {
+            if !key.starts_with(prefix) {
+                break;
+            }
+
+            let entry: BalanceHistoryEntry = serde_json::from_slice(&value)?;
+            if to_height.is_some_and(|to| entry.block_height > to) {
+                break;
+            }
+
+            history.push(entry);
+        }
+
+        Ok(history)
+    }
+}
@@ -214,6 +214,8 @@ impl BestLedgerStore for IndexerStore {
                             update_token_accounts(self, d.new_pk_balances, d.accounts_created);
 
                         AccountUpdate {
+                            block_state_hash: d.state_hash,
+                            blockchain_length: d.blockchain_length,
                             account_diffs: d.account_diffs.into_iter().flatten().collect(),
                             token_diffs: d.token_diffs.into_iter().collect(),
                             new_accounts,
@@ -233,6 +235,8 @@ impl BestLedgerStore for IndexerStore {
                             update_token_accounts(self, d.new_pk_balances, d.accounts_created);
 
                         AccountUpdate {
+                            block_state_hash: d.state_hash,
+                            blockchain_length: d.blockchain_length,
                             account_diffs: d.account_diffs.into_iter().flatten().collect(),
                             token_diffs: d.token_diffs.into_iter().collect(),
                             new_accounts,
@@ -8,8 +8,10 @@ use crate::{
     command::internal::{store::InternalCommandStore, DbInternalCommandWithData},
     constants::MAINNET_COINBASE_REWARD,
     event::{db::*, store::EventStore, IndexerEvent},
+    ledger::store::staking::StakingLedgerStore,
 };
 use log::trace;
+use speedb::{Direction, IteratorMode};
 
 impl CanonicityStore for IndexerStore {
     fn add_canonical_block(
@@ -88,6 +90,9 @@ impl CanonicityStore for IndexerStore {
                 state_hash: state_hash.0.clone().into(),
             },
         )))?;
+
+        // staking ledgers are derived at epoch boundaries
+        self.add_derived_staking_ledger(state_hash)?;
         Ok(())
     }
 
@@ -127,6 +132,23 @@ impl CanonicityStore for IndexerStore {
             .and_then(|bytes| StateHash::from_bytes(&bytes).ok()))
     }
 
+    fn get_canonical_hash_at_or_before_slot(
+        &self,
+        global_slot: u32,
+    ) -> anyhow::Result<Option<StateHash>> {
+        trace!("Getting canonical state hash at or before slot {global_slot}");
+        let slot_bytes = global_slot.to_be_bytes();
+        let mut iter = self.database.iterator_cf(
+            self.canonicity_slot_cf(),
+            IteratorMode::From(&slot_bytes, Direction::Reverse),
+        );
+
+        Ok(iter
+            .next()
+            .transpose()?
+            .and_then(|(_, bytes)| StateHash::from_bytes(&bytes).ok()))
+    }
+
     fn get_block_canonicity(&self, state_hash: &StateHash) -> anyhow::Result<Option<Canonicity>> {
         trace!("Getting canonicity of block {state_hash}");
         if let Ok(Some(height)) = self.get_block_height(state_hash) {
@@ -146,6 +146,9 @@ pub trait ColumnFamilyHelpers {
     /// CF for sorting user commands per token by global slot
     fn user_commands_per_token_slot_sort_cf(&self) -> &ColumnFamily;
 
+    /// CF for storing user commands with invalid signatures
+    fn user_commands_invalid_signature_cf(&self) -> &ColumnFamily;
+
     /// CF for sorting user commands by sender public key
     fn txn_from_slot_sort_cf(&self) -> &ColumnFamily;
 
@@ -378,6 +381,13 @@ pub trait ColumnFamilyHelpers {
     /// state hash -> staged ledger hash
     fn block_staged_ledger_hash_cf(&self) -> &ColumnFamily;
 
+    ///////////////////////////////
+    // Balance history store CFs //
+    ///////////////////////////////
+
+    /// CF for storing token account balances after each best chain block
+    fn balance_history_cf(&self) -> &ColumnFamily;
+
     //////////////////////////////
     // Staking ledger store CFs //
     //////////////////////////////
@@ -598,4 +608,21 @@ pub trait ColumnFamilyHelpers {
 
     /// CF for storing indexer store events by sequence number
     fn events_cf(&self) -> &ColumnFamily;
+
+    ///////////////////////
+    // Webhook store CFs //
+    ///////////////////////
+
+    /// CF for storing watched public keys & their webhook urls
+    fn webhooks_cf(&self) -> &ColumnFamily;
+
+    ////////////////////////////
+    // Address book store CFs //
+    ////////////////////////////
+
+    /// CF for storing public key labels
+    fn address_book_cf(&self) -> &ColumnFamily;
+
+    /// CF for sorting public key labels by category
+    fn address_book_category_sort_cf(&self) -> &ColumnFamily;
 }
@@ -338,6 +338,21 @@ impl ColumnFamilyHelpers for IndexerStore {
             .expect("user-commands-per-token-height-sort column family exists")
     }
 
+    /// Key-value pairs
+    /// ```
+    /// - key: {txn_hash}{state_hash}
+    /// - val: [InvalidSignature] serde bytes
+    /// where
+    /// - txn_hash:   [TxnHash::V1_LEN] bytes (v2 is right-padded)
+    /// - state_hash: [StateHash] bytes
+    /// ```
+    /// Use with [txn_block_key]
+    fn user_commands_invalid_signature_cf(&self) -> &ColumnFamily {
+        self.database
+            .cf_handle("user-commands-invalid-signature")
+            .expect("user-commands-invalid-signature column family exists")
+    }
+
     /// Key-value pairs
     /// ```
     /// - key: {token}{slot}{txn_hash}{state_hash}
@@ -1417,6 +1432,24 @@ impl ColumnFamilyHelpers for IndexerStore {
             .expect("blocks-staged-ledger-hash column family exists")
     }
 
+    ///////////////////////////////
+    // Balance history store CFs //
+    ///////////////////////////////
+
+    /// CF for storing token account balances after each best chain block
+    /// ```
+    /// key: {pk}{token}{height}
+    /// val: [BalanceHistoryEntry] serde bytes
+    /// where
+    /// - pk:     [PublicKey] bytes
+    /// - token:  [TokenAddress] bytes
+    /// - height: [u32] BE bytes
+    fn balance_history_cf(&self) -> &ColumnFamily {
+        self.database
+            .cf_handle("balance-history")
+            .expect("balance-history column family exists")
+    }
+
     //////////////////////////////
     // Staking ledger store CFs //
     //////////////////////////////
@@ -1903,6 +1936,54 @@ impl ColumnFamilyHelpers for IndexerStore {
             .expect("events column family exists")
     }
 
+    ///////////////////////
+    // Webhook store CFs //
+    ///////////////////////
+
+    /// CF for storing watched public keys & their webhook urls
+    /// ```
+    /// key: {pk}{url}
+    /// val: b""
+    /// where
+    /// - pk:  [PublicKey] bytes
+    /// - url: [String] bytes
+    fn webhooks_cf(&self) -> &ColumnFamily {
+        self.database
+            .cf_handle("webhooks")
+            .expect("webhooks column family exists")
+    }
+
+    ////////////////////////////
+    // Address book store CFs //
+    ////////////////////////////
+
+    /// CF for storing public key labels
+    /// ```
+    /// key: {pk}
+    /// val: [Label] serde bytes
+    /// where
+    /// - pk: [PublicKey] bytes
+    fn address_book_cf(&self) -> &ColumnFamily {
+        self.database
+            .cf_handle("address-book")
+            .expect("address-book column family exists")
+    }
+
+    /// CF for sorting public key labels by category
+    /// ```
+    /// key: {category}{pk}
+    /// val: b""
+    /// where
+    /// - category: [Category] byte
+    /// - pk:       [PublicKey] bytes
+    /// ```
+    /// Use with [label_category_key]
+    fn address_book_category_sort_cf(&self) -> &ColumnFamily {
+        self.database
+            .cf_handle("address-book-category-sort")
+            .expect("address-book-category-sort column family exists")
+    }
+
     ////////////////////
     // Data count CFs //
     ////////////////////
@@ -47,6 +47,9 @@ impl EventStore for IndexerStore {
         self.database
             .put(Self::NEXT_EVENT_SEQ_NUM_KEY, next_seq_num.to_be_bytes())?;
 
+        // notify subscribers, no receivers is not an error
+        let _ = self.event_sender.send(event.clone());
+
         // return next event sequence number
         Ok(next_seq_num)
     }
@@ -83,4 +86,8 @@ impl EventStore for IndexerStore {
     fn event_log_iterator(&self, mode: speedb::IteratorMode) -> speedb::DBIterator<'_> {
         self.database.iterator_cf(self.events_cf(), mode)
     }
+
+    fn subscribe_events(&self) -> tokio::sync::broadcast::Receiver<IndexerEvent> {
+        self.event_sender.subscribe()
+    }
 }
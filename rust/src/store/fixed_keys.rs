@@ -9,9 +9,11 @@ pub trait FixedKeys {
     const KNOWN_GENESIS_PREV_STATE_HASHES_KEY: &'static [u8] =
         "genesis_prev_state_hashes".as_bytes();
     const NUM_BLOCK_BYTES_PROCESSED: &'static [u8] = "num_block_bytes_processed".as_bytes();
+    const WEBHOOK_CURSOR_KEY: &'static [u8] = "webhook_cursor".as_bytes();
 
     // version info
     const INDEXER_STORE_VERSION_KEY: &'static [u8] = "indexer_store_version".as_bytes();
+    const MIGRATION_CHECKPOINT_KEY: &'static [u8] = "migration_checkpoint".as_bytes();
 
     // indexed totals
     const TOTAL_NUM_ACCOUNTS_KEY: &'static [u8] = "total_num_accounts".as_bytes();
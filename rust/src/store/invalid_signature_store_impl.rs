@@ -0,0 +1,67 @@
+//! Invalid signature store impl
+
+use super::{column_families::ColumnFamilyHelpers, IndexerStore};
+use crate::{
+    base::state_hash::StateHash,
+    command::signed::{store::InvalidSignatureStore, verify::InvalidSignature, TxnHash},
+    store::Result,
+    utility::store::command::user::txn_block_key,
+};
+use log::trace;
+use speedb::{IteratorMode, WriteBatch};
+
+impl InvalidSignatureStore for IndexerStore {
+    fn set_invalid_signature_batch(
+        &self,
+        invalid: &InvalidSignature,
+        batch: &mut WriteBatch,
+    ) -> Result<()> {
+        trace!(
+            "Setting invalid signature {} block {}",
+            invalid.txn_hash,
+            invalid.state_hash
+        );
+
+        batch.put_cf(
+            self.user_commands_invalid_signature_cf(),
+            txn_block_key(&invalid.txn_hash, &invalid.state_hash),
+            serde_json::to_vec(invalid)?,
+        );
+        Ok(())
+    }
+
+    fn get_invalid_signature(
+        &self,
+        txn_hash: &TxnHash,
+        state_hash: &StateHash,
+    ) -> Result<Option<InvalidSignature>> {
+        trace!("Getting invalid signature {txn_hash} block {state_hash}");
+
+        Ok(self
+            .database
+            .get_cf(
+                self.user_commands_invalid_signature_cf(),
+                txn_block_key(txn_hash, state_hash),
+            )?
+            .map(|bytes| serde_json::from_slice(&bytes))
+            .transpose()?)
+    }
+
+    fn get_invalid_signatures(&self) -> Result<Vec<InvalidSignature>> {
+        trace!("Getting invalid signatures");
+
+        let mut invalid = vec![];
+        for (_, value) in self
+            .database
+            .iterator_cf(
+                self.user_commands_invalid_signature_cf(),
+                IteratorMode::Start,
+            )
+            .flatten()
+        {
+            invalid.push(serde_json::from_slice(&value)?);
+        }
+
+        Ok(invalid)
+    }
+}
@@ -0,0 +1,123 @@
+//! v0.16.3 -> v0.16.4 balance history backfill
+//!
+//! Walks the best chain, recording each staged ledger account whose balance
+//! differs from its previous entry. Accounts first seen in a persisted staged
+//! ledger (e.g. genesis) are not recorded.
+
+use super::MigrationCheckpoint;
+use crate::{
+    base::{public_key::PublicKey, state_hash::StateHash},
+    block::store::BlockStore,
+    ledger::{
+        account::Account,
+        store::balance_history::{BalanceHistoryEntry, BalanceHistoryStore},
+        token::TokenAddress,
+    },
+    store::{column_families::ColumnFamilyHelpers, IndexerStore, Result},
+    utility::store::ledger::staged::split_staged_account_key,
+};
+use log::info;
+use speedb::{Direction, IteratorMode};
+use std::collections::HashMap;
+
+/// Blocks between checkpoints & progress reports
+const CHECKPOINT_FREQ: usize = 1000;
+
+pub fn backfill(db: &IndexerStore, checkpoint: &mut MigrationCheckpoint) -> Result<()> {
+    let resume_height = checkpoint.cursor.unwrap_or_default();
+
+    // best chain blocks past the checkpoint, ascending
+    let mut best_chain = vec![];
+    let mut curr = db.get_best_block_hash()?;
+
+    while let Some(state_hash) = curr {
+        match db.get_block_height(&state_hash)? {
+            Some(height) if height > resume_height => {
+                curr = db.get_block_parent_hash(&state_hash)?;
+                best_chain.push((height, state_hash));
+            }
+            _ => break,
+        }
+    }
+
+    best_chain.reverse();
+
+    let num_blocks = best_chain.len();
+    let mut latest_balances = <HashMap<(PublicKey, TokenAddress), u64>>::new();
+
+    for (n, (height, state_hash)) in best_chain.into_iter().enumerate() {
+        backfill_block(db, &state_hash, height, &mut latest_balances)?;
+
+        if (n + 1) % CHECKPOINT_FREQ == 0 || n + 1 == num_blocks {
+            checkpoint.cursor = Some(height);
+            db.set_migration_checkpoint(checkpoint)?;
+
+            info!(
+                "Backfilled balance history for {}/{num_blocks} blocks",
+                n + 1
+            );
+        }
+    }
+
+    Ok(())
+}
+
+fn backfill_block(
+    db: &IndexerStore,
+    state_hash: &StateHash,
+    height: u32,
+    latest_balances: &mut HashMap<(PublicKey, TokenAddress), u64>,
+) -> Result<()> {
+    let is_persisted = db
+        .database
+        .get_cf(db.staged_ledgers_persisted_cf(), state_hash.0.as_bytes())?
+        .is_some();
+
+    let prefix = state_hash.0.as_bytes();
+    let iter = db.database.iterator_cf(
+        db.staged_ledger_accounts_cf(),
+        IteratorMode::From(prefix, Direction::Forward),
+    );
+
+    for (key, value) in iter.flatten() {
+        if !key.starts_with(prefix) {
+            break;
+        }
+
+        let Some((_, token, pk)) = split_staged_account_key(&key) else {
+            continue;
+        };
+
+        let account: Account = serde_json::from_slice(&value)?;
+        let balance = account.deduct_mina_account_creation_fee().balance.0;
+
+        let prev_balance = match latest_balances.get(&(pk.clone(), token.clone())) {
+            Some(balance) => Some(*balance),
+            None => db
+                .get_balance_history(&pk, &token, None, Some(height.saturating_sub(1)))?
+                .last()
+                .map(|entry| entry.balance),
+        };
+
+        let changed = match prev_balance {
+            Some(prev_balance) => prev_balance != balance,
+            None => !is_persisted,
+        };
+
+        if changed {
+            db.set_balance_history(
+                &pk,
+                &token,
+                &BalanceHistoryEntry {
+                    state_hash: state_hash.to_owned(),
+                    block_height: height,
+                    balance,
+                },
+            )?;
+        }
+
+        latest_balances.insert((pk, token), balance);
+    }
+
+    Ok(())
+}
@@ -0,0 +1,279 @@
+//! Online schema migrations between indexer store versions
+//!
+//! Each registered step transforms or backfills column families in place &
+//! bumps the on-disk version once it completes. A step's progress is
+//! checkpointed so an interrupted migration resumes where it left off.
+
+pub mod balance_history;
+pub mod zkapp_meta;
+
+use super::{fixed_keys::FixedKeys, version::IndexerStoreVersion, IndexerStore};
+use crate::store::Result;
+use anyhow::bail;
+use log::info;
+use serde::{Deserialize, Serialize};
+use speedb::WriteBatch;
+use std::collections::BTreeMap;
+
+/// `(major, minor, patch)`
+pub type Semver = (u32, u32, u32);
+
+/// Migration step from one store version to the next
+pub struct Migration {
+    pub description: &'static str,
+
+    /// Must be idempotent from the last saved checkpoint
+    pub step: fn(&IndexerStore, &mut MigrationCheckpoint) -> Result<()>,
+}
+
+/// Progress of the in-flight migration step
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct MigrationCheckpoint {
+    pub from: Semver,
+    pub to: Semver,
+
+    /// Step-specific resume point, e.g. the last processed block height
+    pub cursor: Option<u32>,
+}
+
+/// Migration steps keyed by `(from, to)` version
+///
+/// Versions without a path to the current version must be re-indexed
+pub fn registry() -> BTreeMap<(Semver, Semver), Migration> {
+    BTreeMap::from([
+        (
+            ((0, 16, 2), (0, 16, 3)),
+            Migration {
+                description: "Re-encode zkapp actions & events with their block & transaction",
+                step: zkapp_meta::reencode,
+            },
+        ),
+        (
+            ((0, 16, 3), (0, 16, 4)),
+            Migration {
+                description: "Backfill account balance history from staged ledger accounts",
+                step: balance_history::backfill,
+            },
+        ),
+        (
+            ((0, 16, 4), (0, 16, 5)),
+            Migration {
+                description: "Add the webhooks column family",
+                // missing column families are created when the store is opened
+                step: |_, _| Ok(()),
+            },
+        ),
+        (
+            ((0, 16, 5), (0, 16, 6)),
+            Migration {
+                description: "Add the invalid user command signatures column family",
+                step: |_, _| Ok(()),
+            },
+        ),
+        (
+            ((0, 16, 6), (0, 16, 7)),
+            Migration {
+                description: "Add the address book column families",
+                step: |_, _| Ok(()),
+            },
+        ),
+    ])
+}
+
+/// Steps from `from` to `to`, in order
+pub fn pending_migrations(
+    registry: &BTreeMap<(Semver, Semver), Migration>,
+    from: Semver,
+    to: Semver,
+) -> Result<Vec<(Semver, Semver, &Migration)>> {
+    let mut steps = vec![];
+    let mut curr = from;
+
+    while curr < to {
+        // take the largest step which doesn't overshoot
+        match registry
+            .iter()
+            .filter(|((step_from, step_to), _)| *step_from == curr && *step_to <= to)
+            .max_by_key(|((_, step_to), _)| *step_to)
+        {
+            Some(((step_from, step_to), migration)) => {
+                steps.push((*step_from, *step_to, migration));
+                curr = *step_to;
+            }
+            None => bail!(
+                "No migration from indexer store v{} to v{}, re-index from blocks",
+                display(from),
+                display(to)
+            ),
+        }
+    }
+
+    Ok(steps)
+}
+
+pub fn display((major, minor, patch): Semver) -> String {
+    format!("{major}.{minor}.{patch}")
+}
+
+impl IndexerStoreVersion {
+    pub fn semver(&self) -> Semver {
+        (self.major, self.minor, self.patch)
+    }
+
+    pub fn current_semver() -> Semver {
+        (Self::MAJOR, Self::MINOR, Self::PATCH)
+    }
+}
+
+impl IndexerStore {
+    /// Migrates an older on-disk store to the current version, returns
+    /// whether any steps were run
+    pub fn migrate(&self) -> Result<bool> {
+        let Some(version) = self.get_on_disk_version()? else {
+            // new store
+            return Ok(false);
+        };
+
+        let current = IndexerStoreVersion::current_semver();
+        if version.semver() >= current {
+            return Ok(false);
+        }
+
+        let registry = registry();
+        let steps = pending_migrations(&registry, version.semver(), current)?;
+        let num_steps = steps.len();
+
+        for (n, (from, to, migration)) in steps.into_iter().enumerate() {
+            info!(
+                "Migration step {}/{num_steps} v{} -> v{}: {}",
+                n + 1,
+                display(from),
+                display(to),
+                migration.description
+            );
+
+            // resume from the saved checkpoint
+            let mut checkpoint = match self.get_migration_checkpoint()? {
+                Some(checkpoint) if checkpoint.from == from && checkpoint.to == to => {
+                    info!("Resuming from checkpoint {:?}", checkpoint.cursor);
+                    checkpoint
+                }
+                _ => MigrationCheckpoint {
+                    from,
+                    to,
+                    cursor: None,
+                },
+            };
+
+            self.set_migration_checkpoint(&checkpoint)?;
+            (migration.step)(self, &mut checkpoint)?;
+
+            // bump the version & clear the checkpoint together
+            let (major, minor, patch) = to;
+            let version = IndexerStoreVersion {
+                major,
+                minor,
+                patch,
+                ..Default::default()
+            };
+
+            let mut batch = WriteBatch::default();
+            batch.put(
+                Self::INDEXER_STORE_VERSION_KEY,
+                serde_json::to_vec(&version)?,
+            );
+            batch.delete(Self::MIGRATION_CHECKPOINT_KEY);
+            self.database.write(batch)?;
+        }
+
+        info!("Migrated indexer store to v{}", display(current));
+        Ok(true)
+    }
+
+    /// Saves the in-flight step's progress
+    pub fn set_migration_checkpoint(&self, checkpoint: &MigrationCheckpoint) -> Result<()> {
+        Ok(self.database.put(
+            Self::MIGRATION_CHECKPOINT_KEY,
+            serde_json::to_vec(checkpoint)?,
+        )?)
+    }
+
+    pub fn get_migration_checkpoint(&self) -> Result<Option<MigrationCheckpoint>> {
+        Ok(self
+            .database
+            .get(Self::MIGRATION_CHECKPOINT_KEY)?
+            .map(|bytes| serde_json::from_slice(&bytes))
+            .transpose()?)
+    }
+
+    /// Version stored in the database, if any
+    fn get_on_disk_version(&self) -> Result<Option<IndexerStoreVersion>> {
+        Ok(self
+            .database
+            .get(Self::INDEXER_STORE_VERSION_KEY)?
+            .map(|bytes| serde_json::from_slice(&bytes))
+            .transpose()?)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    #[test]
+    fn pending_steps() -> anyhow::Result<()> {
+        let registry = registry();
+        let current = IndexerStoreVersion::current_semver();
+
+        // up to date
+        assert!(pending_migrations(&registry, current, current)?.is_empty());
+
+        // chained steps
+        let steps = pending_migrations(&registry, (0, 16, 3), (0, 16, 5))?;
+        let steps: Vec<_> = steps.into_iter().map(|(from, to, _)| (from, to)).collect();
+        assert_eq!(
+            steps,
+            vec![((0, 16, 3), (0, 16, 4)), ((0, 16, 4), (0, 16, 5))]
+        );
+
+        // 0.16.2 stores are migrated from the first step
+        let steps = pending_migrations(&registry, (0, 16, 2), current)?;
+        assert_eq!(steps[0].0, (0, 16, 2));
+        assert_eq!(steps.last().map(|(_, to, _)| *to), Some(current));
+
+        // no path
+        assert!(pending_migrations(&registry, (0, 16, 1), current).is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn migrate_from_0_16_2() -> anyhow::Result<()> {
+        let store_dir = TempDir::with_prefix(std::env::current_dir()?)?;
+        let store = IndexerStore::new(store_dir.path(), true)?;
+
+        // roll the on-disk version back
+        let version = IndexerStoreVersion {
+            major: 0,
+            minor: 16,
+            patch: 2,
+            ..Default::default()
+        };
+        store.database.put(
+            IndexerStore::INDEXER_STORE_VERSION_KEY,
+            serde_json::to_vec(&version)?,
+        )?;
+
+        // every step applies
+        assert!(store.migrate()?);
+        assert_eq!(
+            store.get_on_disk_version()?.map(|version| version.semver()),
+            Some(IndexerStoreVersion::current_semver())
+        );
+        assert!(store.get_migration_checkpoint()?.is_none());
+
+        // nothing left to do
+        assert!(!store.migrate()?);
+        Ok(())
+    }
+}
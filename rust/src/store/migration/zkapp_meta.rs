@@ -0,0 +1,224 @@
+//! v0.16.2 -> v0.16.3 zkapp actions & events re-encoding
+//!
+//! Actions & events were stored bare, they are now stored with the block &
+//! transaction which produced them. Walks the best chain, re-encoding each
+//! token account's stored actions & events in the order they were added.
+
+use super::MigrationCheckpoint;
+use crate::{
+    base::{public_key::PublicKey, state_hash::StateHash},
+    block::{precomputed::PcbVersion, store::BlockStore},
+    ledger::{
+        diff::account::AccountDiff, store::update::zkapp_action_event_diffs, token::TokenAddress,
+    },
+    mina_blocks::v2::{ActionState, ZkappEvent},
+    store::{
+        column_families::ColumnFamilyHelpers,
+        zkapp::{
+            actions::{ZkappActionStore, ZkappActionWithMeta},
+            events::{ZkappEventStore, ZkappEventWithMeta},
+        },
+        IndexerStore, Result,
+    },
+    utility::store::zkapp::{actions::zkapp_actions_key, events::zkapp_events_key},
+};
+use anyhow::{bail, Context};
+use log::info;
+use std::collections::HashMap;
+
+/// Blocks between checkpoints & progress reports
+const CHECKPOINT_FREQ: usize = 1000;
+
+/// Next stored index to re-encode, per token account
+type NextIndex = HashMap<(PublicKey, TokenAddress), u32>;
+
+pub fn reencode(db: &IndexerStore, checkpoint: &mut MigrationCheckpoint) -> Result<()> {
+    let resume_height = checkpoint.cursor.unwrap_or_default();
+
+    // best chain blocks past the checkpoint, ascending
+    let mut best_chain = vec![];
+    let mut curr = db.get_best_block_hash()?;
+
+    while let Some(state_hash) = curr {
+        match db.get_block_height(&state_hash)? {
+            Some(height) if height > resume_height => {
+                curr = db.get_block_parent_hash(&state_hash)?;
+                best_chain.push((height, state_hash));
+            }
+            _ => break,
+        }
+    }
+
+    best_chain.reverse();
+
+    let num_blocks = best_chain.len();
+    let mut next_actions = NextIndex::new();
+    let mut next_events = NextIndex::new();
+
+    for (n, (height, state_hash)) in best_chain.into_iter().enumerate() {
+        // only post-hardfork blocks contain zkapp commands
+        if db.get_block_version(&state_hash)? == Some(PcbVersion::V2) {
+            reencode_block(db, &state_hash, height, &mut next_actions, &mut next_events)?;
+        }
+
+        if (n + 1) % CHECKPOINT_FREQ == 0 || n + 1 == num_blocks {
+            checkpoint.cursor = Some(height);
+            db.set_migration_checkpoint(checkpoint)?;
+
+            info!(
+                "Re-encoded zkapp actions & events for {}/{num_blocks} blocks",
+                n + 1
+            );
+        }
+    }
+
+    Ok(())
+}
+
+fn reencode_block(
+    db: &IndexerStore,
+    state_hash: &StateHash,
+    height: u32,
+    next_actions: &mut NextIndex,
+    next_events: &mut NextIndex,
+) -> Result<()> {
+    for (txn_hash, diff) in zkapp_action_event_diffs(db, state_hash)? {
+        match diff {
+            AccountDiff::ZkappActions(diff) => {
+                for action in diff.actions {
+                    let index = next_index(next_actions, &diff.public_key, &diff.token, |i| {
+                        get_bare_action(db, &diff.public_key, &diff.token, i)
+                    })?;
+
+                    db.set_action(
+                        &diff.public_key,
+                        &diff.token,
+                        &ZkappActionWithMeta {
+                            action,
+                            state_hash: state_hash.to_owned(),
+                            block_height: height,
+                            txn_hash: txn_hash.to_owned(),
+                        },
+                        index,
+                    )?;
+                }
+            }
+            AccountDiff::ZkappEvents(diff) => {
+                for event in diff.events {
+                    let index = next_index(next_events, &diff.public_key, &diff.token, |i| {
+                        get_bare_event(db, &diff.public_key, &diff.token, i)
+                    })?;
+
+                    db.set_event(
+                        &diff.public_key,
+                        &diff.token,
+                        &ZkappEventWithMeta {
+                            event,
+                            state_hash: state_hash.to_owned(),
+                            block_height: height,
+                            txn_hash: txn_hash.to_owned(),
+                        },
+                        index,
+                    )?;
+                }
+            }
+            _ => (),
+        }
+    }
+
+    Ok(())
+}
+
+/// Index of the token account's first value still in the bare encoding
+///
+/// `is_bare(index)` is `Some(true)` for a bare value, `Some(false)` for a
+/// re-encoded value & `None` past the last stored value. Scanning (rather
+/// than counting from 0) makes resuming from a checkpoint idempotent.
+fn next_index<F>(
+    next: &mut NextIndex,
+    pk: &PublicKey,
+    token: &TokenAddress,
+    is_bare: F,
+) -> Result<u32>
+where
+    F: Fn(u32) -> Result<Option<bool>>,
+{
+    let key = (pk.to_owned(), token.to_owned());
+    let mut index = next.get(&key).copied().unwrap_or_default();
+
+    loop {
+        match is_bare(index)? {
+            Some(true) => break,
+            Some(false) => index += 1,
+            None => bail!("No stored value to re-encode for ({pk}, {token}) at {index}"),
+        }
+    }
+
+    next.insert(key, index + 1);
+    Ok(index)
+}
+
+fn get_bare_action(
+    db: &IndexerStore,
+    pk: &PublicKey,
+    token: &TokenAddress,
+    index: u32,
+) -> Result<Option<bool>> {
+    let Some(bytes) = db
+        .database
+        .get_cf(db.zkapp_actions_cf(), zkapp_actions_key(token, pk, index))?
+    else {
+        return Ok(None);
+    };
+
+    if serde_json::from_slice::<ZkappActionWithMeta>(&bytes).is_ok() {
+        return Ok(Some(false));
+    }
+
+    serde_json::from_slice::<ActionState>(&bytes)
+        .with_context(|| format!("invalid action {index} for ({pk}, {token})"))?;
+    Ok(Some(true))
+}
+
+fn get_bare_event(
+    db: &IndexerStore,
+    pk: &PublicKey,
+    token: &TokenAddress,
+    index: u32,
+) -> Result<Option<bool>> {
+    let Some(bytes) = db
+        .database
+        .get_cf(db.zkapp_events_cf(), zkapp_events_key(token, pk, index))?
+    else {
+        return Ok(None);
+    };
+
+    if serde_json::from_slice::<ZkappEventWithMeta>(&bytes).is_ok() {
+        return Ok(Some(false));
+    }
+
+    serde_json::from_slice::<ZkappEvent>(&bytes)
+        .with_context(|| format!("invalid event {index} for ({pk}, {token})"))?;
+    Ok(Some(true))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn next_index_skips_reencoded_values() -> anyhow::Result<()> {
+        let pk = PublicKey::default();
+        let token = TokenAddress::default();
+
+        // 0 & 1 re-encoded before an interruption, 2 & 3 bare
+        let stored = [false, false, true, true];
+        let is_bare = |i: u32| Ok(stored.get(i as usize).copied());
+
+        let mut next = NextIndex::new();
+        assert_eq!(next_index(&mut next, &pk, &token, is_bare)?, 2);
+        assert_eq!(next_index(&mut next, &pk, &token, is_bare)?, 3);
+        assert!(next_index(&mut next, &pk, &token, is_bare).is_err());
+        Ok(())
+    }
+}
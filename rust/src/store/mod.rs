@@ -4,11 +4,14 @@
 // traits
 pub mod column_families;
 pub mod fixed_keys;
+pub mod migration;
 pub mod username;
 pub mod version;
 pub mod zkapp;
 
 // impls
+pub mod address_book_store_impl;
+pub mod balance_history_store_impl;
 pub mod best_ledger_store_impl;
 pub mod block_store_impl;
 pub mod canonicity_store_impl;
@@ -16,16 +19,22 @@ pub mod chain_store_impl;
 pub mod column_families_impl;
 pub mod event_store_impl;
 pub mod internal_command_store_impl;
+pub mod invalid_signature_store_impl;
 pub mod snark_store_impl;
 pub mod staged_ledger_store_impl;
 pub mod staking_ledger_store_impl;
 pub mod user_command_store_impl;
 pub mod username_store_impl;
 pub mod version_store_impl;
+pub mod webhook_store_impl;
 pub mod zkapp_store_impl;
 
 use self::fixed_keys::FixedKeys;
-use crate::base::username::off_chain::OffChainUsernames;
+use crate::{
+    base::username::off_chain::OffChainUsernames, command::signed::verify::SignatureVerifier,
+    event::IndexerEvent, ledger::supply::Supply, metrics::Metrics,
+    proof_systems::poseidon::SpongeParams,
+};
 use anyhow::{anyhow, bail, Context};
 use log::{debug, info};
 use serde::{Deserialize, Serialize};
@@ -34,7 +43,9 @@ use std::{
     fs::{self, read_dir, File},
     io::{self, BufReader, BufWriter, Write},
     path::{Path, PathBuf},
+    sync::{OnceLock, RwLock},
 };
+use tokio::sync::broadcast;
 use version::{IndexerStoreVersion, VersionStore};
 
 pub(crate) type Result<T> = anyhow::Result<T>;
@@ -44,6 +55,21 @@ pub struct IndexerStore {
     pub db_path: PathBuf,
     pub database: DB,
     pub is_primary: bool,
+
+    /// Broadcasts recorded events to subscribers, e.g. GraphQL subscriptions
+    pub event_sender: broadcast::Sender<IndexerEvent>,
+
+    /// Ingestion & API metrics exported to Prometheus
+    pub metrics: Metrics,
+
+    /// Verifies user command signatures during ingestion, if set
+    pub signature_verifier: OnceLock<SignatureVerifier>,
+
+    /// Verifies staking ledger hashes during ingestion, if set
+    pub ledger_hash_params: OnceLock<SpongeParams>,
+
+    /// Best tip supply, recomputed once per best tip
+    pub best_supply: RwLock<Option<Supply>>,
 }
 
 #[derive(Default, Clone, PartialEq, Serialize, Deserialize)]
@@ -53,9 +79,14 @@ pub struct DbUpdate<T> {
 }
 
 impl IndexerStore {
+    /// Capacity of the event broadcast channel
+    ///
+    /// Slow subscribers skip the oldest events once this many are buffered
+    pub const EVENT_CHANNEL_CAPACITY: usize = 1024;
+
     /// Add the corresponding CF helper to [ColumnFamilyHelpers]
     /// & modify [IndexerStoreVersion] as needed!
-    const COLUMN_FAMILIES: [&'static str; 179] = [
+    const COLUMN_FAMILIES: [&'static str; 184] = [
         //////////////////////
         // Blocks store CFs //
         //////////////////////
@@ -107,6 +138,7 @@ impl IndexerStore {
         "user-commands-state-hashes",
         "user-commands-per-token-slot-sort",
         "user-commands-per-token-height-sort",
+        "user-commands-invalid-signature",
         // sorting user commands by sender/receiver
         "txn-from-slot-sort",
         "txn-from-height-sort",
@@ -202,6 +234,15 @@ impl IndexerStore {
         // Event store CFs //
         /////////////////////
         "events",
+        ///////////////////////
+        // Webhook store CFs //
+        ///////////////////////
+        "webhooks",
+        ////////////////////////////
+        // Address book store CFs //
+        ////////////////////////////
+        "address-book",
+        "address-book-category-sort",
         ///////////////////////////
         // Best ledger store CFs //
         ///////////////////////////
@@ -223,6 +264,10 @@ impl IndexerStore {
         "staged-ledger-accounts-min-block",
         "blocks-ledger-diff",
         "blocks-staged-ledger-hash",
+        ///////////////////////////////
+        // Balance history store CFs //
+        ///////////////////////////////
+        "balance-history",
         //////////////////////////////
         // Staking ledger store CFs //
         //////////////////////////////
@@ -303,6 +348,11 @@ impl IndexerStore {
         let primary = Self {
             is_primary: true,
             db_path: path.into(),
+            event_sender: broadcast::channel(Self::EVENT_CHANNEL_CAPACITY).0,
+            metrics: Metrics::default(),
+            signature_verifier: OnceLock::new(),
+            ledger_hash_params: OnceLock::new(),
+            best_supply: RwLock::default(),
             database: speedb::DBWithThreadMode::open_cf_descriptors(
                 &database_opts,
                 path,
@@ -310,6 +360,12 @@ impl IndexerStore {
             )?,
         };
 
+        // migrate older stores in place
+        if primary.migrate()? {
+            // refresh the persisted version
+            fs::remove_file(path.join(INDEXER_VERSION_FILE)).ok();
+        }
+
         // set db version
         primary.set_db_version_with_git_commit(
             IndexerStoreVersion::MAJOR,
@@ -371,6 +427,11 @@ impl IndexerStore {
         let read_only = Self {
             is_primary: false,
             db_path: secondary.into(),
+            event_sender: broadcast::channel(Self::EVENT_CHANNEL_CAPACITY).0,
+            metrics: Metrics::default(),
+            signature_verifier: OnceLock::new(),
+            ledger_hash_params: OnceLock::new(),
+            best_supply: RwLock::default(),
             database: speedb::DBWithThreadMode::open_cf_descriptors_as_secondary(
                 &database_opts,
                 primary,
@@ -452,12 +513,15 @@ fn user_command_db_key_pk(pk: &str, n: u32) -> Vec<u8> {
     format!("{COMMAND_KEY_PREFIX}{pk}{n}").into_bytes()
 }
 
+/// Database directory file recording the indexer store version
+pub const INDEXER_VERSION_FILE: &str = "INDEXER_VERSION";
+
 pub fn persist_indexer_version(
     indexer_version: &IndexerStoreVersion,
     path: impl AsRef<Path>,
 ) -> Result<()> {
     let mut versioned = path.as_ref().to_path_buf();
-    versioned.push("INDEXER_VERSION");
+    versioned.push(INDEXER_VERSION_FILE);
     if !versioned.exists() {
         debug!("persisting INDEXER_VERSION in the database directory");
         let serialized = serde_json::to_string(indexer_version)?;
@@ -469,6 +533,16 @@ pub fn persist_indexer_version(
     Ok(())
 }
 
+/// Read the persisted indexer store version without opening the database
+pub fn read_indexer_version(path: impl AsRef<Path>) -> Result<Option<IndexerStoreVersion>> {
+    let versioned = path.as_ref().join(INDEXER_VERSION_FILE);
+    if !versioned.exists() {
+        return Ok(None);
+    }
+
+    Ok(Some(serde_json::from_slice(&fs::read(versioned)?)?))
+}
+
 impl FixedKeys for IndexerStore {}
 
 impl IndexerStore {
@@ -14,7 +14,7 @@ use crate::{
             best::BestLedgerStore,
             staged::{StagedLedgerStore, StateHashWithHeight},
         },
-        token::{Token, TokenAddress, TokenSymbol},
+        token::{account::TokenAccount, Token, TokenAddress, TokenSymbol},
         Ledger, LedgerHash,
     },
     store::{zkapp::tokens::ZkappTokenStore, Result},
@@ -35,27 +35,15 @@ impl StagedLedgerStore for IndexerStore {
     ) -> Result<Option<Account>> {
         trace!("Getting {} staged ledger {} account", pk, state_hash);
 
-        // check if the account is in a sufficiently low staged ledger
-        match self.get_pk_min_staged_ledger_block(pk)? {
-            Some(pk_min_block) => {
-                if let Some(block_height) = self.get_block_height(state_hash)? {
-                    if pk_min_block.blockchain_length > block_height {
-                        return Ok(None);
-                    }
-                }
-            }
-            None => return Ok(None),
-        }
-
-        // calculate account from canonical ancestor if needed
+        // walk chain back to the nearest persisted staged ledger
         let mut apply_block_diffs = vec![];
         let mut curr_state_hash = state_hash.clone();
 
         while self
             .database
             .get_cf(
-                self.staged_ledger_accounts_cf(),
-                staged_account_key(&curr_state_hash, token, pk),
+                self.staged_ledgers_persisted_cf(),
+                curr_state_hash.0.as_bytes(),
             )?
             .is_none()
         {
@@ -69,24 +57,36 @@ impl StagedLedgerStore for IndexerStore {
 
         apply_block_diffs.reverse();
 
+        // the account may not exist in the persisted staged ledger
         let mut staged_account = self
             .database
             .get_cf(
                 self.staged_ledger_accounts_cf(),
                 staged_account_key(&curr_state_hash, token, pk),
             )?
-            .map(|bytes| serde_json::from_slice::<Account>(&bytes).expect("staged account"))
-            .with_context(|| format!("pk {} state hash {}", pk, curr_state_hash))
-            .expect("account exists");
+            .map(|bytes| serde_json::from_slice::<Account>(&bytes))
+            .transpose()
+            .with_context(|| format!("pk {} state hash {}", pk, curr_state_hash))?;
+
+        // apply the token account's diffs from each subsequent block
+        for block_state_hash in apply_block_diffs.iter() {
+            let diff = self
+                .get_block_ledger_diff(block_state_hash)?
+                .with_context(|| format!("ledger diff missing from store {block_state_hash}"))?;
+
+            for acct_diff in diff.account_diffs.iter().flatten() {
+                if acct_diff.public_key() != *pk || acct_diff.token() != *token {
+                    continue;
+                }
 
-        for diff in apply_block_diffs
-            .iter()
-            .flat_map(|state_hash| self.get_block_ledger_diff(state_hash).expect("ledger diff"))
-        {
-            staged_account = staged_account.apply_ledger_diff(&diff);
+                let account = staged_account.unwrap_or_else(|| {
+                    Account::empty(pk.clone(), token.clone(), acct_diff.creation_fee_paid())
+                });
+                staged_account = Some(account.apply_account_diff(acct_diff, &diff.state_hash));
+            }
         }
 
-        Ok(Some(staged_account))
+        Ok(staged_account)
     }
 
     fn get_staged_account_display(
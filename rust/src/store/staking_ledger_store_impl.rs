@@ -4,13 +4,17 @@ use super::{column_families::ColumnFamilyHelpers, IndexerStore};
 use crate::{
     base::{public_key::PublicKey, state_hash::StateHash},
     block::store::BlockStore,
+    canonicity::store::CanonicityStore,
     chain::store::ChainStore,
     event::{db::*, store::EventStore, IndexerEvent},
     ledger::{
         staking::{
             AggregatedEpochStakeDelegations, EpochStakeDelegation, StakingAccount, StakingLedger,
         },
-        store::staking::{StakingAccountWithEpochDelegation, StakingLedgerStore},
+        store::{
+            staged::StagedLedgerStore,
+            staking::{StakingAccountWithEpochDelegation, StakingLedgerStore},
+        },
         LedgerHash,
     },
     store::Result,
@@ -20,10 +24,16 @@ use crate::{
     },
 };
 use anyhow::Context;
-use log::{error, trace};
+use log::{debug, error, info, trace, warn};
 use speedb::{DBIterator, Direction, IteratorMode};
 use std::collections::HashMap;
 
+/// Staking ledger mismatches logged per epoch
+const MAX_REPORTED_MISMATCHES: usize = 20;
+
+/// Canonical blocks searched for the snarked ledger's staged ledger
+const SNARKED_LEDGER_SEARCH_DEPTH: u32 = 1_000;
+
 impl StakingLedgerStore for IndexerStore {
     fn get_staking_account(
         &self,
@@ -218,6 +228,137 @@ impl StakingLedgerStore for IndexerStore {
         Ok(())
     }
 
+    fn add_derived_staking_ledger(&self, state_hash: &StateHash) -> Result<()> {
+        let (Some(height), Some(parent_hash)) = (
+            self.get_block_height(state_hash)?,
+            self.get_block_parent_hash(state_hash)?,
+        ) else {
+            return Ok(());
+        };
+
+        // only canonical blocks with a canonical parent
+        if height <= 1
+            || self.get_canonical_hash_at_height(height)?.as_ref() != Some(state_hash)
+            || self.get_canonical_hash_at_height(height - 1)?.as_ref() != Some(&parent_hash)
+        {
+            return Ok(());
+        }
+
+        // only the first canonical block of each epoch
+        match (
+            self.get_block_epoch(state_hash)?,
+            self.get_block_epoch(&parent_hash)?,
+        ) {
+            (Some(epoch), Some(parent_epoch)) if epoch != parent_epoch => (),
+            _ => return Ok(()),
+        }
+
+        let (Some((block, _)), Some((parent, _))) =
+            (self.get_block(state_hash)?, self.get_block(&parent_hash)?)
+        else {
+            return Ok(());
+        };
+
+        // a new chain's genesis block has no previous epoch
+        let genesis_state_hash = block.genesis_state_hash();
+        if genesis_state_hash == *state_hash {
+            return Ok(());
+        }
+
+        // entering an epoch, the next epoch ledger becomes the snarked ledger
+        // as of the end of the previous epoch
+        let ledger_hash = block.next_epoch_ledger_hash();
+        if ledger_hash != parent.snarked_ledger_hash() {
+            warn!(
+                "Block {} next epoch ledger hash {ledger_hash} isn't its parent's snarked ledger hash {}",
+                block.summary(),
+                parent.snarked_ledger_hash()
+            );
+            return Ok(());
+        }
+
+        self.derive_staking_ledger(
+            &parent_hash,
+            block.epoch_count() + 1,
+            &ledger_hash,
+            &genesis_state_hash,
+        )
+    }
+
+    fn derive_staking_ledger(
+        &self,
+        state_hash: &StateHash,
+        epoch: u32,
+        ledger_hash: &LedgerHash,
+        genesis_state_hash: &StateHash,
+    ) -> Result<()> {
+        let Some(height) = self.get_block_height(state_hash)? else {
+            return Ok(());
+        };
+
+        // the snarked ledger lags the staged ledger, it's the staged ledger of
+        // the latest canonical block with its ledger hash
+        let mut snarked_ledger = None;
+        for height in (height.saturating_sub(SNARKED_LEDGER_SEARCH_DEPTH).max(1)..=height).rev() {
+            let Some(canonical_hash) = self.get_canonical_hash_at_height(height)? else {
+                break;
+            };
+
+            if self.get_block_staged_ledger_hash(&canonical_hash)?.as_ref() == Some(ledger_hash) {
+                snarked_ledger = self.get_staged_ledger_at_state_hash(&canonical_hash, false)?;
+                break;
+            }
+        }
+
+        let Some(snarked_ledger) = snarked_ledger else {
+            warn!(
+                "Unable to derive epoch {epoch} staking ledger, no canonical staged ledger has the snarked ledger hash {ledger_hash}"
+            );
+            return Ok(());
+        };
+
+        let derived = StakingLedger::from_staged_ledger(
+            &snarked_ledger,
+            epoch,
+            self.get_current_network()?,
+            ledger_hash.clone(),
+            genesis_state_hash.clone(),
+        );
+
+        match self.build_staking_ledger(epoch, genesis_state_hash)? {
+            None => {
+                info!("Adding derived staking ledger {}", derived.summary());
+                self.add_staking_ledger(derived)?;
+            }
+            Some(supplied) => {
+                if supplied.ledger_hash != derived.ledger_hash {
+                    warn!(
+                        "Supplied staking ledger {} hash differs from derived {}",
+                        supplied.summary(),
+                        derived.summary()
+                    );
+                }
+
+                let mismatches = derived.mismatches(&supplied);
+                if mismatches.is_empty() {
+                    debug!("Derived staking ledger {} matches", derived.summary());
+                } else {
+                    warn!(
+                        "Derived staking ledger {} has {} mismatches with the supplied ledger",
+                        derived.summary(),
+                        mismatches.len()
+                    );
+
+                    for mismatch in mismatches.iter().take(MAX_REPORTED_MISMATCHES) {
+                        warn!("  {mismatch}");
+                    }
+                }
+            }
+        }
+
+        Ok(())
+    }
+
     fn get_epoch_delegations(
         &self,
         pk: &PublicKey,
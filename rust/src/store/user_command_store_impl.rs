@@ -12,7 +12,9 @@ use crate::{
         BlockComparison,
     },
     command::{
-        signed::{SignedCommandWithData, TxnHash},
+        signed::{
+            store::InvalidSignatureStore, verify::InvalidSignature, SignedCommandWithData, TxnHash,
+        },
         store::UserCommandStore,
         UserCommandWithStatus, UserCommandWithStatusT,
     },
@@ -22,16 +24,16 @@ use crate::{
     utility::store::{
         block::{epoch_key, epoch_pk_key},
         command::user::{
-            pk_txn_sort_key, pk_txn_sort_key_nonce, token_txn_sort_key, txn_block_key,
-            txn_hash_of_key, txn_sort_key,
+            pk_txn_sort_key, pk_txn_sort_key_nonce, pk_txn_sort_key_state_hash, token_txn_sort_key,
+            txn_block_key, txn_hash_of_key, txn_sort_key,
         },
         common::{from_be_bytes, pk_key_prefix, pk_txn_sort_key_sort, U32_LEN},
     },
 };
 use anyhow::{bail, Context, Result};
 use log::{trace, warn};
-use speedb::{DBIterator, Direction, IteratorMode, WriteBatch};
-use std::path::PathBuf;
+use speedb::{ColumnFamily, DBIterator, Direction, IteratorMode, WriteBatch};
+use std::{iter::Peekable, path::PathBuf};
 
 impl UserCommandStore for IndexerStore {
     fn add_user_commands_batch(
@@ -84,6 +86,26 @@ impl UserCommandStore for IndexerStore {
                 );
             }
 
+            // record invalid signatures
+            if let Some(reason) = self
+                .signature_verifier
+                .get()
+                .and_then(|verifier| verifier.invalid_reason(&signed_command_with_data.command))
+            {
+                warn!(
+                    "Invalid signature {txn_hash} block {}: {reason}",
+                    block.summary()
+                );
+                self.set_invalid_signature_batch(
+                    &InvalidSignature {
+                        txn_hash: txn_hash.clone(),
+                        state_hash: state_hash.clone(),
+                        reason,
+                    },
+                    batch,
+                )?;
+            }
+
             // add state hash index
             self.set_user_command_state_hash_batch(state_hash.clone(), &txn_hash, batch)?;
 
@@ -398,6 +420,84 @@ impl UserCommandStore for IndexerStore {
         Ok(None)
     }
 
+    fn get_user_commands_for_public_key_page(
+        &self,
+        pk: &PublicKey,
+        cursor: Option<(&TxnHash, &StateHash)>,
+        offset: usize,
+        limit: usize,
+    ) -> Result<Vec<SignedCommandWithData>> {
+        trace!("Getting user commands page for public key {pk}");
+
+        // start just past the cursor's sort key, keys have a fixed length
+        let mut start = pk.0.as_bytes().to_vec();
+        if let Some((txn_hash, state_hash)) = cursor {
+            let Some(command) = self.get_user_command_state_hash(txn_hash, state_hash)? else {
+                bail!("Missing user command {txn_hash} in block {state_hash}");
+            };
+
+            start = pk_txn_sort_key(
+                pk,
+                command.blockchain_length,
+                command.nonce.0,
+                txn_hash,
+                state_hash,
+            )
+            .to_vec();
+            start.push(0);
+        }
+
+        fn pk_keys<'a>(
+            db: &'a IndexerStore,
+            cf: &'a ColumnFamily,
+            start: &[u8],
+            pk: &'a PublicKey,
+        ) -> Peekable<impl Iterator<Item = Box<[u8]>> + 'a> {
+            db.database
+                .iterator_cf(cf, IteratorMode::From(start, Direction::Forward))
+                .flatten()
+                .map(|(key, _)| key)
+                .take_while(|key| key.starts_with(pk.0.as_bytes()))
+                .peekable()
+        }
+
+        // merge sent & received, self-sent commands appear in both
+        let mut sent = pk_keys(self, self.txn_from_height_sort_cf(), &start, pk);
+        let mut received = pk_keys(self, self.txn_to_height_sort_cf(), &start, pk);
+        let mut commands = Vec::with_capacity(limit);
+        let mut skipped = 0;
+
+        while commands.len() < limit {
+            let key = match (sent.peek(), received.peek()) {
+                (None, None) => break,
+                (Some(_), None) => sent.next(),
+                (None, Some(_)) => received.next(),
+                (Some(s), Some(r)) => match s.cmp(r) {
+                    std::cmp::Ordering::Less => sent.next(),
+                    std::cmp::Ordering::Greater => received.next(),
+                    std::cmp::Ordering::Equal => {
+                        received.next();
+                        sent.next()
+                    }
+                },
+            }
+            .expect("peeked key");
+
+            if skipped < offset {
+                skipped += 1;
+                continue;
+            }
+
+            let txn_hash = txn_hash_of_key(&key);
+            let state_hash = pk_txn_sort_key_state_hash(&key);
+            if let Some(command) = self.get_user_command_state_hash(&txn_hash, &state_hash)? {
+                commands.push(command);
+            }
+        }
+
+        Ok(commands)
+    }
+
     fn get_user_commands_with_bounds(
         &self,
         pk: &PublicKey,
@@ -1541,6 +1641,61 @@ mod tests {
         IndexerStore::new(temp_dir.path(), true)
     }
 
+    #[test]
+    fn public_key_user_commands_pages() -> Result<()> {
+        let store = create_indexer_store()?;
+
+        let path = Path::new("./tests/data/misc_blocks/mainnet-128743-3NLmYZD9eaV58opgC5RzQXaoPbyC15McNxw1CuCNatj7F9vGBbNz.json");
+        let pcb = PrecomputedBlock::parse_file(path, PcbVersion::V1)?;
+
+        let mut batch = WriteBatch::default();
+        store.add_user_commands_batch(&pcb, &mut batch)?;
+        store.database.write(batch)?;
+
+        let pk = pcb.commands()[0].sender();
+        let mut all: Vec<_> = store
+            .get_user_commands_for_public_key(&pk)?
+            .unwrap_or_default()
+            .into_iter()
+            .map(|cmd| (cmd.txn_hash, cmd.state_hash))
+            .collect();
+        all.sort();
+        all.dedup();
+
+        // page through one command at a time from the last command
+        let mut paged = vec![];
+        let mut cursor: Option<(TxnHash, StateHash)> = None;
+        loop {
+            let page = store.get_user_commands_for_public_key_page(
+                &pk,
+                cursor
+                    .as_ref()
+                    .map(|(txn_hash, state_hash)| (txn_hash, state_hash)),
+                0,
+                1,
+            )?;
+
+            let Some(cmd) = page.into_iter().next() else {
+                break;
+            };
+
+            cursor = Some((cmd.txn_hash.clone(), cmd.state_hash.clone()));
+            paged.push((cmd.txn_hash, cmd.state_hash));
+        }
+
+        assert!(!paged.is_empty());
+        assert_eq!(
+            store
+                .get_user_commands_for_public_key_page(&pk, None, 1, paged.len())?
+                .len(),
+            paged.len() - 1
+        );
+
+        paged.sort();
+        assert_eq!(paged, all);
+        Ok(())
+    }
+
     #[test]
     fn increment_non_zkapp_commands_counts() -> Result<()> {
         let store = create_indexer_store()?;
@@ -0,0 +1,84 @@
+//! Webhook store impl
+
+use super::{column_families::ColumnFamilyHelpers, IndexerStore};
+use crate::{
+    base::public_key::PublicKey,
+    event::store::EventStore,
+    store::Result,
+    utility::store::{
+        common::from_be_bytes,
+        webhook::{split_webhook_key, webhook_cursor_key, webhook_key},
+    },
+    webhook::{store::WebhookStore, Webhook},
+};
+use log::trace;
+use speedb::IteratorMode;
+
+impl WebhookStore for IndexerStore {
+    fn add_webhook(&self, pk: &PublicKey, url: &str) -> Result<()> {
+        trace!("Adding webhook {url} for {pk}");
+
+        if self.get_webhook_cursor(url)?.is_none() {
+            self.set_webhook_cursor(url, self.get_next_seq_num()?)?;
+        }
+
+        Ok(self
+            .database
+            .put_cf(self.webhooks_cf(), webhook_key(pk, url), b"")?)
+    }
+
+    fn remove_webhook(&self, pk: &PublicKey, url: &str) -> Result<bool> {
+        trace!("Removing webhook {url} for {pk}");
+
+        let key = webhook_key(pk, url);
+        if self.database.get_cf(self.webhooks_cf(), &key)?.is_none() {
+            return Ok(false);
+        }
+
+        self.database.delete_cf(self.webhooks_cf(), key)?;
+
+        // drop the cursor of unwatched urls
+        if self
+            .get_webhooks()?
+            .iter()
+            .all(|webhook| webhook.url != url)
+        {
+            self.database.delete(webhook_cursor_key(url))?;
+        }
+
+        Ok(true)
+    }
+
+    fn get_webhooks(&self) -> Result<Vec<Webhook>> {
+        trace!("Getting webhooks");
+
+        let mut webhooks = vec![];
+        for (key, _) in self
+            .database
+            .iterator_cf(self.webhooks_cf(), IteratorMode::Start)
+            .flatten()
+        {
+            let (public_key, url) = split_webhook_key(&key)?;
+            webhooks.push(Webhook { public_key, url });
+        }
+
+        Ok(webhooks)
+    }
+
+    fn get_webhook_cursor(&self, url: &str) -> Result<Option<u32>> {
+        trace!("Getting webhook cursor for {url}");
+
+        Ok(self
+            .database
+            .get(webhook_cursor_key(url))?
+            .map(from_be_bytes))
+    }
+
+    fn set_webhook_cursor(&self, url: &str, seq_num: u32) -> Result<()> {
+        trace!("Setting webhook cursor for {url} to {seq_num}");
+
+        Ok(self
+            .database
+            .put(webhook_cursor_key(url), seq_num.to_be_bytes())?)
+    }
+}
@@ -1,9 +1,22 @@
 //! Zkapp action store trait
 
 use crate::{
-    base::public_key::PublicKey, ledger::token::TokenAddress, mina_blocks::v2::ActionState,
+    base::{public_key::PublicKey, state_hash::StateHash},
+    command::TxnHash,
+    ledger::token::TokenAddress,
+    mina_blocks::v2::ActionState,
     store::Result,
 };
+use serde::{Deserialize, Serialize};
+
+/// Zkapp action with the block & transaction which produced it
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ZkappActionWithMeta {
+    pub action: ActionState,
+    pub state_hash: StateHash,
+    pub block_height: u32,
+    pub txn_hash: TxnHash,
+}
 
 pub trait ZkappActionStore {
     /// Add actions to the token account
@@ -13,7 +26,7 @@ pub trait ZkappActionStore {
         &self,
         pk: &PublicKey,
         token: &TokenAddress,
-        actions: &[ActionState],
+        actions: &[ZkappActionWithMeta],
     ) -> Result<u32>;
 
     /// Get the `index`th action for the token account
@@ -22,14 +35,14 @@ pub trait ZkappActionStore {
         pk: &PublicKey,
         token: &TokenAddress,
         index: u32,
-    ) -> Result<Option<ActionState>>;
+    ) -> Result<Option<ZkappActionWithMeta>>;
 
     /// Set the `index`th action for the token account
     fn set_action(
         &self,
         pk: &PublicKey,
         token: &TokenAddress,
-        action: &ActionState,
+        action: &ZkappActionWithMeta,
         index: u32,
     ) -> Result<()>;
 
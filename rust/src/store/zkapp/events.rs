@@ -1,9 +1,22 @@
 //! Zkapp event store trait
 
 use crate::{
-    base::public_key::PublicKey, ledger::token::TokenAddress, mina_blocks::v2::ZkappEvent,
+    base::{public_key::PublicKey, state_hash::StateHash},
+    command::TxnHash,
+    ledger::token::TokenAddress,
+    mina_blocks::v2::ZkappEvent,
     store::Result,
 };
+use serde::{Deserialize, Serialize};
+
+/// Zkapp event with the block & transaction which produced it
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ZkappEventWithMeta {
+    pub event: ZkappEvent,
+    pub state_hash: StateHash,
+    pub block_height: u32,
+    pub txn_hash: TxnHash,
+}
 
 pub trait ZkappEventStore {
     /// Add events to the token account
@@ -13,7 +26,7 @@ pub trait ZkappEventStore {
         &self,
         pk: &PublicKey,
         token: &TokenAddress,
-        events: &[ZkappEvent],
+        events: &[ZkappEventWithMeta],
     ) -> Result<u32>;
 
     /// Get the `index`th event for the token account
@@ -22,14 +35,14 @@ pub trait ZkappEventStore {
         pk: &PublicKey,
         token: &TokenAddress,
         index: u32,
-    ) -> Result<Option<ZkappEvent>>;
+    ) -> Result<Option<ZkappEventWithMeta>>;
 
     /// Set the `index`th event for the token account
     fn set_event(
         &self,
         pk: &PublicKey,
         token: &TokenAddress,
-        event: &ZkappEvent,
+        event: &ZkappEventWithMeta,
         index: u32,
     ) -> Result<()>;
 
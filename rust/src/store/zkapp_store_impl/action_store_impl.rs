@@ -3,10 +3,10 @@
 use crate::{
     base::public_key::PublicKey,
     ledger::token::TokenAddress,
-    mina_blocks::v2::zkapp::action_state::ActionState,
     store::{
-        column_families::ColumnFamilyHelpers, zkapp::actions::ZkappActionStore, IndexerStore,
-        Result,
+        column_families::ColumnFamilyHelpers,
+        zkapp::actions::{ZkappActionStore, ZkappActionWithMeta},
+        IndexerStore, Result,
     },
     utility::store::{
         common::from_be_bytes,
@@ -21,7 +21,7 @@ impl ZkappActionStore for IndexerStore {
         &self,
         pk: &PublicKey,
         token: &TokenAddress,
-        actions: &[ActionState],
+        actions: &[ZkappActionWithMeta],
     ) -> Result<u32> {
         trace!("Adding actions to token account ({pk}, {token}): {actions:?}");
 
@@ -49,7 +49,7 @@ impl ZkappActionStore for IndexerStore {
         pk: &PublicKey,
         token: &TokenAddress,
         index: u32,
-    ) -> Result<Option<ActionState>> {
+    ) -> Result<Option<ZkappActionWithMeta>> {
         trace!("Getting action {index} for token account ({pk}, {token})");
 
         Ok(self
@@ -66,7 +66,7 @@ impl ZkappActionStore for IndexerStore {
         &self,
         pk: &PublicKey,
         token: &TokenAddress,
-        action: &ActionState,
+        action: &ZkappActionWithMeta,
         index: u32,
     ) -> Result<()> {
         trace!("Setting action {index} for token account ({pk}, {token})");
@@ -3,9 +3,10 @@
 use crate::{
     base::public_key::PublicKey,
     ledger::token::TokenAddress,
-    mina_blocks::v2::ZkappEvent,
     store::{
-        column_families::ColumnFamilyHelpers, zkapp::events::ZkappEventStore, IndexerStore, Result,
+        column_families::ColumnFamilyHelpers,
+        zkapp::events::{ZkappEventStore, ZkappEventWithMeta},
+        IndexerStore, Result,
     },
     utility::store::{
         common::from_be_bytes,
@@ -20,7 +21,7 @@ impl ZkappEventStore for IndexerStore {
         &self,
         pk: &PublicKey,
         token: &TokenAddress,
-        events: &[ZkappEvent],
+        events: &[ZkappEventWithMeta],
     ) -> Result<u32> {
         trace!("Adding events to token account ({pk}, {token}): {events:?}");
 
@@ -48,7 +49,7 @@ impl ZkappEventStore for IndexerStore {
         pk: &PublicKey,
         token: &TokenAddress,
         index: u32,
-    ) -> Result<Option<ZkappEvent>> {
+    ) -> Result<Option<ZkappEventWithMeta>> {
         trace!("Getting event {index} for token account ({pk}, {token})");
 
         Ok(self
@@ -65,7 +66,7 @@ impl ZkappEventStore for IndexerStore {
         &self,
         pk: &PublicKey,
         token: &TokenAddress,
-        event: &ZkappEvent,
+        event: &ZkappEventWithMeta,
         index: u32,
     ) -> Result<()> {
         trace!("Setting event {index} for token account ({pk}, {token})");
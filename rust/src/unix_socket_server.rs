@@ -1,40 +1,71 @@
 use crate::{
-    base::{public_key::PublicKey, state_hash::StateHash},
+    address_book::{store::AddressBookStore, Label},
+    base::{public_key::PublicKey, state_hash::StateHash, username::Username},
     block::{
-        precomputed::{PcbVersion, PrecomputedBlockWithCanonicity},
+        precomputed::{PcbVersion, PrecomputedBlock},
+        production::ProducerReport,
         store::BlockStore,
         BlockWithoutHeight,
     },
-    canonicity::store::CanonicityStore,
+    canonicity::{store::CanonicityStore, Canonicity},
+    cli::{
+        output::OutputFormat,
+        response::{write_items, BlockData, CommandData, SummaryData, TransactionData},
+    },
     client::*,
     command::{
-        internal::store::InternalCommandStore, signed::TxnHash, store::UserCommandStore, Command,
+        hash,
+        internal::store::InternalCommandStore,
+        signed::{SignedCommandWithData, TxnHash},
+        store::UserCommandStore,
+        Command,
     },
     constants::{HARDFORK_GENESIS_HASH, MAINNET_GENESIS_HASH},
     ledger::{
-        staking::AggregatedEpochStakeDelegation,
-        store::{best::BestLedgerStore, staged::StagedLedgerStore, staking::StakingLedgerStore},
+        staking::{payout::PoolPayouts, AggregatedEpochStakeDelegation, StakingLedger},
+        store::{
+            balance_history::BalanceHistoryStore, best::BestLedgerStore, staged::StagedLedgerStore,
+            staking::StakingLedgerStore,
+        },
         token::TokenAddress,
         Ledger, LedgerHash,
     },
     snark_work::store::SnarkStore,
-    state::{summary::SummaryShort, IndexerState},
-    store::version::VersionStore,
+    state::IndexerState,
+    store::{version::VersionStore, IndexerStore},
+    webhook::{http::HttpUrl, store::WebhookStore, Webhook},
 };
 use anyhow::{bail, Context};
-use bincode::{Decode, Encode};
+use bincode::{error::DecodeError, Decode, Encode};
 use log::{debug, error, info, trace, warn};
+use serde::{Deserialize, Serialize};
 use std::{
     io::{self, ErrorKind},
-    path::Path,
+    path::{Path, PathBuf},
     sync::Arc,
+    time::Instant,
 };
 use tokio::{
-    io::AsyncWriteExt,
+    io::{AsyncRead, AsyncReadExt, AsyncWrite, AsyncWriteExt},
     net::{UnixListener, UnixStream},
-    sync::RwLock,
+    sync::{mpsc, RwLock, Semaphore},
 };
-use tokio_graceful_shutdown::{FutureExt, SubsystemHandle};
+use tokio_graceful_shutdown::{ErrorAction, FutureExt, SubsystemBuilder, SubsystemHandle};
+
+/// Maximum number of client connections served concurrently
+pub const MAX_CONCURRENT_CONNECTIONS: usize = 32;
+
+/// Maximum size of an encoded client request
+pub const MAX_REQUEST_SIZE: usize = 64 * 1024;
+
+/// Maximum size of a streamed response chunk
+pub const RESPONSE_CHUNK_SIZE: usize = 64 * 1024;
+
+/// Maximum number of response chunks buffered ahead of the connection
+pub const RESPONSE_CHANNEL_CAPACITY: usize = 4;
+
+/// Number of user commands read from the store at a time while streaming
+pub const USER_COMMANDS_PAGE_SIZE: usize = 256;
 
 /// Create Unix Domain Socket listener
 pub fn create_socket_listener(domain_socket_path: &Path) -> UnixListener {
@@ -48,1137 +79,1442 @@ pub fn create_socket_listener(domain_socket_path: &Path) -> UnixListener {
     listener
 }
 
-async fn parse_conn_to_cli(stream: &UnixStream) -> anyhow::Result<ClientCli> {
-    loop {
-        stream.readable().await?;
+/// Writes the client's request, prefixed by its big-endian `u32` length
+pub async fn write_request<W>(writer: &mut W, request: ClientRequest) -> anyhow::Result<()>
+where
+    W: AsyncWrite + Unpin,
+{
+    let encoded = bincode::encode_to_vec(request, BIN_CODE_CONFIG)?;
 
-        let mut buffer = Vec::with_capacity(BUFFER_SIZE);
-        match stream.try_read_buf(&mut buffer) {
-            Ok(0) => break,
-            Ok(n) => {
-                buffer.truncate(n);
-            }
-            Err(ref e) if e.kind() == io::ErrorKind::WouldBlock => {
-                continue;
-            }
-            Err(e) => {
-                return Err(e.into());
-            }
-        }
+    writer.write_u32(encoded.len() as u32).await?;
+    writer.write_all(&encoded).await?;
+    writer.flush().await?;
+    Ok(())
+}
 
-        let (command, _): (ClientCli, usize) =
-            bincode::decode_from_slice(&buffer, BIN_CODE_CONFIG)?;
+/// Reads a request written by [write_request], however many reads it takes
+async fn read_request<R>(reader: &mut R) -> anyhow::Result<ClientRequest>
+where
+    R: AsyncRead + Unpin,
+{
+    let len = reader
+        .read_u32()
+        .await
+        .context("Unix domain socket request ended before its length")? as usize;
 
-        return Ok(command);
+    if len > MAX_REQUEST_SIZE {
+        bail!("Unix domain socket request too large: {len} bytes");
     }
 
-    bail!("Unexpected Unix domain socket read error");
+    let mut buffer = vec![0; len];
+    reader
+        .read_exact(&mut buffer)
+        .await
+        .context("Unix domain socket request ended early")?;
+
+    let (request, _) = bincode::decode_from_slice(&buffer, BIN_CODE_CONFIG)?;
+    Ok(request)
 }
 
 #[derive(Debug, Encode, Decode)]
 pub enum ServerCliResponse {
     Success(String),
-    Error(String),
+    Error(ServerCliError),
 }
 
-#[allow(clippy::too_many_lines)]
+/// Structured error, scripts should match on the code rather than the
+/// message
+#[derive(Debug, Clone, PartialEq, Eq, Encode, Decode, Serialize, Deserialize)]
+pub struct ServerCliError {
+    pub code: ServerCliErrorCode,
+    pub message: String,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Encode, Decode, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ServerCliErrorCode {
+    InvalidPublicKey,
+    InvalidStateHash,
+    InvalidLedgerHash,
+    InvalidTokenAddress,
+    InvalidTxnHash,
+    InvalidWebhookUrl,
+    InvalidPath,
+    InvalidQuery,
+    NotFound,
+    StoreUnavailable,
+    Unsupported,
+    Internal,
+}
+
+impl ServerCliResponse {
+    pub fn error(code: ServerCliErrorCode, message: impl Into<String>) -> Self {
+        Self::Error(ServerCliError {
+            code,
+            message: message.into(),
+        })
+    }
+}
+
+impl std::fmt::Display for ServerCliError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.message)
+    }
+}
+
+/// What the Unix domain socket server answers queries from
+#[derive(Clone)]
+pub enum SocketState {
+    /// Ingesting indexer, the witness tree backs summaries
+    Indexer(Arc<RwLock<IndexerState>>),
+
+    /// Read replica, only store queries are available
+    Replica(Arc<IndexerStore>),
+}
+
+/// Accepts client connections, serving each on its own subsystem
+///
+/// At most [MAX_CONCURRENT_CONNECTIONS] are served at once, further
+/// connections wait in the listener's backlog
 pub async fn handle_connection(
     listener: UnixListener,
-    state: Arc<RwLock<IndexerState>>,
+    state: SocketState,
     subsys: SubsystemHandle,
 ) -> anyhow::Result<()> {
-    use helpers::*;
+    let permits = Arc::new(Semaphore::new(MAX_CONCURRENT_CONNECTIONS));
 
     loop {
+        let permit = match permits
+            .clone()
+            .acquire_owned()
+            .cancel_on_shutdown(&subsys)
+            .await
+        {
+            Ok(permit) => permit,
+            Err(_) => break,
+        }?;
+
         let (connection, _) = match listener.accept().cancel_on_shutdown(&subsys).await {
             Ok(connection) => connection,
             Err(_) => break,
         }?;
 
-        let command = parse_conn_to_cli(&connection).await?;
-        let (_, mut writer) = connection.into_split();
+        let state = state.clone();
+        subsys.start(
+            SubsystemBuilder::new("Socket Connection", move |subsys| async move {
+                // held until the connection is served
+                let _permit = permit;
 
-        let state = state.read().await;
-        let db = if let Some(store) = state.indexer_store.as_ref() {
-            store
+                if let Err(e) = serve_connection(connection, &state, &subsys).await {
+                    error!("Unix domain socket connection error: {e}");
+                }
+
+                Ok::<_, anyhow::Error>(())
+            })
+            .on_panic(ErrorAction::CatchAndLocalShutdown),
+        );
+    }
+
+    Ok(())
+}
+
+/// Error response to a command which writes to the store, on a read replica
+fn replica_unsupported(command: &ClientCli) -> ServerCliResponse {
+    ServerCliResponse::error(
+        ServerCliErrorCode::Unsupported,
+        format!("{} is unavailable on read replicas", command.name()),
+    )
+}
+
+/// Reads the client's command & streams the response back
+///
+/// The command is answered on a blocking thread, its frames are written to
+/// the client as they are produced. Requests in flight are cancelled on
+/// shutdown
+async fn serve_connection(
+    connection: UnixStream,
+    state: &SocketState,
+    subsys: &SubsystemHandle,
+) -> anyhow::Result<()> {
+    let (mut reader, mut writer) = connection.into_split();
+    let ClientRequest { command, output } = read_request(&mut reader).await?;
+    let is_shutdown = matches!(command, ClientCli::Shutdown);
+
+    let (chunks, received) = mpsc::channel(RESPONSE_CHANNEL_CAPACITY);
+    let state = state.clone();
+    let handler = tokio::task::spawn_blocking(move || {
+        respond(command, output, &state, ResponseStream::new(chunks))
+    });
+
+    // dropping the receiver on shutdown stops the handler at its next write
+    match write_chunks(&mut writer, received)
+        .cancel_on_shutdown(subsys)
+        .await
+    {
+        Ok(res) => res?,
+        Err(_) => {
+            debug!("Cancelled Unix domain socket request on shutdown");
+            return Ok(());
+        }
+    }
+    handler.await??;
+
+    // respond to the client before shutting down
+    if is_shutdown {
+        subsys.request_shutdown();
+    }
+
+    Ok(())
+}
+
+/// Answers the command on the response stream
+fn respond(
+    command: ClientCli,
+    output: OutputFormat,
+    state: &SocketState,
+    mut stream: ResponseStream,
+) -> anyhow::Result<()> {
+    let (db, state) = match state {
+        SocketState::Indexer(state) => (state.blocking_read().indexer_store.clone(), Some(state)),
+        SocketState::Replica(db) => (Some(db.clone()), None),
+    };
+
+    let response = match db {
+        // read replicas open the store read-only
+        Some(_) if state.is_none() && command.is_mutating() => replica_unsupported(&command),
+        Some(db) => {
+            let command_name = command.name();
+            let start = Instant::now();
+
+            // failed requests no longer bring down the listener
+            let response = handle_command(
+                command,
+                output,
+                state.map(|state| &**state),
+                &db,
+                &mut stream,
+            )
+            .unwrap_or_else(|e| {
+                ServerCliResponse::error(ServerCliErrorCode::Internal, e.to_string())
+            });
+
+            db.metrics.observe_socket(&command_name, start.elapsed());
+            response
+        }
+        None => ServerCliResponse::error(
+            ServerCliErrorCode::StoreUnavailable,
+            "Unable to get a handle on indexer store...".to_string(),
+        ),
+    };
+
+    if let ServerCliResponse::Error(ref error) = response {
+        error!("{}", error);
+    }
+
+    stream.respond(response)?;
+    stream.finish()
+}
+
+/// Response frames, a response is a sequence of output frames, possibly
+/// ending in an error frame
+#[derive(Debug, Encode, Decode)]
+pub enum ResponseFrame {
+    Output(String),
+    Error(ServerCliError),
+}
+
+/// Streams encoded [ResponseFrame]s to the connection's writer as chunks of
+/// at most [RESPONSE_CHUNK_SIZE] bytes
+pub struct ResponseStream {
+    chunk: Vec<u8>,
+    chunks: mpsc::Sender<Vec<u8>>,
+}
+
+impl ResponseStream {
+    pub fn new(chunks: mpsc::Sender<Vec<u8>>) -> Self {
+        Self {
+            chunk: Vec::with_capacity(RESPONSE_CHUNK_SIZE),
+            chunks,
+        }
+    }
+
+    /// Encodes the frame straight into the chunks
+    pub fn frame(&mut self, frame: &ResponseFrame) -> anyhow::Result<()> {
+        bincode::encode_into_std_write(frame, self, BIN_CODE_CONFIG)?;
+        Ok(())
+    }
+
+    /// Writes an output frame
+    pub fn output(&mut self, output: impl Into<String>) -> anyhow::Result<()> {
+        self.frame(&ResponseFrame::Output(output.into()))
+    }
+
+    /// Writes the command's response, streamed commands have already written
+    /// their output
+    pub fn respond(&mut self, response: ServerCliResponse) -> anyhow::Result<()> {
+        match response {
+            ServerCliResponse::Success(output) if output.is_empty() => Ok(()),
+            ServerCliResponse::Success(output) => self.output(output),
+            ServerCliResponse::Error(error) => self.frame(&ResponseFrame::Error(error)),
+        }
+    }
+
+    /// Streams the items, one frame per item
+    ///
+    /// Machine-readable output is the typed [CommandData] `data` builds from
+    /// the collected items, text output is formatted in `text` as
+    /// [crate::cli::response::format_vec_jq_compatible] would format the
+    /// collected items
+    ///
+    /// Returns the response of a streamed command
+    pub fn items<T, I, D>(
+        &mut self,
+        items: I,
+        output: OutputFormat,
+        text: OutputFormat,
+        data: D,
+    ) -> anyhow::Result<ServerCliResponse>
+    where
+        T: std::fmt::Debug + Serialize,
+        I: IntoIterator<Item = anyhow::Result<T>>,
+        D: FnOnce(Vec<T>) -> CommandData,
+    {
+        if output.is_machine_readable() {
+            let tag = data(vec![]).tag()?;
+            self.output(format!(
+                "{{\"type\":{},\"value\":",
+                serde_json::to_string(&tag)?
+            ))?;
+            write_items(items, OutputFormat::Json, |item| self.output(item))?;
+            self.output("}")?;
         } else {
-            let response =
-                ServerCliResponse::Error("Unable to get a handle on indexer store...".to_string());
-            let encoded = bincode::encode_to_vec(&response, BIN_CODE_CONFIG)?;
+            write_items(items, text, |item| self.output(item))?;
+        }
 
-            writer.write_all(&encoded).await?;
-            continue;
-        };
+        Ok(ServerCliResponse::Success(String::new()))
+    }
+
+    /// Sends the last chunk, the writer ends the response once the stream is
+    /// dropped
+    pub fn finish(mut self) -> anyhow::Result<()> {
+        self.send_chunk()?;
+        Ok(())
+    }
+
+    fn send_chunk(&mut self) -> io::Result<()> {
+        if self.chunk.is_empty() {
+            return Ok(());
+        }
+
+        let chunk = std::mem::replace(&mut self.chunk, Vec::with_capacity(RESPONSE_CHUNK_SIZE));
+        self.chunks.blocking_send(chunk).map_err(|_| {
+            io::Error::new(
+                ErrorKind::BrokenPipe,
+                "Unix domain socket response writer closed",
+            )
+        })
+    }
+}
+
+impl io::Write for ResponseStream {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        let len = buf.len().min(RESPONSE_CHUNK_SIZE - self.chunk.len());
+        self.chunk.extend_from_slice(&buf[..len]);
+
+        if self.chunk.len() == RESPONSE_CHUNK_SIZE {
+            self.send_chunk()?;
+        }
+
+        Ok(len)
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        self.send_chunk()
+    }
+}
+
+/// Writes the chunks as they arrive, each prefixed by its big-endian `u32`
+/// length, followed by an empty chunk once the [ResponseStream] is dropped
+pub async fn write_chunks<W>(
+    writer: &mut W,
+    mut chunks: mpsc::Receiver<Vec<u8>>,
+) -> anyhow::Result<()>
+where
+    W: AsyncWrite + Unpin,
+{
+    while let Some(chunk) = chunks.recv().await {
+        writer.write_u32(chunk.len() as u32).await?;
+        writer.write_all(&chunk).await?;
+    }
+
+    writer.write_u32(0).await?;
+    writer.flush().await?;
+    Ok(())
+}
+
+/// Decodes the frames written by [write_chunks] as their chunks arrive,
+/// passing each output frame to `output`
+///
+/// Returns the error frame the response ends with, if any. Output frames
+/// preceding it have already been passed on
+pub async fn read_frames<R, F>(
+    reader: &mut R,
+    mut output: F,
+) -> anyhow::Result<Option<ServerCliError>>
+where
+    R: AsyncRead + Unpin,
+    F: FnMut(String) -> anyhow::Result<()>,
+{
+    let mut buffer = Vec::with_capacity(BUFFER_SIZE);
+
+    loop {
+        let len = reader
+            .read_u32()
+            .await
+            .context("Unix domain socket response ended before the final chunk")?
+            as usize;
+
+        if len == 0 {
+            break;
+        }
+
+        if len > RESPONSE_CHUNK_SIZE {
+            bail!("Unix domain socket response chunk too large: {len} bytes");
+        }
+
+        let start = buffer.len();
+        buffer.resize(start + len, 0);
+        reader.read_exact(&mut buffer[start..]).await?;
+
+        // frames may span chunks, a partial frame waits for the next chunk
+        let mut decoded = 0;
+        loop {
+            match bincode::decode_from_slice(&buffer[decoded..], BIN_CODE_CONFIG) {
+                Ok((ResponseFrame::Output(frame), len)) => {
+                    decoded += len;
+                    output(frame)?;
+                }
+                Ok((ResponseFrame::Error(error), _)) => return Ok(Some(error)),
+                Err(DecodeError::UnexpectedEnd { .. }) => break,
+                Err(e) => return Err(e.into()),
+            }
+        }
+        buffer.drain(..decoded);
+    }
+
+    if !buffer.is_empty() {
+        bail!("Unix domain socket response ended within a frame");
+    }
+
+    Ok(None)
+}
+
+/// Reassembles the response written by [write_chunks]
+///
+/// Output frames are concatenated, an error frame replaces the output
+pub async fn read_response<R>(reader: &mut R) -> anyhow::Result<ServerCliResponse>
+where
+    R: AsyncRead + Unpin,
+{
+    let mut output = String::new();
+    let error = read_frames(reader, |frame| {
+        output.push_str(&frame);
+        Ok(())
+    })
+    .await?;
+
+    Ok(match error {
+        Some(error) => ServerCliResponse::Error(error),
+        None => ServerCliResponse::Success(output),
+    })
+}
+
+/// Answers a single client command, list output is streamed as it's produced
+///
+/// Runs on a blocking thread
+#[allow(clippy::too_many_lines)]
+fn handle_command(
+    command: ClientCli,
+    output: OutputFormat,
+    state: Option<&RwLock<IndexerState>>,
+    db: &IndexerStore,
+    stream: &mut ResponseStream,
+) -> anyhow::Result<ServerCliResponse> {
+    use helpers::*;
 
-        let response = match command {
-            ClientCli::Accounts(Accounts::PublicKey { public_key: pk }) => {
-                debug!("Received account command for {pk}");
-                if !PublicKey::is_valid(&pk) {
-                    invalid_public_key(&pk)
+    let response = match command {
+        ClientCli::Accounts(Accounts::PublicKey { public_key: pk }) => {
+            debug!("Received account command for {pk}");
+            if !PublicKey::is_valid(&pk) {
+                invalid_public_key(&pk)
+            } else {
+                let pk: PublicKey = pk.into();
+                if let Some(mut account) = db.get_best_account(&pk, &TokenAddress::default())? {
+                    account.username = db.resolve_username(&pk)?;
+
+                    debug!("Writing account {pk} to client");
+                    reply(CommandData::AccountsPublicKey(account), output)?
                 } else {
+                    account_missing_from_db(&pk)
+                }
+            }
+        }
+        ClientCli::Accounts(Accounts::BalanceHistory {
+            public_key: pk,
+            token,
+            from_height,
+            to_height,
+        }) => {
+            debug!("Received balance history command for {pk}");
+            let token = match token {
+                Some(token) => TokenAddress::new(&token).ok_or(token),
+                None => Ok(TokenAddress::default()),
+            };
+
+            match token {
+                _ if !PublicKey::is_valid(&pk) => invalid_public_key(&pk),
+                Err(token) => invalid_token_address(&token),
+                Ok(token) => {
                     let pk: PublicKey = pk.into();
-                    if let Some(account) = db.get_best_account(&pk, &TokenAddress::default())? {
-                        debug!("Writing account {pk} to client");
-                        ServerCliResponse::Success(format!("{account}"))
-                    } else {
-                        account_missing_from_db(&pk)
-                    }
+                    let history = db.get_balance_history(&pk, &token, from_height, to_height)?;
+
+                    debug!("Writing {pk} balance history to client");
+                    reply(CommandData::AccountsBalanceHistory(history), output)?
                 }
             }
-            ClientCli::Blocks(Blocks::Best { verbose, path }) => {
-                debug!("Received best block command");
-                if let Some(best_tip) = db.get_best_block()? {
-                    let block_str = if let Some(canonicity) =
-                        db.get_block_canonicity(&best_tip.state_hash())?
-                    {
-                        if verbose {
-                            serde_json::to_string_pretty(&best_tip.with_canonicity(canonicity))?
-                        } else {
-                            let block = BlockWithoutHeight::with_canonicity(&best_tip, canonicity);
-                            serde_json::to_string_pretty(&block)?
-                        }
-                    } else {
-                        block_missing_from_db(&best_tip.state_hash().0)
+        }
+        ClientCli::Webhooks(Webhooks::Add {
+            public_key: pk,
+            url,
+        }) => {
+            debug!("Received add webhook command {url} for {pk}");
+            if !PublicKey::is_valid(&pk) {
+                invalid_public_key(&pk)
+            } else if !HttpUrl::is_valid(&url) {
+                invalid_webhook_url(&url)
+            } else {
+                let public_key: PublicKey = pk.into();
+                db.add_webhook(&public_key, &url)?;
+                reply(
+                    CommandData::WebhooksAdd(Webhook { public_key, url }),
+                    output,
+                )?
+            }
+        }
+        ClientCli::Webhooks(Webhooks::Remove {
+            public_key: pk,
+            url,
+        }) => {
+            debug!("Received remove webhook command {url} for {pk}");
+            if !PublicKey::is_valid(&pk) {
+                invalid_public_key(&pk)
+            } else {
+                let pk: PublicKey = pk.into();
+                if db.remove_webhook(&pk, &url)? {
+                    let webhook = Webhook {
+                        public_key: pk,
+                        url,
                     };
-
-                    if path.is_some() {
-                        let path = &path.unwrap();
-                        debug!("Writing best tip block to {path:?}");
-                        std::fs::write(path, block_str)?;
-                        ServerCliResponse::Success(format!("Best block written to {path:?}"))
-                    } else {
-                        debug!("Writing best tip block to stdout");
-                        ServerCliResponse::Success(block_str)
-                    }
+                    reply(CommandData::WebhooksRemove(webhook), output)?
                 } else {
-                    best_tip_missing_from_db()
+                    ServerCliResponse::error(
+                        ServerCliErrorCode::NotFound,
+                        format!("Webhook missing from store: {url} for {pk}"),
+                    )
                 }
             }
-            ClientCli::Blocks(Blocks::StateHash {
-                state_hash,
-                verbose,
-                path,
-            }) => {
-                debug!("Received block-state-hash command");
-                if !StateHash::is_valid(&state_hash) {
-                    invalid_state_hash(&state_hash)
+        }
+        ClientCli::Webhooks(Webhooks::List) => {
+            debug!("Received list webhooks command");
+            reply(CommandData::WebhooksList(db.get_webhooks()?), output)?
+        }
+        ClientCli::AddressBook(AddressBook::Add {
+            public_key: pk,
+            label,
+            category,
+            source,
+        }) => {
+            debug!("Received add {category} label {label} for {pk}");
+            if !PublicKey::is_valid(&pk) {
+                invalid_public_key(&pk)
+            } else if label.is_empty() {
+                ServerCliResponse::error(
+                    ServerCliErrorCode::InvalidQuery,
+                    format!("Empty label for {pk}"),
+                )
+            } else {
+                let label = Label {
+                    public_key: pk.into(),
+                    label,
+                    category,
+                    source,
+                };
+                db.add_label(&label)?;
+                reply(CommandData::AddressBookAdd(label), output)?
+            }
+        }
+        ClientCli::AddressBook(AddressBook::Remove { public_key: pk }) => {
+            debug!("Received remove label command for {pk}");
+            if !PublicKey::is_valid(&pk) {
+                invalid_public_key(&pk)
+            } else {
+                let pk: PublicKey = pk.into();
+                if db.remove_label(&pk)? {
+                    reply(CommandData::AddressBookRemove { public_key: pk }, output)?
                 } else {
-                    match db.get_block(&state_hash.clone().into()) {
-                        Ok(Some((ref block, _))) => {
-                            let block_str = if let Some(canonicity) =
-                                db.get_block_canonicity(&block.state_hash())?
-                            {
-                                if verbose {
-                                    serde_json::to_string_pretty(
-                                        &block.with_canonicity(canonicity),
-                                    )?
-                                } else {
-                                    let block =
-                                        BlockWithoutHeight::with_canonicity(block, canonicity);
-                                    serde_json::to_string_pretty(&block)?
-                                }
-                            } else {
-                                block_missing_from_db(&block.state_hash().0)
-                            };
-                            if path.is_some() {
-                                let path = &path.unwrap();
-                                debug!("Writing block {state_hash} to {path:?}");
-                                std::fs::write(path, block_str)?;
-                                ServerCliResponse::Success(format!(
-                                    "Block {} written to {:?}",
-                                    block.state_hash().0,
-                                    path
-                                ))
-                            } else {
-                                debug!("Writing block to stdout {}", block.summary());
-                                ServerCliResponse::Success(block_str)
-                            }
-                        }
-                        Ok(None) => ServerCliResponse::Success(format!(
-                            "Block at state hash not present in store: {state_hash}"
-                        )),
-                        Err(e) => ServerCliResponse::Error(format!(
-                            "Failed to lookup block for '{state_hash}': {e}"
-                        )),
-                    }
+                    not_found(format!("Label missing from store: {pk}"))
                 }
             }
-            ClientCli::Blocks(Blocks::Height {
-                height,
-                verbose,
-                path,
-            }) => {
-                debug!("Received blocks-at-height {height} command");
-                let blocks_at_height = db.get_blocks_at_height(height)?;
-                let blocks_str = if verbose {
-                    let blocks: Vec<PrecomputedBlockWithCanonicity> = blocks_at_height
-                        .iter()
-                        .flat_map(|state_hash| {
-                            if let Ok(Some(canonicity)) = db.get_block_canonicity(state_hash) {
-                                let block = db
-                                    .get_block(state_hash)
-                                    .with_context(|| {
-                                        format!("block missing from store {state_hash}")
-                                    })
-                                    .unwrap()
-                                    .unwrap()
-                                    .0;
-                                Some(block.with_canonicity(canonicity))
-                            } else {
-                                None
-                            }
-                        })
-                        .collect();
-                    serde_json::to_string(&blocks)?
-                } else {
-                    let blocks: Vec<BlockWithoutHeight> = blocks_at_height
-                        .iter()
-                        .flat_map(|state_hash| {
-                            if let Ok(Some(canonicity)) = db.get_block_canonicity(state_hash) {
-                                let block = db
-                                    .get_block(state_hash)
-                                    .with_context(|| {
-                                        format!("block missing from store {state_hash}")
-                                    })
-                                    .unwrap()
-                                    .unwrap()
-                                    .0;
-                                Some(BlockWithoutHeight::with_canonicity(&block, canonicity))
-                            } else {
-                                None
-                            }
-                        })
-                        .collect();
-                    format_vec_jq_compatible(&blocks)
-                };
+        }
+        ClientCli::AddressBook(AddressBook::Load { path }) => {
+            debug!("Received load labels command for {}", path.display());
+            match Label::from_path(&path) {
+                Ok(labels) => {
+                    for label in labels.iter() {
+                        db.add_label(label)?;
+                    }
 
-                if path.is_none() {
-                    debug!("Writing blocks at height {height} to stdout");
-                    ServerCliResponse::Success(blocks_str)
-                } else {
-                    let path = path.unwrap();
-                    if !path.is_dir() {
-                        debug!("Writing blocks at height {height} to {path:?}");
-                        std::fs::write(path.clone(), blocks_str)?;
-                        ServerCliResponse::Success(format!(
-                            "Blocks at height {height} written to {path:?}"
-                        ))
-                    } else {
-                        file_must_not_be_a_directory(&path)
+                    let count = labels.len();
+                    reply(CommandData::AddressBookLoad { path, count }, output)?
+                }
+                Err(e) => ServerCliResponse::error(
+                    ServerCliErrorCode::InvalidPath,
+                    format!("Failed to load labels from {}: {e}", path.display()),
+                ),
+            }
+        }
+        ClientCli::AddressBook(AddressBook::List { category }) => {
+            debug!("Received list labels command");
+            reply(
+                CommandData::AddressBookList(db.get_labels(category)?),
+                output,
+            )?
+        }
+        ClientCli::Blocks(Blocks::Best { verbose, path }) => {
+            debug!("Received best block command");
+            if let Some(best_tip) = db.get_best_block()? {
+                match db.get_block_canonicity(&best_tip.state_hash())? {
+                    Some(canonicity) => {
+                        let block = block_data(&best_tip, canonicity, verbose);
+                        reply_or_write(CommandData::BlocksBest(block), output, path, "Best block")?
                     }
+                    None => not_found(block_missing_from_db(&best_tip.state_hash().0)),
                 }
+            } else {
+                best_tip_missing_from_db()
             }
-            ClientCli::Blocks(Blocks::GlobalSlot {
-                slot,
-                verbose,
-                path,
-            }) => {
-                debug!("Received blocks-at-slot {slot} command");
-                let slot: u32 = slot.parse()?;
-                let blocks_at_slot = db.get_blocks_at_slot(slot)?;
-                let blocks_str = if verbose {
-                    let blocks: Vec<PrecomputedBlockWithCanonicity> = blocks_at_slot
-                        .iter()
-                        .flat_map(|state_hash| {
-                            if let Ok(Some(canonicity)) = db.get_block_canonicity(state_hash) {
-                                let block = db
-                                    .get_block(state_hash)
-                                    .with_context(|| {
-                                        format!("block missing from store {state_hash}")
-                                    })
-                                    .unwrap()
-                                    .unwrap()
-                                    .0;
-                                Some(block.with_canonicity(canonicity))
-                            } else {
-                                None
-                            }
-                        })
-                        .collect();
-                    serde_json::to_string(&blocks)?
-                } else {
-                    let blocks: Vec<BlockWithoutHeight> = blocks_at_slot
-                        .iter()
-                        .flat_map(|state_hash| {
-                            if let Ok(Some(canonicity)) = db.get_block_canonicity(state_hash) {
-                                let block = db
-                                    .get_block(state_hash)
-                                    .with_context(|| {
-                                        format!("block missing from store {state_hash}")
-                                    })
-                                    .unwrap()
-                                    .unwrap()
-                                    .0;
-                                Some(BlockWithoutHeight::with_canonicity(&block, canonicity))
-                            } else {
-                                None
+        }
+        ClientCli::Blocks(Blocks::StateHash {
+            state_hash,
+            verbose,
+            path,
+        }) => {
+            debug!("Received block-state-hash command");
+            if !StateHash::is_valid(&state_hash) {
+                invalid_state_hash(&state_hash)
+            } else {
+                match db.get_block(&state_hash.clone().into()) {
+                    Ok(Some((ref block, _))) => {
+                        match db.get_block_canonicity(&block.state_hash())? {
+                            Some(canonicity) => {
+                                debug!("Writing block {}", block.summary());
+                                reply_or_write(
+                                    CommandData::BlocksStateHash(block_data(
+                                        block, canonicity, verbose,
+                                    )),
+                                    output,
+                                    path,
+                                    &format!("Block {}", block.state_hash().0),
+                                )?
                             }
-                        })
-                        .collect();
-                    format_vec_jq_compatible(&blocks)
-                };
-
-                if path.is_none() {
-                    debug!("Writing blocks at slot {slot} to stdout");
-                    ServerCliResponse::Success(blocks_str)
-                } else {
-                    let path = path.unwrap();
-                    if !path.is_dir() {
-                        debug!("Writing blocks at slot {slot} to {path:?}");
-
-                        std::fs::write(path.clone(), blocks_str)?;
-                        ServerCliResponse::Success(format!(
-                            "Blocks at slot {slot} written to {path:?}"
-                        ))
-                    } else {
-                        file_must_not_be_a_directory(&path)
+                            None => not_found(block_missing_from_db(&block.state_hash().0)),
+                        }
                     }
+                    Ok(None) => not_found(format!(
+                        "Block at state hash not present in store: {state_hash}"
+                    )),
+                    Err(e) => ServerCliResponse::error(
+                        ServerCliErrorCode::Internal,
+                        format!("Failed to lookup block for '{state_hash}': {e}"),
+                    ),
                 }
             }
-            ClientCli::Blocks(Blocks::PublicKey {
-                public_key: pk,
+        }
+        ClientCli::Blocks(Blocks::Height {
+            height,
+            verbose,
+            path,
+        }) => {
+            debug!("Received blocks-at-height {height} command");
+            let blocks_at_height = db.get_blocks_at_height(height)?;
+            write_blocks(
+                stored_blocks(db, &blocks_at_height),
                 verbose,
+                output,
+                CommandData::BlocksHeight,
                 path,
-            }) => {
-                debug!("Received blocks-at-public-key command {pk}");
-                if !PublicKey::is_valid(&pk) {
-                    invalid_public_key(&pk)
-                } else {
-                    let blocks_at_pk = db.get_blocks_at_public_key(&pk.clone().into())?;
-                    let blocks_str = if verbose {
-                        let blocks: Vec<PrecomputedBlockWithCanonicity> = blocks_at_pk
-                            .iter()
-                            .flat_map(|state_hash| {
-                                if let Ok(Some(canonicity)) = db.get_block_canonicity(state_hash) {
-                                    let block = db
-                                        .get_block(state_hash)
-                                        .with_context(|| {
-                                            format!("block missing from store {state_hash}")
-                                        })
-                                        .unwrap()
-                                        .unwrap()
-                                        .0;
-                                    Some(block.with_canonicity(canonicity))
-                                } else {
-                                    None
-                                }
-                            })
-                            .collect();
-                        serde_json::to_string(&blocks)?
-                    } else {
-                        let blocks: Vec<BlockWithoutHeight> = blocks_at_pk
-                            .iter()
-                            .flat_map(|state_hash| {
-                                if let Ok(Some(canonicity)) = db.get_block_canonicity(state_hash) {
-                                    let block = db
-                                        .get_block(state_hash)
-                                        .with_context(|| {
-                                            format!("block missing from store {state_hash}")
-                                        })
-                                        .unwrap()
-                                        .unwrap()
-                                        .0;
-                                    Some(BlockWithoutHeight::with_canonicity(&block, canonicity))
-                                } else {
-                                    None
-                                }
-                            })
-                            .collect();
-                        format_vec_jq_compatible(&blocks)
-                    };
-
-                    if path.is_none() {
-                        debug!("Writing blocks at public key {pk} to stdout");
-                        ServerCliResponse::Success(blocks_str)
-                    } else {
-                        let path = path.unwrap();
-                        if !path.is_dir() {
-                            debug!("Writing blocks at public key {pk} to {path:?}");
-
-                            std::fs::write(path.clone(), blocks_str)?;
-                            ServerCliResponse::Success(format!(
-                                "Blocks at public key {pk} written to {path:?}"
-                            ))
-                        } else {
-                            file_must_not_be_a_directory(&path)
-                        }
-                    }
-                }
+                &format!("Blocks at height {height}"),
+                stream,
+            )?
+        }
+        ClientCli::Blocks(Blocks::GlobalSlot {
+            slot,
+            verbose,
+            path,
+        }) => {
+            debug!("Received blocks-at-slot {slot} command");
+            let slot: u32 = slot.parse()?;
+            let blocks_at_slot = db.get_blocks_at_slot(slot)?;
+            write_blocks(
+                stored_blocks(db, &blocks_at_slot),
+                verbose,
+                output,
+                CommandData::BlocksGlobalSlot,
+                path,
+                &format!("Blocks at slot {slot}"),
+                stream,
+            )?
+        }
+        ClientCli::Blocks(Blocks::PublicKey {
+            public_key: pk,
+            verbose,
+            path,
+        }) => {
+            debug!("Received blocks-at-public-key command {pk}");
+            if !PublicKey::is_valid(&pk) {
+                invalid_public_key(&pk)
+            } else {
+                let blocks_at_pk = db.get_blocks_at_public_key(&pk.clone().into())?;
+                write_blocks(
+                    stored_blocks(db, &blocks_at_pk),
+                    verbose,
+                    output,
+                    CommandData::BlocksPublicKey,
+                    path,
+                    &format!("Blocks at public key {pk}"),
+                    stream,
+                )?
             }
-            ClientCli::Blocks(Blocks::Children {
-                state_hash,
+        }
+        ClientCli::Blocks(Blocks::Children {
+            state_hash,
+            verbose,
+            path,
+        }) => {
+            debug!("Received block-children command for block {state_hash}");
+            let children = db.get_block_children(&state_hash.clone().into())?;
+            write_blocks(
+                stored_blocks(db, &children),
                 verbose,
+                output,
+                CommandData::BlocksChildren,
                 path,
-            }) => {
-                debug!("Received block-children command for block {state_hash}");
-                let children = db.get_block_children(&state_hash.clone().into())?;
-                let blocks_str = if verbose {
-                    let blocks: Vec<PrecomputedBlockWithCanonicity> = children
-                        .iter()
-                        .flat_map(|state_hash| {
-                            if let Ok(Some(canonicity)) = db.get_block_canonicity(state_hash) {
-                                let block = db
-                                    .get_block(state_hash)
-                                    .with_context(|| {
-                                        format!("block missing from store {state_hash}")
-                                    })
-                                    .unwrap()
-                                    .unwrap()
-                                    .0;
-                                Some(block.with_canonicity(canonicity))
-                            } else {
-                                None
-                            }
-                        })
-                        .collect();
-                    serde_json::to_string(&blocks)?
-                } else {
-                    let blocks: Vec<BlockWithoutHeight> = children
-                        .iter()
-                        .flat_map(|state_hash| {
-                            if let Ok(Some(canonicity)) = db.get_block_canonicity(state_hash) {
-                                let block = db
-                                    .get_block(state_hash)
-                                    .with_context(|| {
-                                        format!("block missing from store {state_hash}")
-                                    })
-                                    .unwrap()
-                                    .unwrap()
-                                    .0;
-                                Some(BlockWithoutHeight::with_canonicity(&block, canonicity))
-                            } else {
-                                None
-                            }
-                        })
-                        .collect();
-                    format_vec_jq_compatible(&blocks)
+                &format!("Children of block {state_hash}"),
+                stream,
+            )?
+        }
+        ClientCli::Blocks(Blocks::Missing { path }) => {
+            debug!("Received blocks-missing command");
+
+            // read replicas have no witness tree
+            let Some(state) = state else {
+                return Ok(ServerCliResponse::error(
+                    ServerCliErrorCode::Unsupported,
+                    "Missing blocks are unavailable on read replicas",
+                ));
+            };
+
+            let missing_blocks = state.blocking_read().missing_blocks();
+            reply_or_write(
+                CommandData::BlocksMissing(missing_blocks),
+                output,
+                path,
+                "Missing blocks",
+            )?
+        }
+        ClientCli::Blocks(Blocks::Production {
+            public_key: pk,
+            epoch,
+            genesis_state_hash,
+            path,
+        }) => {
+            debug!("Received blocks-production command for {pk}");
+
+            if !StateHash::is_valid(&genesis_state_hash) {
+                invalid_state_hash(&genesis_state_hash)
+            } else if !PublicKey::is_valid(&pk) {
+                invalid_public_key(&pk)
+            } else {
+                let epoch = match epoch {
+                    Some(epoch) => epoch,
+                    None => db.get_current_epoch()?,
                 };
 
-                if path.is_none() {
-                    debug!("Writing children of block {state_hash} to stdout");
-                    ServerCliResponse::Success(blocks_str)
+                if let Some(report) = ProducerReport::from_store(
+                    db,
+                    &pk.clone().into(),
+                    epoch,
+                    &genesis_state_hash.into(),
+                )? {
+                    reply_or_write(
+                        CommandData::BlocksProduction(report),
+                        output,
+                        path,
+                        &format!("Production report for {pk} epoch {epoch}"),
+                    )?
                 } else {
-                    let path = path.unwrap();
-                    if !path.is_dir() {
-                        debug!("Writing children of block {state_hash} to {path:?}");
-                        std::fs::write(path.clone(), blocks_str)?;
-                        ServerCliResponse::Success(format!(
-                            "Children of block {state_hash} written to {path:?}"
-                        ))
+                    ServerCliResponse::error(
+                        ServerCliErrorCode::NotFound,
+                        format!("Staking ledger at epoch {epoch} is not in the store"),
+                    )
+                }
+            }
+        }
+        ClientCli::Chain(Chain::Best {
+            num,
+            verbose,
+            start_state_hash,
+            end_state_hash,
+            path,
+        }) => {
+            debug!("Received best-chain command");
+            let start_state_hash: StateHash = match start_state_hash {
+                None => {
+                    if let Ok(Some(PcbVersion::V2)) = db.get_best_block_version() {
+                        HARDFORK_GENESIS_HASH.into()
                     } else {
-                        file_must_not_be_a_directory(&path)
+                        MAINNET_GENESIS_HASH.into()
                     }
                 }
-            }
-            ClientCli::Chain(Chain::Best {
-                num,
-                verbose,
-                start_state_hash,
-                end_state_hash,
-                path,
-            }) => {
-                debug!("Received best-chain command");
-                let start_state_hash: StateHash = match start_state_hash {
-                    None => {
-                        if let Ok(Some(PcbVersion::V2)) = db.get_best_block_version() {
-                            HARDFORK_GENESIS_HASH.into()
-                        } else {
-                            MAINNET_GENESIS_HASH.into()
+                Some(start_state_hash) => start_state_hash.into(),
+            };
+
+            if let Some(best_tip) = db.get_best_block()? {
+                let end_state_hash = {
+                    match end_state_hash {
+                        None => best_tip.state_hash(),
+                        Some(end_state_hash) => {
+                            if !StateHash::is_valid(&end_state_hash) {
+                                best_tip.state_hash()
+                            } else {
+                                end_state_hash.into()
+                            }
                         }
                     }
-                    Some(start_state_hash) => start_state_hash.into(),
                 };
 
-                if let Some(best_tip) = db.get_best_block()? {
-                    let end_state_hash = {
-                        match end_state_hash {
-                            None => best_tip.state_hash(),
-                            Some(end_state_hash) => {
-                                if !StateHash::is_valid(&end_state_hash) {
-                                    best_tip.state_hash()
-                                } else {
-                                    end_state_hash.into()
-                                }
-                            }
-                        }
-                    };
+                if !StateHash::is_valid(&start_state_hash.0) {
+                    invalid_state_hash(&start_state_hash.0)
+                } else if let (Some((end_block, _)), Some((start_block, _))) = (
+                    db.get_block(&end_state_hash)?,
+                    db.get_block(&start_state_hash)?,
+                ) {
+                    let start_height = start_block.blockchain_length();
+                    let end_height = end_block.blockchain_length();
 
-                    if !StateHash::is_valid(&start_state_hash.0) {
-                        invalid_state_hash(&start_state_hash.0)
-                    } else if let (Some((end_block, _)), Some((start_block, _))) = (
-                        db.get_block(&end_state_hash)?,
-                        db.get_block(&start_state_hash)?,
-                    ) {
-                        let start_height = start_block.blockchain_length();
-                        let end_height = end_block.blockchain_length();
-                        let mut parent_hash = end_block.previous_state_hash();
-                        let mut best_chain = vec![end_block];
-
-                        // constrain by num and state hash bound
-                        for _ in 1..num.min(end_height.saturating_sub(start_height) + 1) {
-                            if let Some((parent_pcb, _)) = db.get_block(&parent_hash)? {
-                                let curr_hash: StateHash = parent_pcb.state_hash();
-                                parent_hash = parent_pcb.previous_state_hash();
-                                best_chain.push(parent_pcb);
-
-                                if curr_hash == start_state_hash {
-                                    break;
-                                }
-                            } else {
-                                break;
-                            }
+                    // walk back from the end block, constrained by num and state
+                    // hash bound
+                    let len = num.min(end_height.saturating_sub(start_height) + 1);
+                    let mut next = Some(end_block);
+                    let best_chain = std::iter::from_fn(move || {
+                        let block = next.take()?;
+                        if block.state_hash() != start_state_hash {
+                            next = match db.get_block(&block.previous_state_hash()) {
+                                Ok(parent) => parent.map(|(parent, _)| parent),
+                                Err(e) => return Some(Err(e)),
+                            };
                         }
 
-                        let best_chain_str = if verbose {
-                            let best_chain: Vec<PrecomputedBlockWithCanonicity> = best_chain
-                                .iter()
-                                .flat_map(|block| {
-                                    if let Ok(Some(canonicity)) =
-                                        db.get_block_canonicity(&block.state_hash())
-                                    {
-                                        Some(block.with_canonicity(canonicity))
-                                    } else {
-                                        None
-                                    }
-                                })
-                                .collect();
-                            serde_json::to_string(&best_chain)?
-                        } else {
-                            let best_chain: Vec<BlockWithoutHeight> = best_chain
-                                .iter()
-                                .flat_map(|block| {
-                                    if let Ok(Some(canonicity)) =
-                                        db.get_block_canonicity(&block.state_hash())
-                                    {
-                                        Some(BlockWithoutHeight::with_canonicity(block, canonicity))
-                                    } else {
-                                        None
-                                    }
-                                })
-                                .collect();
-                            format_vec_jq_compatible(&best_chain)
-                        };
-
-                        if path.is_none() {
-                            debug!("Writing best chain to stdout");
-                            ServerCliResponse::Success(best_chain_str)
-                        } else {
-                            let path = path.unwrap();
-                            if !path.is_dir() {
-                                debug!("Writing best chain to {path:?}");
-
-                                std::fs::write(path.clone(), best_chain_str)?;
-                                ServerCliResponse::Success(format!(
-                                    "Best chain written to {path:?}"
-                                ))
-                            } else {
-                                file_must_not_be_a_directory(&path)
-                            }
-                        }
-                    } else {
-                        ServerCliResponse::Success("No results".to_string())
-                    }
+                        Some(Ok(block))
+                    })
+                    .take(len as usize)
+                    .filter_map(|block| match block {
+                        Ok(block) => match db.get_block_canonicity(&block.state_hash()) {
+                            Ok(Some(canonicity)) => Some(Ok((block, canonicity))),
+                            _ => None,
+                        },
+                        Err(e) => Some(Err(e)),
+                    });
+
+                    write_blocks(
+                        best_chain,
+                        verbose,
+                        output,
+                        CommandData::ChainBest,
+                        path,
+                        "Best chain",
+                        stream,
+                    )?
+                } else if output.is_machine_readable() {
+                    reply(CommandData::ChainBest(vec![]), output)?
                 } else {
-                    best_tip_missing_from_db()
+                    ServerCliResponse::Success("No results".to_string())
                 }
+            } else {
+                best_tip_missing_from_db()
             }
-            ClientCli::CreateSnapshot { output_path } => {
-                debug!("Received create-snapshot command");
-                match db.create_snapshot(&output_path) {
-                    Err(e) => ServerCliResponse::Error(e.to_string()),
-                    Ok(s) => ServerCliResponse::Success(s),
-                }
+        }
+        ClientCli::CreateSnapshot { output_path } => {
+            debug!("Received create-snapshot command");
+            match db.create_snapshot(&output_path) {
+                Err(e) => ServerCliResponse::error(ServerCliErrorCode::Internal, e.to_string()),
+                Ok(s) => written(output_path, s, output)?,
             }
-            ClientCli::Ledgers(Ledgers::Best { path, memoize }) => {
-                debug!("Received best-ledger command");
-                if let Some(ledger) = db.get_best_ledger(memoize)? {
-                    let ledger = ledger.to_string_pretty();
-                    if path.is_none() {
-                        debug!("Writing best ledger to stdout");
-                        ServerCliResponse::Success(ledger)
-                    } else {
-                        let path = path.unwrap();
-                        if path.is_dir() {
-                            file_must_not_be_a_directory(&path)
-                        } else {
-                            debug!("Writing best ledger to {path:?}");
-                            std::fs::write(path.clone(), ledger)?;
-                            ServerCliResponse::Success(format!("Best ledger written to {path:?}"))
-                        }
-                    }
-                } else {
-                    ServerCliResponse::Error("Best ledger cannot be calculated".to_string())
-                }
+        }
+        ClientCli::Ledgers(Ledgers::Best { path, memoize }) => {
+            debug!("Received best-ledger command");
+            if let Some(ledger) = db.get_best_ledger(memoize)? {
+                let ledger = with_labels(db, ledger)?.accounts_by_token();
+                reply_or_write(
+                    CommandData::LedgersBest(ledger),
+                    output,
+                    path,
+                    "Best ledger",
+                )?
+            } else {
+                ServerCliResponse::error(
+                    ServerCliErrorCode::NotFound,
+                    "Best ledger cannot be calculated".to_string(),
+                )
             }
-            ClientCli::Ledgers(Ledgers::Hash {
-                hash,
-                path,
-                memoize,
-            }) => {
-                debug!("Received staged ledger command for {hash}");
-                fn write_ledger(
-                    path: Option<std::path::PathBuf>,
-                    ledger: Ledger,
-                    hash: &str,
-                ) -> ServerCliResponse {
-                    let ledger = ledger.to_string_pretty();
-                    if path.is_none() {
-                        debug!("Writing staged ledger at hash {hash} to stdout");
-                        ServerCliResponse::Success(ledger)
-                    } else {
-                        let path = path.unwrap();
-                        if !path.is_dir() {
-                            debug!("Writing staged ledger at {hash} to {path:?}");
-                            std::fs::write(path.clone(), ledger).ok();
-                            ServerCliResponse::Success(format!(
-                                "Ledger at hash {hash} written to {path:?}"
-                            ))
-                        } else {
-                            file_must_not_be_a_directory(&path)
-                        }
-                    }
-                }
-
-                // check if ledger or state hash and use appropriate getter
-                if StateHash::is_valid(&hash) {
-                    trace!("{hash} is a state hash");
-                    if let Some(ledger) =
-                        db.get_staged_ledger_at_state_hash(&hash.clone().into(), memoize)?
-                    {
-                        write_ledger(path, ledger, &hash)
-                    } else {
-                        ServerCliResponse::Error(format!(
-                            "Ledger at state hash {hash} is not in the store"
-                        ))
-                    }
-                } else if LedgerHash::is_valid(&hash) {
-                    trace!("{hash} is a ledger hash");
-                    if let Some(ledger) = db.get_staged_ledger_at_ledger_hash(
-                        &LedgerHash::new_or_panic(hash.clone()),
-                        memoize,
-                    )? {
-                        write_ledger(path, ledger, &hash)
-                    } else {
-                        ServerCliResponse::Error(format!(
-                            "Ledger at ledger hash {hash} is not in the store"
-                        ))
-                    }
-                } else {
-                    ServerCliResponse::Error(format!("Invalid ledger or state hash: {hash}"))
-                }
+        }
+        ClientCli::Ledgers(Ledgers::Hash {
+            hash,
+            path,
+            memoize,
+        }) => {
+            debug!("Received staged ledger command for {hash}");
+            fn write_ledger(
+                db: &IndexerStore,
+                output: OutputFormat,
+                path: Option<PathBuf>,
+                ledger: Ledger,
+                hash: &str,
+            ) -> anyhow::Result<ServerCliResponse> {
+                let ledger = with_labels(db, ledger)?.accounts_by_token();
+                reply_or_write(
+                    CommandData::LedgersHash(ledger),
+                    output,
+                    path,
+                    &format!("Ledger at hash {hash}"),
+                )
             }
-            ClientCli::Ledgers(Ledgers::Height {
-                height,
-                path,
-                memoize,
-            }) => {
-                debug!("Received staged ledger at height {height} command");
-                if let Ok(Some(best_tip_height)) = db.get_best_block_height() {
-                    if height > best_tip_height {
-                        // ahead of witness tree - cannot compute
-                        ServerCliResponse::Error(format!("Invalid query: ledger at height {height} cannot be determined from a chain of length {best_tip_height}"))
-                    } else {
-                        let ledger_str = db
-                            .get_staged_ledger_at_block_height(height, memoize)?
-                            .unwrap()
-                            .to_string_pretty();
-                        if path.is_none() {
-                            debug!("Writing ledger at height {height} to stdout");
-                            ServerCliResponse::Success(ledger_str)
-                        } else {
-                            let path = path.unwrap();
-                            if !path.is_dir() {
-                                debug!("Writing ledger at height {height} to {path:?}");
-                                std::fs::write(&path, ledger_str)?;
-                                ServerCliResponse::Success(format!(
-                                    "Ledger at height {height} written to {path:?}"
-                                ))
-                            } else {
-                                file_must_not_be_a_directory(&path)
-                            }
-                        }
-                    }
+
+            // check if ledger or state hash and use appropriate getter
+            if StateHash::is_valid(&hash) {
+                trace!("{hash} is a state hash");
+                if let Some(ledger) =
+                    db.get_staged_ledger_at_state_hash(&hash.clone().into(), memoize)?
+                {
+                    write_ledger(db, output, path, ledger, &hash)?
                 } else {
-                    best_tip_missing_from_db()
+                    ServerCliResponse::error(
+                        ServerCliErrorCode::NotFound,
+                        format!("Ledger at state hash {hash} is not in the store"),
+                    )
                 }
-            }
-            ClientCli::StakingLedgers(StakingLedgers::Hash { hash, path }) => {
-                debug!("Received staking-ledgers-hash command for {hash}");
-                if LedgerHash::is_valid(&hash) {
-                    trace!("{hash} is a ledger hash");
-                    if let Some(staking_ledger) =
-                        db.get_staking_ledger(&hash.clone().into(), None, None)?
-                    {
-                        let ledger_json = serde_json::to_string_pretty(&staking_ledger)?;
-                        if path.is_none() {
-                            debug!("Writing staking ledger at hash {hash} to stdout");
-                            ServerCliResponse::Success(ledger_json)
-                        } else {
-                            let path = path.unwrap();
-                            if !path.is_dir() {
-                                debug!("Writing ledger at {hash} to {path:?}");
-
-                                std::fs::write(path.clone(), ledger_json)?;
-                                ServerCliResponse::Success(format!(
-                                    "Staking ledger at hash {hash} written to {path:?}"
-                                ))
-                            } else {
-                                file_must_not_be_a_directory(&path)
-                            }
-                        }
-                    } else {
-                        ServerCliResponse::Error(format!(
-                            "Staking ledger at {hash} is not in the store"
-                        ))
-                    }
+            } else if LedgerHash::is_valid(&hash) {
+                trace!("{hash} is a ledger hash");
+                if let Some(ledger) = db.get_staged_ledger_at_ledger_hash(
+                    &LedgerHash::new_or_panic(hash.clone()),
+                    memoize,
+                )? {
+                    write_ledger(db, output, path, ledger, &hash)?
                 } else {
-                    ServerCliResponse::Error(format!("Invalid ledger hash: {hash}"))
+                    ServerCliResponse::error(
+                        ServerCliErrorCode::NotFound,
+                        format!("Ledger at ledger hash {hash} is not in the store"),
+                    )
                 }
+            } else {
+                ServerCliResponse::error(
+                    ServerCliErrorCode::InvalidLedgerHash,
+                    format!("Invalid ledger or state hash: {hash}"),
+                )
             }
-            ClientCli::StakingLedgers(StakingLedgers::Epoch {
-                epoch,
-                genesis_state_hash,
-                path,
-            }) => {
-                debug!("Received staking-ledgers-epoch {epoch} command");
-                if !StateHash::is_valid(&genesis_state_hash) {
-                    invalid_state_hash(&genesis_state_hash)
-                } else if let Some(staking_ledger) =
-                    db.build_staking_ledger(epoch, &genesis_state_hash.into())?
-                {
-                    let ledger_json = serde_json::to_string_pretty(&staking_ledger)?;
-                    if path.is_none() {
-                        debug!("Writing staking ledger at epoch {epoch} to stdout");
-                        ServerCliResponse::Success(ledger_json)
-                    } else {
-                        let path = path.unwrap();
-                        if !path.is_dir() {
-                            debug!("Writing ledger at epoch {epoch} to {path:?}");
-                            std::fs::write(path.clone(), ledger_json)?;
-                            ServerCliResponse::Success(format!(
-                                "Staking ledger at epoch {epoch} written to {path:?}"
-                            ))
-                        } else {
-                            file_must_not_be_a_directory(&path)
-                        }
-                    }
+        }
+        ClientCli::Ledgers(Ledgers::Height {
+            height,
+            path,
+            memoize,
+        }) => {
+            debug!("Received staged ledger at height {height} command");
+            if let Ok(Some(best_tip_height)) = db.get_best_block_height() {
+                if height > best_tip_height {
+                    // ahead of witness tree - cannot compute
+                    ServerCliResponse::error(
+                        ServerCliErrorCode::InvalidQuery,
+                        format!("Invalid query: ledger at height {height} cannot be determined from a chain of length {best_tip_height}"),
+                    )
                 } else {
-                    ServerCliResponse::Error(format!(
-                        "Staking ledger at epoch {epoch} is not in the store"
-                    ))
+                    let ledger = db
+                        .get_staged_ledger_at_block_height(height, memoize)?
+                        .unwrap();
+                    let ledger = with_labels(db, ledger)?.accounts_by_token();
+                    reply_or_write(
+                        CommandData::LedgersHeight(ledger),
+                        output,
+                        path,
+                        &format!("Ledger at height {height}"),
+                    )?
                 }
+            } else {
+                best_tip_missing_from_db()
             }
-            ClientCli::StakingLedgers(StakingLedgers::PublicKey {
-                epoch,
-                genesis_state_hash,
-                public_key: pk,
-            }) => {
-                debug!(
-                    "Received staking ledger account command for pk {} epoch {} genesis {}",
-                    pk, epoch, genesis_state_hash,
-                );
-
-                if !StateHash::is_valid(&genesis_state_hash) {
-                    invalid_state_hash(&genesis_state_hash)
-                } else if !PublicKey::is_valid(&pk) {
-                    invalid_public_key(&pk)
-                } else if let Some(aggregated_delegations) =
-                    db.build_aggregated_delegations(epoch, &genesis_state_hash.into())?
+        }
+        ClientCli::StakingLedgers(StakingLedgers::Hash { hash, path }) => {
+            debug!("Received staking-ledgers-hash command for {hash}");
+            if LedgerHash::is_valid(&hash) {
+                trace!("{hash} is a ledger hash");
+                if let Some(staking_ledger) =
+                    db.get_staking_ledger(&hash.clone().into(), None, None)?
                 {
-                    let pk: PublicKey = pk.into();
-                    let epoch = aggregated_delegations.epoch;
-                    let network = aggregated_delegations.network;
-                    let total_stake = aggregated_delegations.total_delegations;
-                    let count_delegates = aggregated_delegations
-                        .delegations
-                        .get(&pk)
-                        .map(|agg_del| agg_del.count_delegates)
-                        .unwrap_or_default();
-                    let total_delegated = aggregated_delegations
-                        .delegations
-                        .get(&pk)
-                        .map(|agg_del| agg_del.total_delegated)
-                        .unwrap_or_default();
-                    let delegates = aggregated_delegations
-                        .delegations
-                        .get(&pk)
-                        .map_or(vec![], |agg_del| {
-                            agg_del.delegates.iter().cloned().collect()
-                        });
-
-                    ServerCliResponse::Success(serde_json::to_string_pretty(
-                        &AggregatedEpochStakeDelegation {
-                            pk,
-                            epoch,
-                            network,
-                            count_delegates,
-                            total_delegated,
-                            total_stake,
-                            delegates,
-                        },
-                    )?)
+                    let staking_ledger = staking_with_labels(db, staking_ledger)?;
+                    reply_or_write(
+                        CommandData::StakingLedgersHash(staking_ledger),
+                        output,
+                        path,
+                        &format!("Staking ledger at hash {hash}"),
+                    )?
                 } else {
-                    ServerCliResponse::Error(format!(
-                        "Public key {pk} is missing from staking ledger epoch {epoch}"
-                    ))
+                    ServerCliResponse::error(
+                        ServerCliErrorCode::NotFound,
+                        format!("Staking ledger at {hash} is not in the store"),
+                    )
                 }
+            } else {
+                ServerCliResponse::error(
+                    ServerCliErrorCode::InvalidLedgerHash,
+                    format!("Invalid ledger hash: {hash}"),
+                )
             }
-            ClientCli::StakingLedgers(StakingLedgers::Delegations {
-                epoch,
-                genesis_state_hash,
-                path,
-            }) => {
-                debug!("Received staking-delegations command for epoch {epoch}");
-                let aggregated_delegations =
-                    db.build_aggregated_delegations(epoch, &genesis_state_hash.into())?;
-                if let Some(agg_del_str) = aggregated_delegations
-                    .map(|agg_del| serde_json::to_string_pretty(&agg_del).unwrap())
-                {
-                    if path.is_none() {
-                        debug!("Writing aggregated staking delegations epoch {epoch} to stdout");
-                        ServerCliResponse::Success(agg_del_str)
-                    } else {
-                        let path = path.unwrap();
-                        if !path.is_dir() {
-                            debug!(
-                                "Writing aggregated staking delegations epoch {epoch} to {path:?}"
-                            );
-                            std::fs::write(&path, agg_del_str)?;
-                            ServerCliResponse::Success(format!(
-                                "Aggregated staking delegations epoch {epoch} written to {path:?}"
-                            ))
-                        } else {
-                            file_must_not_be_a_directory(&path)
-                        }
-                    }
-                } else {
-                    ServerCliResponse::Error(format!(
-                        "Unable to aggregate staking delegations epoch {epoch}"
-                    ))
-                }
+        }
+        ClientCli::StakingLedgers(StakingLedgers::Epoch {
+            epoch,
+            genesis_state_hash,
+            path,
+        }) => {
+            debug!("Received staking-ledgers-epoch {epoch} command");
+            if !StateHash::is_valid(&genesis_state_hash) {
+                invalid_state_hash(&genesis_state_hash)
+            } else if let Some(staking_ledger) =
+                db.build_staking_ledger(epoch, &genesis_state_hash.into())?
+            {
+                let staking_ledger = staking_with_labels(db, staking_ledger)?;
+                reply_or_write(
+                    CommandData::StakingLedgersEpoch(staking_ledger),
+                    output,
+                    path,
+                    &format!("Staking ledger at epoch {epoch}"),
+                )?
+            } else {
+                ServerCliResponse::error(
+                    ServerCliErrorCode::NotFound,
+                    format!("Staking ledger at epoch {epoch} is not in the store"),
+                )
             }
-            ClientCli::Snarks(Snarks::PublicKey {
-                public_key: pk,
-                path,
-            }) => {
-                debug!("Received SNARK work command for public key {pk}");
+        }
+        ClientCli::StakingLedgers(StakingLedgers::PublicKey {
+            epoch,
+            genesis_state_hash,
+            public_key: pk,
+        }) => {
+            debug!(
+                "Received staking ledger account command for pk {} epoch {} genesis {}",
+                pk, epoch, genesis_state_hash,
+            );
 
-                if !PublicKey::is_valid(&pk) {
-                    invalid_public_key(&pk)
-                } else {
-                    let snarks = db.get_snark_work_by_public_key(&pk.clone().into())?;
-                    let snarks_str = format_vec_jq_compatible(&snarks);
+            if !StateHash::is_valid(&genesis_state_hash) {
+                invalid_state_hash(&genesis_state_hash)
+            } else if !PublicKey::is_valid(&pk) {
+                invalid_public_key(&pk)
+            } else if let Some(aggregated_delegations) =
+                db.build_aggregated_delegations(epoch, &genesis_state_hash.into())?
+            {
+                let pk: PublicKey = pk.into();
+                let epoch = aggregated_delegations.epoch;
+                let network = aggregated_delegations.network;
+                let total_stake = aggregated_delegations.total_delegations;
+                let count_delegates = aggregated_delegations
+                    .delegations
+                    .get(&pk)
+                    .map(|agg_del| agg_del.count_delegates)
+                    .unwrap_or_default();
+                let total_delegated = aggregated_delegations
+                    .delegations
+                    .get(&pk)
+                    .map(|agg_del| agg_del.total_delegated)
+                    .unwrap_or_default();
+                let delegates = aggregated_delegations
+                    .delegations
+                    .get(&pk)
+                    .map_or(vec![], |agg_del| {
+                        agg_del.delegates.iter().cloned().collect()
+                    });
 
-                    if path.is_none() {
-                        debug!("Writing SNARK work for public key {pk} to stdout");
-                        ServerCliResponse::Success(snarks_str)
-                    } else {
-                        let path = path.unwrap();
-
-                        if !path.is_dir() {
-                            debug!("Writing SNARK work for public key {pk} to {path:?}");
-                            std::fs::write(&path, snarks_str)?;
-                            ServerCliResponse::Success(format!(
-                                "SNARK work for public key {pk} written to {path:?}"
-                            ))
-                        } else {
-                            file_must_not_be_a_directory(&path)
-                        }
-                    }
-                }
+                let delegation = AggregatedEpochStakeDelegation {
+                    pk,
+                    epoch,
+                    network,
+                    count_delegates,
+                    total_delegated,
+                    total_stake,
+                    delegates,
+                };
+                reply(CommandData::StakingLedgersPublicKey(delegation), output)?
+            } else {
+                ServerCliResponse::error(
+                    ServerCliErrorCode::NotFound,
+                    format!("Public key {pk} is missing from staking ledger epoch {epoch}"),
+                )
             }
-            ClientCli::Snarks(Snarks::StateHash { state_hash, path }) => {
-                debug!("Received SNARK work command for state hash {state_hash}");
+        }
+        ClientCli::StakingLedgers(StakingLedgers::Payouts {
+            delegate,
+            epoch,
+            genesis_state_hash,
+            fee_percent,
+            scheme,
+            path,
+        }) => {
+            debug!(
+                "Received staking-ledgers-payouts {scheme} command for {delegate} epoch {epoch}"
+            );
 
-                if !StateHash::is_valid(&state_hash) {
-                    invalid_state_hash(&state_hash)
-                } else {
-                    match db.get_block_snark_work(&state_hash.clone().into())? {
-                        Some(snarks) => {
-                            let snarks_str = format_vec_jq_compatible(&snarks);
-                            if path.is_none() {
-                                debug!("Writing SNARK work for block {state_hash} to stdout");
-                                ServerCliResponse::Success(snarks_str)
-                            } else {
-                                let path = path.unwrap();
-                                if !path.is_dir() {
-                                    debug!("Writing SNARK work for block {state_hash} to {path:?}");
-                                    std::fs::write(&path, snarks_str).unwrap();
-                                    ServerCliResponse::Success(format!(
-                                        "SNARK work for block {state_hash} written to {path:?}"
-                                    ))
-                                } else {
-                                    file_must_not_be_a_directory(&path)
-                                }
-                            }
-                        }
-                        None => ServerCliResponse::Success(format!(
-                            "No SNARK work found for block {state_hash}"
-                        )),
-                    }
-                }
+            if !StateHash::is_valid(&genesis_state_hash) {
+                invalid_state_hash(&genesis_state_hash)
+            } else if !PublicKey::is_valid(&delegate) {
+                invalid_public_key(&delegate)
+            } else if !(0.0..=100.0).contains(&fee_percent) {
+                ServerCliResponse::error(
+                    ServerCliErrorCode::InvalidQuery,
+                    format!("Invalid pool fee percent: {fee_percent}"),
+                )
+            } else if let Some(payouts) = PoolPayouts::from_store(
+                db,
+                &delegate.clone().into(),
+                epoch,
+                &genesis_state_hash.into(),
+                scheme,
+                fee_percent,
+            )? {
+                reply_or_write(
+                    CommandData::StakingLedgersPayouts(payouts),
+                    output,
+                    path,
+                    &format!("Payouts for {delegate} epoch {epoch}"),
+                )?
+            } else {
+                ServerCliResponse::error(
+                    ServerCliErrorCode::NotFound,
+                    format!("Staking ledger at epoch {epoch} is not in the store"),
+                )
             }
-            ClientCli::Snarks(Snarks::Top { num }) => {
-                debug!("Received top {num} SNARKers command");
-                ServerCliResponse::Success(serde_json::to_string_pretty(
-                    &db.get_top_snark_provers_by_total_fees(num)?,
-                )?)
+        }
+        ClientCli::StakingLedgers(StakingLedgers::Delegations {
+            epoch,
+            genesis_state_hash,
+            path,
+        }) => {
+            debug!("Received staking-delegations command for epoch {epoch}");
+            let aggregated_delegations =
+                db.build_aggregated_delegations(epoch, &genesis_state_hash.into())?;
+            if let Some(aggregated_delegations) = aggregated_delegations {
+                reply_or_write(
+                    CommandData::StakingLedgersDelegations(aggregated_delegations),
+                    output,
+                    path,
+                    &format!("Aggregated staking delegations epoch {epoch}"),
+                )?
+            } else {
+                ServerCliResponse::error(
+                    ServerCliErrorCode::Internal,
+                    format!("Unable to aggregate staking delegations epoch {epoch}"),
+                )
             }
-            ClientCli::Shutdown => {
-                debug!("Received shutdown command");
-                // First respond success to client before shutting down
-                let response =
-                    ServerCliResponse::Success("Shutting down Mina Indexer...".to_string());
-                let encoded = bincode::encode_to_vec(&response, BIN_CODE_CONFIG)?;
-                writer.write_all(&encoded).await?;
-                writer.flush().await?; // Ensure response is sent
-
-                // Then initiate clean shutdown
-                subsys.request_shutdown();
-                return Ok(()); // Exit the function
+        }
+        ClientCli::Snarks(Snarks::PublicKey {
+            public_key: pk,
+            path,
+        }) => {
+            debug!("Received SNARK work command for public key {pk}");
+
+            if !PublicKey::is_valid(&pk) {
+                invalid_public_key(&pk)
+            } else {
+                let snarks = db.get_snark_work_by_public_key(&pk.clone().into())?;
+                reply_or_write(
+                    CommandData::SnarksPublicKey(snarks),
+                    output,
+                    path,
+                    &format!("SNARK work for public key {pk}"),
+                )?
             }
-            ClientCli::Summary {
-                verbose,
-                json,
-                path,
-            } => {
-                debug!("Received summary command");
+        }
+        ClientCli::Snarks(Snarks::StateHash { state_hash, path }) => {
+            debug!("Received SNARK work command for state hash {state_hash}");
 
-                let summary = state.summary_verbose();
-                let summary_str = if verbose {
-                    format_json(&summary, json)
-                } else {
-                    let summary: SummaryShort = summary.clone().into();
-                    format_json(&summary, json)
-                };
+            if !StateHash::is_valid(&state_hash) {
+                invalid_state_hash(&state_hash)
+            } else {
+                match db.get_block_snark_work(&state_hash.clone().into())? {
+                    Some(snarks) => reply_or_write(
+                        CommandData::SnarksStateHash(snarks),
+                        output,
+                        path,
+                        &format!("SNARK work for block {state_hash}"),
+                    )?,
+                    None => not_found(format!("No SNARK work found for block {state_hash}")),
+                }
+            }
+        }
+        ClientCli::Snarks(Snarks::Top { num }) => {
+            debug!("Received top {num} SNARKers command");
+            reply(
+                CommandData::SnarksTop(db.get_top_snark_provers_by_total_fees(num)?),
+                output,
+            )?
+        }
+        ClientCli::Shutdown => {
+            debug!("Received shutdown command");
+            // shutdown is initiated once the response is sent
+            reply(CommandData::Shutdown, output)?
+        }
+        ClientCli::Summary {
+            verbose,
+            json,
+            path,
+        } => {
+            debug!("Received summary command");
 
-                if path.is_none() {
-                    debug!("Writing summary to stdout");
-                    ServerCliResponse::Success(summary_str)
-                } else {
-                    let path = path.unwrap();
-                    if !path.is_dir() {
+            // read replicas have no witness tree
+            let Some(state) = state else {
+                return Ok(ServerCliResponse::error(
+                    ServerCliErrorCode::Unsupported,
+                    "Summary is unavailable on read replicas",
+                ));
+            };
+
+            let summary = state.blocking_read().summary_verbose();
+            let data = CommandData::Summary(if verbose {
+                SummaryData::Verbose(Box::new(summary))
+            } else {
+                SummaryData::Short(summary.into())
+            });
+
+            if json && !output.is_machine_readable() {
+                // the summary's JSON text
+                let summary_str = data.render(OutputFormat::Json)?;
+                match path {
+                    None => ServerCliResponse::Success(summary_str),
+                    Some(path) if path.is_dir() => file_must_not_be_a_directory(&path),
+                    Some(path) => {
                         debug!("Writing summary to {path:?}");
                         std::fs::write(&path, summary_str)?;
                         ServerCliResponse::Success(format!("Summary written to {path:?}"))
-                    } else {
-                        file_must_not_be_a_directory(&path)
                     }
                 }
+            } else {
+                reply_or_write(data, output, path, "Summary")?
             }
-            ClientCli::Transactions(Transactions::PublicKey {
-                public_key: pk,
-                verbose,
-                start_state_hash,
-                end_state_hash,
-                path,
-                csv,
-            }) => {
-                let start_state_hash: StateHash = start_state_hash.into();
-                let end_state_hash_result = match end_state_hash {
-                    Some(hash) => Ok(hash.into()),
-                    None => match db.get_best_block()? {
-                        Some(best_tip) => Ok(best_tip.state_hash()),
-                        None => Err(()),
-                    },
-                };
+        }
+        ClientCli::Transactions(Transactions::PublicKey {
+            public_key: pk,
+            verbose,
+            start_state_hash,
+            end_state_hash,
+            path,
+            csv,
+        }) => {
+            let start_state_hash: StateHash = start_state_hash.into();
+            let end_state_hash_result = match end_state_hash {
+                Some(hash) => Ok(hash.into()),
+                None => match db.get_best_block()? {
+                    Some(best_tip) => Ok(best_tip.state_hash()),
+                    None => Err(()),
+                },
+            };
 
-                debug!("Received tx-public-key command for {pk}");
+            debug!("Received tx-public-key command for {pk}");
 
-                if !PublicKey::is_valid(&pk) {
-                    invalid_public_key(&pk)
-                } else if !StateHash::is_valid(&start_state_hash.0) {
-                    invalid_state_hash(&start_state_hash.0)
-                } else if end_state_hash_result.is_err() {
-                    best_tip_missing_from_db()
-                } else {
-                    let end_state_hash = end_state_hash_result.unwrap();
-                    if !StateHash::is_valid(&end_state_hash.0) {
-                        invalid_state_hash(&end_state_hash.0)
-                    } else if csv {
-                        match db.write_user_commands_csv(&pk.clone().into(), path) {
-                            Ok(path) => ServerCliResponse::Success(format!(
+            if !PublicKey::is_valid(&pk) {
+                invalid_public_key(&pk)
+            } else if !StateHash::is_valid(&start_state_hash.0) {
+                invalid_state_hash(&start_state_hash.0)
+            } else if end_state_hash_result.is_err() {
+                best_tip_missing_from_db()
+            } else {
+                let end_state_hash = end_state_hash_result.unwrap();
+                if !StateHash::is_valid(&end_state_hash.0) {
+                    invalid_state_hash(&end_state_hash.0)
+                } else if csv {
+                    match db.write_user_commands_csv(&pk.clone().into(), path) {
+                        Ok(path) => {
+                            let message = format!(
                                 "Successfully wrote user commands CSV for {pk} to {path:?}"
-                            )),
-                            Err(e) => ServerCliResponse::Error(format!(
-                                "Error writing user commands CSV for {pk}: {e}"
-                            )),
-                        }
-                    } else {
-                        let transactions = db
-                            .get_user_commands_for_public_key(&pk.clone().into())?
-                            .unwrap_or_default();
-                        let transaction_str = if verbose {
-                            format_vec_jq_compatible(&transactions)
-                        } else {
-                            let txs: Vec<Command> =
-                                transactions.into_iter().map(Command::from).collect();
-                            format_vec_jq_compatible(&txs)
-                        };
-
-                        if path.is_none() {
-                            debug!("Writing transactions for {pk} to stdout");
-                            ServerCliResponse::Success(transaction_str)
-                        } else {
-                            let path = path.unwrap();
-                            if !path.is_dir() {
-                                debug!("Writing transactions for {pk} to {path:?}");
-                                std::fs::write(&path, transaction_str)?;
-                                ServerCliResponse::Success(format!(
-                                    "Transactions for {pk} written to {path:?}"
-                                ))
-                            } else {
-                                file_must_not_be_a_directory(&path)
-                            }
+                            );
+                            written(path, message, output)?
                         }
+                        Err(e) => ServerCliResponse::error(
+                            ServerCliErrorCode::Internal,
+                            format!("Error writing user commands CSV for {pk}: {e}"),
+                        ),
                     }
+                } else {
+                    let public_key: PublicKey = pk.clone().into();
+                    let transactions = public_key_user_commands(db, &public_key);
+                    let what = format!("Transactions for {pk}");
+
+                    let txns =
+                        transactions.map(|txn| txn.map(|txn| transaction_data(txn, verbose)));
+                    write_list(
+                        txns,
+                        output,
+                        OutputFormat::Text,
+                        CommandData::TransactionsPublicKey,
+                        path,
+                        &what,
+                        stream,
+                    )?
                 }
             }
-            ClientCli::Transactions(Transactions::Hash { hash, verbose }) => {
-                debug!("Received tx-hash command for {hash}");
-                let hash = TxnHash::new(hash)?;
-                match db.get_user_command(&hash, 0) {
-                    Ok(Some(cmd)) => {
-                        let msg = if verbose {
-                            format!("{cmd:?}")
-                        } else {
-                            let cmd: Command = cmd.into();
-                            format!("{cmd:?}")
-                        };
-                        ServerCliResponse::Success(msg)
-                    }
-                    Ok(None) => ServerCliResponse::Success(format!(
-                        "Transaction at hash not present in store: '{hash}'"
-                    )),
-                    Err(e) => ServerCliResponse::Error(format!(
-                        "Failed to retrieve transaction hash for '{hash}': {e}"
-                    )),
+        }
+        ClientCli::Transactions(Transactions::Hash {
+            command: Some(path),
+            bin_prot,
+            ..
+        }) => {
+            debug!("Received tx-hash command for {}", path.display());
+            let bytes = match std::fs::read(&path) {
+                Ok(bytes) => bytes,
+                Err(e) => {
+                    return Ok(ServerCliResponse::error(
+                        ServerCliErrorCode::InvalidPath,
+                        format!("Failed to read command {}: {e}", path.display()),
+                    ))
                 }
-            }
-            ClientCli::Transactions(Transactions::StateHash {
-                state_hash,
-                verbose,
-                path,
-            }) => {
-                debug!("Received tx-state-hash command for {state_hash}");
-                if !StateHash::is_valid(&state_hash) {
-                    invalid_state_hash(&state_hash)
-                } else {
-                    let block_hash = StateHash(state_hash.to_owned());
-                    match db.get_block_user_commands(&block_hash).unwrap_or_default() {
-                        Some(cmds) => {
-                            let transaction_str = if verbose {
-                                format_vec_jq_compatible(&cmds)
-                            } else {
-                                let cmds: Vec<Command> =
-                                    cmds.into_iter().map(Command::from).collect();
-                                format_vec_jq_compatible(&cmds)
-                            };
+            };
 
-                            if path.is_none() {
-                                debug!("Writing transactions for {state_hash} to stdout");
-                                ServerCliResponse::Success(transaction_str)
-                            } else {
-                                let path = path.unwrap();
-                                if !path.is_dir() {
-                                    debug!("Writing transactions for {state_hash} to {path:?}");
-                                    std::fs::write(&path, transaction_str).unwrap();
-                                    ServerCliResponse::Success(format!(
-                                        "Transactions for {state_hash} written to {path:?}"
-                                    ))
-                                } else {
-                                    file_must_not_be_a_directory(&path)
-                                }
-                            }
-                        }
-                        None => ServerCliResponse::Success(format!(
-                            "No transactions found for block {state_hash}"
-                        )),
-                    }
-                }
+            let hash = if bin_prot {
+                hash::hash_user_command_bin_prot(&bytes)
+            } else {
+                hash::hash_user_command_json(&bytes)
+            };
+
+            match hash {
+                Ok(hash) => reply(
+                    CommandData::TransactionsCommandHash {
+                        hash: hash.to_string(),
+                    },
+                    output,
+                )?,
+                Err(e) => ServerCliResponse::error(
+                    ServerCliErrorCode::InvalidQuery,
+                    format!("Failed to compute the hash of {}: {e}", path.display()),
+                ),
             }
-            ClientCli::InternalCommands(InternalCommands::PublicKey {
-                path,
-                public_key: pk,
-                csv,
-            }) => {
-                if !PublicKey::is_valid(&pk) {
-                    invalid_public_key(&pk)
-                } else if csv {
-                    match db.write_internal_commands_csv(pk.clone().into(), path) {
-                        Ok(path) => ServerCliResponse::Success(format!(
-                            "Successfully wrote internal commands CSV for {pk} to {path:?}"
-                        )),
-                        Err(e) => ServerCliResponse::Error(format!(
-                            "Error writing internal commands CSV for {pk}: {e}"
-                        )),
-                    }
-                } else {
-                    let internal_cmds =
-                        db.get_internal_commands_public_key(&pk.clone().into(), 0, usize::MAX)?;
-                    let internal_cmds_str = serde_json::to_string_pretty(&internal_cmds)?;
+        }
+        ClientCli::Transactions(Transactions::Hash { hash, verbose, .. }) => {
+            let hash = hash.unwrap_or_default();
+            debug!("Received tx-hash command for {hash}");
+            let hash = match TxnHash::new(hash.to_owned()) {
+                Ok(hash) => hash,
+                Err(_) => return Ok(invalid_txn_hash(&hash)),
+            };
 
-                    if path.is_none() {
-                        debug!("Writing internal commands for {pk} to stdout");
-                        ServerCliResponse::Success(internal_cmds_str)
-                    } else {
-                        let path = path.unwrap();
-                        if !path.is_dir() {
-                            debug!("Writing internal commands for {pk} to {path:?}");
-                            std::fs::write(&path, internal_cmds_str)?;
-                            ServerCliResponse::Success(format!(
-                                "Internal commands for {pk} written to {path:?}"
-                            ))
-                        } else {
-                            file_must_not_be_a_directory(&path)
-                        }
+            match db.get_user_command(&hash, 0) {
+                Ok(Some(cmd)) => reply(
+                    CommandData::TransactionsHash(transaction_data(cmd, verbose)),
+                    output,
+                )?,
+                Ok(None) => not_found(format!(
+                    "Transaction at hash not present in store: '{hash}'"
+                )),
+                Err(e) => ServerCliResponse::error(
+                    ServerCliErrorCode::Internal,
+                    format!("Failed to retrieve transaction hash for '{hash}': {e}"),
+                ),
+            }
+        }
+        ClientCli::Transactions(Transactions::StateHash {
+            state_hash,
+            verbose,
+            path,
+        }) => {
+            debug!("Received tx-state-hash command for {state_hash}");
+            if !StateHash::is_valid(&state_hash) {
+                invalid_state_hash(&state_hash)
+            } else {
+                let block_hash = StateHash(state_hash.to_owned());
+                match db.get_block_user_commands(&block_hash).unwrap_or_default() {
+                    Some(cmds) => {
+                        let txns = cmds
+                            .into_iter()
+                            .map(|cmd| Ok(transaction_data(cmd, verbose)));
+                        write_list(
+                            txns,
+                            output,
+                            OutputFormat::Text,
+                            CommandData::TransactionsStateHash,
+                            path,
+                            &format!("Transactions for {state_hash}"),
+                            stream,
+                        )?
                     }
+                    None => not_found(format!("No transactions found for block {state_hash}")),
                 }
             }
-            ClientCli::InternalCommands(InternalCommands::StateHash { path, state_hash }) => {
-                debug!("Received internal-state-hash command for {}", state_hash);
-                if !StateHash::is_valid(&state_hash) {
-                    invalid_state_hash(&state_hash)
-                } else {
-                    let state_hash = StateHash(state_hash);
-                    let internal_cmds_str =
-                        serde_json::to_string_pretty(&db.get_internal_commands(&state_hash)?)?;
-
-                    if path.is_none() {
-                        debug!("Writing block internal commands for {state_hash} to stdout");
-                        ServerCliResponse::Success(internal_cmds_str)
-                    } else {
-                        let path = path.unwrap();
-                        if !path.is_dir() {
-                            debug!("Writing block internal commands for {state_hash} to {path:?}");
-
-                            std::fs::write(&path, internal_cmds_str)?;
-                            ServerCliResponse::Success(format!(
-                                "Block internal commands for {state_hash} written to {path:?}"
-                            ))
-                        } else {
-                            file_must_not_be_a_directory(&path)
-                        }
+        }
+        ClientCli::InternalCommands(InternalCommands::PublicKey {
+            path,
+            public_key: pk,
+            csv,
+        }) => {
+            if !PublicKey::is_valid(&pk) {
+                invalid_public_key(&pk)
+            } else if csv {
+                match db.write_internal_commands_csv(pk.clone().into(), path) {
+                    Ok(path) => {
+                        let message = format!(
+                            "Successfully wrote internal commands CSV for {pk} to {path:?}"
+                        );
+                        written(path, message, output)?
                     }
+                    Err(e) => ServerCliResponse::error(
+                        ServerCliErrorCode::Internal,
+                        format!("Error writing internal commands CSV for {pk}: {e}"),
+                    ),
                 }
+            } else {
+                let internal_cmds =
+                    db.get_internal_commands_public_key(&pk.clone().into(), 0, usize::MAX)?;
+                reply_or_write(
+                    CommandData::InternalCommandsPublicKey(internal_cmds),
+                    output,
+                    path,
+                    &format!("Internal commands for {pk}"),
+                )?
             }
-            ClientCli::DbVersion => ServerCliResponse::Success(format!(
-                "mina-indexer database v{}",
-                db.get_db_version()?
-            )),
-        };
-
-        match response {
-            ServerCliResponse::Success(_) => {}
-            ServerCliResponse::Error(ref msg) => {
-                error!("{}", msg);
+        }
+        ClientCli::InternalCommands(InternalCommands::StateHash { path, state_hash }) => {
+            debug!("Received internal-state-hash command for {}", state_hash);
+            if !StateHash::is_valid(&state_hash) {
+                invalid_state_hash(&state_hash)
+            } else {
+                let state_hash = StateHash(state_hash);
+                let internal_cmds = db.get_internal_commands(&state_hash)?;
+                reply_or_write(
+                    CommandData::InternalCommandsStateHash(internal_cmds),
+                    output,
+                    path,
+                    &format!("Block internal commands for {state_hash}"),
+                )?
             }
-        };
+        }
+        ClientCli::DbVersion => reply(CommandData::DbVersion(db.get_db_version()?), output)?,
+    };
 
-        let encoded = bincode::encode_to_vec(&response, BIN_CODE_CONFIG)?;
-        writer.write_all(&encoded).await?;
-    }
-    Ok(())
+    Ok(response)
 }
 
 fn try_replace_old_socket(e: io::Error, unix_socket_path: &Path) -> io::Result<UnixListener> {
@@ -1199,17 +1535,52 @@ pub fn remove_unix_socket(unix_socket_path: &Path) -> io::Result<()> {
 
 mod helpers {
     use super::*;
+    use std::{
+        fs::File,
+        io::{BufWriter, Write},
+    };
 
     pub fn invalid_public_key(input: &str) -> ServerCliResponse {
-        ServerCliResponse::Success(format!("Invalid public key: {input}"))
+        ServerCliResponse::error(
+            ServerCliErrorCode::InvalidPublicKey,
+            format!("Invalid public key: {input}"),
+        )
+    }
+
+    pub fn invalid_token_address(input: &str) -> ServerCliResponse {
+        ServerCliResponse::error(
+            ServerCliErrorCode::InvalidTokenAddress,
+            format!("Invalid token address: {input}"),
+        )
+    }
+
+    pub fn invalid_webhook_url(input: &str) -> ServerCliResponse {
+        ServerCliResponse::error(
+            ServerCliErrorCode::InvalidWebhookUrl,
+            format!("Invalid webhook url (only http:// is supported): {input}"),
+        )
     }
 
     pub fn invalid_state_hash(input: &str) -> ServerCliResponse {
-        ServerCliResponse::Success(format!("Invalid state hash: {input}"))
+        ServerCliResponse::error(
+            ServerCliErrorCode::InvalidStateHash,
+            format!("Invalid state hash: {input}"),
+        )
+    }
+
+    pub fn invalid_txn_hash(input: &str) -> ServerCliResponse {
+        ServerCliResponse::error(
+            ServerCliErrorCode::InvalidTxnHash,
+            format!("Invalid transaction hash: {input}"),
+        )
+    }
+
+    pub fn not_found(msg: String) -> ServerCliResponse {
+        ServerCliResponse::error(ServerCliErrorCode::NotFound, msg)
     }
 
     pub fn account_missing_from_db(pk: &PublicKey) -> ServerCliResponse {
-        ServerCliResponse::Success(format!("Account missing from store: {pk}"))
+        not_found(format!("Account missing from store: {pk}"))
     }
 
     pub fn block_missing_from_db(state_hash: &str) -> String {
@@ -1217,32 +1588,503 @@ mod helpers {
     }
 
     pub fn best_tip_missing_from_db() -> ServerCliResponse {
-        ServerCliResponse::Error("Best tip block missing from store".to_string())
+        ServerCliResponse::error(
+            ServerCliErrorCode::NotFound,
+            "Best tip block missing from store".to_string(),
+        )
     }
 
     pub fn file_must_not_be_a_directory(path: &std::path::Path) -> ServerCliResponse {
-        ServerCliResponse::Success(format!(
-            "The path provided must not be a directory: {}",
-            path.display()
+        ServerCliResponse::error(
+            ServerCliErrorCode::InvalidPath,
+            format!(
+                "The path provided must not be a directory: {}",
+                path.display()
+            ),
+        )
+    }
+
+    /// Display the ledger's labeled accounts under their labels
+    pub fn with_labels(db: &IndexerStore, mut ledger: Ledger) -> anyhow::Result<Ledger> {
+        for label in db.get_labels(None)? {
+            for token_ledger in ledger.tokens.values_mut() {
+                if let Some(account) = token_ledger.accounts.get_mut(&label.public_key) {
+                    account.username = Some(Username(label.label.clone()));
+                }
+            }
+        }
+
+        Ok(ledger)
+    }
+
+    /// Display the staking ledger's labeled accounts under their labels
+    pub fn staking_with_labels(
+        db: &IndexerStore,
+        mut staking_ledger: StakingLedger,
+    ) -> anyhow::Result<StakingLedger> {
+        for label in db.get_labels(None)? {
+            if let Some(account) = staking_ledger.staking_ledger.get_mut(&label.public_key) {
+                account.username = Some(label.label);
+            }
+        }
+
+        Ok(staking_ledger)
+    }
+
+    /// Typed data for machine-readable output, the data's text otherwise
+    pub fn reply(data: CommandData, output: OutputFormat) -> anyhow::Result<ServerCliResponse> {
+        Ok(ServerCliResponse::Success(
+            if output.is_machine_readable() {
+                serde_json::to_string(&data)?
+            } else {
+                data.text()?
+            },
         ))
     }
 
-    pub fn format_vec_jq_compatible<T>(vec: &Vec<T>) -> String
+    /// Response to output written to `path`
+    pub fn written(
+        path: PathBuf,
+        message: String,
+        output: OutputFormat,
+    ) -> anyhow::Result<ServerCliResponse> {
+        if output.is_machine_readable() {
+            return reply(CommandData::Written { path }, output);
+        }
+
+        Ok(ServerCliResponse::Success(message))
+    }
+
+    /// Replies with the data or writes it to `path`
+    pub fn reply_or_write(
+        data: CommandData,
+        output: OutputFormat,
+        path: Option<PathBuf>,
+        what: &str,
+    ) -> anyhow::Result<ServerCliResponse> {
+        let Some(path) = path else {
+            return reply(data, output);
+        };
+
+        if path.is_dir() {
+            return Ok(file_must_not_be_a_directory(&path));
+        }
+
+        debug!("Writing {} to {path:?}", what.to_lowercase());
+        std::fs::write(&path, data.render(output)?)?;
+
+        let message = format!("{what} written to {path:?}");
+        written(path, message, output)
+    }
+
+    /// Streams the items to the client or writes them to `path`, see
+    /// [ResponseStream::items]
+    pub fn write_list<T, I, D>(
+        items: I,
+        output: OutputFormat,
+        text: OutputFormat,
+        data: D,
+        path: Option<PathBuf>,
+        what: &str,
+        stream: &mut ResponseStream,
+    ) -> anyhow::Result<ServerCliResponse>
     where
-        T: std::fmt::Debug,
+        T: std::fmt::Debug + serde::Serialize,
+        I: IntoIterator<Item = anyhow::Result<T>>,
+        D: FnOnce(Vec<T>) -> CommandData,
     {
-        let pp = format!("{vec:#?}");
-        pp.replace(",\n]", "\n]")
+        let Some(path) = path else {
+            return stream.items(items, output, text, data);
+        };
+
+        if path.is_dir() {
+            return Ok(file_must_not_be_a_directory(&path));
+        }
+
+        // files hold the data without the type tag
+        let format = if output.is_machine_readable() {
+            OutputFormat::Json
+        } else {
+            text
+        };
+
+        let mut file = BufWriter::new(File::create(&path)?);
+        write_items(items, format, |item| Ok(file.write_all(item.as_bytes())?))?;
+        file.flush()?;
+
+        let message = format!("{what} written to {path:?}");
+        written(path, message, output)
+    }
+
+    /// The block's data, verbose blocks are precomputed blocks
+    pub fn block_data(
+        block: &PrecomputedBlock,
+        canonicity: Canonicity,
+        verbose: bool,
+    ) -> BlockData {
+        if verbose {
+            BlockData::Verbose(Box::new(block.with_canonicity(canonicity)))
+        } else {
+            BlockData::Short(BlockWithoutHeight::with_canonicity(block, canonicity))
+        }
     }
 
-    pub fn format_json<T>(input: &T, json: bool) -> String
+    /// Stored blocks with known canonicity
+    pub fn stored_blocks<'a>(
+        db: &'a IndexerStore,
+        state_hashes: &'a [StateHash],
+    ) -> impl Iterator<Item = anyhow::Result<(PrecomputedBlock, Canonicity)>> + 'a {
+        state_hashes.iter().filter_map(|state_hash| {
+            let Ok(Some(canonicity)) = db.get_block_canonicity(state_hash) else {
+                return None;
+            };
+
+            Some(
+                db.get_block(state_hash)
+                    .and_then(|block| {
+                        block.with_context(|| format!("block missing from store {state_hash}"))
+                    })
+                    .map(|(block, _)| (block, canonicity)),
+            )
+        })
+    }
+
+    /// The transaction's data, verbose transactions carry their block data
+    pub fn transaction_data(txn: SignedCommandWithData, verbose: bool) -> TransactionData {
+        if verbose {
+            TransactionData::Verbose(Box::new(txn))
+        } else {
+            TransactionData::Short(Command::from(txn))
+        }
+    }
+
+    /// Streams the blocks to the client or writes them to `path`, verbose
+    /// blocks are always JSON
+    pub fn write_blocks<I, D>(
+        blocks: I,
+        verbose: bool,
+        output: OutputFormat,
+        data: D,
+        path: Option<PathBuf>,
+        what: &str,
+        stream: &mut ResponseStream,
+    ) -> anyhow::Result<ServerCliResponse>
     where
-        T: ?Sized + serde::Serialize + std::fmt::Display,
+        I: Iterator<Item = anyhow::Result<(PrecomputedBlock, Canonicity)>>,
+        D: FnOnce(Vec<BlockData>) -> CommandData,
     {
-        if json {
-            serde_json::to_string(input).unwrap()
+        let text = if verbose {
+            OutputFormat::Json
         } else {
-            format!("{input}")
+            OutputFormat::Text
+        };
+        let blocks = blocks
+            .map(|block| block.map(|(block, canonicity)| block_data(&block, canonicity, verbose)));
+
+        write_list(blocks, output, text, data, path, what, stream)
+    }
+
+    /// The public key's user commands, read a page at a time
+    pub fn public_key_user_commands<'a>(
+        db: &'a IndexerStore,
+        pk: &'a PublicKey,
+    ) -> impl Iterator<Item = anyhow::Result<SignedCommandWithData>> + 'a {
+        let mut cursor: Option<(TxnHash, StateHash)> = None;
+        let mut page = vec![].into_iter();
+        let mut done = false;
+
+        std::iter::from_fn(move || loop {
+            if let Some(cmd) = page.next() {
+                return Some(Ok(cmd));
+            }
+
+            if done {
+                return None;
+            }
+
+            let cmds = match db.get_user_commands_for_public_key_page(
+                pk,
+                cursor
+                    .as_ref()
+                    .map(|(txn_hash, state_hash)| (txn_hash, state_hash)),
+                0,
+                USER_COMMANDS_PAGE_SIZE,
+            ) {
+                Ok(cmds) => cmds,
+                Err(e) => {
+                    done = true;
+                    return Some(Err(e));
+                }
+            };
+
+            done = cmds.len() < USER_COMMANDS_PAGE_SIZE;
+            cursor = cmds
+                .last()
+                .map(|cmd| (cmd.txn_hash.to_owned(), cmd.state_hash.to_owned()));
+            page = cmds.into_iter();
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::address_book::Category;
+
+    /// Runs the writer on a blocking thread, returns the client's response
+    async fn streamed<F>(write: F) -> anyhow::Result<ServerCliResponse>
+    where
+        F: FnOnce(&mut ResponseStream) -> anyhow::Result<()> + Send + 'static,
+    {
+        let (mut client, mut server) = tokio::io::duplex(RESPONSE_CHUNK_SIZE);
+        let (chunks, received) = mpsc::channel(RESPONSE_CHANNEL_CAPACITY);
+
+        let handler = tokio::task::spawn_blocking(move || {
+            let mut stream = ResponseStream::new(chunks);
+            write(&mut stream)?;
+            stream.finish()
+        });
+        let writer = tokio::spawn(async move { write_chunks(&mut server, received).await });
+
+        let response = read_response(&mut client).await?;
+        handler.await??;
+        writer.await??;
+        Ok(response)
+    }
+
+    #[tokio::test]
+    async fn chunked_response() -> anyhow::Result<()> {
+        let output = "x".repeat(3 * RESPONSE_CHUNK_SIZE + 1);
+        let response = streamed({
+            let output = output.clone();
+            move |stream| stream.respond(ServerCliResponse::Success(output))
+        })
+        .await?;
+
+        match response {
+            ServerCliResponse::Success(received) => assert_eq!(received, output),
+            _ => panic!("Unexpected response"),
         }
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn streamed_items() -> anyhow::Result<()> {
+        use crate::cli::response::format_vec_jq_compatible;
+
+        let webhooks: Vec<Webhook> = (0..3)
+            .map(|n| Webhook {
+                public_key: PublicKey::default(),
+                url: format!("http://localhost:808{n}/hook"),
+            })
+            .collect();
+
+        for webhooks in [vec![], webhooks] {
+            // streamed text matches the collected output
+            let expected = format_vec_jq_compatible(&webhooks, OutputFormat::Text)?;
+            let response = streamed({
+                let webhooks = webhooks.clone();
+                move |stream| {
+                    let items = webhooks.into_iter().map(Ok);
+                    stream.items(
+                        items,
+                        OutputFormat::Text,
+                        OutputFormat::Text,
+                        CommandData::WebhooksList,
+                    )?;
+                    Ok(())
+                }
+            })
+            .await?;
+
+            match response {
+                ServerCliResponse::Success(received) => assert_eq!(received, expected),
+                _ => panic!("Unexpected response"),
+            }
+
+            // streamed JSON is the typed data
+            let response = streamed({
+                let webhooks = webhooks.clone();
+                move |stream| {
+                    let items = webhooks.into_iter().map(Ok);
+                    stream.items(
+                        items,
+                        OutputFormat::Json,
+                        OutputFormat::Text,
+                        CommandData::WebhooksList,
+                    )?;
+                    Ok(())
+                }
+            })
+            .await?;
+
+            match response {
+                ServerCliResponse::Success(received) => match serde_json::from_str(&received)? {
+                    CommandData::WebhooksList(received) => assert_eq!(received, webhooks),
+                    data => panic!("Unexpected data {data:?}"),
+                },
+                _ => panic!("Unexpected response"),
+            }
+        }
+
+        // errors replace the streamed output
+        let response = streamed(|stream| {
+            let items = [
+                Ok(Webhook {
+                    public_key: PublicKey::default(),
+                    url: "http://localhost:8080/hook".to_string(),
+                }),
+                Err(anyhow::anyhow!("store error")),
+            ];
+            if let Err(e) = stream.items(
+                items,
+                OutputFormat::Json,
+                OutputFormat::Text,
+                CommandData::WebhooksList,
+            ) {
+                stream.respond(ServerCliResponse::error(
+                    ServerCliErrorCode::Internal,
+                    e.to_string(),
+                ))?;
+            }
+            Ok(())
+        })
+        .await?;
+
+        match response {
+            ServerCliResponse::Error(error) => assert_eq!(error.message, "store error"),
+            _ => panic!("Expected an error"),
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn replicas_reject_mutating_commands() {
+        let pk = "B62qrxNgwAdhGYZv1BXQRt2HgopUceFyrtXZMikwsuaHu5FigRJjhwY".to_string();
+        let url = "http://localhost:8080/hook".to_string();
+
+        let mutating = [
+            ClientCli::CreateSnapshot {
+                output_path: "./snapshot".into(),
+            },
+            ClientCli::Webhooks(Webhooks::Add {
+                public_key: pk.clone(),
+                url: url.clone(),
+            }),
+            ClientCli::Webhooks(Webhooks::Remove {
+                public_key: pk.clone(),
+                url,
+            }),
+            ClientCli::AddressBook(AddressBook::Add {
+                public_key: pk.clone(),
+                label: "label".to_string(),
+                category: Category::Other,
+                source: "test".to_string(),
+            }),
+            ClientCli::AddressBook(AddressBook::Remove {
+                public_key: pk.clone(),
+            }),
+            ClientCli::AddressBook(AddressBook::Load {
+                path: "./labels.csv".into(),
+            }),
+        ];
+
+        for command in mutating {
+            assert!(command.is_mutating(), "{}", command.name());
+            match replica_unsupported(&command) {
+                ServerCliResponse::Error(error) => {
+                    assert_eq!(error.code, ServerCliErrorCode::Unsupported)
+                }
+                _ => panic!("Expected an error for {}", command.name()),
+            }
+        }
+
+        // reads are served
+        for command in [
+            ClientCli::Webhooks(Webhooks::List),
+            ClientCli::AddressBook(AddressBook::List { category: None }),
+            ClientCli::Accounts(Accounts::PublicKey { public_key: pk }),
+            ClientCli::DbVersion,
+        ] {
+            assert!(!command.is_mutating(), "{}", command.name());
+        }
+    }
+
+    /// Encodes the frames into chunks of the given sizes
+    fn chunked(frames: &[ResponseFrame], sizes: &[usize]) -> anyhow::Result<Vec<u8>> {
+        let mut encoded = vec![];
+        for frame in frames {
+            encoded.extend(bincode::encode_to_vec(frame, BIN_CODE_CONFIG)?);
+        }
+
+        let mut bytes = vec![];
+        let mut rest = encoded.as_slice();
+        for size in sizes {
+            let (chunk, tail) = rest.split_at((*size).min(rest.len()));
+            bytes.extend_from_slice(&(chunk.len() as u32).to_be_bytes());
+            bytes.extend_from_slice(chunk);
+            rest = tail;
+        }
+
+        Ok(bytes)
+    }
+
+    #[tokio::test]
+    async fn frames_decoded_as_chunks_arrive() -> anyhow::Result<()> {
+        let frames = [
+            ResponseFrame::Output("first".to_string()),
+            ResponseFrame::Output("second".to_string()),
+        ];
+
+        // the second frame spans both chunks & the final chunk never arrives
+        let first_len = bincode::encode_to_vec(&frames[0], BIN_CODE_CONFIG)?.len();
+        let bytes = chunked(&frames, &[first_len + 2, usize::MAX])?;
+
+        let mut received = vec![];
+        let res = read_frames(&mut bytes.as_slice(), |frame| {
+            received.push(frame);
+            Ok(())
+        })
+        .await;
+
+        assert!(res.is_err());
+        assert_eq!(received, vec!["first", "second"]);
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn request_read_across_writes() -> anyhow::Result<()> {
+        let path = PathBuf::from("labels").join("x".repeat(4 * BUFFER_SIZE));
+        let (mut client, mut server) = tokio::io::duplex(BUFFER_SIZE);
+
+        let writer = tokio::spawn(async move {
+            let request = ClientRequest {
+                command: ClientCli::AddressBook(AddressBook::Load { path }),
+                output: OutputFormat::Json,
+            };
+            write_request(&mut client, request).await
+        });
+        let request = read_request(&mut server).await?;
+        writer.await??;
+
+        match request.command {
+            ClientCli::AddressBook(AddressBook::Load { path }) => {
+                assert_eq!(path.file_name().unwrap().len(), 4 * BUFFER_SIZE)
+            }
+            command => panic!("Unexpected command {}", command.name()),
+        }
+        assert_eq!(request.output, OutputFormat::Json);
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn truncated_response() -> anyhow::Result<()> {
+        // a chunk without the final empty chunk
+        let mut truncated = vec![];
+        truncated.extend_from_slice(&3u32.to_be_bytes());
+        truncated.extend_from_slice(&[0, 1, 2]);
+
+        assert!(read_response(&mut truncated.as_slice()).await.is_err());
+        Ok(())
     }
 }
@@ -125,15 +125,9 @@ where
     if let Some(ext) = path.as_ref().extension().and_then(|ext| ext.to_str()) {
         if ext == "gz" {
             // gzip compressed json
-            if let Some((stem, ext)) = file_stem.as_ref().map(|file| {
-                let mut parts = file.split('.');
-                (parts.next().unwrap(), parts.next().unwrap())
-            }) {
-                if ext != "json" {
-                    return false;
-                }
-
-                file_stem = Some(stem);
+            match file_stem.and_then(|file| file.split_once('.')) {
+                Some((stem, "json")) => file_stem = Some(stem),
+                _ => return false,
             }
         } else if ext != "json" {
             // uncompressed
@@ -253,6 +247,11 @@ mod tests {
             "mainnet-3Nabcdef12345678901234567890123456789012345678901234.json"
         ));
 
+        // bin_prot encoded
+        assert!(is_valid_block_file(
+            "mainnet-42-3Nabcdef12345678901234567890123456789012345678901234.bin"
+        ));
+
         ///////////////////
         // Invalid cases //
         ///////////////////
@@ -272,10 +271,24 @@ mod tests {
         assert!(!is_valid_block_file(
             "mainnet-42-3Nabcdef12345678901234567890123456789012345678901234.txt"
         ));
+        assert!(!is_valid_block_file(
+            "mainnet-42-3Nabcdef12345678901234567890123456789012345678901234.bin.gz"
+        ));
 
         // Too many parts
         assert!(!is_valid_block_file(
             "mainnet-42-3Nabcdef12345678901234567890123456789012345678901234-123.json"
         ));
+
+        // gzip compressed, but not json
+        assert!(is_valid_block_file(
+            "mainnet-42-3Nabcdef12345678901234567890123456789012345678901234.json.gz"
+        ));
+        assert!(!is_valid_block_file(
+            "mainnet-42-3Nabcdef12345678901234567890123456789012345678901234.gz"
+        ));
+        assert!(!is_valid_block_file(
+            "mainnet-42-3Nabcdef12345678901234567890123456789012345678901234.txt.gz"
+        ));
     }
 }
@@ -0,0 +1,43 @@
+//! Address book store helpers
+
+use crate::{address_book::Category, base::public_key::PublicKey};
+
+/// Key format for sorting labels by category
+/// ```
+/// {category}{pk}
+/// where
+/// - category: 1 byte
+/// - pk:       [PublicKey::LEN] bytes
+pub fn label_category_key(category: Category, pk: &PublicKey) -> Vec<u8> {
+    let mut key = vec![category.to_byte()];
+    key.extend_from_slice(pk.0.as_bytes());
+    key
+}
+
+/// Split [label_category_key] into category & public key
+pub fn split_label_category_key(key: &[u8]) -> anyhow::Result<(Category, PublicKey)> {
+    if key.len() != 1 + PublicKey::LEN {
+        anyhow::bail!("Invalid label category key length: {}", key.len());
+    }
+
+    Ok((
+        Category::from_byte(key[0])?,
+        PublicKey::from_bytes(&key[1..])?,
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn label_category_key_roundtrip() -> anyhow::Result<()> {
+        let pk = PublicKey::default();
+        let key = label_category_key(Category::Exchange, &pk);
+
+        assert_eq!(key[0], Category::Exchange.to_byte());
+        assert_eq!(&key[1..], pk.0.as_bytes());
+        assert_eq!(split_label_category_key(&key)?, (Category::Exchange, pk));
+        Ok(())
+    }
+}
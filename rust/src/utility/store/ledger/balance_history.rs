@@ -0,0 +1,61 @@
+use crate::{
+    base::public_key::PublicKey, ledger::token::TokenAddress, utility::store::common::U32_LEN,
+};
+
+/// Key format for storing account balance history
+/// ```
+/// {pk}{token}{height}
+/// where
+/// - pk:     [PublicKey::LEN] bytes
+/// - token:  [TokenAddress::LEN] bytes
+/// - height: [u32] BE bytes
+pub fn balance_history_key(
+    pk: &PublicKey,
+    token: &TokenAddress,
+    block_height: u32,
+) -> [u8; PublicKey::LEN + TokenAddress::LEN + U32_LEN] {
+    let mut key = [0; PublicKey::LEN + TokenAddress::LEN + U32_LEN];
+
+    key[..PublicKey::LEN].copy_from_slice(pk.0.as_bytes());
+    key[PublicKey::LEN..][..TokenAddress::LEN].copy_from_slice(token.0.as_bytes());
+    key[PublicKey::LEN..][TokenAddress::LEN..].copy_from_slice(&block_height.to_be_bytes());
+
+    key
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn balance_history_key_content() {
+        let pk = PublicKey::default();
+        let token = TokenAddress::default();
+        let block_height = 100;
+
+        let key = balance_history_key(&pk, &token, block_height);
+
+        // first chunk of bytes match the public key
+        assert_eq!(&key[..PublicKey::LEN], pk.0.as_bytes());
+
+        // second chunk of bytes match the token
+        assert_eq!(
+            &key[PublicKey::LEN..][..TokenAddress::LEN],
+            token.0.as_bytes()
+        );
+
+        // remaining bytes match the block height
+        assert_eq!(
+            &key[PublicKey::LEN..][TokenAddress::LEN..],
+            &block_height.to_be_bytes()
+        );
+    }
+
+    #[test]
+    fn balance_history_key_height_order() {
+        let pk = PublicKey::default();
+        let token = TokenAddress::default();
+
+        assert!(balance_history_key(&pk, &token, 255) < balance_history_key(&pk, &token, 256));
+    }
+}
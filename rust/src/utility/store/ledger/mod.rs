@@ -1,3 +1,4 @@
+pub mod balance_history;
 pub mod best;
 pub mod staged;
 pub mod staking;
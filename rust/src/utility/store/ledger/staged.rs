@@ -25,6 +25,20 @@ pub fn staged_account_key(
     key
 }
 
+/// Split [staged_account_key] into constituent parts
+pub fn split_staged_account_key(key: &[u8]) -> Option<(StateHash, TokenAddress, PublicKey)> {
+    if key.len() == StateHash::LEN + TokenAddress::LEN + PublicKey::LEN {
+        let state_hash = StateHash::from_bytes(&key[..StateHash::LEN]).expect("block hash");
+        let token = TokenAddress::from_bytes(key[StateHash::LEN..][..TokenAddress::LEN].to_vec())
+            .expect("token address");
+        let pk = pk_key_prefix(&key[StateHash::LEN..][TokenAddress::LEN..]);
+
+        return Some((state_hash, token, pk));
+    }
+
+    None
+}
+
 /// Key format for sorting staged ledger accounts by balance
 /// ```
 /// {state_hash}{token}{balance}{pk}
@@ -106,6 +120,22 @@ mod tests {
         assert_eq!(&key[StateHash::LEN..][TokenAddress::LEN..], pk.0.as_bytes());
     }
 
+    #[test]
+    fn test_split_staged_account_key() {
+        let state_hash = StateHash::default();
+        let token = TokenAddress::default();
+        let pk = PublicKey::default();
+
+        let key = staged_account_key(&state_hash, &token, &pk);
+        assert_eq!(
+            split_staged_account_key(&key),
+            Some((state_hash, token, pk))
+        );
+
+        // wrong length
+        assert_eq!(split_staged_account_key(&key[1..]), None);
+    }
+
     #[test]
     fn test_staged_account_balance_sort_key_length() -> anyhow::Result<()> {
         let state_hash = StateHash::default();
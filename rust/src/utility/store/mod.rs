@@ -1,11 +1,13 @@
 //! User command store helpers
 
+pub mod address_book;
 pub mod block;
 pub mod command;
 pub mod common;
 pub mod ledger;
 pub mod snarks;
 pub mod username;
+pub mod webhook;
 pub mod zkapp;
 
 #[cfg(test)]
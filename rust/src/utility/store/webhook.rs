@@ -0,0 +1,58 @@
+//! Webhook store helpers
+
+use crate::{
+    base::public_key::PublicKey,
+    store::{fixed_keys::FixedKeys, IndexerStore},
+};
+
+/// Key format for storing webhook watches
+/// ```
+/// {pk}{url}
+/// where
+/// - pk:  [PublicKey::LEN] bytes
+/// - url: [String] bytes
+pub fn webhook_key(pk: &PublicKey, url: &str) -> Vec<u8> {
+    let mut key = pk.0.as_bytes().to_vec();
+    key.extend_from_slice(url.as_bytes());
+    key
+}
+
+/// Key format for storing per url webhook cursors
+/// ```
+/// {prefix}{url}
+/// where
+/// - prefix: [FixedKeys::WEBHOOK_CURSOR_KEY] bytes
+/// - url:    [String] bytes
+pub fn webhook_cursor_key(url: &str) -> Vec<u8> {
+    let mut key = IndexerStore::WEBHOOK_CURSOR_KEY.to_vec();
+    key.extend_from_slice(url.as_bytes());
+    key
+}
+
+/// Split [webhook_key] into public key & url
+pub fn split_webhook_key(key: &[u8]) -> anyhow::Result<(PublicKey, String)> {
+    if key.len() <= PublicKey::LEN {
+        anyhow::bail!("Invalid webhook key length: {}", key.len());
+    }
+
+    let pk = PublicKey::from_bytes(&key[..PublicKey::LEN])?;
+    let url = String::from_utf8(key[PublicKey::LEN..].to_vec())?;
+    Ok((pk, url))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn webhook_key_roundtrip() -> anyhow::Result<()> {
+        let pk = PublicKey::default();
+        let url = "http://localhost:8080/hook";
+        let key = webhook_key(&pk, url);
+
+        assert_eq!(&key[..PublicKey::LEN], pk.0.as_bytes());
+        assert_eq!(&key[PublicKey::LEN..], url.as_bytes());
+        assert_eq!(split_webhook_key(&key)?, (pk, url.to_string()));
+        Ok(())
+    }
+}
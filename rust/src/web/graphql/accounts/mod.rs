@@ -3,26 +3,32 @@
 mod zkapp;
 
 use super::{
+    address_book::LabelCategory,
+    connection::{Page, SortConnection},
     db,
     pk::{DelegatePK, PK},
 };
 use crate::{
+    address_book::store::AddressBookStore,
     base::public_key::PublicKey,
     block::store::BlockStore,
+    canonicity::store::CanonicityStore,
     command::{internal::store::InternalCommandStore, store::UserCommandStore},
     constants::MINA_TOKEN_ADDRESS,
     ledger::{
         account::{self, Permission},
-        store::best::BestLedgerStore,
+        store::{
+            balance_history::BalanceHistoryStore, best::BestLedgerStore, staged::StagedLedgerStore,
+        },
         token::TokenAddress,
     },
     snark_work::store::SnarkStore,
-    store::{username::UsernameStore, IndexerStore},
+    store::IndexerStore,
     utility::store::common::U64_LEN,
     web::graphql::timing::Timing,
 };
 use async_graphql::{Context, Enum, InputObject, Object, Result, SimpleObject};
-use speedb::IteratorMode;
+use speedb::{Direction, IteratorMode};
 use zkapp::ZkappAccount;
 
 #[derive(InputObject)]
@@ -34,6 +40,9 @@ pub struct AccountQueryInput {
     token: Option<String>,
     zkapp: Option<bool>,
 
+    /// Input label category, e.g. exchange-labeled accounts
+    category: Option<LabelCategory>,
+
     #[graphql(name = "balance_gt")]
     balance_gt: Option<u64>,
 
@@ -192,11 +201,86 @@ pub struct AccountWithMeta {
     username: String,
 }
 
+#[derive(SimpleObject)]
+pub struct AccountBalanceHistory {
+    #[graphql(name = "state_hash")]
+    state_hash: String,
+
+    #[graphql(name = "block_height")]
+    block_height: u32,
+
+    /// Value balance after the block (nanomina)
+    balance: u64,
+}
+
 #[derive(Default)]
 pub struct AccountQueryRoot;
 
 #[Object]
 impl AccountQueryRoot {
+    /// Account at the canonical block height, defaults to the best ledger
+    #[graphql(cache_control(max_age = 3600))]
+    async fn account(
+        &self,
+        ctx: &Context<'_>,
+        public_key: String,
+        block_height: Option<u32>,
+        token: Option<String>,
+    ) -> Result<Option<AccountWithMeta>> {
+        let db = db(ctx);
+
+        // validate public key & token
+        let pk = match PublicKey::new(&public_key) {
+            Ok(pk) => pk,
+            Err(_) => {
+                return Err(async_graphql::Error::new(format!(
+                    "Invalid public key: {}",
+                    public_key
+                )))
+            }
+        };
+        let token = match token {
+            Some(token) => match TokenAddress::new(&token) {
+                Some(token) => token,
+                None => {
+                    return Err(async_graphql::Error::new(format!(
+                        "Invalid token address: {}",
+                        token
+                    )))
+                }
+            },
+            None => TokenAddress::default(),
+        };
+
+        // best ledger account
+        let block_height = match block_height {
+            Some(block_height) => block_height,
+            None => {
+                return Ok(db
+                    .get_best_account_display(&pk, &token)?
+                    .map(|account| AccountWithMeta::new(db, account)))
+            }
+        };
+
+        // staged ledger account at the canonical block
+        let state_hash = match db.get_canonical_hash_at_height(block_height)? {
+            Some(state_hash) => state_hash,
+            None => {
+                return Err(async_graphql::Error::new(format!(
+                    "Missing canonical block at height {}",
+                    block_height
+                )))
+            }
+        };
+
+        Ok(db
+            .get_staged_account_display(&pk, &token, &state_hash)?
+            .map(|account| AccountWithMeta {
+                block_height,
+                ..AccountWithMeta::new(db, account)
+            }))
+    }
+
     #[graphql(cache_control(max_age = 3600))]
     async fn accounts(
         &self,
@@ -225,12 +309,9 @@ impl AccountQueryRoot {
                     .get_best_account_display(&pk, &token)?
                     .into_iter()
                     .filter_map(|acct| {
-                        let username = match db.get_username(&pk) {
-                            Ok(None) | Err(_) => None,
-                            Ok(Some(username)) => Some(username.0),
-                        };
+                        let names = PK::new(db, pk.clone());
 
-                        if query.as_ref().unwrap().matches(&acct, username.as_ref()) {
+                        if query.as_ref().unwrap().matches(&acct, &names) {
                             let account = AccountWithMeta::new(db, acct);
                             return Some(account);
                         }
@@ -275,15 +356,9 @@ impl AccountQueryRoot {
 
             let account = serde_json::from_slice::<account::Account>(&value)?
                 .deduct_mina_account_creation_fee();
-            let username = match db.get_username(&account.public_key) {
-                Ok(None) | Err(_) => None,
-                Ok(Some(username)) => Some(username.0),
-            };
+            let names = PK::new(db, account.public_key.clone());
 
-            if query
-                .as_ref()
-                .is_none_or(|q| q.matches(&account, username.as_ref()))
-            {
+            if query.as_ref().is_none_or(|q| q.matches(&account, &names)) {
                 let account_with_meta = AccountWithMeta::new(db, account);
                 accounts.push(account_with_meta);
             }
@@ -291,14 +366,154 @@ impl AccountQueryRoot {
 
         Ok(accounts)
     }
+
+    #[graphql(cache_control(max_age = 3600))]
+    async fn accounts_connection(
+        &self,
+        ctx: &Context<'_>,
+        query: Option<AccountQueryInput>,
+        first: Option<usize>,
+        after: Option<String>,
+        sort_by: Option<AccountSortByInput>,
+    ) -> Result<SortConnection<AccountWithMeta>> {
+        use AccountSortByInput::*;
+
+        let db = db(ctx);
+        let direction = match sort_by.unwrap_or_default() {
+            BalanceAsc => Direction::Forward,
+            BalanceDesc => Direction::Reverse,
+        };
+
+        // validate token
+        let token = match query.as_ref().and_then(|q| q.token.as_ref()) {
+            Some(token) => match TokenAddress::new(token) {
+                Some(token) => Some(token),
+                None => {
+                    return Err(async_graphql::Error::new(format!(
+                        "Invalid token address: {}",
+                        token
+                    )))
+                }
+            },
+            None => None,
+        };
+
+        // iterator mode
+        let mut start = [0u8; TokenAddress::LEN + U64_LEN + 1];
+        let mode = match token.as_ref() {
+            Some(token) => {
+                start[..TokenAddress::LEN].copy_from_slice(token.0.as_bytes());
+
+                if let Direction::Reverse = direction {
+                    // go beyond current token accounts
+                    start[TokenAddress::LEN..][..U64_LEN].copy_from_slice(&u64::MAX.to_be_bytes());
+                    start[TokenAddress::LEN..][U64_LEN..].copy_from_slice("Z".as_bytes());
+                }
+
+                IteratorMode::From(&start, direction)
+            }
+            None => match direction {
+                Direction::Forward => IteratorMode::Start,
+                Direction::Reverse => IteratorMode::End,
+            },
+        };
+
+        let (page, mut iter, total_count) = match query.as_ref().and_then(|q| q.zkapp) {
+            None | Some(false) => (
+                Page::new("best-ledger-account-balance-sort", direction, after, first)?,
+                db.best_ledger_account_balance_iterator(mode),
+                db.get_num_accounts()?,
+            ),
+            Some(true) => (
+                Page::new(
+                    "zkapp-best-ledger-account-balance-sort",
+                    direction,
+                    after,
+                    first,
+                )?,
+                db.zkapp_best_ledger_account_balance_iterator(mode),
+                db.get_num_zkapp_accounts()?,
+            ),
+        };
+        page.seek(&mut iter);
+
+        // token accounts are prefixed by the token
+        let prefix = token.as_ref().map(|t| t.0.as_bytes()).unwrap_or_default();
+        let entries = iter
+            .flatten()
+            .take_while(|(key, _)| key.starts_with(prefix));
+
+        Ok(
+            page.collect(entries, total_count.unwrap_or_default(), |_, value| {
+                let account = serde_json::from_slice::<account::Account>(value)?
+                    .deduct_mina_account_creation_fee();
+                let names = PK::new(db, account.public_key.clone());
+
+                if query.as_ref().is_none_or(|q| q.matches(&account, &names)) {
+                    return Ok(vec![AccountWithMeta::new(db, account)]);
+                }
+
+                Ok(vec![])
+            })?,
+        )
+    }
+
+    /// Account balance after each best chain block which changed it, between
+    /// the (inclusive) heights
+    #[graphql(cache_control(max_age = 3600))]
+    async fn account_balance_history(
+        &self,
+        ctx: &Context<'_>,
+        public_key: String,
+        token: Option<String>,
+        from_height: Option<u32>,
+        to_height: Option<u32>,
+    ) -> Result<Vec<AccountBalanceHistory>> {
+        let db = db(ctx);
+
+        // validate public key & token
+        let pk = match PublicKey::new(&public_key) {
+            Ok(pk) => pk,
+            Err(_) => {
+                return Err(async_graphql::Error::new(format!(
+                    "Invalid public key: {}",
+                    public_key
+                )))
+            }
+        };
+        let token = match token {
+            Some(token) => match TokenAddress::new(&token) {
+                Some(token) => token,
+                None => {
+                    return Err(async_graphql::Error::new(format!(
+                        "Invalid token address: {}",
+                        token
+                    )))
+                }
+            },
+            None => TokenAddress::default(),
+        };
+
+        Ok(db
+            .get_balance_history(&pk, &token, from_height, to_height)?
+            .into_iter()
+            .map(|entry| AccountBalanceHistory {
+                state_hash: entry.state_hash.0,
+                block_height: entry.block_height,
+                balance: entry.balance,
+            })
+            .collect())
+    }
 }
 
 impl AccountQueryInput {
-    fn matches(&self, account: &account::Account, username: Option<&String>) -> bool {
+    /// `names` holds the account's resolved username & label category
+    fn matches(&self, account: &account::Account, names: &PK) -> bool {
         let AccountQueryInput {
             public_key,
             delegate,
             username: query_username_prefix,
+            category,
             balance,
             balance_gt,
             balance_gte,
@@ -322,7 +537,7 @@ impl AccountQueryInput {
         }
 
         if let Some(username_prefix) = query_username_prefix {
-            if username.is_none_or(|u| {
+            if names.username.as_ref().is_none_or(|u| {
                 !u.to_lowercase()
                     .starts_with(&username_prefix.to_lowercase())
             }) {
@@ -330,6 +545,12 @@ impl AccountQueryInput {
             }
         }
 
+        if let Some(category) = category {
+            if names.category != Some(*category) {
+                return false;
+            }
+        }
+
         if let Some(balance) = balance {
             if account.balance.0 != *balance {
                 return false;
@@ -438,13 +659,9 @@ impl AccountQueryInput {
             let account = serde_json::from_slice::<account::Account>(&value)?
                 .deduct_mina_account_creation_fee();
 
-            let pk = &account.public_key;
-            let username = match db.get_username(pk) {
-                Ok(None) | Err(_) => None,
-                Ok(Some(username)) => Some(username.0),
-            };
+            let names = PK::new(db, account.public_key.clone());
 
-            if self.matches(&account, username.as_ref()) {
+            if self.matches(&account, &names) {
                 let account_with_meta = AccountWithMeta::new(db, account);
                 accounts.push(account_with_meta);
             }
@@ -496,7 +713,11 @@ impl AccountWithMeta {
                 .get_best_block_height()
                 .unwrap()
                 .expect("best block height"),
-            username: db.get_username(pk).expect("username").unwrap_or_default().0,
+            username: db
+                .resolve_username(pk)
+                .expect("username")
+                .unwrap_or_default()
+                .0,
             account: Account::new(db, account),
         }
     }
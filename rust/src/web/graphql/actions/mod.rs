@@ -1,11 +1,39 @@
 //! GraphQL `getActions` endpoint
 
-use super::{date_time::DateTime, db};
+use super::{date_time::DateTime, date_time_to_scalar, db};
+use crate::{
+    base::{public_key::PublicKey, state_hash::StateHash},
+    block::store::BlockStore,
+    canonicity::{store::CanonicityStore, Canonicity},
+    command::{store::UserCommandStore, TxnHash},
+    ledger::{store::staged::StagedLedgerStore, token::TokenAddress},
+    store::{
+        zkapp::actions::{ZkappActionStore, ZkappActionWithMeta},
+        IndexerStore,
+    },
+};
 use async_graphql::{Context, Enum, InputObject, Object, Result, SimpleObject};
+use std::sync::Arc;
 
 #[derive(InputObject, Debug)]
 pub struct ActionsQueryInput {
+    /// Zkapp account public key, no actions are returned without it
     pub address: Option<String>,
+
+    /// Token address (defaults to MINA)
+    pub token: Option<String>,
+
+    /// Minimum block height (inclusive)
+    pub from_block_height: Option<u32>,
+
+    /// Maximum block height (inclusive)
+    pub to_block_height: Option<u32>,
+
+    /// Minimum action index (inclusive)
+    pub from_index: Option<u32>,
+
+    /// Maximum action index (inclusive)
+    pub to_index: Option<u32>,
 }
 
 #[derive(Default, Enum, Copy, Clone, Debug, Eq, PartialEq)]
@@ -20,6 +48,8 @@ pub enum ActionsSortByInput {
 
 #[derive(SimpleObject, Debug)]
 pub struct Action {
+    /// Index of the action in the token account's action sequence
+    pub index: u32,
     pub block_info: BlockInfo,
     pub action_state: ActionState,
     pub action_data: ActionData,
@@ -37,7 +67,9 @@ pub struct BlockInfo {
     pub global_slot_since_genesis: u32,
 }
 
-#[derive(SimpleObject, Debug)]
+/// The token account's action state after the action's block, empty if the
+/// block didn't record the account
+#[derive(Default, SimpleObject, Debug)]
 pub struct ActionState {
     pub action_state_one: String,
     pub action_state_two: String,
@@ -74,13 +106,214 @@ impl ActionsQueryRoot {
         #[graphql(default = 100)] limit: usize,
     ) -> Result<Option<Vec<Action>>> {
         let db = db(ctx);
+        let Some(query) = query else {
+            return Ok(None);
+        };
+        let Some((pk, token)) = query.token_account()? else {
+            return Ok(None);
+        };
+
+        let num_actions = db.get_num_actions(&pk, &token)?.unwrap_or_default();
+        let (start, end) = match index_range(query.from_index, query.to_index, num_actions) {
+            Some(range) => range,
+            None => return Ok(Some(vec![])),
+        };
+
+        let indices: Box<dyn Iterator<Item = u32>> = match sort_by.unwrap_or_default() {
+            ActionsSortByInput::BlockHeightAsc => Box::new(start..=end),
+            ActionsSortByInput::BlockHeightDesc => Box::new((start..=end).rev()),
+        };
+
+        let mut actions = Vec::with_capacity(limit.min(num_actions as usize));
+        for index in indices {
+            if actions.len() >= limit {
+                break;
+            }
+
+            if let Some(action) = db.get_action(&pk, &token, index)? {
+                if !in_block_height_range(
+                    action.block_height,
+                    query.from_block_height,
+                    query.to_block_height,
+                ) {
+                    continue;
+                }
+
+                actions.push(Action::new(db, &pk, &token, index, action)?);
+            }
+        }
+
+        Ok(Some(actions))
+    }
+}
+
+impl ActionsQueryInput {
+    /// Validate the query's zkapp token account, if an address is given
+    fn token_account(&self) -> Result<Option<(PublicKey, TokenAddress)>> {
+        self.address
+            .as_deref()
+            .map(|address| token_account(address, self.token.as_deref()))
+            .transpose()
+    }
+}
+
+impl Action {
+    fn new(
+        db: &Arc<IndexerStore>,
+        pk: &PublicKey,
+        token: &TokenAddress,
+        index: u32,
+        action: ZkappActionWithMeta,
+    ) -> Result<Self> {
+        Ok(Self {
+            index,
+            block_info: BlockInfo::new(db, &action.state_hash, action.block_height)?,
+            action_state: ActionState::new(db, pk, token, &action.state_hash)?,
+            action_data: ActionData {
+                data: action.action.0,
+                transaction_info: TxnInfo::new(db, &action.txn_hash, &action.state_hash)?,
+            },
+        })
+    }
+}
+
+impl ActionState {
+    /// Read from the block's accessed accounts
+    fn new(
+        db: &Arc<IndexerStore>,
+        pk: &PublicKey,
+        token: &TokenAddress,
+        state_hash: &StateHash,
+    ) -> Result<Self> {
+        let zkapp = db.get_block(state_hash)?.and_then(|(block, _)| {
+            block
+                .accounts_accessed()
+                .into_iter()
+                .map(|accessed| accessed.account)
+                .find(|account| account.public_key == *pk && account.token.as_ref() == Some(token))
+                .and_then(|account| account.zkapp)
+        });
+
+        Ok(match zkapp {
+            Some(zkapp) => {
+                let [one, two, three, four, five] = zkapp.action_state.map(|state| state.0);
+                Self {
+                    action_state_one: one,
+                    action_state_two: two,
+                    action_state_three: three,
+                    action_state_four: four,
+                    action_state_five: five,
+                }
+            }
+            None => Self::default(),
+        })
+    }
+}
+
+impl BlockInfo {
+    pub(crate) fn new(
+        db: &Arc<IndexerStore>,
+        state_hash: &StateHash,
+        block_height: u32,
+    ) -> Result<Self> {
+        let best_block_height = db.get_best_block_height()?.unwrap_or_default();
+        let chain_status = match db.get_block_canonicity(state_hash)? {
+            Some(Canonicity::Canonical) => "canonical",
+            Some(Canonicity::Orphaned) => "orphaned",
+            Some(Canonicity::Pending) | None => "pending",
+        };
+
+        Ok(Self {
+            state_hash: state_hash.0.to_owned(),
+            timestamp: date_time_to_scalar(db.get_block_date_time(state_hash)?.unwrap_or_default()),
+            ledger_hash: db
+                .get_block_staged_ledger_hash(state_hash)?
+                .map(|hash| hash.0)
+                .unwrap_or_default(),
+            height: block_height,
+            parent_hash: db
+                .get_block_parent_hash(state_hash)?
+                .map(|hash| hash.0)
+                .unwrap_or_default(),
+            chain_status: chain_status.to_string(),
+            distance_from_max_block_height: best_block_height.saturating_sub(block_height),
+            global_slot_since_genesis: db.get_block_global_slot(state_hash)?.unwrap_or_default(),
+        })
+    }
+}
+
+impl TxnInfo {
+    /// Only applied zkapp commands produce actions & events
+    pub(crate) fn new(
+        db: &Arc<IndexerStore>,
+        txn_hash: &TxnHash,
+        state_hash: &StateHash,
+    ) -> Result<Self> {
+        let memo = db
+            .get_user_command_state_hash(txn_hash, state_hash)?
+            .map(|cmd| cmd.command.memo())
+            .unwrap_or_default();
+
+        Ok(Self {
+            status: "applied".to_string(),
+            hash: txn_hash.to_string(),
+            memo,
+        })
+    }
+}
+
+/// Validate the zkapp token account public key & token address
+pub(crate) fn token_account(
+    address: &str,
+    token: Option<&str>,
+) -> Result<(PublicKey, TokenAddress)> {
+    let pk = PublicKey::new(address)
+        .map_err(|_| async_graphql::Error::new(format!("Invalid public key: {}", address)))?;
+
+    let token = match token {
+        Some(token) => TokenAddress::new(token).ok_or_else(|| {
+            async_graphql::Error::new(format!("Invalid token address: {}", token))
+        })?,
+        None => TokenAddress::default(),
+    };
+
+    Ok((pk, token))
+}
+
+/// Inclusive index range, clamped to the number of stored items
+pub(crate) fn index_range(from: Option<u32>, to: Option<u32>, num: u32) -> Option<(u32, u32)> {
+    if num == 0 {
+        return None;
+    }
+
+    let start = from.unwrap_or_default();
+    let end = to.unwrap_or(u32::MAX).min(num - 1);
+
+    (start <= end).then_some((start, end))
+}
+
+pub(crate) fn in_block_height_range(height: u32, from: Option<u32>, to: Option<u32>) -> bool {
+    from.is_none_or(|from| from <= height) && to.is_none_or(|to| height <= to)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn index_range_clamped() {
+        assert_eq!(index_range(None, None, 0), None);
+        assert_eq!(index_range(None, None, 5), Some((0, 4)));
+        assert_eq!(index_range(Some(2), Some(10), 5), Some((2, 4)));
+        assert_eq!(index_range(Some(5), None, 5), None);
+        assert_eq!(index_range(Some(3), Some(1), 5), None);
+    }
 
-        todo!(
-            "getActions:\n  db:      {:?}\n  query:   {:?}\n  sort_by: {:?}\n  limit:   {:?}",
-            db,
-            query,
-            sort_by,
-            limit
-        )
+    #[test]
+    fn block_height_range() {
+        assert!(in_block_height_range(10, None, None));
+        assert!(in_block_height_range(10, Some(10), Some(10)));
+        assert!(!in_block_height_range(9, Some(10), None));
+        assert!(!in_block_height_range(11, None, Some(10)));
     }
 }
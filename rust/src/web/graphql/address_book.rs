@@ -0,0 +1,92 @@
+//! GraphQL `addressBook` endpoint
+
+use super::db;
+use crate::address_book::{self, store::AddressBookStore};
+use async_graphql::{Context, Enum, Object, Result, SimpleObject};
+use serde::Serialize;
+
+#[derive(Enum, Copy, Clone, Debug, Eq, PartialEq, Serialize)]
+pub enum LabelCategory {
+    Exchange,
+    Foundation,
+    Pool,
+    Other,
+}
+
+#[derive(SimpleObject)]
+pub struct AddressLabel {
+    /// Value labeled public key
+    public_key: String,
+
+    /// Value label
+    label: String,
+
+    /// Value label category
+    category: LabelCategory,
+
+    /// Value label attribution
+    source: String,
+}
+
+#[derive(Default)]
+pub struct AddressBookQueryRoot;
+
+#[Object]
+impl AddressBookQueryRoot {
+    /// Public key labels, optionally only the category's
+    #[graphql(cache_control(max_age = 60))]
+    async fn address_book(
+        &self,
+        ctx: &Context<'_>,
+        category: Option<LabelCategory>,
+        #[graphql(default = 100)] limit: usize,
+    ) -> Result<Vec<AddressLabel>> {
+        let db = db(ctx);
+
+        Ok(db
+            .get_labels(category.map(Into::into))?
+            .into_iter()
+            .take(limit)
+            .map(Into::into)
+            .collect())
+    }
+}
+
+/////////////////
+// conversions //
+/////////////////
+
+impl From<address_book::Category> for LabelCategory {
+    fn from(value: address_book::Category) -> Self {
+        use address_book::Category::*;
+
+        match value {
+            Exchange => Self::Exchange,
+            Foundation => Self::Foundation,
+            Pool => Self::Pool,
+            Other => Self::Other,
+        }
+    }
+}
+
+impl From<LabelCategory> for address_book::Category {
+    fn from(value: LabelCategory) -> Self {
+        match value {
+            LabelCategory::Exchange => Self::Exchange,
+            LabelCategory::Foundation => Self::Foundation,
+            LabelCategory::Pool => Self::Pool,
+            LabelCategory::Other => Self::Other,
+        }
+    }
+}
+
+impl From<address_book::Label> for AddressLabel {
+    fn from(value: address_book::Label) -> Self {
+        Self {
+            public_key: value.public_key.0,
+            label: value.label,
+            category: value.category.into(),
+            source: value.source,
+        }
+    }
+}
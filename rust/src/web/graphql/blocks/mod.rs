@@ -15,6 +15,7 @@ use crate::{
     web::{
         common::unique_block_producers_last_n_blocks,
         graphql::{
+            connection::{Page, SortConnection},
             gen::{BlockProtocolStateConsensusStateQueryInput, BlockQueryInput},
             get_block,
         },
@@ -512,6 +513,67 @@ impl BlocksQueryRoot {
 
         Ok(blocks)
     }
+
+    #[graphql(cache_control(max_age = 3600))]
+    async fn blocks_connection(
+        &self,
+        ctx: &Context<'_>,
+        query: Option<BlockQueryInput>,
+        first: Option<usize>,
+        after: Option<String>,
+        sort_by: Option<BlockSortByInput>,
+    ) -> Result<SortConnection<Block>> {
+        use speedb::{Direction::*, IteratorMode::*};
+        use BlockSortByInput::*;
+        let db = db(ctx);
+
+        let epoch = query.as_ref().and_then(|q| {
+            q.protocol_state
+                .as_ref()
+                .and_then(|ps| ps.consensus_state.as_ref().and_then(|cs| cs.epoch))
+        });
+        let genesis_state_hash = query
+            .as_ref()
+            .and_then(|q| q.genesis_state_hash.clone())
+            .map(Into::into);
+        let counts = get_counts(db, epoch, genesis_state_hash.as_ref())?;
+
+        let (page, mut iter) = match sort_by.unwrap_or(BlockHeightDesc) {
+            BlockHeightAsc => (
+                Page::new("blocks-height-sort", Forward, after, first)?,
+                db.blocks_height_iterator(Start),
+            ),
+            BlockHeightDesc => (
+                Page::new("blocks-height-sort", Reverse, after, first)?,
+                db.blocks_height_iterator(End),
+            ),
+            GlobalSlotAsc => (
+                Page::new("blocks-global-slot-sort", Forward, after, first)?,
+                db.blocks_global_slot_iterator(Start),
+            ),
+            GlobalSlotDesc => (
+                Page::new("blocks-global-slot-sort", Reverse, after, first)?,
+                db.blocks_global_slot_iterator(End),
+            ),
+        };
+        page.seek(&mut iter);
+
+        // total number of blocks
+        Ok(page.collect(iter.flatten(), counts[3], |key, _| {
+            // avoid deserializing PCB if possible
+            let state_hash = state_hash_suffix(key)?;
+            if let Some(query_canonicity) = query.as_ref().and_then(|q| q.canonical) {
+                if get_block_canonicity(db, &state_hash) != query_canonicity {
+                    return Ok(vec![]);
+                }
+            }
+
+            let pcb = get_block(db, &state_hash);
+            Ok(precomputed_matches_query(db, &query, &pcb, counts)
+                .into_iter()
+                .collect())
+        })?)
+    }
 }
 
 impl BlockQueryInput {
@@ -0,0 +1,276 @@
+//! Relay-style cursor connections over sort column families
+//!
+//! A cursor is the sort column family's name, the key of the entry the node
+//! came from & the number of nodes of that entry already returned. Resuming
+//! seeks straight to the key, so every page costs the same regardless of its
+//! depth.
+
+use async_graphql::{
+    connection::{Connection, CursorType, Edge},
+    SimpleObject,
+};
+use base64::{prelude::BASE64_URL_SAFE_NO_PAD, Engine};
+use speedb::{DBIterator, Direction, IteratorMode};
+
+pub const DEFAULT_PAGE_SIZE: usize = 100;
+pub const MAX_PAGE_SIZE: usize = 1000;
+
+/// Opaque position in a sort column family
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SortCursor {
+    /// Sort column family name
+    pub sort: String,
+
+    /// Key of the entry
+    pub key: Vec<u8>,
+
+    /// Number of the entry's nodes before the cursor
+    pub index: u32,
+}
+
+#[derive(Debug, Clone, Copy, SimpleObject)]
+pub struct ConnectionFields {
+    /// Total number of sorted nodes, ignoring query filters
+    pub total_count: u32,
+}
+
+pub type SortConnection<T> = Connection<SortCursor, T, ConnectionFields>;
+
+/// A page of a sort column family
+#[derive(Debug)]
+pub struct Page {
+    sort: &'static str,
+    direction: Direction,
+    after: Option<SortCursor>,
+    first: usize,
+}
+
+//////////
+// impl //
+//////////
+
+impl Page {
+    /// Checks the `after` cursor belongs to `sort` & bounds the page size
+    pub fn new(
+        sort: &'static str,
+        direction: Direction,
+        after: Option<String>,
+        first: Option<usize>,
+    ) -> anyhow::Result<Self> {
+        let after = after
+            .map(|cursor| SortCursor::decode_cursor(&cursor))
+            .transpose()?;
+
+        if let Some(cursor) = after.as_ref() {
+            if cursor.sort != sort {
+                anyhow::bail!("Cursor is for {}, not {}", cursor.sort, sort)
+            }
+        }
+
+        Ok(Self {
+            sort,
+            direction,
+            after,
+            first: first.unwrap_or(DEFAULT_PAGE_SIZE).min(MAX_PAGE_SIZE),
+        })
+    }
+
+    /// Moves `iter` to the `after` cursor's entry, if there is one
+    pub fn seek(&self, iter: &mut DBIterator<'_>) {
+        if let Some(cursor) = self.after.as_ref() {
+            iter.set_mode(IteratorMode::From(&cursor.key, self.direction));
+        }
+    }
+
+    /// Collects the page's edges from the sorted `entries`
+    ///
+    /// `nodes` maps an entry to its nodes which match the query
+    pub fn collect<T, K, V, F>(
+        &self,
+        entries: impl IntoIterator<Item = (K, V)>,
+        total_count: u32,
+        mut nodes: F,
+    ) -> anyhow::Result<SortConnection<T>>
+    where
+        T: async_graphql::OutputType,
+        K: AsRef<[u8]>,
+        V: AsRef<[u8]>,
+        F: FnMut(&[u8], &[u8]) -> anyhow::Result<Vec<T>>,
+    {
+        let mut edges = Vec::with_capacity(self.first);
+        let mut has_next_page = false;
+
+        'entries: for (key, value) in entries {
+            let key = key.as_ref();
+
+            // skip the nodes returned before the cursor
+            let skip = match self.after.as_ref() {
+                Some(cursor) if cursor.key == key => cursor.index as usize,
+                _ => 0,
+            };
+
+            for (index, node) in nodes(key, value.as_ref())?
+                .into_iter()
+                .enumerate()
+                .skip(skip)
+            {
+                if edges.len() >= self.first {
+                    has_next_page = true;
+                    break 'entries;
+                }
+
+                let cursor = SortCursor {
+                    sort: self.sort.to_string(),
+                    key: key.to_vec(),
+                    index: index as u32 + 1,
+                };
+                edges.push(Edge::new(cursor, node));
+            }
+        }
+
+        let mut connection = Connection::with_additional_fields(
+            self.after.is_some(),
+            has_next_page,
+            ConnectionFields { total_count },
+        );
+        connection.edges = edges;
+
+        Ok(connection)
+    }
+}
+
+impl CursorType for SortCursor {
+    type Error = anyhow::Error;
+
+    /// Decodes `{sort length}{sort}{index BE}{key}`
+    fn decode_cursor(s: &str) -> Result<Self, Self::Error> {
+        use anyhow::Context;
+
+        let bytes = BASE64_URL_SAFE_NO_PAD
+            .decode(s)
+            .with_context(|| format!("Invalid cursor: {s}"))?;
+        let (&sort_len, bytes) = bytes
+            .split_first()
+            .with_context(|| format!("Invalid cursor: {s}"))?;
+
+        let sort_len = sort_len as usize;
+        if bytes.len() < sort_len + 4 {
+            anyhow::bail!("Invalid cursor: {s}")
+        }
+
+        let (sort, bytes) = bytes.split_at(sort_len);
+        let (index, key) = bytes.split_at(4);
+
+        Ok(Self {
+            sort: String::from_utf8(sort.to_vec())
+                .with_context(|| format!("Invalid cursor: {s}"))?,
+            key: key.to_vec(),
+            index: u32::from_be_bytes(index.try_into()?),
+        })
+    }
+
+    fn encode_cursor(&self) -> String {
+        let mut bytes = Vec::with_capacity(1 + self.sort.len() + 4 + self.key.len());
+
+        bytes.push(self.sort.len() as u8);
+        bytes.extend_from_slice(self.sort.as_bytes());
+        bytes.extend_from_slice(&self.index.to_be_bytes());
+        bytes.extend_from_slice(&self.key);
+
+        BASE64_URL_SAFE_NO_PAD.encode(bytes)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SORT: &str = "blocks-height-sort";
+
+    fn entries() -> Vec<(Vec<u8>, Vec<u8>)> {
+        (0u32..10)
+            .map(|n| (n.to_be_bytes().to_vec(), vec![]))
+            .collect()
+    }
+
+    /// Entry `n` has nodes `10n` & `10n + 1`, odd entries are filtered out
+    fn nodes(key: &[u8], _: &[u8]) -> anyhow::Result<Vec<u32>> {
+        let n = u32::from_be_bytes(key.try_into()?);
+        Ok(if n % 2 == 0 {
+            vec![10 * n, 10 * n + 1]
+        } else {
+            vec![]
+        })
+    }
+
+    fn page_nodes(connection: &SortConnection<u32>) -> Vec<u32> {
+        connection.edges.iter().map(|edge| edge.node).collect()
+    }
+
+    #[test]
+    fn cursor_roundtrip() -> anyhow::Result<()> {
+        let cursor = SortCursor {
+            sort: SORT.to_string(),
+            key: vec![0, 1, 2, 255],
+            index: 3,
+        };
+        assert_eq!(SortCursor::decode_cursor(&cursor.encode_cursor())?, cursor);
+
+        assert!(SortCursor::decode_cursor("not a cursor").is_err());
+        assert!(SortCursor::decode_cursor("").is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn pages_resume() -> anyhow::Result<()> {
+        let page = Page::new(SORT, Direction::Forward, None, Some(3))?;
+        let first = page.collect(entries(), 10, nodes)?;
+
+        assert_eq!(page_nodes(&first), vec![0, 1, 20]);
+        assert!(first.has_next_page);
+        assert!(!first.has_previous_page);
+        assert_eq!(first.additional_fields.total_count, 10);
+
+        // resume mid-entry
+        let after = first.edges.last().unwrap().cursor.encode_cursor();
+        let page = Page::new(SORT, Direction::Forward, Some(after), Some(3))?;
+        let second = page.collect(entries(), 10, nodes)?;
+
+        assert_eq!(page_nodes(&second), vec![21, 40, 41]);
+        assert!(second.has_next_page);
+        assert!(second.has_previous_page);
+
+        // last page
+        let after = second.edges.last().unwrap().cursor.encode_cursor();
+        let page = Page::new(SORT, Direction::Forward, Some(after), Some(10))?;
+        let last = page.collect(entries(), 10, nodes)?;
+
+        assert_eq!(page_nodes(&last), vec![60, 61, 80, 81]);
+        assert!(!last.has_next_page);
+        Ok(())
+    }
+
+    #[test]
+    fn foreign_cursor() {
+        let cursor = SortCursor {
+            sort: "blocks-global-slot-sort".to_string(),
+            key: vec![],
+            index: 0,
+        };
+
+        assert!(Page::new(SORT, Direction::Forward, Some(cursor.encode_cursor()), None).is_err());
+    }
+
+    #[test]
+    fn page_size() -> anyhow::Result<()> {
+        assert_eq!(
+            Page::new(SORT, Direction::Reverse, None, None)?.first,
+            DEFAULT_PAGE_SIZE
+        );
+        assert_eq!(
+            Page::new(SORT, Direction::Reverse, None, Some(usize::MAX))?.first,
+            MAX_PAGE_SIZE
+        );
+        Ok(())
+    }
+}
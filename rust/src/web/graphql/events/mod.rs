@@ -1,11 +1,39 @@
 //! GraphQL `getEvents` endpoint
 
-use super::{date_time::DateTime, db};
+use super::{
+    actions::{in_block_height_range, index_range, token_account, BlockInfo, TxnInfo},
+    db,
+};
+use crate::{
+    base::public_key::PublicKey,
+    ledger::token::TokenAddress,
+    store::{
+        zkapp::events::{ZkappEventStore, ZkappEventWithMeta},
+        IndexerStore,
+    },
+};
 use async_graphql::{Context, Enum, InputObject, Object, Result, SimpleObject};
+use std::sync::Arc;
 
 #[derive(InputObject, Debug)]
 pub struct EventsQueryInput {
+    /// Zkapp account public key, no events are returned without it
     pub address: Option<String>,
+
+    /// Token address (defaults to MINA)
+    pub token: Option<String>,
+
+    /// Minimum block height (inclusive)
+    pub from_block_height: Option<u32>,
+
+    /// Maximum block height (inclusive)
+    pub to_block_height: Option<u32>,
+
+    /// Minimum event index (inclusive)
+    pub from_index: Option<u32>,
+
+    /// Maximum event index (inclusive)
+    pub to_index: Option<u32>,
 }
 
 #[derive(Default, Enum, Copy, Clone, Debug, Eq, PartialEq)]
@@ -20,35 +48,18 @@ pub enum EventsSortByInput {
 
 #[derive(SimpleObject, Debug)]
 pub struct Event {
+    /// Index of the event in the token account's event sequence
+    pub index: u32,
     pub block_info: BlockInfo,
     pub event_data: EventData,
     pub transaction_info: TxnInfo,
 }
 
-#[derive(SimpleObject, Debug)]
-pub struct BlockInfo {
-    pub state_hash: String,
-    pub timestamp: DateTime,
-    pub ledger_hash: String,
-    pub height: u32,
-    pub parent_hash: String,
-    pub chain_status: String,
-    pub distance_from_max_block_height: u32,
-    pub global_slot_since_genesis: u32,
-}
-
 #[derive(SimpleObject, Debug)]
 pub struct EventData {
     pub data: String,
 }
 
-#[derive(SimpleObject, Debug)]
-pub struct TxnInfo {
-    pub status: String,
-    pub hash: String,
-    pub memo: String,
-}
-
 #[derive(Default)]
 pub struct EventsQueryRoot;
 
@@ -56,7 +67,7 @@ pub struct EventsQueryRoot;
 impl EventsQueryRoot {
     // Cache for 1 hour
     #[graphql(cache_control(max_age = 3600))]
-    async fn get_actions(
+    async fn get_events(
         &self,
         ctx: &Context<'_>,
         query: Option<EventsQueryInput>,
@@ -64,13 +75,66 @@ impl EventsQueryRoot {
         #[graphql(default = 100)] limit: usize,
     ) -> Result<Option<Vec<Event>>> {
         let db = db(ctx);
+        let Some(query) = query else {
+            return Ok(None);
+        };
+        let Some((pk, token)) = query.token_account()? else {
+            return Ok(None);
+        };
+
+        let num_events = db.get_num_events(&pk, &token)?.unwrap_or_default();
+        let (start, end) = match index_range(query.from_index, query.to_index, num_events) {
+            Some(range) => range,
+            None => return Ok(Some(vec![])),
+        };
+
+        let indices: Box<dyn Iterator<Item = u32>> = match sort_by.unwrap_or_default() {
+            EventsSortByInput::BlockHeightAsc => Box::new(start..=end),
+            EventsSortByInput::BlockHeightDesc => Box::new((start..=end).rev()),
+        };
+
+        let mut events = Vec::with_capacity(limit.min(num_events as usize));
+        for index in indices {
+            if events.len() >= limit {
+                break;
+            }
+
+            if let Some(event) = db.get_event(&pk, &token, index)? {
+                if !in_block_height_range(
+                    event.block_height,
+                    query.from_block_height,
+                    query.to_block_height,
+                ) {
+                    continue;
+                }
+
+                events.push(Event::new(db, index, event)?);
+            }
+        }
+
+        Ok(Some(events))
+    }
+}
+
+impl EventsQueryInput {
+    /// Validate the query's zkapp token account, if an address is given
+    fn token_account(&self) -> Result<Option<(PublicKey, TokenAddress)>> {
+        self.address
+            .as_deref()
+            .map(|address| token_account(address, self.token.as_deref()))
+            .transpose()
+    }
+}
 
-        todo!(
-            "getEvents:\n  db:      {:?}\n  query:   {:?}\n  sort_by: {:?}\n  limit:   {:?}",
-            db,
-            query,
-            sort_by,
-            limit
-        )
+impl Event {
+    fn new(db: &Arc<IndexerStore>, index: u32, event: ZkappEventWithMeta) -> Result<Self> {
+        Ok(Self {
+            index,
+            block_info: BlockInfo::new(db, &event.state_hash, event.block_height)?,
+            event_data: EventData {
+                data: event.event.to_string(),
+            },
+            transaction_info: TxnInfo::new(db, &event.txn_hash, &event.state_hash)?,
+        })
     }
 }
@@ -2,6 +2,7 @@
 
 use super::{
     blocks::block::{Block, BlockWithoutCanonicity},
+    connection::{Page, SortConnection},
     gen::BlockQueryInput,
     get_block, get_block_canonicity,
     pk::RecipientPK,
@@ -290,6 +291,107 @@ impl InternalCommandQueryRoot {
             total_num_internal_commands,
         )
     }
+
+    #[graphql(cache_control(max_age = 3600))]
+    async fn internal_commands_connection(
+        &self,
+        ctx: &Context<'_>,
+        query: Option<InternalCommandQueryInput>,
+        first: Option<usize>,
+        after: Option<String>,
+        sort_by: Option<InternalCommandSortByInput>,
+    ) -> Result<SortConnection<InternalCommandWithMeta>> {
+        let db = db(ctx);
+
+        let epoch_num_internal_commands = db.get_internal_commands_epoch_count(None, None)?;
+        let total_num_internal_commands = db.get_internal_commands_total_count()?;
+
+        let direction = match sort_by.unwrap_or_default() {
+            InternalCommandSortByInput::BlockHeightAsc => Direction::Forward,
+            InternalCommandSortByInput::BlockHeightDesc => Direction::Reverse,
+        };
+
+        // validate recipient
+        let recipient = match query.as_ref().and_then(|q| q.recipient.as_ref()) {
+            Some(recipient) => match PublicKey::new(recipient) {
+                Ok(recipient) => Some(recipient),
+                Err(_) => {
+                    return Err(async_graphql::Error::new(format!(
+                        "Invalid recipient public key: {}",
+                        recipient
+                    )))
+                }
+            },
+            None => None,
+        };
+
+        let (page, mut iter, total_count) = match recipient.as_ref() {
+            Some(recipient) => (
+                Page::new(
+                    "internal-commands-pk-block-height-sort",
+                    direction,
+                    after,
+                    first,
+                )?,
+                db.internal_commands_pk_block_height_iterator(recipient.clone(), direction),
+                db.get_internal_commands_pk_total_count(recipient)?,
+            ),
+            None => (
+                Page::new(
+                    "internal-commands-block-height-sort",
+                    direction,
+                    after,
+                    first,
+                )?,
+                db.internal_commands_block_height_iterator(match direction {
+                    Direction::Forward => IteratorMode::Start,
+                    Direction::Reverse => IteratorMode::End,
+                }),
+                total_num_internal_commands,
+            ),
+        };
+        page.seek(&mut iter);
+
+        // recipient keys are prefixed by the public key
+        let prefix = recipient
+            .as_ref()
+            .map(|pk| pk.0.as_bytes())
+            .unwrap_or_default();
+        let entries = iter
+            .flatten()
+            .take_while(|(key, _)| key.starts_with(prefix));
+
+        Ok(page.collect(entries, total_count, |key, value| {
+            // avoid deserializing internal command & PCB if possible
+            let state_hash =
+                StateHash::from_bytes(&key[prefix.len()..][U32_LEN..][..StateHash::LEN])?;
+            let canonical = get_block_canonicity(db, &state_hash);
+
+            if let Some(query_canonicity) = query.as_ref().and_then(|q| q.canonical) {
+                if canonical != query_canonicity {
+                    return Ok(vec![]);
+                }
+            }
+
+            let cmd = InternalCommandWithMeta {
+                canonical,
+                block: Some(get_block(db, &state_hash)),
+                internal_command: InternalCommand::new(
+                    db,
+                    serde_json::from_slice(value)?,
+                    epoch_num_internal_commands,
+                    total_num_internal_commands,
+                ),
+            };
+
+            Ok(query
+                .as_ref()
+                .is_none_or(|q| q.matches(&cmd))
+                .then_some(cmd)
+                .into_iter()
+                .collect())
+        })?)
+    }
 }
 
 impl InternalCommandQueryInput {
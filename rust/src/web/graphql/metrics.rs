@@ -0,0 +1,41 @@
+//! GraphQL resolver latency metrics
+
+use crate::store::IndexerStore;
+use async_graphql::{
+    extensions::{Extension, ExtensionContext, ExtensionFactory, NextResolve, ResolveInfo},
+    ServerResult, Value,
+};
+use std::{sync::Arc, time::Instant};
+
+/// Records the latency of each top-level resolver
+pub struct ResolverMetrics(pub Arc<IndexerStore>);
+
+struct ResolverMetricsExtension(Arc<IndexerStore>);
+
+impl ExtensionFactory for ResolverMetrics {
+    fn create(&self) -> Arc<dyn Extension> {
+        Arc::new(ResolverMetricsExtension(self.0.clone()))
+    }
+}
+
+#[async_trait::async_trait]
+impl Extension for ResolverMetricsExtension {
+    async fn resolve(
+        &self,
+        ctx: &ExtensionContext<'_>,
+        info: ResolveInfo<'_>,
+        next: NextResolve<'_>,
+    ) -> ServerResult<Option<Value>> {
+        // nested fields are timed as part of their top-level resolver
+        if info.path_node.parent.is_some() || info.is_for_introspection {
+            return next.run(ctx, info).await;
+        }
+
+        let resolver = info.name.to_string();
+        let start = Instant::now();
+        let res = next.run(ctx, info).await;
+
+        self.0.metrics.observe_graphql(&resolver, start.elapsed());
+        res
+    }
+}
@@ -0,0 +1,65 @@
+//! GraphQL `missingBlocks` endpoint
+
+use crate::state::missing::{MissingBlocks, MissingParent, SharedMissingBlocks};
+use async_graphql::{Context, Object, Result, SimpleObject};
+
+#[derive(Default)]
+pub struct MissingBlocksQueryRoot;
+
+#[derive(SimpleObject)]
+pub struct MissingBlocksWithMeta {
+    /// Value missing parents of dangling branch roots
+    missing_parents: Vec<MissingParentWithMeta>,
+
+    /// Value heights above the canonical root without any known block
+    missing_heights: Vec<u32>,
+}
+
+#[derive(SimpleObject)]
+pub struct MissingParentWithMeta {
+    /// Value state hash of the missing block
+    state_hash: String,
+
+    /// Value block height of the missing block
+    block_height: u32,
+
+    /// Value state hashes of the dangling branch roots whose parent is missing
+    children: Vec<String>,
+}
+
+#[Object]
+impl MissingBlocksQueryRoot {
+    /// Blocks missing from the witness tree, as of the last block the indexer
+    /// processed
+    async fn missing_blocks(&self, ctx: &Context<'_>) -> Result<MissingBlocksWithMeta> {
+        let missing_blocks = ctx
+            .data::<SharedMissingBlocks>()?
+            .read()
+            .map_err(|e| async_graphql::Error::new(e.to_string()))?;
+
+        Ok(missing_blocks.clone().into())
+    }
+}
+
+impl From<MissingBlocks> for MissingBlocksWithMeta {
+    fn from(missing_blocks: MissingBlocks) -> Self {
+        Self {
+            missing_parents: missing_blocks
+                .missing_parents
+                .into_iter()
+                .map(Into::into)
+                .collect(),
+            missing_heights: missing_blocks.missing_heights,
+        }
+    }
+}
+
+impl From<MissingParent> for MissingParentWithMeta {
+    fn from(parent: MissingParent) -> Self {
+        Self {
+            state_hash: parent.state_hash.0,
+            block_height: parent.blockchain_length,
+            children: parent.children.into_iter().map(|hash| hash.0).collect(),
+        }
+    }
+}
@@ -2,19 +2,27 @@
 
 mod date_time;
 mod long;
+mod metrics;
 mod pk;
 mod timing;
 
 pub mod accounts;
 pub mod actions;
+pub mod address_book;
 pub mod blocks;
+pub mod connection;
 pub mod events;
 pub mod feetransfers;
 pub mod gen;
 pub mod internal_commands;
+pub mod missing_blocks;
+pub mod pool_payouts;
+pub mod producer_report;
 pub mod snarks;
 pub mod staged_ledgers;
 pub mod stakes;
+pub mod subscriptions;
+pub mod supply;
 pub mod tokens;
 pub mod top_snarkers;
 pub mod top_stakers;
@@ -26,16 +34,17 @@ use crate::{
     base::state_hash::StateHash,
     block::{precomputed::PrecomputedBlock, store::BlockStore},
     constants::*,
+    state::missing::SharedMissingBlocks,
     store::IndexerStore,
 };
-use actix_web::HttpResponse;
+use actix_web::{web, HttpRequest, HttpResponse};
 use anyhow::Context as aContext;
-use async_graphql::{
-    http::GraphiQLSource, Context, EmptyMutation, EmptySubscription, MergedObject, Schema,
-};
+use async_graphql::{http::GraphiQLSource, Context, EmptyMutation, MergedObject, Schema};
+use async_graphql_actix_web::GraphQLSubscription;
 use date_time::DateTime;
 use long::Long;
 use std::sync::Arc;
+use subscriptions::SubscriptionRoot;
 
 #[derive(MergedObject, Default)]
 pub struct Root(
@@ -48,22 +57,50 @@ pub struct Root(
     snarks::SnarkQueryRoot,
     staged_ledgers::StagedLedgerQueryRoot,
     tokens::TokensQueryRoot,
+    actions::ActionsQueryRoot,
+    events::EventsQueryRoot,
     top_stakers::TopStakersQueryRoot,
     top_snarkers::TopSnarkersQueryRoot,
+    supply::SupplyQueryRoot,
+    missing_blocks::MissingBlocksQueryRoot,
     version::VersionQueryRoot,
+    address_book::AddressBookQueryRoot,
+    pool_payouts::PoolPayoutsQueryRoot,
+    producer_report::ProducerReportQueryRoot,
 );
 
+pub type IndexerSchema = Schema<Root, EmptyMutation, SubscriptionRoot>;
+
 /// Build schema for all endpoints
-pub fn build_schema(store: Arc<IndexerStore>) -> Schema<Root, EmptyMutation, EmptySubscription> {
-    Schema::build(Root::default(), EmptyMutation, EmptySubscription)
+pub fn build_schema(
+    store: Arc<IndexerStore>,
+    missing_blocks: SharedMissingBlocks,
+) -> IndexerSchema {
+    Schema::build(Root::default(), EmptyMutation, SubscriptionRoot)
+        .extension(metrics::ResolverMetrics(store.clone()))
         .data(store)
+        .data(missing_blocks)
         .finish()
 }
 
+/// Serve GraphQL subscriptions over WebSocket
+pub async fn indexer_subscriptions(
+    schema: web::Data<IndexerSchema>,
+    req: HttpRequest,
+    payload: web::Payload,
+) -> actix_web::Result<HttpResponse> {
+    GraphQLSubscription::new(Schema::clone(&*schema)).start(&req, payload)
+}
+
 pub async fn indexer_graphiql() -> actix_web::Result<HttpResponse> {
     Ok(HttpResponse::Ok()
         .content_type("text/html; charset=utf-8")
-        .body(GraphiQLSource::build().endpoint(ENDPOINT_GRAPHQL).finish()))
+        .body(
+            GraphiQLSource::build()
+                .endpoint(ENDPOINT_GRAPHQL)
+                .subscription_endpoint(ENDPOINT_GRAPHQL)
+                .finish(),
+        ))
 }
 
 pub(crate) fn db<'a>(ctx: &'a Context) -> &'a Arc<IndexerStore> {
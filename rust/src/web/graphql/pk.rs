@@ -1,6 +1,8 @@
 //! GQL public key/username pairs
 
+use super::address_book::LabelCategory;
 use crate::{
+    address_book::store::AddressBookStore,
     base::public_key::PublicKey,
     store::{username::UsernameStore, IndexerStore},
 };
@@ -13,6 +15,8 @@ use std::sync::Arc;
 pub struct PK {
     pub public_key: String,
     pub username: Option<String>,
+    pub label: Option<String>,
+    pub category: Option<LabelCategory>,
 }
 
 #[derive(Default, Clone, Debug, PartialEq, SimpleObject, Serialize)]
@@ -20,48 +24,64 @@ pub struct PK_ {
     #[graphql(name = "public_key")]
     pub public_key: String,
     pub username: Option<String>,
+    pub label: Option<String>,
+    pub category: Option<LabelCategory>,
 }
 
 #[derive(Default, Clone, Debug, PartialEq, SimpleObject, Serialize)]
 pub struct DelegatePK {
     pub delegate: String,
     pub delegate_username: Option<String>,
+    pub delegate_label: Option<String>,
+    pub delegate_category: Option<LabelCategory>,
 }
 
 #[derive(Default, Clone, Debug, PartialEq, SimpleObject, Serialize)]
 pub struct CreatorPK {
     pub creator: String,
     pub creator_username: Option<String>,
+    pub creator_label: Option<String>,
+    pub creator_category: Option<LabelCategory>,
 }
 
 #[derive(Default, Clone, Debug, PartialEq, SimpleObject, Serialize)]
 pub struct CoinbaseReceiverPK {
     pub coinbase_receiver: String,
     pub coinbase_receiver_username: Option<String>,
+    pub coinbase_receiver_label: Option<String>,
+    pub coinbase_receiver_category: Option<LabelCategory>,
 }
 
 #[derive(Default, Clone, Debug, PartialEq, SimpleObject, Serialize)]
 pub struct ProverPK {
     pub prover: String,
     pub prover_username: Option<String>,
+    pub prover_label: Option<String>,
+    pub prover_category: Option<LabelCategory>,
 }
 
 #[derive(Default, Clone, Debug, PartialEq, SimpleObject, Serialize)]
 pub struct RecipientPK {
     pub recipient: String,
     pub recipient_username: Option<String>,
+    pub recipient_label: Option<String>,
+    pub recipient_category: Option<LabelCategory>,
 }
 
 #[derive(Default, Clone, Debug, PartialEq, SimpleObject, Serialize)]
 pub struct SenderPK {
     pub sender: String,
     pub sender_username: Option<String>,
+    pub sender_label: Option<String>,
+    pub sender_category: Option<LabelCategory>,
 }
 
 #[derive(Default, Clone, Debug, PartialEq, SimpleObject, Serialize)]
 pub struct WinnerPK {
     pub winner: String,
     pub winner_username: Option<String>,
+    pub winner_label: Option<String>,
+    pub winner_category: Option<LabelCategory>,
 }
 
 ///////////
@@ -69,9 +89,18 @@ pub struct WinnerPK {
 ///////////
 
 impl PK {
+    /// The public key's on-chain username & address book label
     pub fn new(db: &Arc<IndexerStore>, pk: PublicKey) -> Self {
+        let username = db.get_username(&pk).expect("username").map(|u| u.0);
+        let (label, category) = match db.get_label(&pk).expect("label") {
+            Some(label) => (Some(label.label), Some(label.category.into())),
+            None => (None, None),
+        };
+
         Self {
-            username: db.get_username(&pk).expect("username").map(|u| u.0),
+            username,
+            label,
+            category,
             public_key: pk.0,
         }
     }
@@ -134,6 +163,8 @@ impl From<PK> for PK_ {
         Self {
             public_key: value.public_key,
             username: value.username,
+            label: value.label,
+            category: value.category,
         }
     }
 }
@@ -143,6 +174,8 @@ impl From<PK> for DelegatePK {
         Self {
             delegate: value.public_key,
             delegate_username: value.username,
+            delegate_label: value.label,
+            delegate_category: value.category,
         }
     }
 }
@@ -152,6 +185,8 @@ impl From<PK> for CoinbaseReceiverPK {
         Self {
             coinbase_receiver: value.public_key,
             coinbase_receiver_username: value.username,
+            coinbase_receiver_label: value.label,
+            coinbase_receiver_category: value.category,
         }
     }
 }
@@ -161,6 +196,8 @@ impl From<PK> for CreatorPK {
         Self {
             creator: value.public_key,
             creator_username: value.username,
+            creator_label: value.label,
+            creator_category: value.category,
         }
     }
 }
@@ -170,6 +207,8 @@ impl From<PK> for ProverPK {
         Self {
             prover: value.public_key,
             prover_username: value.username,
+            prover_label: value.label,
+            prover_category: value.category,
         }
     }
 }
@@ -179,6 +218,8 @@ impl From<PK> for RecipientPK {
         Self {
             recipient: value.public_key,
             recipient_username: value.username,
+            recipient_label: value.label,
+            recipient_category: value.category,
         }
     }
 }
@@ -188,6 +229,8 @@ impl From<PK> for SenderPK {
         Self {
             sender: value.public_key,
             sender_username: value.username,
+            sender_label: value.label,
+            sender_category: value.category,
         }
     }
 }
@@ -197,6 +240,8 @@ impl From<PK> for WinnerPK {
         Self {
             winner: value.public_key,
             winner_username: value.username,
+            winner_label: value.label,
+            winner_category: value.category,
         }
     }
 }
@@ -0,0 +1,163 @@
+//! GraphQL `poolPayouts` endpoint
+
+use super::{db, pk::PK_};
+use crate::{
+    base::{public_key::PublicKey, state_hash::StateHash},
+    block::store::BlockStore,
+    ledger::staking::payout::{self, PoolPayouts},
+};
+use async_graphql::{Context, Enum, Object, Result, SimpleObject};
+
+#[derive(Default, Enum, Copy, Clone, Debug, Eq, PartialEq)]
+pub enum PayoutScheme {
+    /// Block rewards are shared in proportion to stake
+    #[default]
+    Proportional,
+
+    /// Supercharged bonuses are only shared among unlocked delegators
+    Supercharged,
+
+    /// Coinbases are shared in proportion to stake, the pool keeps the fees
+    FeeSplit,
+}
+
+#[derive(SimpleObject)]
+pub struct PoolPayoutsResult {
+    /// Value pool delegate
+    #[graphql(flatten)]
+    delegate: PK_,
+
+    /// Value epoch
+    epoch: u32,
+
+    /// Value genesis state hash
+    genesis_state_hash: String,
+
+    /// Value payout scheme
+    scheme: PayoutScheme,
+
+    /// Value pool fee percent
+    fee_percent: f64,
+
+    /// Value total delegated stake (nanomina)
+    total_stake_nanomina: u64,
+
+    /// Value canonical blocks produced
+    num_blocks: u32,
+
+    /// Value canonical supercharged blocks produced
+    num_supercharged_blocks: u32,
+
+    /// Value coinbases & fees net of SNARK fees (nanomina)
+    total_rewards_nanomina: u64,
+
+    /// Value pool fee, retained fees & rounding remainders (nanomina)
+    operator_reward_nanomina: u64,
+
+    /// Value delegator payouts, largest first
+    payouts: Vec<DelegatorPayout>,
+}
+
+#[derive(SimpleObject)]
+pub struct DelegatorPayout {
+    /// Value delegator
+    #[graphql(flatten)]
+    delegator: PK_,
+
+    /// Value delegator staking ledger balance (nanomina)
+    stake_nanomina: u64,
+
+    /// Value delegator stake time-locked during a pool block
+    locked: bool,
+
+    /// Value delegator payout (nanomina)
+    payout_nanomina: u64,
+}
+
+#[derive(Default)]
+pub struct PoolPayoutsQueryRoot;
+
+#[Object]
+impl PoolPayoutsQueryRoot {
+    /// Delegator payouts for the delegate's canonical blocks in the epoch
+    #[graphql(cache_control(max_age = 60))]
+    async fn pool_payouts(
+        &self,
+        ctx: &Context<'_>,
+        delegate: String,
+        epoch: Option<u32>,
+        #[graphql(default = 0.0)] fee_percent: f64,
+        scheme: Option<PayoutScheme>,
+        genesis_state_hash: Option<String>,
+    ) -> Result<Option<PoolPayoutsResult>> {
+        let db = db(ctx);
+        let delegate = PublicKey::new(delegate)?;
+
+        let epoch = match epoch {
+            Some(epoch) => epoch,
+            None => db.get_current_epoch()?,
+        };
+        let genesis_state_hash = match genesis_state_hash {
+            Some(genesis_state_hash) => StateHash::new(genesis_state_hash)?,
+            None => match db.get_best_block_genesis_hash()? {
+                Some(genesis_state_hash) => genesis_state_hash,
+                None => return Ok(None),
+            },
+        };
+
+        Ok(PoolPayouts::from_store(
+            db,
+            &delegate,
+            epoch,
+            &genesis_state_hash,
+            scheme.unwrap_or_default().into(),
+            fee_percent,
+        )?
+        .map(|payouts| PoolPayoutsResult {
+            delegate: PK_::new(db, payouts.delegate),
+            epoch: payouts.epoch,
+            genesis_state_hash: payouts.genesis_state_hash.0,
+            scheme: payouts.scheme.into(),
+            fee_percent: payouts.fee_percent,
+            total_stake_nanomina: payouts.total_stake,
+            num_blocks: payouts.num_blocks,
+            num_supercharged_blocks: payouts.num_supercharged_blocks,
+            total_rewards_nanomina: payouts.total_rewards,
+            operator_reward_nanomina: payouts.operator_reward,
+            payouts: payouts
+                .payouts
+                .into_iter()
+                .map(|payout| DelegatorPayout {
+                    delegator: PK_::new(db, payout.public_key),
+                    stake_nanomina: payout.stake,
+                    locked: payout.locked,
+                    payout_nanomina: payout.payout,
+                })
+                .collect(),
+        }))
+    }
+}
+
+/////////////////
+// conversions //
+/////////////////
+
+impl From<payout::PayoutScheme> for PayoutScheme {
+    fn from(value: payout::PayoutScheme) -> Self {
+        match value {
+            payout::PayoutScheme::Proportional => Self::Proportional,
+            payout::PayoutScheme::Supercharged => Self::Supercharged,
+            payout::PayoutScheme::FeeSplit => Self::FeeSplit,
+        }
+    }
+}
+
+impl From<PayoutScheme> for payout::PayoutScheme {
+    fn from(value: PayoutScheme) -> Self {
+        match value {
+            PayoutScheme::Proportional => Self::Proportional,
+            PayoutScheme::Supercharged => Self::Supercharged,
+            PayoutScheme::FeeSplit => Self::FeeSplit,
+        }
+    }
+}
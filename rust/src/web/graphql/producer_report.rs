@@ -0,0 +1,106 @@
+//! GraphQL `producerReport` endpoint
+
+use super::{db, pk::PK_};
+use crate::{
+    base::{public_key::PublicKey, state_hash::StateHash},
+    block::{production::ProducerReport, store::BlockStore},
+};
+use async_graphql::{Context, Object, Result, SimpleObject};
+
+#[derive(SimpleObject)]
+pub struct ProducerReportResult {
+    /// Value block producer
+    #[graphql(flatten)]
+    producer: PK_,
+
+    /// Value epoch
+    epoch: u32,
+
+    /// Value genesis state hash
+    genesis_state_hash: String,
+
+    /// Value delegated stake (nanomina)
+    stake_nanomina: u64,
+
+    /// Value staking ledger total currency (nanomina)
+    total_stake_nanomina: u64,
+
+    /// Value share of the total stake
+    stake_share: f64,
+
+    /// Value epoch slots elapsed
+    slots_elapsed: u32,
+
+    /// Value epoch slots produced
+    slots_produced: u32,
+
+    /// Value epoch blocks produced
+    num_blocks_produced: u32,
+
+    /// Value epoch canonical blocks produced
+    num_canonical_blocks: u32,
+
+    /// Value epoch supercharged blocks produced
+    num_supercharged_blocks: u32,
+
+    /// Value share of produced blocks which are not canonical
+    orphan_rate: f64,
+
+    /// Value slots expected to be won from the stake share
+    expected_blocks: f64,
+
+    /// Value expected slots won which were not produced
+    missed_slots: f64,
+}
+
+#[derive(Default)]
+pub struct ProducerReportQueryRoot;
+
+#[Object]
+impl ProducerReportQueryRoot {
+    /// Block producer's epoch performance vs its stake share
+    #[graphql(cache_control(max_age = 60))]
+    async fn producer_report(
+        &self,
+        ctx: &Context<'_>,
+        public_key: String,
+        epoch: Option<u32>,
+        genesis_state_hash: Option<String>,
+    ) -> Result<Option<ProducerReportResult>> {
+        let db = db(ctx);
+        let pk = PublicKey::new(public_key)?;
+
+        let epoch = match epoch {
+            Some(epoch) => epoch,
+            None => db.get_current_epoch()?,
+        };
+        let genesis_state_hash = match genesis_state_hash {
+            Some(genesis_state_hash) => StateHash::new(genesis_state_hash)?,
+            None => match db.get_best_block_genesis_hash()? {
+                Some(genesis_state_hash) => genesis_state_hash,
+                None => return Ok(None),
+            },
+        };
+
+        Ok(
+            ProducerReport::from_store(db, &pk, epoch, &genesis_state_hash)?.map(|report| {
+                ProducerReportResult {
+                    producer: PK_::new(db, report.public_key),
+                    epoch: report.epoch,
+                    genesis_state_hash: report.genesis_state_hash.0,
+                    stake_nanomina: report.stake,
+                    total_stake_nanomina: report.total_stake,
+                    stake_share: report.stake_share,
+                    slots_elapsed: report.slots_elapsed,
+                    slots_produced: report.slots_produced,
+                    num_blocks_produced: report.num_blocks_produced,
+                    num_canonical_blocks: report.num_canonical_blocks,
+                    num_supercharged_blocks: report.num_supercharged_blocks,
+                    orphan_rate: report.orphan_rate,
+                    expected_blocks: report.expected_blocks,
+                    missed_slots: report.missed_slots,
+                }
+            }),
+        )
+    }
+}
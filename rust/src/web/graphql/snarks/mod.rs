@@ -1,6 +1,12 @@
 //! GraphQL `snarks` endpoint
 
-use super::{db, gen::BlockQueryInput, get_block, get_block_canonicity, pk::ProverPK};
+use super::{
+    connection::{Page, SortConnection},
+    db,
+    gen::BlockQueryInput,
+    get_block, get_block_canonicity,
+    pk::ProverPK,
+};
 use crate::{
     base::{public_key::PublicKey, state_hash::StateHash},
     block::{precomputed::PrecomputedBlock, store::BlockStore},
@@ -461,6 +467,68 @@ impl SnarkQueryRoot {
 
         Ok(snarks)
     }
+
+    #[graphql(cache_control(max_age = 3600))]
+    async fn snarks_connection(
+        &self,
+        ctx: &Context<'_>,
+        query: Option<SnarkQueryInput>,
+        first: Option<usize>,
+        after: Option<String>,
+        sort_by: Option<SnarkSortByInput>,
+    ) -> Result<SortConnection<SnarkWithCanonicity>> {
+        use speedb::{Direction::*, IteratorMode::*};
+
+        let db = db(ctx);
+        let epoch_num_snarks = db.get_snarks_epoch_count(None, None)?;
+        let total_num_snarks = db.get_snarks_total_count()?;
+
+        // a block's SNARKs are paged through in order
+        let (page, mut iter) = match sort_by.unwrap_or(SnarkSortByInput::BlockHeightDesc) {
+            SnarkSortByInput::BlockHeightAsc => (
+                Page::new("blocks-height-sort", Forward, after, first)?,
+                db.blocks_height_iterator(Start),
+            ),
+            SnarkSortByInput::BlockHeightDesc => (
+                Page::new("blocks-height-sort", Reverse, after, first)?,
+                db.blocks_height_iterator(End),
+            ),
+        };
+        page.seek(&mut iter);
+
+        Ok(page.collect(iter.flatten(), total_num_snarks, |key, _| {
+            let state_hash = state_hash_suffix(key)?;
+
+            // avoid deserializing PCB if possible
+            let canonical = get_block_canonicity(db, &state_hash);
+            if let Some(query_canonicity) = query.as_ref().and_then(|q| q.canonical) {
+                if canonical != query_canonicity {
+                    return Ok(vec![]);
+                }
+            }
+
+            let snark_work = db.get_block_snark_work(&state_hash)?.unwrap_or_default();
+            if snark_work.is_empty() {
+                return Ok(vec![]);
+            }
+
+            let pcb = get_block(db, &state_hash);
+            Ok(snark_work
+                .into_iter()
+                .map(|snark| SnarkWithCanonicity {
+                    canonical,
+                    pcb: pcb.clone(),
+                    snark: Snark::new(
+                        db,
+                        SnarkWorkSummaryWithStateHash::from(snark, state_hash.clone()),
+                        epoch_num_snarks,
+                        total_num_snarks,
+                    ),
+                })
+                .filter(|sw| query.as_ref().is_none_or(|q| q.matches(sw)))
+                .collect())
+        })?)
+    }
 }
 
 fn snark_summary_matches_query(
@@ -1,10 +1,13 @@
 //! GraphQL `stakes` endpoint
 
 use super::{
+    address_book::LabelCategory,
+    connection::{ConnectionFields, Page, SortConnection},
     db,
     pk::{DelegatePK, PK, PK_},
 };
 use crate::{
+    address_book::store::AddressBookStore,
     base::{amount::Amount, state_hash::StateHash},
     block::store::BlockStore,
     command::{internal::store::InternalCommandStore, store::UserCommandStore},
@@ -47,6 +50,9 @@ pub struct StakesQueryInput {
 
     /// Input staking account username
     username: Option<String>,
+
+    /// Input staking account label category, e.g. pool-labeled accounts
+    category: Option<LabelCategory>,
 }
 
 #[derive(Default, Enum, Copy, Clone, Eq, PartialEq)]
@@ -271,58 +277,11 @@ impl StakesQueryRoot {
             }
         }
 
-        // default to best block genesis state hash
-        let genesis_state_hash = match query.as_ref().and_then(|q| q.genesis_state_hash.as_ref()) {
-            Some(genesis) => match StateHash::new(genesis) {
-                Ok(genesis) => genesis,
-                Err(_) => {
-                    return Err(async_graphql::Error::new(format!(
-                        "Invalid genesis state hash: {}",
-                        genesis
-                    )))
-                }
-            },
-            None => db
-                .get_best_block_genesis_hash()
-                .ok()
-                .flatten()
-                .expect("genesis state hash"),
-        };
-
-        // if ledger hash is provided as a query input, use it for the ledger
-        // otherwise, use the provided or current epoch number
-        let (ledger_hash, epoch) = match query.as_ref().map(|q| (q.ledger_hash.as_ref(), q.epoch)) {
-            Some((Some(ledger_hash), query_epoch)) => {
-                let ledger_hash = match LedgerHash::new(ledger_hash) {
-                    Ok(ledger_hash) => ledger_hash,
-                    Err(_) => {
-                        return Err(async_graphql::Error::new(format!(
-                            "Invalid ledger hash: {}",
-                            ledger_hash
-                        )))
-                    }
-                };
-
-                let epoch = query_epoch.unwrap_or_else(|| {
-                    db.get_epoch(&ledger_hash)
-                        .expect("epoch from ledger hash")
-                        .unwrap_or_default()
-                });
-
-                (ledger_hash, epoch)
-            }
-            _ => {
-                let ledger_hash = if let Some(ledger_hash) =
-                    db.get_staking_ledger_hash_by_epoch(epoch, &genesis_state_hash)?
-                {
-                    ledger_hash
-                } else {
-                    return Ok(vec![]);
-                };
-
-                (ledger_hash, epoch)
-            }
-        };
+        let (ledger_hash, epoch, genesis_state_hash) =
+            match StakesQueryInput::staking_ledger(db, query.as_ref(), epoch)? {
+                Some(ledger) => ledger,
+                None => return Ok(vec![]),
+            };
         let total_currency = db.get_total_currency(&ledger_hash)?.unwrap_or_default();
 
         use StakesSortByInput::*;
@@ -330,7 +289,13 @@ impl StakesQueryRoot {
 
         // username query
         if let Some(username) = query.as_ref().and_then(|q| q.username.as_ref()) {
-            let username_pks = db.get_username_pks(username)?.unwrap_or_default();
+            // labeled & on-chain usernames
+            let mut username_pks = db.get_label_pks(username)?;
+            for pk in db.get_username_pks(username)?.unwrap_or_default() {
+                if !username_pks.contains(&pk) {
+                    username_pks.push(pk);
+                }
+            }
 
             for pk in username_pks {
                 // check limit
@@ -342,7 +307,7 @@ impl StakesQueryRoot {
                     db.get_staking_account(&pk, epoch, &genesis_state_hash)?
                 {
                     // add username to account
-                    account.username = db.get_username(&pk)?.map(|u| u.0);
+                    account.username = db.resolve_username(&pk)?.map(|u| u.0);
 
                     if StakesQueryInput::matches_staking_account(
                         query.as_ref(),
@@ -437,6 +402,135 @@ impl StakesQueryRoot {
 
         Ok(accounts)
     }
+
+    // Cache for 1 day
+    #[graphql(cache_control(max_age = 86400))]
+    async fn stakes_connection(
+        &self,
+        ctx: &Context<'_>,
+        query: Option<StakesQueryInput>,
+        first: Option<usize>,
+        after: Option<String>,
+        sort_by: Option<StakesSortByInput>,
+    ) -> Result<SortConnection<StakesLedgerAccountWithMeta>> {
+        use StakesSortByInput::*;
+        let db = db(ctx);
+
+        // default to current epoch
+        let epoch = query
+            .as_ref()
+            .and_then(|q| q.epoch)
+            .unwrap_or_else(|| db.get_current_epoch().expect("epoch"));
+
+        let (ledger_hash, epoch, genesis_state_hash) =
+            match StakesQueryInput::staking_ledger(db, query.as_ref(), epoch)? {
+                Some(ledger) => ledger,
+                None => {
+                    return Ok(SortConnection::with_additional_fields(
+                        false,
+                        false,
+                        ConnectionFields { total_count: 0 },
+                    ))
+                }
+            };
+        let total_currency = db.get_total_currency(&ledger_hash)?.unwrap_or_default();
+
+        let (page, mut iter) = match sort_by.unwrap_or_default() {
+            StakeDesc => (
+                Page::new(
+                    "staking-ledger-stake-sort",
+                    Direction::Reverse,
+                    after,
+                    first,
+                )?,
+                db.staking_ledger_account_stake_iterator(
+                    epoch,
+                    &genesis_state_hash,
+                    Direction::Reverse,
+                ),
+            ),
+            StakeAsc => (
+                Page::new(
+                    "staking-ledger-stake-sort",
+                    Direction::Forward,
+                    after,
+                    first,
+                )?,
+                db.staking_ledger_account_stake_iterator(
+                    epoch,
+                    &genesis_state_hash,
+                    Direction::Forward,
+                ),
+            ),
+            BalanceDesc => (
+                Page::new(
+                    "staking-ledger-balance-sort",
+                    Direction::Reverse,
+                    after,
+                    first,
+                )?,
+                db.staking_ledger_account_balance_iterator(
+                    epoch,
+                    &genesis_state_hash,
+                    Direction::Reverse,
+                ),
+            ),
+            BalanceAsc => (
+                Page::new(
+                    "staking-ledger-balance-sort",
+                    Direction::Forward,
+                    after,
+                    first,
+                )?,
+                db.staking_ledger_account_balance_iterator(
+                    epoch,
+                    &genesis_state_hash,
+                    Direction::Forward,
+                ),
+            ),
+        };
+        page.seek(&mut iter);
+
+        // keys are prefixed by the staking ledger's genesis state hash & epoch
+        let mut prefix = genesis_state_hash.0.as_bytes().to_vec();
+        prefix.extend_from_slice(&epoch.to_be_bytes());
+
+        let entries = iter
+            .flatten()
+            .take_while(|(key, _)| key.starts_with(&prefix));
+        let total_count = db.get_staking_ledger_accounts_count_epoch(epoch, &genesis_state_hash)?;
+
+        Ok(page.collect(entries, total_count, |_, value| {
+            let StakingAccountWithEpochDelegation {
+                account,
+                delegation,
+            } = serde_json::from_slice(value)?;
+
+            if !StakesQueryInput::matches_staking_account(
+                query.as_ref(),
+                &account,
+                &ledger_hash,
+                &genesis_state_hash,
+                epoch,
+            ) {
+                return Ok(vec![]);
+            }
+
+            let account = StakesLedgerAccountWithMeta::new(
+                db,
+                account,
+                delegation,
+                epoch,
+                ledger_hash.to_owned(),
+                total_currency,
+            );
+
+            Ok(StakesQueryInput::matches(query.as_ref(), &account)
+                .then_some(account)
+                .into_iter()
+                .collect())
+        })?)
+    }
 }
 
 #[ComplexObject]
@@ -510,6 +604,64 @@ impl StakesLedgerAccount {
 }
 
 impl StakesQueryInput {
+    /// Resolves the queried staking ledger's hash, epoch & genesis state hash
+    ///
+    /// Defaults to the best block's genesis state hash & the given epoch's
+    /// ledger, `None` if there's no such ledger
+    fn staking_ledger(
+        db: &Arc<IndexerStore>,
+        query: Option<&Self>,
+        epoch: u32,
+    ) -> Result<Option<(LedgerHash, u32, StateHash)>> {
+        // default to best block genesis state hash
+        let genesis_state_hash = match query.and_then(|q| q.genesis_state_hash.as_ref()) {
+            Some(genesis) => match StateHash::new(genesis) {
+                Ok(genesis) => genesis,
+                Err(_) => {
+                    return Err(async_graphql::Error::new(format!(
+                        "Invalid genesis state hash: {}",
+                        genesis
+                    )))
+                }
+            },
+            None => db
+                .get_best_block_genesis_hash()
+                .ok()
+                .flatten()
+                .expect("genesis state hash"),
+        };
+
+        // if ledger hash is provided as a query input, use it for the ledger
+        // otherwise, use the provided or current epoch number
+        let (ledger_hash, epoch) = match query.map(|q| (q.ledger_hash.as_ref(), q.epoch)) {
+            Some((Some(ledger_hash), query_epoch)) => {
+                let ledger_hash = match LedgerHash::new(ledger_hash) {
+                    Ok(ledger_hash) => ledger_hash,
+                    Err(_) => {
+                        return Err(async_graphql::Error::new(format!(
+                            "Invalid ledger hash: {}",
+                            ledger_hash
+                        )))
+                    }
+                };
+
+                let epoch = query_epoch.unwrap_or_else(|| {
+                    db.get_epoch(&ledger_hash)
+                        .expect("epoch from ledger hash")
+                        .unwrap_or_default()
+                });
+
+                (ledger_hash, epoch)
+            }
+            _ => match db.get_staking_ledger_hash_by_epoch(epoch, &genesis_state_hash)? {
+                Some(ledger_hash) => (ledger_hash, epoch),
+                None => return Ok(None),
+            },
+        };
+
+        Ok(Some((ledger_hash, epoch, genesis_state_hash)))
+    }
+
     pub fn matches(
         query: Option<&Self>,
         stakes_ledger_account: &StakesLedgerAccountWithMeta,
@@ -520,6 +672,12 @@ impl StakesQueryInput {
                     return false;
                 }
             }
+
+            if let Some(category) = query.category {
+                if stakes_ledger_account.account.public_key.category != Some(category) {
+                    return false;
+                }
+            }
         }
 
         true
@@ -541,6 +699,7 @@ impl StakesQueryInput {
                 genesis_state_hash: query_genesis_state_hash,
                 username,
                 stake_lte: _,
+                category: _,
             } = query;
 
             if let Some(public_key) = public_key {
@@ -0,0 +1,118 @@
+//! GraphQL `newBlock`, `newBestTip` & `newCanonicalBlock` subscriptions
+
+use super::{
+    blocks::{block::Block, get_counts},
+    db,
+};
+use crate::{
+    base::state_hash::StateHash,
+    block::store::BlockStore,
+    event::{db::*, store::EventStore, IndexerEvent},
+    store::IndexerStore,
+};
+use async_graphql::{Context, Object, Result, Subscription};
+use futures_util::{stream, Stream, StreamExt};
+use log::warn;
+use std::sync::Arc;
+use tokio::sync::broadcast::error::RecvError;
+
+#[derive(Default)]
+pub struct SubscriptionRoot;
+
+/// Block event subscription payload
+#[derive(Debug, Clone)]
+pub struct BlockEvent {
+    state_hash: StateHash,
+    block_height: u32,
+}
+
+//////////
+// impl //
+//////////
+
+#[Subscription]
+impl SubscriptionRoot {
+    /// Blocks as they are added to the store
+    async fn new_block(&self, ctx: &Context<'_>) -> impl Stream<Item = BlockEvent> {
+        block_events(db(ctx), |event| match event {
+            IndexerEvent::Db(DbEvent::Block(DbBlockEvent::NewBlock {
+                state_hash,
+                blockchain_length,
+            })) => Some(BlockEvent::new(state_hash, blockchain_length)),
+            _ => None,
+        })
+    }
+
+    /// Best tip changes
+    async fn new_best_tip(&self, ctx: &Context<'_>) -> impl Stream<Item = BlockEvent> {
+        block_events(db(ctx), |event| match event {
+            IndexerEvent::Db(DbEvent::Block(DbBlockEvent::NewBestTip {
+                state_hash,
+                blockchain_length,
+            })) => Some(BlockEvent::new(state_hash, blockchain_length)),
+            _ => None,
+        })
+    }
+
+    /// Blocks as they become canonical
+    async fn new_canonical_block(&self, ctx: &Context<'_>) -> impl Stream<Item = BlockEvent> {
+        block_events(db(ctx), |event| match event {
+            IndexerEvent::Db(DbEvent::Canonicity(DbCanonicityEvent::NewCanonicalBlock {
+                state_hash,
+                blockchain_length,
+            })) => Some(BlockEvent::new(state_hash, blockchain_length)),
+            _ => None,
+        })
+    }
+}
+
+#[Object]
+impl BlockEvent {
+    /// Value state hash
+    async fn state_hash(&self) -> String {
+        self.state_hash.0.to_owned()
+    }
+
+    /// Value block height
+    async fn block_height(&self) -> u32 {
+        self.block_height
+    }
+
+    /// Value block
+    async fn block(&self, ctx: &Context<'_>) -> Result<Option<Block>> {
+        let db = db(ctx);
+        let counts = get_counts(db, None, None)?;
+
+        Ok(db
+            .get_block(&self.state_hash)?
+            .map(|(pcb, _)| Block::from_precomputed(db, &pcb, counts)))
+    }
+}
+
+impl BlockEvent {
+    fn new(state_hash: StateHash, block_height: u32) -> Self {
+        Self {
+            state_hash,
+            block_height,
+        }
+    }
+}
+
+/// Stream of the db events selected by `select`
+fn block_events<F>(db: &Arc<IndexerStore>, select: F) -> impl Stream<Item = BlockEvent>
+where
+    F: Fn(IndexerEvent) -> Option<BlockEvent> + Send + 'static,
+{
+    stream::unfold(db.subscribe_events(), |mut receiver| async move {
+        loop {
+            match receiver.recv().await {
+                Ok(event) => return Some((event, receiver)),
+                Err(RecvError::Lagged(num_skipped)) => {
+                    warn!("GraphQL subscriber lagging, skipped {num_skipped} events")
+                }
+                Err(RecvError::Closed) => return None,
+            }
+        }
+    })
+    .filter_map(move |event| std::future::ready(select(event)))
+}
@@ -0,0 +1,66 @@
+//! GraphQL `supply` endpoint
+
+use super::db;
+use crate::{ledger::supply::Supply, utility::functions::nanomina_to_mina};
+use async_graphql::{Context, Object, Result, SimpleObject};
+
+#[derive(Default)]
+pub struct SupplyQueryRoot;
+
+#[derive(SimpleObject)]
+pub struct SupplyWithMeta {
+    /// Value global slot
+    global_slot: u32,
+
+    /// Value state hash of the block whose ledger the supply is computed from
+    state_hash: String,
+
+    /// Value block height of the block whose ledger the supply is computed
+    /// from
+    block_height: u32,
+
+    /// Value total currency (MINA)
+    total_currency: String,
+
+    /// Value time-locked supply (MINA)
+    locked_supply: String,
+
+    /// Value liquid ledger supply (MINA)
+    liquid_supply: String,
+
+    /// Value circulating supply (MINA)
+    circulating_supply: String,
+}
+
+#[Object]
+impl SupplyQueryRoot {
+    /// Locked, liquid & circulating supply at the global slot, defaults to
+    /// the best tip's slot
+    async fn supply(
+        &self,
+        ctx: &Context<'_>,
+        global_slot: Option<u32>,
+    ) -> Result<Option<SupplyWithMeta>> {
+        let db = db(ctx);
+        let supply = match global_slot {
+            Some(global_slot) => Supply::at_slot(db, Some(global_slot))?,
+            None => Supply::at_best_tip(db)?,
+        };
+
+        Ok(supply.map(Into::into))
+    }
+}
+
+impl From<Supply> for SupplyWithMeta {
+    fn from(supply: Supply) -> Self {
+        Self {
+            global_slot: supply.global_slot,
+            state_hash: supply.state_hash.0,
+            block_height: supply.block_height,
+            total_currency: nanomina_to_mina(supply.total_currency),
+            locked_supply: nanomina_to_mina(supply.locked),
+            liquid_supply: nanomina_to_mina(supply.liquid),
+            circulating_supply: nanomina_to_mina(supply.circulating),
+        }
+    }
+}
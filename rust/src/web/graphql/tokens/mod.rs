@@ -2,6 +2,7 @@
 
 use super::{
     accounts::{self, AccountWithMeta},
+    connection::{Page, SortConnection},
     db,
     pk::PK,
 };
@@ -216,6 +217,34 @@ impl TokensQueryRoot {
         Ok(tokens)
     }
 
+    /// Tokens in the order they were first seen
+    #[graphql(cache_control(max_age = 3600))]
+    async fn tokens_connection(
+        &self,
+        ctx: &Context<'_>,
+        query: Option<TokensQueryInput>,
+        first: Option<usize>,
+        after: Option<String>,
+    ) -> Result<SortConnection<Token>> {
+        let db = db(ctx);
+
+        let page = Page::new("zkapp-tokens-at-index", Direction::Forward, after, first)?;
+        let mut iter = db.token_iterator();
+        page.seek(&mut iter);
+
+        Ok(
+            page.collect(iter.flatten(), db.get_num_tokens()?, |_, value| {
+                let token = serde_json::from_slice(value)?;
+                if !TokensQueryInput::matches(query.as_ref(), &token) {
+                    return Ok(vec![]);
+                }
+
+                let token = TokenWithMeta::new(db, token);
+                Ok(vec![Token::new(db, token)])
+            })?,
+        )
+    }
+
     #[graphql(cache_control(max_age = 3600))]
     async fn token_holders(
         &self,
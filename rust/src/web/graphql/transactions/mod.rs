@@ -8,7 +8,7 @@ use crate::{
     base::{amount::Amount, public_key::PublicKey, state_hash::StateHash},
     block::store::BlockStore,
     command::{
-        signed::{SignedCommandWithData, TxnHash},
+        signed::{store::InvalidSignatureStore, SignedCommandWithData, TxnHash},
         store::UserCommandStore,
         AccountUpdate, CommandStatusData,
     },
@@ -19,7 +19,11 @@ use crate::{
         command::user::{user_commands_iterator_state_hash, user_commands_iterator_txn_hash},
         common::{state_hash_suffix, U32_LEN},
     },
-    web::graphql::{gen::TransactionQueryInput, DateTime},
+    web::graphql::{
+        connection::{Page, SortConnection},
+        gen::TransactionQueryInput,
+        DateTime,
+    },
 };
 use anyhow::Context as AC;
 use async_graphql::{Context, Enum, Object, Result, SimpleObject};
@@ -92,6 +96,10 @@ pub struct TransactionWithoutBlock {
     memo: String,
     failure_reason: Option<String>,
     is_applied: bool,
+
+    /// Signature failed verification during ingestion
+    invalid_signature: bool,
+
     zkapp: Option<TransactionZkapp>,
     tokens: Vec<String>,
 
@@ -434,6 +442,139 @@ impl TransactionsQueryRoot {
 
         Ok(transactions)
     }
+
+    pub async fn transactions_connection(
+        &self,
+        ctx: &Context<'_>,
+        query: Option<TransactionQueryInput>,
+        first: Option<usize>,
+        after: Option<String>,
+        sort_by: Option<TransactionSortByInput>,
+    ) -> Result<SortConnection<Transaction>> {
+        use TransactionSortByInput::*;
+
+        let db = db(ctx);
+        let num_commands = [
+            db.get_user_commands_epoch_count(None, None)?,
+            db.get_user_commands_total_count()?,
+            db.get_zkapp_commands_epoch_count(None, None)?,
+            db.get_zkapp_commands_total_count()?,
+        ];
+
+        let sort_by = sort_by.unwrap_or(BlockHeightDesc);
+        let by_height = matches!(sort_by, BlockHeightAsc | BlockHeightDesc);
+        let direction = match sort_by {
+            BlockHeightAsc | DateTimeAsc | GlobalSlotAsc => Direction::Forward,
+            BlockHeightDesc | DateTimeDesc | GlobalSlotDesc => Direction::Reverse,
+        };
+
+        // sender/receiver account
+        let pk = match query
+            .as_ref()
+            .and_then(|q| q.from.as_ref().or(q.to.as_ref()))
+        {
+            Some(pk) => match PublicKey::new(pk) {
+                Ok(pk) => Some(pk),
+                Err(_) => {
+                    return Err(async_graphql::Error::new(format!(
+                        "Invalid public key: {}",
+                        pk
+                    )))
+                }
+            },
+            None => None,
+        };
+        let from = query.as_ref().is_some_and(|q| q.from.is_some());
+        let zkapp = query.as_ref().and_then(|q| q.zkapp).unwrap_or_default();
+
+        let (sort, mut iter, total_count) = match pk.as_ref() {
+            Some(pk) => {
+                let total_count = db.get_user_commands_pk_total_count(pk)?;
+                match (from, by_height) {
+                    (true, true) => (
+                        "txn-from-height-sort",
+                        db.txn_from_height_iterator(pk, direction),
+                        total_count,
+                    ),
+                    (true, false) => (
+                        "txn-from-slot-sort",
+                        db.txn_from_slot_iterator(pk, direction),
+                        total_count,
+                    ),
+                    (false, true) => (
+                        "txn-to-height-sort",
+                        db.txn_to_height_iterator(pk, direction),
+                        total_count,
+                    ),
+                    (false, false) => (
+                        "txn-to-slot-sort",
+                        db.txn_to_slot_iterator(pk, direction),
+                        total_count,
+                    ),
+                }
+            }
+            None => {
+                let mode = match direction {
+                    Direction::Forward => IteratorMode::Start,
+                    Direction::Reverse => IteratorMode::End,
+                };
+
+                match (zkapp, by_height) {
+                    (false, true) => (
+                        "user-commands-height-sort",
+                        db.user_commands_height_iterator(mode),
+                        num_commands[1],
+                    ),
+                    (false, false) => (
+                        "user-commands-slot-sort",
+                        db.user_commands_slot_iterator(mode),
+                        num_commands[1],
+                    ),
+                    (true, true) => (
+                        "zkapp-commands-height-sort",
+                        db.zkapp_commands_height_iterator(mode),
+                        num_commands[3],
+                    ),
+                    (true, false) => (
+                        "zkapp-commands-slot-sort",
+                        db.zkapp_commands_slot_iterator(mode),
+                        num_commands[3],
+                    ),
+                }
+            }
+        };
+
+        let page = Page::new(sort, direction, after, first)?;
+        page.seek(&mut iter);
+
+        // sender/receiver keys are prefixed by the public key
+        let prefix = pk.as_ref().map(|pk| pk.0.as_bytes()).unwrap_or_default();
+        let entries = iter
+            .flatten()
+            .take_while(|(key, _)| key.starts_with(prefix));
+
+        Ok(page.collect(entries, total_count, |key, value| {
+            let state_hash = if pk.is_some() {
+                state_hash_suffix(key)?
+            } else {
+                user_commands_iterator_state_hash(key)?
+            };
+
+            if let Some(query_canonicity) = query.as_ref().and_then(|q| q.canonical) {
+                if get_block_canonicity(db, &state_hash) != query_canonicity {
+                    return Ok(vec![]);
+                }
+            }
+
+            let txn = Transaction::new(serde_json::from_slice(value)?, db, num_commands);
+            Ok(query
+                .as_ref()
+                .is_none_or(|q| q.matches(&txn))
+                .then_some(txn)
+                .into_iter()
+                .collect())
+        })?)
+    }
 }
 
 impl Transaction {
@@ -501,12 +642,18 @@ impl TransactionWithoutBlock {
             }
         };
         let is_applied = failure_reason.is_none();
+        let invalid_signature = db
+            .get_invalid_signature(&cmd.txn_hash, &cmd.state_hash)
+            .ok()
+            .flatten()
+            .is_some();
 
         Self {
             zkapp,
             canonical,
             is_applied,
             failure_reason,
+            invalid_signature,
             amount: cmd.command.amount(),
             block_height: cmd.blockchain_length,
             global_slot: cmd.global_slot_since_genesis,
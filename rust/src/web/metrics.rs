@@ -0,0 +1,15 @@
+//! Prometheus `/metrics` endpoint
+
+use crate::store::IndexerStore;
+use actix_web::{get, web::Data, HttpResponse};
+use std::sync::Arc;
+
+/// Prometheus text exposition format
+const CONTENT_TYPE: &str = "text/plain; version=0.0.4";
+
+#[get("/metrics")]
+pub async fn get_metrics(store: Data<Arc<IndexerStore>>) -> HttpResponse {
+    HttpResponse::Ok()
+        .content_type(CONTENT_TYPE)
+        .body(store.metrics.render(&store))
+}
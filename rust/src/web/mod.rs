@@ -1,50 +1,61 @@
 mod common;
 pub mod graphql;
+mod metrics;
 pub mod rest;
+pub mod rosetta;
 
 pub const ENDPOINT_GRAPHQL: &str = "/graphql";
+pub const ENDPOINT_ROSETTA: &str = "/rosetta";
 
 use self::{
-    graphql::{build_schema, indexer_graphiql},
-    rest::{accounts, blockchain, blocks, locked_balances::LockedBalances},
+    graphql::{build_schema, indexer_graphiql, indexer_subscriptions},
+    rest::{accounts, blockchain, blocks, v1},
 };
-use crate::store::IndexerStore;
+use crate::{chain::Network, state::missing::SharedMissingBlocks, store::IndexerStore};
 use actix_cors::Cors;
 use actix_web::{guard, middleware, web, web::Data, App, HttpServer};
 use async_graphql_actix_web::GraphQL;
-use log::warn;
 use std::{net, sync::Arc};
 use tokio_graceful_shutdown::{FutureExt, SubsystemHandle};
 
-fn load_locked_balances() -> LockedBalances {
-    match LockedBalances::new() {
-        Ok(locked_balances) => locked_balances,
-        Err(e) => {
-            warn!("locked supply csv ingestion failed. {}", e);
-            LockedBalances::default()
-        }
-    }
-}
-
 pub async fn start_web_server<A: net::ToSocketAddrs>(
     subsys: SubsystemHandle,
     state: Arc<IndexerStore>,
+    missing_blocks: SharedMissingBlocks,
+    network: Network,
     addrs: A,
 ) -> anyhow::Result<()> {
-    let locked = Arc::new(load_locked_balances());
+    let schema = build_schema(state.clone(), missing_blocks);
+    let rosetta_config = rosetta::RosettaConfig::new(network);
 
     let _ = HttpServer::new(move || {
         App::new()
             .app_data(Data::new(state.clone()))
-            .app_data(Data::new(locked.clone()))
+            .app_data(Data::new(schema.clone()))
             .service(blocks::get_blocks)
             .service(blocks::get_block_by_state_hash)
             .service(accounts::get_account)
             .service(blockchain::get_blockchain_summary)
+            .service(metrics::get_metrics)
+            .service(
+                web::scope(ENDPOINT_ROSETTA)
+                    .configure(|cfg| rosetta::configure(cfg, rosetta_config.clone())),
+            )
+            .service(
+                web::scope(v1::ENDPOINT_API_V1)
+                    .configure(v1::configure)
+                    .default_service(web::to(v1::not_found)),
+            )
             .service(
                 web::resource(ENDPOINT_GRAPHQL)
                     .guard(guard::Post())
-                    .to(GraphQL::new(build_schema(state.clone()))),
+                    .to(GraphQL::new(schema.clone())),
+            )
+            .service(
+                web::resource(ENDPOINT_GRAPHQL)
+                    .guard(guard::Get())
+                    .guard(guard::Header("upgrade", "websocket"))
+                    .to(indexer_subscriptions),
             )
             .service(
                 web::resource(ENDPOINT_GRAPHQL)
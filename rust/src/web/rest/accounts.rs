@@ -1,10 +1,16 @@
 use crate::{
-    base::public_key::PublicKey,
+    address_book::store::AddressBookStore,
+    base::{public_key::PublicKey, state_hash::StateHash},
     block::store::BlockStore,
+    canonicity::store::CanonicityStore,
     command::{internal::store::InternalCommandStore, store::UserCommandStore},
-    ledger::{account, store::best::BestLedgerStore, token::TokenAddress},
+    ledger::{
+        account,
+        store::{best::BestLedgerStore, staged::StagedLedgerStore},
+        token::TokenAddress,
+    },
     snark_work::store::SnarkStore,
-    store::{username::UsernameStore, IndexerStore},
+    store::IndexerStore,
 };
 use actix_web::{
     get,
@@ -46,20 +52,42 @@ struct Account {
     total_num_internal_commands: u32,
 }
 
+/// Account at a specific block
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct HistoricalAccount {
+    #[serde(flatten)]
+    account: account::Account,
+
+    state_hash: StateHash,
+    block_height: u32,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct AccountQuery {
+    /// Canonical block height or block state hash
+    block: Option<String>,
+}
+
 #[get("/accounts/{public_key}")]
 pub async fn get_account(
     store: Data<Arc<IndexerStore>>,
     public_key: web::Path<String>,
+    query: web::Query<AccountQuery>,
 ) -> HttpResponse {
     let db = store.as_ref();
     let pk: PublicKey = public_key.clone().into();
 
+    // historical account
+    if let Some(block) = query.block.as_ref() {
+        return get_historical_account(db, &pk, block);
+    }
+
     if let Ok(Some(account)) = db.get_best_account(&pk, &TokenAddress::default()) {
         debug!("Found account in ledger: {account}");
 
         let account = Account {
             account: account::Account {
-                username: db.get_username(&pk).unwrap_or_default(),
+                username: db.resolve_username(&pk).unwrap_or_default(),
                 ..account
             },
 
@@ -121,3 +149,43 @@ pub async fn get_account(
 
     HttpResponse::NotFound().finish()
 }
+
+/// Serve `pk`'s MINA account at the block with the given canonical height or
+/// state hash
+fn get_historical_account(db: &Arc<IndexerStore>, pk: &PublicKey, block: &str) -> HttpResponse {
+    let state_hash = match block.parse::<u32>() {
+        Ok(height) => db.get_canonical_hash_at_height(height),
+        Err(_) => match StateHash::new(block) {
+            Ok(state_hash) => Ok(Some(state_hash)),
+            Err(_) => {
+                return HttpResponse::BadRequest()
+                    .body(format!("Invalid block height or state hash: {block}"))
+            }
+        },
+    };
+
+    let (state_hash, block_height) = match state_hash {
+        Ok(Some(state_hash)) => match db.get_block_height(&state_hash) {
+            Ok(Some(block_height)) => (state_hash, block_height),
+            _ => return HttpResponse::NotFound().finish(),
+        },
+        _ => return HttpResponse::NotFound().finish(),
+    };
+
+    if let Ok(Some(account)) =
+        db.get_staged_account_display(pk, &TokenAddress::default(), &state_hash)
+    {
+        debug!("Found account in {state_hash} staged ledger: {account}");
+
+        return HttpResponse::Ok().content_type(ContentType::json()).body(
+            serde_json::to_string_pretty(&HistoricalAccount {
+                account,
+                state_hash,
+                block_height,
+            })
+            .expect("serde historical account bytes"),
+        );
+    }
+
+    HttpResponse::NotFound().finish()
+}
@@ -1,21 +1,21 @@
 use crate::{
-    base::{amount::Amount, state_hash::StateHash},
+    base::state_hash::StateHash,
     block::{precomputed::PrecomputedBlock, store::BlockStore},
     chain::{store::ChainStore, ChainId},
     command::{internal::store::InternalCommandStore, store::UserCommandStore},
     constants::{MAINNET_EPOCH_SLOT_COUNT, VERSION},
-    ledger::store::best::BestLedgerStore,
+    ledger::{store::best::BestLedgerStore, supply::Supply},
     snark_work::store::SnarkStore,
     store::{
         version::{IndexerStoreVersion, VersionStore},
         IndexerStore,
     },
     utility::functions::nanomina_to_mina,
-    web::{common::unique_block_producers_last_n_blocks, rest::locked_balances::LockedBalances},
+    web::common::unique_block_producers_last_n_blocks,
 };
 use actix_web::{get, http::header::ContentType, web::Data, HttpResponse};
 use chrono::DateTime;
-use log::trace;
+use log::{error, trace};
 use serde::Serialize;
 use std::sync::Arc;
 
@@ -47,6 +47,7 @@ pub struct BlockchainSummary {
     // currency
     total_currency: String,
     locked_supply: String,
+    liquid_supply: String,
     circulating_supply: String,
 
     // accounts
@@ -139,7 +140,7 @@ struct SummaryInput {
     genesis_state_hash: StateHash,
 
     best_tip: PrecomputedBlock,
-    locked_balance: Option<Amount>,
+    supply: Option<Supply>,
 
     // accounts
     total_num_accounts: u32,
@@ -208,7 +209,7 @@ impl BlockchainSummary {
             genesis_state_hash,
 
             best_tip,
-            locked_balance,
+            supply,
 
             total_num_accounts,
             total_num_mina_accounts,
@@ -265,10 +266,19 @@ impl BlockchainSummary {
         let staking_epoch_ledger_hash = best_tip.staking_epoch_ledger_hash().0;
         let state_hash = best_tip.state_hash().0;
         let total_currency_u64 = best_tip.total_currency();
-        let locked_currency_u64 = locked_balance.map(|a| a.0).unwrap_or_default();
         let total_currency = nanomina_to_mina(total_currency_u64);
-        let circulating_supply = nanomina_to_mina(total_currency_u64 - locked_currency_u64);
-        let locked_supply = nanomina_to_mina(locked_currency_u64);
+        let (locked_supply, liquid_supply, circulating_supply) = match supply {
+            Some(supply) => (
+                nanomina_to_mina(supply.locked),
+                nanomina_to_mina(supply.liquid),
+                nanomina_to_mina(supply.circulating),
+            ),
+            None => (
+                nanomina_to_mina(0),
+                nanomina_to_mina(0),
+                total_currency.clone(),
+            ),
+        };
         let db_version = db_version.to_string();
 
         Some(Self {
@@ -292,6 +302,7 @@ impl BlockchainSummary {
 
             total_currency,
             locked_supply,
+            liquid_supply,
             circulating_supply,
 
             total_num_accounts,
@@ -337,10 +348,7 @@ impl BlockchainSummary {
 }
 
 #[get("/summary")]
-pub async fn get_blockchain_summary(
-    store: Data<Arc<IndexerStore>>,
-    locked_balances: Data<Arc<LockedBalances>>,
-) -> HttpResponse {
+pub async fn get_blockchain_summary(store: Data<Arc<IndexerStore>>) -> HttpResponse {
     let db = store.as_ref();
     if let Ok(Some(best_tip)) = db.get_best_block() {
         trace!("Found best tip: {}", best_tip.summary());
@@ -363,15 +371,20 @@ pub async fn get_blockchain_summary(
             .expect("num mina zkapp accounts")
             .unwrap_or_default();
 
-        // aggregated on-chain & off-chain time-locked tokens
+        // time-locked tokens computed from the best ledger
         let chain_id = store.get_chain_id().expect("chain id");
         let genesis_state_hash = store
             .get_block_genesis_state_hash(&best_tip.state_hash())
             .unwrap()
             .expect("genesis state hash");
 
-        let global_slot = best_tip.global_slot_since_genesis();
-        let locked_balance = locked_balances.get_locked_amount(global_slot);
+        let supply = match Supply::at_best_tip(db) {
+            Ok(supply) => supply,
+            Err(e) => {
+                error!("Failed to compute the best tip supply: {e}");
+                return HttpResponse::InternalServerError().body(e.to_string());
+            }
+        };
 
         // version info
         let db_version = store.get_db_version().expect("store version");
@@ -480,7 +493,7 @@ pub async fn get_blockchain_summary(
             genesis_state_hash,
 
             best_tip,
-            locked_balance,
+            supply,
             db_version,
             indexer_version,
 
@@ -29,7 +29,7 @@ fn get_limit(limit: Option<u32>) -> u32 {
 }
 
 fn format_blocks(blocks: Vec<Block>) -> String {
-    format!("{blocks:#?}").replace(",\n]", "\n]")
+    serde_json::to_string_pretty(&blocks).expect("serde blocks")
 }
 
 #[get("/blocks")]
@@ -105,7 +105,7 @@ pub async fn get_block_by_state_hash(
                 Block::from_precomputed(db, block, get_counts(db, None, None).expect("counts"));
             return HttpResponse::Ok()
                 .content_type(ContentType::json())
-                .body(format!("{block:?}"));
+                .body(serde_json::to_string_pretty(&block).expect("serde block"));
         }
     }
 
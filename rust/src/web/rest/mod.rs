@@ -1,4 +1,4 @@
 pub mod accounts;
 pub mod blockchain;
 pub mod blocks;
-pub mod locked_balances;
+pub mod v1;
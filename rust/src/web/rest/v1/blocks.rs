@@ -0,0 +1,154 @@
+//! `/api/v1/blocks` endpoints
+
+use super::{
+    openapi::{Endpoint, Location, Param, Schema},
+    parse_state_hash, parse_u32, respond, types, ApiError, Page, LIMIT, OFFSET,
+};
+use crate::{
+    base::state_hash::StateHash,
+    block::{precomputed::PrecomputedBlock, store::BlockStore},
+    canonicity::store::CanonicityStore,
+    command::{internal::store::InternalCommandStore, store::UserCommandStore},
+    constants::millis_to_iso_date_string,
+    snark_work::store::SnarkStore,
+    store::IndexerStore,
+};
+use actix_web::{
+    web::{self, Data},
+    HttpResponse,
+};
+use std::sync::Arc;
+
+pub const STATE_HASH: Param = Param {
+    name: "state_hash",
+    location: Location::Path,
+    description: "Block state hash",
+    schema: String::schema,
+};
+
+pub const BLOCKS_AT_HEIGHT: Endpoint = Endpoint {
+    path: "/blocks/height/{height}",
+    operation_id: "getBlocksAtHeight",
+    tag: "blocks",
+    summary: "Blocks at a blockchain length",
+    params: &[
+        Param {
+            name: "height",
+            location: Location::Path,
+            description: "Blockchain length",
+            schema: u32::schema,
+        },
+        LIMIT,
+        OFFSET,
+    ],
+    response: Vec::<types::Block>::schema,
+};
+
+pub const BLOCKS_AT_SLOT: Endpoint = Endpoint {
+    path: "/blocks/slot/{slot}",
+    operation_id: "getBlocksAtSlot",
+    tag: "blocks",
+    summary: "Blocks at a global slot since genesis",
+    params: &[
+        Param {
+            name: "slot",
+            location: Location::Path,
+            description: "Global slot since genesis",
+            schema: u32::schema,
+        },
+        LIMIT,
+        OFFSET,
+    ],
+    response: Vec::<types::Block>::schema,
+};
+
+pub const BLOCK: Endpoint = Endpoint {
+    path: "/blocks/{state_hash}",
+    operation_id: "getBlock",
+    tag: "blocks",
+    summary: "Block by state hash",
+    params: &[STATE_HASH],
+    response: types::Block::reference,
+};
+
+pub async fn blocks_at_height(
+    store: Data<Arc<IndexerStore>>,
+    height: web::Path<String>,
+    page: web::Query<Page>,
+) -> HttpResponse {
+    respond(parse_u32("height", &height).and_then(|height| {
+        let state_hashes = store.get_blocks_at_height(height)?;
+        get_blocks(store.as_ref(), page.apply(state_hashes))
+    }))
+}
+
+pub async fn blocks_at_slot(
+    store: Data<Arc<IndexerStore>>,
+    slot: web::Path<String>,
+    page: web::Query<Page>,
+) -> HttpResponse {
+    respond(parse_u32("slot", &slot).and_then(|slot| {
+        let state_hashes = store.get_blocks_at_slot(slot)?;
+        get_blocks(store.as_ref(), page.apply(state_hashes))
+    }))
+}
+
+pub async fn block(store: Data<Arc<IndexerStore>>, state_hash: web::Path<String>) -> HttpResponse {
+    respond(parse_state_hash(&state_hash).and_then(|state_hash| {
+        match store.get_block(&state_hash)? {
+            Some((block, _)) => Ok(to_block(store.as_ref(), &block)?),
+            None => Err(ApiError::NotFound(format!("Block {state_hash}"))),
+        }
+    }))
+}
+
+fn get_blocks(
+    db: &Arc<IndexerStore>,
+    state_hashes: Vec<StateHash>,
+) -> Result<Vec<types::Block>, ApiError> {
+    let mut blocks = Vec::with_capacity(state_hashes.len());
+    for state_hash in state_hashes {
+        if let Some((block, _)) = db.get_block(&state_hash)? {
+            blocks.push(to_block(db, &block)?);
+        }
+    }
+
+    Ok(blocks)
+}
+
+/// Checks the block is in the store
+pub fn check_block(db: &Arc<IndexerStore>, state_hash: &StateHash) -> Result<(), ApiError> {
+    match db.get_block_height(state_hash)? {
+        Some(_) => Ok(()),
+        None => Err(ApiError::NotFound(format!("Block {state_hash}"))),
+    }
+}
+
+fn to_block(db: &Arc<IndexerStore>, block: &PrecomputedBlock) -> anyhow::Result<types::Block> {
+    let state_hash = block.state_hash();
+    let canonicity = db
+        .get_block_canonicity(&state_hash)?
+        .map(|canonicity| format!("{canonicity:?}"))
+        .unwrap_or_default();
+
+    Ok(types::Block {
+        previous_state_hash: block.previous_state_hash().0,
+        genesis_state_hash: block.genesis_state_hash().0,
+        blockchain_length: block.blockchain_length(),
+        global_slot_since_genesis: block.global_slot_since_genesis(),
+        epoch: block.epoch_count(),
+        date_time: millis_to_iso_date_string(block.timestamp() as i64),
+        block_creator: block.block_creator().0,
+        coinbase_receiver: block.coinbase_receiver().0,
+        canonicity,
+        total_currency: block.total_currency(),
+        user_commands_count: db
+            .get_block_user_commands_count(&state_hash)?
+            .unwrap_or_default(),
+        internal_commands_count: db
+            .get_block_internal_commands_count(&state_hash)?
+            .unwrap_or_default(),
+        snark_work_count: db.get_block_snarks_count(&state_hash)?.unwrap_or_default(),
+        state_hash: state_hash.0,
+    })
+}
@@ -0,0 +1,263 @@
+//! `/api/v1` user & internal command endpoints
+
+use super::{
+    blocks::{check_block, STATE_HASH},
+    openapi::{Endpoint, Location, Param, Schema},
+    parse_public_key, parse_state_hash, respond, types, ApiError, Page, LIMIT, OFFSET,
+};
+use crate::{
+    base::state_hash::StateHash,
+    command::{
+        internal::{store::InternalCommandStore, DbInternalCommandWithData},
+        signed::{SignedCommandWithData, TxnHash},
+        store::UserCommandStore,
+    },
+    constants::millis_to_iso_date_string,
+    store::IndexerStore,
+};
+use actix_web::{
+    web::{self, Data},
+    HttpResponse,
+};
+use serde::Deserialize;
+use std::sync::Arc;
+
+pub const PUBLIC_KEY: Param = Param {
+    name: "public_key",
+    location: Location::Path,
+    description: "Account public key",
+    schema: String::schema,
+};
+
+/// Cursor query param of cursor-paginated endpoints
+#[derive(Debug, Default, Deserialize)]
+pub struct Cursor {
+    pub cursor: Option<String>,
+}
+
+pub const CURSOR: Param = Param {
+    name: "cursor",
+    location: Location::Query,
+    description: "Return results after this `{txn_hash}:{state_hash}` command",
+    schema: String::schema,
+};
+
+pub const USER_COMMAND: Endpoint = Endpoint {
+    path: "/user-commands/{txn_hash}",
+    operation_id: "getUserCommand",
+    tag: "user-commands",
+    summary: "User command by hash, once per containing block",
+    params: &[Param {
+        name: "txn_hash",
+        location: Location::Path,
+        description: "Transaction hash",
+        schema: String::schema,
+    }],
+    response: Vec::<types::UserCommand>::schema,
+};
+
+pub const ACCOUNT_USER_COMMANDS: Endpoint = Endpoint {
+    path: "/accounts/{public_key}/user-commands",
+    operation_id: "getAccountUserCommands",
+    tag: "user-commands",
+    summary: "User commands sent or received by an account, ascending by block height",
+    params: &[PUBLIC_KEY, LIMIT, OFFSET, CURSOR],
+    response: Vec::<types::UserCommand>::schema,
+};
+
+pub const BLOCK_INTERNAL_COMMANDS: Endpoint = Endpoint {
+    path: "/blocks/{state_hash}/internal-commands",
+    operation_id: "getBlockInternalCommands",
+    tag: "internal-commands",
+    summary: "Internal commands of a block",
+    params: &[STATE_HASH],
+    response: Vec::<types::InternalCommand>::schema,
+};
+
+pub const ACCOUNT_INTERNAL_COMMANDS: Endpoint = Endpoint {
+    path: "/accounts/{public_key}/internal-commands",
+    operation_id: "getAccountInternalCommands",
+    tag: "internal-commands",
+    summary: "Internal commands received by an account",
+    params: &[PUBLIC_KEY, LIMIT, OFFSET],
+    response: Vec::<types::InternalCommand>::schema,
+};
+
+pub async fn user_command(
+    store: Data<Arc<IndexerStore>>,
+    txn_hash: web::Path<String>,
+) -> HttpResponse {
+    respond(get_user_commands(store.as_ref(), &txn_hash))
+}
+
+fn get_user_commands(
+    db: &Arc<IndexerStore>,
+    txn_hash: &str,
+) -> Result<Vec<types::UserCommand>, ApiError> {
+    let txn_hash = TxnHash::new(txn_hash).map_err(|_| ApiError::InvalidParam {
+        name: "txn_hash",
+        value: txn_hash.to_string(),
+    })?;
+
+    let mut commands = vec![];
+    for state_hash in db
+        .get_user_command_state_hashes(&txn_hash)?
+        .unwrap_or_default()
+    {
+        if let Some(command) = db.get_user_command_state_hash(&txn_hash, &state_hash)? {
+            commands.push(to_user_command(command));
+        }
+    }
+
+    if commands.is_empty() {
+        return Err(ApiError::NotFound(format!(
+            "User command {}",
+            txn_hash.ref_inner()
+        )));
+    }
+    Ok(commands)
+}
+
+pub async fn account_user_commands(
+    store: Data<Arc<IndexerStore>>,
+    public_key: web::Path<String>,
+    page: web::Query<Page>,
+    cursor: web::Query<Cursor>,
+) -> HttpResponse {
+    respond(parse_public_key(&public_key).and_then(|pk| {
+        let cursor = match cursor.cursor.as_deref() {
+            Some(cursor) => {
+                let (txn_hash, state_hash) = parse_cursor(cursor)?;
+                if store
+                    .get_user_command_state_hash(&txn_hash, &state_hash)?
+                    .is_none()
+                {
+                    return Err(ApiError::InvalidParam {
+                        name: "cursor",
+                        value: cursor.to_string(),
+                    });
+                }
+
+                Some((txn_hash, state_hash))
+            }
+            None => None,
+        };
+        let commands = store.get_user_commands_for_public_key_page(
+            &pk,
+            cursor
+                .as_ref()
+                .map(|(txn_hash, state_hash)| (txn_hash, state_hash)),
+            page.offset.unwrap_or_default(),
+            page.limit(),
+        )?;
+
+        Ok(commands
+            .into_iter()
+            .map(to_user_command)
+            .collect::<Vec<_>>())
+    }))
+}
+
+/// Parses a `{txn_hash}:{state_hash}` cursor
+fn parse_cursor(cursor: &str) -> Result<(TxnHash, StateHash), ApiError> {
+    let invalid = || ApiError::InvalidParam {
+        name: "cursor",
+        value: cursor.to_string(),
+    };
+
+    let (txn_hash, state_hash) = cursor.split_once(':').ok_or_else(invalid)?;
+    Ok((
+        TxnHash::new(txn_hash).map_err(|_| invalid())?,
+        parse_state_hash(state_hash).map_err(|_| invalid())?,
+    ))
+}
+
+pub async fn block_internal_commands(
+    store: Data<Arc<IndexerStore>>,
+    state_hash: web::Path<String>,
+) -> HttpResponse {
+    let db = store.as_ref();
+    respond(parse_state_hash(&state_hash).and_then(|state_hash| {
+        check_block(db, &state_hash)?;
+
+        let commands = db.get_internal_commands(&state_hash)?;
+        Ok(commands
+            .into_iter()
+            .map(to_internal_command)
+            .collect::<Vec<_>>())
+    }))
+}
+
+pub async fn account_internal_commands(
+    store: Data<Arc<IndexerStore>>,
+    public_key: web::Path<String>,
+    page: web::Query<Page>,
+) -> HttpResponse {
+    respond(parse_public_key(&public_key).and_then(|pk| {
+        let commands = store.get_internal_commands_public_key(
+            &pk,
+            page.offset.unwrap_or_default(),
+            page.limit(),
+        )?;
+        Ok(commands
+            .into_iter()
+            .map(to_internal_command)
+            .collect::<Vec<_>>())
+    }))
+}
+
+fn to_user_command(command: SignedCommandWithData) -> types::UserCommand {
+    types::UserCommand {
+        txn_hash: command.txn_hash.inner(),
+        state_hash: command.state_hash.0,
+        blockchain_length: command.blockchain_length,
+        global_slot_since_genesis: command.global_slot_since_genesis,
+        date_time: millis_to_iso_date_string(command.date_time as i64),
+        kind: command.command.kind().to_string(),
+        sender: command.command.source_pk().0,
+        receivers: command
+            .command
+            .receiver_pk()
+            .into_iter()
+            .map(|pk| pk.0)
+            .collect(),
+        amount: command.command.amount(),
+        fee: command.command.fee(),
+        nonce: command.nonce.0,
+        memo: command.command.memo(),
+        status: if command.status.is_applied() {
+            "Applied"
+        } else {
+            "Failed"
+        }
+        .to_string(),
+    }
+}
+
+fn to_internal_command(command: DbInternalCommandWithData) -> types::InternalCommand {
+    match command {
+        DbInternalCommandWithData::FeeTransfer {
+            receiver,
+            amount,
+            state_hash,
+            kind,
+            date_time,
+            block_height,
+        }
+        | DbInternalCommandWithData::Coinbase {
+            receiver,
+            amount,
+            state_hash,
+            kind,
+            date_time,
+            block_height,
+        } => types::InternalCommand {
+            kind: kind.to_string(),
+            receiver: receiver.0,
+            amount,
+            state_hash: state_hash.0,
+            blockchain_length: block_height,
+            date_time: millis_to_iso_date_string(date_time),
+        },
+    }
+}
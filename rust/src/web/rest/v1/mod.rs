@@ -0,0 +1,307 @@
+//! Versioned REST API served under `/api/v1`
+//!
+//! All responses are serde-serialized JSON, failures have the same body
+//!
+//! ```json
+//! { "error": { "status": 404, "code": "not_found", "message": "..." } }
+//! ```
+//!
+//! The OpenAPI spec is generated from [ENDPOINTS] & served at
+//! `/api/v1/openapi.json`
+
+pub mod blocks;
+pub mod commands;
+pub mod openapi;
+pub mod snarks;
+pub mod staking;
+pub mod tokens;
+pub mod types;
+
+use crate::base::{public_key::PublicKey, state_hash::StateHash};
+use actix_web::{
+    error::InternalError,
+    http::{header::ContentType, StatusCode},
+    web, HttpResponse,
+};
+use openapi::{Endpoint, Location, Param, Schema};
+use serde::{Deserialize, Serialize};
+
+pub const ENDPOINT_API_V1: &str = "/api/v1";
+
+pub const DEFAULT_LIMIT: usize = 10;
+pub const MAX_LIMIT: usize = 100;
+
+/// Every `/api/v1` endpoint, in spec order
+pub const ENDPOINTS: &[Endpoint] = &[
+    blocks::BLOCKS_AT_HEIGHT,
+    blocks::BLOCKS_AT_SLOT,
+    blocks::BLOCK,
+    commands::USER_COMMAND,
+    commands::ACCOUNT_USER_COMMANDS,
+    commands::BLOCK_INTERNAL_COMMANDS,
+    commands::ACCOUNT_INTERNAL_COMMANDS,
+    snarks::BLOCK_SNARK_WORK,
+    snarks::ACCOUNT_SNARK_WORK,
+    staking::STAKING_LEDGER,
+    staking::STAKING_ACCOUNT,
+    tokens::TOKEN,
+    OPENAPI,
+];
+
+pub const OPENAPI: Endpoint = Endpoint {
+    path: "/openapi.json",
+    operation_id: "getOpenApiSpec",
+    tag: "meta",
+    summary: "This OpenAPI spec",
+    params: &[],
+    response: spec_schema,
+};
+
+fn spec_schema() -> serde_json::Value {
+    serde_json::json!({ "type": "object" })
+}
+
+/// Register all `/api/v1` endpoints
+pub fn configure(cfg: &mut web::ServiceConfig) {
+    cfg.app_data(web::QueryConfig::default().error_handler(|err, _| {
+        let message = err.to_string();
+        InternalError::from_response(err, respond_error(ApiError::InvalidQuery(message))).into()
+    }))
+    .route(
+        blocks::BLOCKS_AT_HEIGHT.path,
+        web::get().to(blocks::blocks_at_height),
+    )
+    .route(
+        blocks::BLOCKS_AT_SLOT.path,
+        web::get().to(blocks::blocks_at_slot),
+    )
+    .route(blocks::BLOCK.path, web::get().to(blocks::block))
+    .route(
+        commands::USER_COMMAND.path,
+        web::get().to(commands::user_command),
+    )
+    .route(
+        commands::ACCOUNT_USER_COMMANDS.path,
+        web::get().to(commands::account_user_commands),
+    )
+    .route(
+        commands::BLOCK_INTERNAL_COMMANDS.path,
+        web::get().to(commands::block_internal_commands),
+    )
+    .route(
+        commands::ACCOUNT_INTERNAL_COMMANDS.path,
+        web::get().to(commands::account_internal_commands),
+    )
+    .route(
+        snarks::BLOCK_SNARK_WORK.path,
+        web::get().to(snarks::block_snark_work),
+    )
+    .route(
+        snarks::ACCOUNT_SNARK_WORK.path,
+        web::get().to(snarks::account_snark_work),
+    )
+    .route(
+        staking::STAKING_LEDGER.path,
+        web::get().to(staking::staking_ledger),
+    )
+    .route(
+        staking::STAKING_ACCOUNT.path,
+        web::get().to(staking::staking_account),
+    )
+    .route(tokens::TOKEN.path, web::get().to(tokens::token))
+    .route(OPENAPI.path, web::get().to(openapi_spec));
+}
+
+#[derive(Debug)]
+pub enum ApiError {
+    InvalidParam { name: &'static str, value: String },
+    InvalidQuery(String),
+    NotFound(String),
+    Store(anyhow::Error),
+}
+
+/// Pagination query params of list endpoints
+#[derive(Debug, Default, Deserialize)]
+pub struct Page {
+    pub limit: Option<usize>,
+    pub offset: Option<usize>,
+}
+
+pub const LIMIT: Param = Param {
+    name: "limit",
+    location: Location::Query,
+    description: "Max number of results, default 10, at most 100",
+    schema: u32::schema,
+};
+
+pub const OFFSET: Param = Param {
+    name: "offset",
+    location: Location::Query,
+    description: "Number of results to skip",
+    schema: u32::schema,
+};
+
+//////////
+// impl //
+//////////
+
+impl ApiError {
+    pub fn status(&self) -> StatusCode {
+        match self {
+            Self::InvalidParam { .. } | Self::InvalidQuery(_) => StatusCode::BAD_REQUEST,
+            Self::NotFound(_) => StatusCode::NOT_FOUND,
+            Self::Store(_) => StatusCode::INTERNAL_SERVER_ERROR,
+        }
+    }
+
+    pub fn code(&self) -> &'static str {
+        match self {
+            Self::InvalidParam { .. } | Self::InvalidQuery(_) => "invalid_parameter",
+            Self::NotFound(_) => "not_found",
+            Self::Store(_) => "store_error",
+        }
+    }
+
+    pub fn to_body(&self) -> types::ErrorBody {
+        types::ErrorBody {
+            error: types::Error {
+                status: self.status().as_u16(),
+                code: self.code().to_string(),
+                message: self.to_string(),
+            },
+        }
+    }
+}
+
+impl Page {
+    /// Applies the page to `items`
+    pub fn apply<T>(&self, items: impl IntoIterator<Item = T>) -> Vec<T> {
+        items
+            .into_iter()
+            .skip(self.offset.unwrap_or_default())
+            .take(self.limit())
+            .collect()
+    }
+
+    pub fn limit(&self) -> usize {
+        self.limit
+            .map_or(DEFAULT_LIMIT, |limit| limit.min(MAX_LIMIT))
+    }
+}
+
+/// Serializes the response body, errors get their status & error body
+pub fn respond<T: Serialize>(res: Result<T, ApiError>) -> HttpResponse {
+    match res {
+        Ok(body) => HttpResponse::Ok()
+            .content_type(ContentType::json())
+            .body(serde_json::to_string(&body).expect("serde api response")),
+        Err(err) => respond_error(err),
+    }
+}
+
+fn respond_error(err: ApiError) -> HttpResponse {
+    HttpResponse::build(err.status())
+        .content_type(ContentType::json())
+        .body(serde_json::to_string(&err.to_body()).expect("serde api error"))
+}
+
+/// Fallback for unknown `/api/v1` paths
+pub async fn not_found(req: actix_web::HttpRequest) -> HttpResponse {
+    respond_error(ApiError::NotFound(format!("Endpoint {}", req.path())))
+}
+
+async fn openapi_spec() -> HttpResponse {
+    respond(Ok(openapi::spec()))
+}
+
+////////////
+// params //
+////////////
+
+pub fn parse_u32(name: &'static str, value: &str) -> Result<u32, ApiError> {
+    value.parse().map_err(|_| ApiError::InvalidParam {
+        name,
+        value: value.to_string(),
+    })
+}
+
+pub fn parse_state_hash(value: &str) -> Result<StateHash, ApiError> {
+    StateHash::new(value).map_err(|_| ApiError::InvalidParam {
+        name: "state_hash",
+        value: value.to_string(),
+    })
+}
+
+pub fn parse_public_key(value: &str) -> Result<PublicKey, ApiError> {
+    PublicKey::new(value).map_err(|_| ApiError::InvalidParam {
+        name: "public_key",
+        value: value.to_string(),
+    })
+}
+
+/////////////////
+// conversions //
+/////////////////
+
+impl From<anyhow::Error> for ApiError {
+    fn from(value: anyhow::Error) -> Self {
+        Self::Store(value)
+    }
+}
+
+/////////////
+// display //
+/////////////
+
+impl std::fmt::Display for ApiError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::InvalidParam { name, value } => write!(f, "Invalid {name}: {value}"),
+            Self::InvalidQuery(msg) => write!(f, "Invalid query: {msg}"),
+            Self::NotFound(what) => write!(f, "{what} not found"),
+            Self::Store(e) => write!(f, "Store error: {e}"),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn error_bodies() {
+        let err = parse_u32("height", "tip").unwrap_err();
+        assert_eq!(err.status(), StatusCode::BAD_REQUEST);
+
+        let body = serde_json::to_value(err.to_body()).unwrap();
+        assert_eq!(
+            body,
+            serde_json::json!({
+                "error": {
+                    "status": 400,
+                    "code": "invalid_parameter",
+                    "message": "Invalid height: tip",
+                }
+            })
+        );
+
+        let err = ApiError::NotFound("Block 3NK...".into());
+        assert_eq!(err.to_body().error.status, 404);
+        assert_eq!(err.to_body().error.message, "Block 3NK... not found");
+    }
+
+    #[test]
+    fn pages() {
+        let items = 0..200;
+        assert_eq!(
+            Page::default().apply(items.clone()),
+            (0..10).collect::<Vec<_>>()
+        );
+
+        let page = Page {
+            limit: Some(500),
+            offset: Some(150),
+        };
+        assert_eq!(page.apply(items), (150..200).collect::<Vec<_>>());
+    }
+}
@@ -0,0 +1,256 @@
+//! OpenAPI spec generation
+//!
+//! The spec is built from the same [Endpoint] table the routes are registered
+//! from & the [Schema]s of the response types, so it can't drift from the API
+
+use super::{types, ENDPOINTS, ENDPOINT_API_V1};
+use serde_json::{json, Map, Value};
+
+pub const OPENAPI_VERSION: &str = "3.0.3";
+pub const OPENAPI_TITLE: &str = "Mina Indexer REST API";
+
+/// JSON schema of a response type
+pub trait Schema {
+    /// The type's own schema
+    fn schema() -> Value;
+
+    /// Schema used wherever the type appears, a reference for named types
+    fn reference() -> Value {
+        Self::schema()
+    }
+
+    /// Whether an object field of this type is required
+    fn required() -> bool {
+        true
+    }
+}
+
+/// Reference to the named schema in the spec's components
+pub fn reference(name: &str) -> Value {
+    json!({ "$ref": format!("#/components/schemas/{name}") })
+}
+
+/// A `GET` endpoint, relative to [ENDPOINT_API_V1]
+pub struct Endpoint {
+    pub path: &'static str,
+    pub operation_id: &'static str,
+    pub tag: &'static str,
+    pub summary: &'static str,
+    pub params: &'static [Param],
+    pub response: fn() -> Value,
+}
+
+pub struct Param {
+    pub name: &'static str,
+    pub location: Location,
+    pub description: &'static str,
+    pub schema: fn() -> Value,
+}
+
+pub enum Location {
+    Path,
+    Query,
+}
+
+//////////
+// impl //
+//////////
+
+impl Param {
+    fn to_spec(&self) -> Value {
+        let (location, required) = match self.location {
+            Location::Path => ("path", true),
+            Location::Query => ("query", false),
+        };
+
+        json!({
+            "name": self.name,
+            "in": location,
+            "required": required,
+            "description": self.description,
+            "schema": (self.schema)(),
+        })
+    }
+}
+
+impl Endpoint {
+    fn to_spec(&self) -> Value {
+        let error = json!({
+            "content": {
+                "application/json": { "schema": types::ErrorBody::reference() }
+            }
+        });
+        let with_description = |description: &str| {
+            let mut response = error.clone();
+            response["description"] = description.into();
+            response
+        };
+
+        json!({
+            "get": {
+                "operationId": self.operation_id,
+                "tags": [self.tag],
+                "summary": self.summary,
+                "parameters": self.params.iter().map(Param::to_spec).collect::<Vec<_>>(),
+                "responses": {
+                    "200": {
+                        "description": "Success",
+                        "content": {
+                            "application/json": { "schema": (self.response)() }
+                        }
+                    },
+                    "400": with_description("Invalid parameter"),
+                    "404": with_description("Not found"),
+                    "500": with_description("Store error"),
+                }
+            }
+        })
+    }
+}
+
+/// The OpenAPI spec of all `/api/v1` endpoints
+pub fn spec() -> Value {
+    let paths: Map<String, Value> = ENDPOINTS
+        .iter()
+        .map(|endpoint| (endpoint.path.to_string(), endpoint.to_spec()))
+        .collect();
+    let schemas: Map<String, Value> = types::SCHEMAS
+        .iter()
+        .map(|(name, schema)| (name.to_string(), schema()))
+        .collect();
+
+    json!({
+        "openapi": OPENAPI_VERSION,
+        "info": {
+            "title": OPENAPI_TITLE,
+            "version": env!("CARGO_PKG_VERSION"),
+        },
+        "servers": [{ "url": ENDPOINT_API_V1 }],
+        "paths": paths,
+        "components": { "schemas": schemas },
+    })
+}
+
+/////////////////////
+// primitive impls //
+/////////////////////
+
+impl Schema for String {
+    fn schema() -> Value {
+        json!({ "type": "string" })
+    }
+}
+
+impl Schema for u32 {
+    fn schema() -> Value {
+        json!({ "type": "integer", "format": "int64", "minimum": 0 })
+    }
+}
+
+impl Schema for u64 {
+    fn schema() -> Value {
+        json!({ "type": "integer", "format": "int64", "minimum": 0 })
+    }
+}
+
+impl Schema for u16 {
+    fn schema() -> Value {
+        json!({ "type": "integer", "format": "int32", "minimum": 0 })
+    }
+}
+
+impl<T: Schema> Schema for Option<T> {
+    fn schema() -> Value {
+        let mut schema = T::reference();
+
+        // sibling keys of `$ref` are ignored
+        if schema.get("$ref").is_some() {
+            return json!({ "allOf": [schema], "nullable": true });
+        }
+
+        if let Some(object) = schema.as_object_mut() {
+            object.insert("nullable".into(), true.into());
+        }
+        schema
+    }
+
+    fn required() -> bool {
+        false
+    }
+}
+
+impl<T: Schema> Schema for Vec<T> {
+    fn schema() -> Value {
+        json!({ "type": "array", "items": T::reference() })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::HashSet;
+
+    /// Collects all `$ref` targets in `value`
+    fn refs(value: &Value, acc: &mut Vec<String>) {
+        match value {
+            Value::Object(object) => {
+                if let Some(Value::String(target)) = object.get("$ref") {
+                    acc.push(target.clone());
+                }
+                object.values().for_each(|value| refs(value, acc));
+            }
+            Value::Array(values) => values.iter().for_each(|value| refs(value, acc)),
+            _ => (),
+        }
+    }
+
+    #[test]
+    fn spec_refs_resolve() {
+        let spec = spec();
+        let mut targets = vec![];
+        refs(&spec, &mut targets);
+
+        assert!(!targets.is_empty());
+        for target in targets {
+            let name = target.strip_prefix("#/components/schemas/").unwrap();
+            assert!(
+                spec["components"]["schemas"].get(name).is_some(),
+                "unresolved {target}"
+            );
+        }
+    }
+
+    #[test]
+    fn spec_paths() {
+        let spec = spec();
+        let paths = spec["paths"].as_object().unwrap();
+        assert_eq!(paths.len(), ENDPOINTS.len());
+
+        let operation_ids: HashSet<_> = ENDPOINTS.iter().map(|e| e.operation_id).collect();
+        assert_eq!(operation_ids.len(), ENDPOINTS.len());
+
+        // every path param is declared
+        for endpoint in ENDPOINTS {
+            for segment in endpoint.path.split('/') {
+                if let Some(name) = segment.strip_prefix('{') {
+                    let name = name.trim_end_matches('}');
+                    assert!(
+                        endpoint.params.iter().any(|param| param.name == name),
+                        "{} {name}",
+                        endpoint.path
+                    );
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn optional_fields() {
+        assert!(!Option::<u32>::required());
+        assert_eq!(Option::<u32>::schema()["nullable"], true);
+        assert_eq!(
+            Option::<types::Block>::schema()["allOf"][0],
+            reference("Block")
+        );
+    }
+}
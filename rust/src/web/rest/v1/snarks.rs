@@ -0,0 +1,67 @@
+//! `/api/v1` SNARK work endpoints
+
+use super::{
+    blocks::{check_block, STATE_HASH},
+    commands::PUBLIC_KEY,
+    openapi::{Endpoint, Schema},
+    parse_public_key, parse_state_hash, respond, types, Page, LIMIT, OFFSET,
+};
+use crate::{snark_work::store::SnarkStore, store::IndexerStore};
+use actix_web::{
+    web::{self, Data},
+    HttpResponse,
+};
+use std::sync::Arc;
+
+pub const BLOCK_SNARK_WORK: Endpoint = Endpoint {
+    path: "/blocks/{state_hash}/snark-work",
+    operation_id: "getBlockSnarkWork",
+    tag: "snark-work",
+    summary: "SNARK work bought in a block",
+    params: &[STATE_HASH],
+    response: Vec::<types::SnarkWork>::schema,
+};
+
+pub const ACCOUNT_SNARK_WORK: Endpoint = Endpoint {
+    path: "/accounts/{public_key}/snark-work",
+    operation_id: "getAccountSnarkWork",
+    tag: "snark-work",
+    summary: "SNARK work sold by a prover",
+    params: &[PUBLIC_KEY, LIMIT, OFFSET],
+    response: Vec::<types::SnarkWork>::schema,
+};
+
+pub async fn block_snark_work(
+    store: Data<Arc<IndexerStore>>,
+    state_hash: web::Path<String>,
+) -> HttpResponse {
+    let db = store.as_ref();
+    respond(parse_state_hash(&state_hash).and_then(|state_hash| {
+        check_block(db, &state_hash)?;
+
+        let snarks = db.get_block_snark_work(&state_hash)?.unwrap_or_default();
+        Ok(snarks
+            .into_iter()
+            .map(|snark| types::SnarkWork {
+                prover: snark.prover.0,
+                fee: snark.fee.0,
+                state_hash: state_hash.0.clone(),
+            })
+            .collect::<Vec<_>>())
+    }))
+}
+
+pub async fn account_snark_work(
+    store: Data<Arc<IndexerStore>>,
+    public_key: web::Path<String>,
+    page: web::Query<Page>,
+) -> HttpResponse {
+    respond(parse_public_key(&public_key).and_then(|pk| {
+        let snarks = store.get_snark_work_by_public_key(&pk)?;
+        Ok(page.apply(snarks.into_iter().map(|snark| types::SnarkWork {
+            prover: snark.prover.0,
+            fee: snark.fee.0,
+            state_hash: snark.state_hash.0,
+        })))
+    }))
+}
@@ -0,0 +1,139 @@
+//! `/api/v1/staking-ledgers` endpoints
+
+use super::{
+    commands::PUBLIC_KEY,
+    openapi::{Endpoint, Location, Param, Schema},
+    parse_public_key, parse_state_hash, parse_u32, respond, types, ApiError,
+};
+use crate::{
+    address_book::store::AddressBookStore, base::state_hash::StateHash, block::store::BlockStore,
+    ledger::store::staking::StakingLedgerStore, store::IndexerStore,
+};
+use actix_web::{
+    web::{self, Data},
+    HttpResponse,
+};
+use serde::Deserialize;
+use std::sync::Arc;
+
+const EPOCH: Param = Param {
+    name: "epoch",
+    location: Location::Path,
+    description: "Staking epoch",
+    schema: u32::schema,
+};
+
+const GENESIS_STATE_HASH: Param = Param {
+    name: "genesis_state_hash",
+    location: Location::Query,
+    description: "Genesis state hash of the chain, defaults to the best block's",
+    schema: String::schema,
+};
+
+pub const STAKING_LEDGER: Endpoint = Endpoint {
+    path: "/staking-ledgers/{epoch}",
+    operation_id: "getStakingLedger",
+    tag: "staking-ledgers",
+    summary: "Summary of an epoch's staking ledger",
+    params: &[EPOCH, GENESIS_STATE_HASH],
+    response: types::StakingLedger::reference,
+};
+
+pub const STAKING_ACCOUNT: Endpoint = Endpoint {
+    path: "/staking-ledgers/{epoch}/accounts/{public_key}",
+    operation_id: "getStakingAccount",
+    tag: "staking-ledgers",
+    summary: "Account in an epoch's staking ledger",
+    params: &[EPOCH, PUBLIC_KEY, GENESIS_STATE_HASH],
+    response: types::StakingAccount::reference,
+};
+
+#[derive(Debug, Default, Deserialize)]
+pub struct Genesis {
+    genesis_state_hash: Option<String>,
+}
+
+pub async fn staking_ledger(
+    store: Data<Arc<IndexerStore>>,
+    epoch: web::Path<String>,
+    genesis: web::Query<Genesis>,
+) -> HttpResponse {
+    respond(get_staking_ledger(store.as_ref(), &epoch, &genesis))
+}
+
+pub async fn staking_account(
+    store: Data<Arc<IndexerStore>>,
+    path: web::Path<(String, String)>,
+    genesis: web::Query<Genesis>,
+) -> HttpResponse {
+    let (epoch, public_key) = path.into_inner();
+    respond(get_staking_account(
+        store.as_ref(),
+        &epoch,
+        &public_key,
+        &genesis,
+    ))
+}
+
+fn get_staking_ledger(
+    db: &Arc<IndexerStore>,
+    epoch: &str,
+    genesis: &Genesis,
+) -> Result<types::StakingLedger, ApiError> {
+    let epoch = parse_u32("epoch", epoch)?;
+    let genesis_state_hash = genesis_state_hash(db, genesis)?;
+
+    let ledger_hash = db
+        .get_staking_ledger_hash_by_epoch(epoch, &genesis_state_hash)?
+        .ok_or_else(|| ApiError::NotFound(format!("Epoch {epoch} staking ledger")))?;
+
+    Ok(types::StakingLedger {
+        epoch,
+        total_currency: db.get_total_currency(&ledger_hash)?,
+        accounts_count: db.get_staking_ledger_accounts_count_epoch(epoch, &genesis_state_hash)?,
+        ledger_hash: ledger_hash.to_string(),
+        genesis_state_hash: genesis_state_hash.0,
+    })
+}
+
+fn get_staking_account(
+    db: &Arc<IndexerStore>,
+    epoch: &str,
+    public_key: &str,
+    genesis: &Genesis,
+) -> Result<types::StakingAccount, ApiError> {
+    let epoch = parse_u32("epoch", epoch)?;
+    let pk = parse_public_key(public_key)?;
+    let genesis_state_hash = genesis_state_hash(db, genesis)?;
+
+    let account = db
+        .get_staking_account(&pk, epoch, &genesis_state_hash)?
+        .ok_or_else(|| ApiError::NotFound(format!("Epoch {epoch} staking account {pk}")))?;
+
+    Ok(types::StakingAccount {
+        epoch,
+        balance: account.balance,
+        delegate: account.delegate.0,
+        nonce: account.nonce.map(|nonce| nonce.0),
+        username: db
+            .resolve_username(&pk)?
+            .map(|username| username.0)
+            .or(account.username),
+        public_key: pk.0,
+    })
+}
+
+/// The query's genesis state hash, defaults to the best block's
+fn genesis_state_hash(db: &Arc<IndexerStore>, genesis: &Genesis) -> Result<StateHash, ApiError> {
+    match genesis.genesis_state_hash.as_deref() {
+        Some(genesis_state_hash) => {
+            parse_state_hash(genesis_state_hash).map_err(|_| ApiError::InvalidParam {
+                name: "genesis_state_hash",
+                value: genesis_state_hash.to_string(),
+            })
+        }
+        None => db
+            .get_best_block_genesis_hash()?
+            .ok_or_else(|| ApiError::NotFound("Best block".to_string())),
+    }
+}
@@ -0,0 +1,50 @@
+//! `/api/v1/tokens` endpoint
+
+use super::{
+    openapi::{Endpoint, Location, Param, Schema},
+    respond, types, ApiError,
+};
+use crate::{
+    ledger::token::TokenAddress,
+    store::{zkapp::tokens::ZkappTokenStore, IndexerStore},
+};
+use actix_web::{
+    web::{self, Data},
+    HttpResponse,
+};
+use std::sync::Arc;
+
+pub const TOKEN: Endpoint = Endpoint {
+    path: "/tokens/{token}",
+    operation_id: "getToken",
+    tag: "tokens",
+    summary: "Token by address",
+    params: &[Param {
+        name: "token",
+        location: Location::Path,
+        description: "Token address",
+        schema: String::schema,
+    }],
+    response: types::Token::reference,
+};
+
+pub async fn token(store: Data<Arc<IndexerStore>>, token: web::Path<String>) -> HttpResponse {
+    respond(get_token(store.as_ref(), &token))
+}
+
+fn get_token(db: &Arc<IndexerStore>, token: &str) -> Result<types::Token, ApiError> {
+    let address = TokenAddress::new(token).ok_or_else(|| ApiError::InvalidParam {
+        name: "token",
+        value: token.to_string(),
+    })?;
+
+    match db.get_token(&address)? {
+        Some(token) => Ok(types::Token {
+            token: token.token.0,
+            owner: token.owner.map(|pk| pk.0),
+            symbol: token.symbol.0,
+            supply: token.supply.0,
+        }),
+        None => Err(ApiError::NotFound(format!("Token {address}"))),
+    }
+}
@@ -0,0 +1,192 @@
+//! `/api/v1` response models
+//!
+//! Amounts & fees are in nanomina
+
+use super::openapi::{reference, Schema};
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Map, Value};
+
+/// Declares a response struct & derives its [Schema] from its fields & their
+/// doc comments
+macro_rules! api_type {
+    (
+        $(#[doc = $doc:literal])*
+        pub struct $name:ident {
+            $(
+                $(#[doc = $field_doc:literal])*
+                pub $field:ident: $ty:ty,
+            )*
+        }
+    ) => {
+        $(#[doc = $doc])*
+        #[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+        pub struct $name {
+            $(
+                $(#[doc = $field_doc])*
+                pub $field: $ty,
+            )*
+        }
+
+        impl Schema for $name {
+            fn schema() -> Value {
+                let mut properties = Map::new();
+                let mut required = vec![];
+                $(
+                    let mut property = <$ty as Schema>::reference();
+                    let description: Vec<&str> = vec![$($field_doc.trim()),*];
+                    let description = description.join(" ");
+                    if !description.is_empty() {
+                        property = json!({ "allOf": [property], "description": description });
+                    }
+
+                    properties.insert(stringify!($field).to_string(), property);
+                    if <$ty as Schema>::required() {
+                        required.push(stringify!($field));
+                    }
+                )*
+
+                let description: Vec<&str> = vec![$($doc.trim()),*];
+                json!({
+                    "type": "object",
+                    "description": description.join(" "),
+                    "required": required,
+                    "properties": properties,
+                })
+            }
+
+            fn reference() -> Value {
+                reference(stringify!($name))
+            }
+        }
+    };
+}
+
+/// All named schemas, i.e. the spec's components
+pub const SCHEMAS: &[(&str, fn() -> Value)] = &[
+    ("Block", Block::schema),
+    ("UserCommand", UserCommand::schema),
+    ("InternalCommand", InternalCommand::schema),
+    ("SnarkWork", SnarkWork::schema),
+    ("StakingLedger", StakingLedger::schema),
+    ("StakingAccount", StakingAccount::schema),
+    ("Token", Token::schema),
+    ("ErrorBody", ErrorBody::schema),
+    ("Error", Error::schema),
+];
+
+api_type! {
+    /// A precomputed block
+    pub struct Block {
+        pub state_hash: String,
+        pub previous_state_hash: String,
+        pub genesis_state_hash: String,
+        pub blockchain_length: u32,
+        pub global_slot_since_genesis: u32,
+        pub epoch: u32,
+        /// ISO 8601 block timestamp
+        pub date_time: String,
+        pub block_creator: String,
+        pub coinbase_receiver: String,
+        /// `Canonical`, `Orphaned` or `Pending`
+        pub canonicity: String,
+        pub total_currency: u64,
+        pub user_commands_count: u32,
+        pub internal_commands_count: u32,
+        pub snark_work_count: u32,
+    }
+}
+
+api_type! {
+    /// A user command in its containing block
+    pub struct UserCommand {
+        pub txn_hash: String,
+        pub state_hash: String,
+        pub blockchain_length: u32,
+        pub global_slot_since_genesis: u32,
+        /// ISO 8601 block timestamp
+        pub date_time: String,
+        /// `PAYMENT`, `STAKE_DELEGATION` or `ZKAPP`
+        pub kind: String,
+        pub sender: String,
+        pub receivers: Vec<String>,
+        pub amount: u64,
+        pub fee: u64,
+        pub nonce: u32,
+        pub memo: String,
+        /// `Applied` or `Failed`
+        pub status: String,
+    }
+}
+
+api_type! {
+    /// A coinbase or fee transfer
+    pub struct InternalCommand {
+        /// `Coinbase`, `Fee_transfer` or `Fee_transfer_via_coinbase`
+        pub kind: String,
+        pub receiver: String,
+        pub amount: u64,
+        pub state_hash: String,
+        pub blockchain_length: u32,
+        /// ISO 8601 block timestamp
+        pub date_time: String,
+    }
+}
+
+api_type! {
+    /// SNARK work bought in a block
+    pub struct SnarkWork {
+        pub prover: String,
+        pub fee: u64,
+        pub state_hash: String,
+    }
+}
+
+api_type! {
+    /// A staking ledger's summary
+    pub struct StakingLedger {
+        pub epoch: u32,
+        pub ledger_hash: String,
+        pub genesis_state_hash: String,
+        pub total_currency: Option<u64>,
+        pub accounts_count: u32,
+    }
+}
+
+api_type! {
+    /// An account in an epoch's staking ledger
+    pub struct StakingAccount {
+        pub epoch: u32,
+        pub public_key: String,
+        pub balance: u64,
+        pub delegate: String,
+        pub nonce: Option<u32>,
+        pub username: Option<String>,
+    }
+}
+
+api_type! {
+    /// A token's current state
+    pub struct Token {
+        pub token: String,
+        pub owner: Option<String>,
+        pub symbol: String,
+        pub supply: u64,
+    }
+}
+
+api_type! {
+    /// Body of all error responses
+    pub struct ErrorBody {
+        pub error: Error,
+    }
+}
+
+api_type! {
+    /// An error's HTTP status, machine-readable code & message
+    pub struct Error {
+        pub status: u16,
+        /// `invalid_parameter`, `not_found` or `store_error`
+        pub code: String,
+        pub message: String,
+    }
+}
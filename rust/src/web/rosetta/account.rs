@@ -0,0 +1,75 @@
+//! Rosetta `/account/balance` endpoint
+
+use super::{mina_amount, resolve_block, respond, types::*, RosettaConfig, RosettaError};
+use crate::{
+    base::{public_key::PublicKey, state_hash::StateHash},
+    ledger::{store::staged::StagedLedgerStore, token::TokenAddress},
+    store::IndexerStore,
+};
+use actix_web::{
+    post,
+    web::{Data, Json},
+    HttpResponse,
+};
+use serde_json::json;
+use std::sync::Arc;
+
+#[post("/account/balance")]
+pub async fn account_balance(
+    store: Data<Arc<IndexerStore>>,
+    config: Data<RosettaConfig>,
+    req: Json<AccountBalanceRequest>,
+) -> HttpResponse {
+    respond(get_account_balance(store.as_ref(), &config, &req))
+}
+
+fn get_account_balance(
+    db: &Arc<IndexerStore>,
+    config: &RosettaConfig,
+    req: &AccountBalanceRequest,
+) -> Result<AccountBalanceResponse, RosettaError> {
+    config.check_network(&req.network_identifier)?;
+
+    let (pk, token) = account_token(&req.account_identifier)?;
+    let block_identifier = resolve_block(db, req.block_identifier.as_ref())?;
+    let state_hash = StateHash::from(block_identifier.hash.as_str());
+
+    // non-existent accounts have zero balance
+    let account = db.get_staged_account_display(&pk, &token, &state_hash)?;
+    let balance = account.as_ref().map_or(0, |account| account.balance.0);
+    let nonce = account
+        .and_then(|account| account.nonce)
+        .unwrap_or_default();
+
+    let mut amount = mina_amount(balance as i128);
+    if token != TokenAddress::default() {
+        amount.currency.metadata = Some(json!({ "token_id": token.0 }));
+    }
+
+    Ok(AccountBalanceResponse {
+        block_identifier,
+        balances: vec![amount],
+        metadata: Some(json!({ "nonce": nonce.0 })),
+    })
+}
+
+/// Account public key & token, defaults to MINA
+pub fn account_token(
+    account: &AccountIdentifier,
+) -> Result<(PublicKey, TokenAddress), RosettaError> {
+    let pk = PublicKey::new(&account.address)
+        .map_err(|_| RosettaError::InvalidAccount(account.address.to_owned()))?;
+
+    let token = match account
+        .metadata
+        .as_ref()
+        .and_then(|metadata| metadata.get("token_id"))
+        .and_then(|token| token.as_str())
+    {
+        Some(token) => TokenAddress::new(token)
+            .ok_or_else(|| RosettaError::InvalidAccount(format!("token {token}")))?,
+        None => TokenAddress::default(),
+    };
+
+    Ok((pk, token))
+}
@@ -0,0 +1,382 @@
+//! Rosetta `/block` & `/block/transaction` endpoints
+
+use super::{mina_amount, resolve_block, respond, types::*, RosettaConfig, RosettaError};
+use crate::{
+    base::{public_key::PublicKey, state_hash::StateHash},
+    block::store::BlockStore,
+    canonicity::store::CanonicityStore,
+    command::{
+        internal::{store::InternalCommandStore, DbInternalCommandWithData, InternalCommandKind},
+        signed::SignedCommand,
+        store::UserCommandStore,
+        CommandType, UserCommandWithStatus, UserCommandWithStatusT,
+    },
+    constants::MAINNET_ACCOUNT_CREATION_FEE,
+    store::IndexerStore,
+};
+use actix_web::{
+    post,
+    web::{Data, Json},
+    HttpResponse,
+};
+use serde_json::json;
+use std::sync::Arc;
+
+pub const OPERATION_STATUS_SUCCESS: &str = "Success";
+pub const OPERATION_STATUS_FAILED: &str = "Failed";
+
+const INTERNAL_COMMAND_INFIX: &str = ":internal:";
+
+#[post("/block")]
+pub async fn block(
+    store: Data<Arc<IndexerStore>>,
+    config: Data<RosettaConfig>,
+    req: Json<BlockRequest>,
+) -> HttpResponse {
+    respond(get_block(store.as_ref(), &config, &req))
+}
+
+#[post("/block/transaction")]
+pub async fn block_transaction(
+    store: Data<Arc<IndexerStore>>,
+    config: Data<RosettaConfig>,
+    req: Json<BlockTransactionRequest>,
+) -> HttpResponse {
+    respond(get_block_transaction(store.as_ref(), &config, &req))
+}
+
+fn get_block(
+    db: &Arc<IndexerStore>,
+    config: &RosettaConfig,
+    req: &BlockRequest,
+) -> Result<BlockResponse, RosettaError> {
+    config.check_network(&req.network_identifier)?;
+
+    let block_identifier = resolve_block(db, Some(&req.block_identifier))?;
+    let state_hash = StateHash::from(block_identifier.hash.as_str());
+
+    // genesis is its own parent
+    let parent_block_identifier = match db.get_block_parent_hash(&state_hash)? {
+        Some(parent_hash) => match db.get_block_height(&parent_hash)? {
+            Some(index) => BlockIdentifier {
+                index,
+                hash: parent_hash.0,
+            },
+            None => block_identifier.clone(),
+        },
+        None => block_identifier.clone(),
+    };
+
+    let canonicity = db.get_block_canonicity(&state_hash)?;
+    let metadata = json!({
+        "creator": db.get_block_creator(&state_hash)?.map(|pk| pk.0),
+        "coinbase_receiver": db.get_coinbase_receiver(&state_hash)?.map(|pk| pk.0),
+        "global_slot_since_genesis": db.get_block_global_slot(&state_hash)?,
+        "block_status": canonicity.map(|c| format!("{c:?}").to_lowercase()),
+    });
+
+    Ok(BlockResponse {
+        block: Block {
+            timestamp: db
+                .get_block_date_time(&state_hash)?
+                .ok_or(RosettaError::BlockNotFound)?,
+            transactions: block_transactions(db, &state_hash)?,
+            block_identifier,
+            parent_block_identifier,
+            metadata: Some(metadata),
+        },
+    })
+}
+
+fn get_block_transaction(
+    db: &Arc<IndexerStore>,
+    config: &RosettaConfig,
+    req: &BlockTransactionRequest,
+) -> Result<BlockTransactionResponse, RosettaError> {
+    config.check_network(&req.network_identifier)?;
+
+    let block = PartialBlockIdentifier {
+        index: Some(req.block_identifier.index),
+        hash: Some(req.block_identifier.hash.to_owned()),
+    };
+    let block_identifier = resolve_block(db, Some(&block))?;
+    let state_hash = StateHash::from(block_identifier.hash);
+
+    block_transactions(db, &state_hash)?
+        .into_iter()
+        .find(|txn| txn.transaction_identifier == req.transaction_identifier)
+        .map(|transaction| BlockTransactionResponse { transaction })
+        .ok_or_else(|| {
+            RosettaError::TransactionNotFound(req.transaction_identifier.hash.to_owned())
+        })
+}
+
+/// All of the block's transactions, user commands followed by internal
+/// commands
+pub fn block_transactions(
+    db: &Arc<IndexerStore>,
+    state_hash: &StateHash,
+) -> Result<Vec<Transaction>, RosettaError> {
+    let mut transactions = vec![];
+
+    for cmd in db.get_block_user_commands(state_hash)?.unwrap_or_default() {
+        transactions.push(user_command_transaction(&cmd)?);
+    }
+
+    let coinbase_receiver = db.get_coinbase_receiver(state_hash)?;
+    for (index, cmd) in db.get_internal_commands(state_hash)?.iter().enumerate() {
+        transactions.push(internal_command_transaction(
+            cmd,
+            index,
+            coinbase_receiver.as_ref(),
+        ));
+    }
+
+    Ok(transactions)
+}
+
+/// Rosetta transaction of a user command
+///
+/// The fee is always paid, the remaining operations fail with the command
+pub fn user_command_transaction(cmd: &UserCommandWithStatus) -> Result<Transaction, RosettaError> {
+    let status = if cmd.is_applied() {
+        OPERATION_STATUS_SUCCESS
+    } else {
+        OPERATION_STATUS_FAILED
+    };
+    let mut ops = Operations::default();
+
+    ops.push(
+        "fee_payment",
+        OPERATION_STATUS_SUCCESS,
+        &cmd.fee_payer_pk(),
+        Some(-(cmd.fee() as i128)),
+        None,
+    );
+
+    match SignedCommand::from_user_command(cmd.to_owned()).kind() {
+        CommandType::Payment => {
+            let receiver = cmd.receiver().first().cloned().unwrap_or_default();
+            let source = ops.push(
+                "payment_source_dec",
+                status,
+                &cmd.sender(),
+                Some(-(cmd.amount() as i128)),
+                None,
+            );
+
+            ops.push_related(
+                "payment_receiver_inc",
+                status,
+                &receiver,
+                Some(cmd.amount() as i128),
+                source,
+            );
+
+            if cmd.is_applied() && cmd.receiver_account_creation_fee_paid() {
+                ops.push_related(
+                    "account_creation_fee_via_payment",
+                    status,
+                    &receiver,
+                    Some(-(MAINNET_ACCOUNT_CREATION_FEE.0 as i128)),
+                    source,
+                );
+            }
+        }
+        CommandType::Delegation => {
+            let delegate = cmd.receiver().first().cloned().unwrap_or_default();
+
+            ops.push(
+                "delegate_change",
+                status,
+                &cmd.sender(),
+                None,
+                Some(json!({ "delegate_change_target": delegate.0 })),
+            );
+        }
+        CommandType::Zkapp => {
+            for update in cmd.accounts_updated() {
+                if update.balance_change == 0 {
+                    continue;
+                }
+
+                ops.push(
+                    "zkapp_balance_update",
+                    status,
+                    &update.public_key,
+                    Some(update.balance_change as i128),
+                    Some(json!({ "token_id": update.token.0 })),
+                );
+            }
+        }
+    }
+
+    Ok(Transaction {
+        transaction_identifier: TransactionIdentifier {
+            hash: cmd.hash()?.to_string(),
+        },
+        operations: ops.0,
+        metadata: Some(json!({
+            "memo": cmd.memo(),
+            "nonce": cmd.nonce().0,
+        })),
+    })
+}
+
+/// Rosetta transaction of an internal command
+///
+/// Internal commands do not have hashes, they are identified by their block
+/// & position within it
+pub fn internal_command_transaction(
+    cmd: &DbInternalCommandWithData,
+    index: usize,
+    coinbase_receiver: Option<&PublicKey>,
+) -> Transaction {
+    let mut ops = Operations::default();
+    let (receiver, amount, state_hash, kind) = match cmd {
+        DbInternalCommandWithData::Coinbase {
+            receiver,
+            amount,
+            state_hash,
+            kind,
+            ..
+        }
+        | DbInternalCommandWithData::FeeTransfer {
+            receiver,
+            amount,
+            state_hash,
+            kind,
+            ..
+        } => (receiver, *amount as i128, state_hash, kind),
+    };
+
+    match kind {
+        InternalCommandKind::Coinbase => {
+            ops.push(
+                "coinbase_inc",
+                OPERATION_STATUS_SUCCESS,
+                receiver,
+                Some(amount),
+                None,
+            );
+        }
+        InternalCommandKind::FeeTransfer => {
+            ops.push(
+                "fee_receiver_inc",
+                OPERATION_STATUS_SUCCESS,
+                receiver,
+                Some(amount),
+                None,
+            );
+        }
+        InternalCommandKind::FeeTransferViaCoinbase => {
+            let inc = ops.push(
+                "fee_receiver_inc",
+                OPERATION_STATUS_SUCCESS,
+                receiver,
+                Some(amount),
+                None,
+            );
+
+            if let Some(coinbase_receiver) = coinbase_receiver {
+                ops.push_related(
+                    "fee_payer_dec",
+                    OPERATION_STATUS_SUCCESS,
+                    coinbase_receiver,
+                    Some(-amount),
+                    inc,
+                );
+            }
+        }
+    }
+
+    Transaction {
+        transaction_identifier: TransactionIdentifier {
+            hash: internal_command_hash(state_hash, index),
+        },
+        operations: ops.0,
+        metadata: None,
+    }
+}
+
+/// Identifier of the block's `index`th internal command
+pub fn internal_command_hash(state_hash: &StateHash, index: usize) -> String {
+    format!("{state_hash}{INTERNAL_COMMAND_INFIX}{index}")
+}
+
+/// Block state hash & index of an [internal_command_hash]
+pub fn parse_internal_command_hash(hash: &str) -> Option<(StateHash, usize)> {
+    let (state_hash, index) = hash.split_once(INTERNAL_COMMAND_INFIX)?;
+
+    if !StateHash::is_valid(state_hash) {
+        return None;
+    }
+
+    Some((state_hash.into(), index.parse().ok()?))
+}
+
+/// Sequentially indexed transaction operations
+#[derive(Default)]
+struct Operations(Vec<Operation>);
+
+impl Operations {
+    /// Returns the index of the added operation
+    fn push(
+        &mut self,
+        kind: &str,
+        status: &str,
+        pk: &PublicKey,
+        amount: Option<i128>,
+        metadata: Option<serde_json::Value>,
+    ) -> u32 {
+        let index = self.0.len() as u32;
+
+        self.0.push(Operation {
+            operation_identifier: OperationIdentifier { index },
+            related_operations: None,
+            kind: kind.to_string(),
+            status: Some(status.to_string()),
+            account: Some(AccountIdentifier {
+                address: pk.0.to_owned(),
+                metadata: None,
+            }),
+            amount: amount.map(mina_amount),
+            metadata,
+        });
+
+        index
+    }
+
+    fn push_related(
+        &mut self,
+        kind: &str,
+        status: &str,
+        pk: &PublicKey,
+        amount: Option<i128>,
+        related: u32,
+    ) -> u32 {
+        let index = self.push(kind, status, pk, amount, None);
+
+        self.0[index as usize].related_operations =
+            Some(vec![OperationIdentifier { index: related }]);
+        index
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn internal_command_hash_roundtrip() {
+        let state_hash = StateHash::from("3NKeMoncuHab5ScarV5ViyF16cJPT4taWNSaTLS64Dp67wuXigPZ");
+        let hash = internal_command_hash(&state_hash, 2);
+
+        assert_eq!(parse_internal_command_hash(&hash), Some((state_hash, 2)));
+        assert_eq!(
+            parse_internal_command_hash("5JvEERsKnNBHRXA9dZqB9mKxbjLzEWSBqiZdzSsLGi5LjvqPbDkj"),
+            None
+        );
+        assert_eq!(parse_internal_command_hash("x:internal:0"), None);
+    }
+}
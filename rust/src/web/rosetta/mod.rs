@@ -0,0 +1,256 @@
+//! Rosetta Data API served from the indexer store
+//!
+//! See `docs/rosetta/README.org` for the data Rosetta requires
+
+pub mod account;
+pub mod block;
+pub mod network;
+pub mod search;
+pub mod types;
+
+use crate::{
+    base::state_hash::StateHash, block::store::BlockStore, canonicity::store::CanonicityStore,
+    chain::Network, constants::MINA_SCALE, store::IndexerStore,
+};
+use actix_web::{
+    error::InternalError,
+    http::{header::ContentType, StatusCode},
+    web, HttpResponse,
+};
+use serde::Serialize;
+use std::sync::Arc;
+use types::*;
+
+pub const ROSETTA_BLOCKCHAIN: &str = "mina";
+pub const ROSETTA_MINA_SYMBOL: &str = "MINA";
+pub const ROSETTA_MINA_DECIMALS: u32 = MINA_SCALE.ilog10();
+
+/// Register all Rosetta endpoints
+///
+/// Malformed requests are answered with Rosetta errors too
+pub fn configure(cfg: &mut web::ServiceConfig, config: RosettaConfig) {
+    let json_config = web::JsonConfig::default().error_handler(|err, _| {
+        let response = error_response(&RosettaError::InvalidRequest(err.to_string()));
+        InternalError::from_response(err, response).into()
+    });
+
+    cfg.app_data(web::Data::new(config))
+        .app_data(json_config)
+        .service(network::network_list)
+        .service(network::network_status)
+        .service(block::block)
+        .service(block::block_transaction)
+        .service(account::account_balance)
+        .service(search::search_transactions);
+}
+
+/// Rosetta API configuration, from the indexer's configuration
+#[derive(Debug, Clone)]
+pub struct RosettaConfig {
+    /// The only network served by the indexer
+    pub network: Network,
+}
+
+#[derive(Debug)]
+pub enum RosettaError {
+    InvalidNetwork(NetworkIdentifier),
+    BlockNotFound,
+    TransactionNotFound(String),
+    InvalidAccount(String),
+    InvalidRequest(String),
+    Store(anyhow::Error),
+}
+
+//////////
+// impl //
+//////////
+
+impl RosettaConfig {
+    pub fn new(network: Network) -> Self {
+        Self { network }
+    }
+
+    /// The only network served by the indexer
+    pub fn network_identifier(&self) -> NetworkIdentifier {
+        NetworkIdentifier {
+            blockchain: ROSETTA_BLOCKCHAIN.to_string(),
+            network: self.network.to_string(),
+        }
+    }
+
+    /// Check the request is for the network served by the indexer
+    pub fn check_network(&self, network: &NetworkIdentifier) -> Result<(), RosettaError> {
+        if *network != self.network_identifier() {
+            return Err(RosettaError::InvalidNetwork(network.to_owned()));
+        }
+
+        Ok(())
+    }
+}
+
+impl RosettaError {
+    pub fn code(&self) -> u32 {
+        match self {
+            Self::InvalidNetwork(_) => 1,
+            Self::BlockNotFound => 2,
+            Self::TransactionNotFound(_) => 3,
+            Self::InvalidAccount(_) => 4,
+            Self::InvalidRequest(_) => 5,
+            Self::Store(_) => 6,
+        }
+    }
+
+    /// HTTP status of the error response
+    pub fn status(&self) -> StatusCode {
+        match self {
+            Self::InvalidNetwork(_) | Self::InvalidAccount(_) | Self::InvalidRequest(_) => {
+                StatusCode::BAD_REQUEST
+            }
+            Self::BlockNotFound | Self::TransactionNotFound(_) => StatusCode::NOT_FOUND,
+            Self::Store(_) => StatusCode::INTERNAL_SERVER_ERROR,
+        }
+    }
+
+    pub fn to_error(&self) -> Error {
+        Error {
+            code: self.code(),
+            message: self.to_string(),
+
+            // store errors may be transient
+            retriable: matches!(self, Self::Store(_)),
+            details: None,
+        }
+    }
+}
+
+/// Resolve a (partial) block identifier to a block's state hash & height
+///
+/// An index is looked up on the canonical chain, an empty identifier
+/// resolves to the best block
+pub fn resolve_block(
+    db: &Arc<IndexerStore>,
+    block: Option<&PartialBlockIdentifier>,
+) -> Result<BlockIdentifier, RosettaError> {
+    let (index, hash) = block
+        .map(|block| (block.index, block.hash.to_owned()))
+        .unwrap_or_default();
+
+    let state_hash = match (index, hash) {
+        (_, Some(hash)) => StateHash::from(hash),
+        (Some(index), None) => db
+            .get_canonical_hash_at_height(index)?
+            .ok_or(RosettaError::BlockNotFound)?,
+        (None, None) => db
+            .get_best_block_hash()?
+            .ok_or(RosettaError::BlockNotFound)?,
+    };
+
+    let height = db
+        .get_block_height(&state_hash)?
+        .ok_or(RosettaError::BlockNotFound)?;
+
+    // index & hash must refer to the same block
+    if index.is_some_and(|index| index != height) {
+        return Err(RosettaError::BlockNotFound);
+    }
+
+    Ok(BlockIdentifier {
+        index: height,
+        hash: state_hash.0,
+    })
+}
+
+/// MINA amount in nanomina
+pub fn mina_amount(value: i128) -> Amount {
+    Amount {
+        value: value.to_string(),
+        currency: Currency {
+            symbol: ROSETTA_MINA_SYMBOL.to_string(),
+            decimals: ROSETTA_MINA_DECIMALS,
+            metadata: None,
+        },
+    }
+}
+
+/// Rosetta responds with an error object for all failures
+pub fn respond<T: Serialize>(res: Result<T, RosettaError>) -> HttpResponse {
+    match res {
+        Ok(body) => HttpResponse::Ok()
+            .content_type(ContentType::json())
+            .body(serde_json::to_string(&body).expect("serde rosetta response")),
+        Err(err) => error_response(&err),
+    }
+}
+
+/// Error object response, with the error's status
+pub fn error_response(err: &RosettaError) -> HttpResponse {
+    HttpResponse::build(err.status())
+        .content_type(ContentType::json())
+        .body(serde_json::to_string(&err.to_error()).expect("serde rosetta error"))
+}
+
+/////////////////
+// conversions //
+/////////////////
+
+impl From<anyhow::Error> for RosettaError {
+    fn from(value: anyhow::Error) -> Self {
+        Self::Store(value)
+    }
+}
+
+/////////////
+// display //
+/////////////
+
+impl std::fmt::Display for RosettaError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::InvalidNetwork(network) => write!(
+                f,
+                "Invalid network {}/{}",
+                network.blockchain, network.network
+            ),
+            Self::BlockNotFound => write!(f, "Block not found"),
+            Self::TransactionNotFound(hash) => write!(f, "Transaction not found {hash}"),
+            Self::InvalidAccount(account) => write!(f, "Invalid account {account}"),
+            Self::InvalidRequest(msg) => write!(f, "Invalid request: {msg}"),
+            Self::Store(e) => write!(f, "Store error: {e}"),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn mina_currency() {
+        assert_eq!(ROSETTA_MINA_DECIMALS, 9);
+        assert_eq!(mina_amount(-1_000_000_000).value, "-1000000000");
+    }
+
+    #[test]
+    fn network_check() {
+        let mainnet = RosettaConfig::new(Network::Mainnet);
+        let devnet = RosettaConfig::new(Network::Devnet);
+
+        assert_eq!(mainnet.network_identifier().network, "mainnet");
+        assert!(mainnet.check_network(&mainnet.network_identifier()).is_ok());
+        assert!(mainnet.check_network(&devnet.network_identifier()).is_err());
+        assert!(devnet.check_network(&devnet.network_identifier()).is_ok());
+    }
+
+    #[test]
+    fn error_status() {
+        assert_eq!(RosettaError::BlockNotFound.status(), StatusCode::NOT_FOUND);
+        assert_eq!(
+            RosettaError::InvalidRequest(String::new()).status(),
+            StatusCode::BAD_REQUEST
+        );
+        assert_eq!(
+            RosettaError::Store(anyhow::anyhow!("store")).status(),
+            StatusCode::INTERNAL_SERVER_ERROR
+        );
+    }
+}
@@ -0,0 +1,87 @@
+//! Rosetta `/network/list` & `/network/status` endpoints
+
+use super::{respond, types::*, RosettaConfig, RosettaError};
+use crate::{
+    block::store::BlockStore,
+    store::IndexerStore,
+    utility::store::common::{from_be_bytes, state_hash_suffix, U32_LEN},
+};
+use actix_web::{
+    post,
+    web::{Data, Json},
+    HttpResponse,
+};
+use speedb::IteratorMode;
+use std::sync::Arc;
+
+#[post("/network/list")]
+pub async fn network_list(
+    config: Data<RosettaConfig>,
+    _req: Json<MetadataRequest>,
+) -> HttpResponse {
+    respond(Ok::<_, RosettaError>(NetworkListResponse {
+        network_identifiers: vec![config.network_identifier()],
+    }))
+}
+
+#[post("/network/status")]
+pub async fn network_status(
+    store: Data<Arc<IndexerStore>>,
+    config: Data<RosettaConfig>,
+    req: Json<NetworkRequest>,
+) -> HttpResponse {
+    respond(status(store.as_ref(), &config, &req))
+}
+
+fn status(
+    db: &Arc<IndexerStore>,
+    config: &RosettaConfig,
+    req: &NetworkRequest,
+) -> Result<NetworkStatusResponse, RosettaError> {
+    config.check_network(&req.network_identifier)?;
+
+    // best block
+    let best_hash = db
+        .get_best_block_hash()?
+        .ok_or(RosettaError::BlockNotFound)?;
+    let best_height = db
+        .get_best_block_height()?
+        .ok_or(RosettaError::BlockNotFound)?;
+    let current_block_timestamp = db
+        .get_block_date_time(&best_hash)?
+        .ok_or(RosettaError::BlockNotFound)?;
+
+    // genesis block
+    let genesis_hash = db
+        .get_best_block_genesis_hash()?
+        .ok_or(RosettaError::BlockNotFound)?;
+    let genesis_height = db
+        .get_block_height(&genesis_hash)?
+        .ok_or(RosettaError::BlockNotFound)?;
+
+    // lowest block in the store
+    let (oldest_height, oldest_hash) = match db.blocks_height_iterator(IteratorMode::Start).next() {
+        Some(Ok((key, _))) => (
+            from_be_bytes(key[..U32_LEN].to_vec()),
+            state_hash_suffix(&key)?,
+        ),
+        _ => (genesis_height, genesis_hash.clone()),
+    };
+
+    Ok(NetworkStatusResponse {
+        current_block_identifier: BlockIdentifier {
+            index: best_height,
+            hash: best_hash.0,
+        },
+        current_block_timestamp,
+        genesis_block_identifier: BlockIdentifier {
+            index: genesis_height,
+            hash: genesis_hash.0,
+        },
+        oldest_block_identifier: BlockIdentifier {
+            index: oldest_height,
+            hash: oldest_hash.0,
+        },
+        peers: vec![],
+    })
+}
@@ -0,0 +1,257 @@
+//! Rosetta `/search/transactions` endpoint
+
+use super::{
+    account::account_token,
+    block::{
+        internal_command_transaction, parse_internal_command_hash, user_command_transaction,
+        OPERATION_STATUS_FAILED, OPERATION_STATUS_SUCCESS,
+    },
+    respond,
+    types::*,
+    RosettaConfig, RosettaError,
+};
+use crate::{
+    base::state_hash::StateHash,
+    block::store::BlockStore,
+    command::{
+        internal::store::InternalCommandStore, store::UserCommandStore, TxnHash,
+        UserCommandWithStatusT,
+    },
+    store::IndexerStore,
+};
+use actix_web::{
+    post,
+    web::{Data, Json},
+    HttpResponse,
+};
+use std::sync::Arc;
+
+pub const DEFAULT_SEARCH_LIMIT: usize = 100;
+pub const MAX_SEARCH_LIMIT: usize = 1000;
+
+#[post("/search/transactions")]
+pub async fn search_transactions(
+    store: Data<Arc<IndexerStore>>,
+    config: Data<RosettaConfig>,
+    req: Json<SearchTransactionsRequest>,
+) -> HttpResponse {
+    respond(search(store.as_ref(), &config, &req))
+}
+
+fn search(
+    db: &Arc<IndexerStore>,
+    config: &RosettaConfig,
+    req: &SearchTransactionsRequest,
+) -> Result<SearchTransactionsResponse, RosettaError> {
+    config.check_network(&req.network_identifier)?;
+
+    let mut transactions = match (&req.transaction_identifier, &req.account_identifier) {
+        (Some(txn), _) => search_txn_hash(db, &txn.hash)?,
+        (None, Some(account)) => search_account(db, account)?,
+        (None, None) => {
+            return Err(RosettaError::InvalidRequest(
+                "transaction_identifier or account_identifier required".to_string(),
+            ))
+        }
+    };
+
+    // filter by account, if both are specified
+    if let (Some(_), Some(account)) = (&req.transaction_identifier, &req.account_identifier) {
+        transactions.retain(|txn| involves_account(&txn.transaction, &account.address));
+    }
+
+    // filter by block & status
+    transactions.retain(|txn| {
+        req.max_block
+            .is_none_or(|max| txn.block_identifier.index <= max)
+            && req
+                .success
+                .is_none_or(|success| is_success(&txn.transaction) == success)
+    });
+
+    // most recent first
+    transactions.sort_by(|a, b| b.block_identifier.index.cmp(&a.block_identifier.index));
+
+    let total_count = transactions.len();
+    let offset = req.offset.unwrap_or_default();
+    let limit = req
+        .limit
+        .unwrap_or(DEFAULT_SEARCH_LIMIT)
+        .min(MAX_SEARCH_LIMIT);
+    let next_offset = (offset + limit < total_count).then_some(offset + limit);
+
+    Ok(SearchTransactionsResponse {
+        transactions: transactions.into_iter().skip(offset).take(limit).collect(),
+        total_count,
+        next_offset,
+    })
+}
+
+/// User commands with the given hash, in every containing block, or the
+/// internal command with the given identifier
+fn search_txn_hash(
+    db: &Arc<IndexerStore>,
+    txn_hash: &str,
+) -> Result<Vec<BlockTransaction>, RosettaError> {
+    if let Some((state_hash, index)) = parse_internal_command_hash(txn_hash) {
+        return block_internal_command(db, &state_hash, index).map(|txn| txn.into_iter().collect());
+    }
+
+    let txn_hash = TxnHash::new(txn_hash)
+        .map_err(|_| RosettaError::TransactionNotFound(txn_hash.to_string()))?;
+    let mut transactions = vec![];
+
+    for state_hash in db
+        .get_user_command_state_hashes(&txn_hash)?
+        .unwrap_or_default()
+    {
+        if let Some(txn) = block_user_command(db, &state_hash, &txn_hash)? {
+            transactions.push(txn);
+        }
+    }
+
+    Ok(transactions)
+}
+
+/// User & internal commands involving the account
+fn search_account(
+    db: &Arc<IndexerStore>,
+    account: &AccountIdentifier,
+) -> Result<Vec<BlockTransaction>, RosettaError> {
+    let (pk, _) = account_token(account)?;
+    let mut transactions = vec![];
+
+    for cmd in db
+        .get_user_commands_for_public_key(&pk)?
+        .unwrap_or_default()
+    {
+        if let Some(txn) = block_user_command(db, &cmd.state_hash, &cmd.txn_hash)? {
+            transactions.push(txn);
+        }
+    }
+
+    for cmd in db.get_internal_commands_public_key(&pk, 0, usize::MAX)? {
+        let state_hash = cmd.state_hash();
+
+        // internal command identifiers depend on their position in the block
+        for (index, block_cmd) in db.get_internal_commands(&state_hash)?.iter().enumerate() {
+            if *block_cmd == cmd {
+                let coinbase_receiver = db.get_coinbase_receiver(&state_hash)?;
+
+                transactions.push(BlockTransaction {
+                    block_identifier: block_identifier(db, &state_hash)?,
+                    transaction: internal_command_transaction(
+                        block_cmd,
+                        index,
+                        coinbase_receiver.as_ref(),
+                    ),
+                });
+                break;
+            }
+        }
+    }
+
+    Ok(transactions)
+}
+
+/// The user command with the given hash in the block, if any
+fn block_user_command(
+    db: &Arc<IndexerStore>,
+    state_hash: &StateHash,
+    txn_hash: &TxnHash,
+) -> Result<Option<BlockTransaction>, RosettaError> {
+    for cmd in db.get_block_user_commands(state_hash)?.unwrap_or_default() {
+        if cmd.hash()? == *txn_hash {
+            return Ok(Some(BlockTransaction {
+                block_identifier: block_identifier(db, state_hash)?,
+                transaction: user_command_transaction(&cmd)?,
+            }));
+        }
+    }
+
+    Ok(None)
+}
+
+/// The block's `index`th internal command, if any
+fn block_internal_command(
+    db: &Arc<IndexerStore>,
+    state_hash: &StateHash,
+    index: usize,
+) -> Result<Option<BlockTransaction>, RosettaError> {
+    let Some(cmd) = db.get_internal_commands(state_hash)?.into_iter().nth(index) else {
+        return Ok(None);
+    };
+    let coinbase_receiver = db.get_coinbase_receiver(state_hash)?;
+
+    Ok(Some(BlockTransaction {
+        block_identifier: block_identifier(db, state_hash)?,
+        transaction: internal_command_transaction(&cmd, index, coinbase_receiver.as_ref()),
+    }))
+}
+
+fn block_identifier(
+    db: &Arc<IndexerStore>,
+    state_hash: &StateHash,
+) -> Result<BlockIdentifier, RosettaError> {
+    Ok(BlockIdentifier {
+        index: db
+            .get_block_height(state_hash)?
+            .ok_or(RosettaError::BlockNotFound)?,
+        hash: state_hash.0.to_owned(),
+    })
+}
+
+fn involves_account(txn: &Transaction, address: &str) -> bool {
+    txn.operations.iter().any(|op| {
+        op.account
+            .as_ref()
+            .is_some_and(|account| account.address == address)
+    })
+}
+
+/// A transaction succeeds if none of its operations failed
+fn is_success(txn: &Transaction) -> bool {
+    txn.operations.iter().all(|op| {
+        op.status
+            .as_deref()
+            .is_none_or(|status| status == OPERATION_STATUS_SUCCESS)
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn transaction(statuses: &[&str]) -> Transaction {
+        Transaction {
+            transaction_identifier: TransactionIdentifier {
+                hash: String::new(),
+            },
+            operations: statuses
+                .iter()
+                .enumerate()
+                .map(|(index, status)| Operation {
+                    operation_identifier: OperationIdentifier {
+                        index: index as u32,
+                    },
+                    related_operations: None,
+                    kind: "fee_payment".to_string(),
+                    status: Some(status.to_string()),
+                    account: None,
+                    amount: None,
+                    metadata: None,
+                })
+                .collect(),
+            metadata: None,
+        }
+    }
+
+    #[test]
+    fn transaction_success() {
+        assert!(is_success(&transaction(&[OPERATION_STATUS_SUCCESS])));
+        assert!(!is_success(&transaction(&[
+            OPERATION_STATUS_SUCCESS,
+            OPERATION_STATUS_FAILED
+        ])));
+    }
+}
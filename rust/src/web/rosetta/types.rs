@@ -0,0 +1,229 @@
+//! Rosetta Data API request & response models
+
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+//////////////
+// requests //
+//////////////
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct MetadataRequest {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub metadata: Option<Value>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct NetworkRequest {
+    pub network_identifier: NetworkIdentifier,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BlockRequest {
+    pub network_identifier: NetworkIdentifier,
+    pub block_identifier: PartialBlockIdentifier,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BlockTransactionRequest {
+    pub network_identifier: NetworkIdentifier,
+    pub block_identifier: BlockIdentifier,
+    pub transaction_identifier: TransactionIdentifier,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AccountBalanceRequest {
+    pub network_identifier: NetworkIdentifier,
+    pub account_identifier: AccountIdentifier,
+
+    #[serde(default)]
+    pub block_identifier: Option<PartialBlockIdentifier>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SearchTransactionsRequest {
+    pub network_identifier: NetworkIdentifier,
+
+    #[serde(default)]
+    pub transaction_identifier: Option<TransactionIdentifier>,
+
+    #[serde(default)]
+    pub account_identifier: Option<AccountIdentifier>,
+
+    /// Only include transactions in blocks at or below this height
+    #[serde(default)]
+    pub max_block: Option<u32>,
+
+    #[serde(default)]
+    pub success: Option<bool>,
+
+    #[serde(default)]
+    pub offset: Option<usize>,
+
+    #[serde(default)]
+    pub limit: Option<usize>,
+}
+
+///////////////
+// responses //
+///////////////
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct NetworkListResponse {
+    pub network_identifiers: Vec<NetworkIdentifier>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct NetworkStatusResponse {
+    pub current_block_identifier: BlockIdentifier,
+    pub current_block_timestamp: i64,
+    pub genesis_block_identifier: BlockIdentifier,
+    pub oldest_block_identifier: BlockIdentifier,
+    pub peers: Vec<Value>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BlockResponse {
+    pub block: Block,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BlockTransactionResponse {
+    pub transaction: Transaction,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AccountBalanceResponse {
+    pub block_identifier: BlockIdentifier,
+    pub balances: Vec<Amount>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub metadata: Option<Value>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SearchTransactionsResponse {
+    pub transactions: Vec<BlockTransaction>,
+    pub total_count: usize,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub next_offset: Option<usize>,
+}
+
+////////////
+// models //
+////////////
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct NetworkIdentifier {
+    pub blockchain: String,
+    pub network: String,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct BlockIdentifier {
+    pub index: u32,
+    pub hash: String,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct PartialBlockIdentifier {
+    #[serde(default)]
+    pub index: Option<u32>,
+
+    #[serde(default)]
+    pub hash: Option<String>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct TransactionIdentifier {
+    pub hash: String,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct OperationIdentifier {
+    pub index: u32,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AccountIdentifier {
+    pub address: String,
+
+    /// Mina rosetta passes the token via `{ "token_id": <address> }`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub metadata: Option<Value>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Currency {
+    pub symbol: String,
+    pub decimals: u32,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub metadata: Option<Value>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Amount {
+    pub value: String,
+    pub currency: Currency,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Block {
+    pub block_identifier: BlockIdentifier,
+    pub parent_block_identifier: BlockIdentifier,
+    pub timestamp: i64,
+    pub transactions: Vec<Transaction>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub metadata: Option<Value>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Transaction {
+    pub transaction_identifier: TransactionIdentifier,
+    pub operations: Vec<Operation>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub metadata: Option<Value>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BlockTransaction {
+    pub block_identifier: BlockIdentifier,
+    pub transaction: Transaction,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Operation {
+    pub operation_identifier: OperationIdentifier,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub related_operations: Option<Vec<OperationIdentifier>>,
+
+    #[serde(rename = "type")]
+    pub kind: String,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub status: Option<String>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub account: Option<AccountIdentifier>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub amount: Option<Amount>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub metadata: Option<Value>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Error {
+    pub code: u32,
+    pub message: String,
+    pub retriable: bool,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub details: Option<Value>,
+}
@@ -0,0 +1,134 @@
+//! Minimal HTTP/1.1 client for webhook delivery
+//!
+//! Only plain `http://` urls are supported, deliver to `https://` endpoints
+//! via a TLS-terminating proxy
+
+use anyhow::{bail, Context};
+use std::time::Duration;
+use tokio::{
+    io::{AsyncBufReadExt, AsyncWriteExt, BufReader},
+    net::TcpStream,
+};
+
+pub const DEFAULT_HTTP_PORT: u16 = 80;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct HttpUrl {
+    pub host: String,
+    pub port: u16,
+    pub path: String,
+}
+
+impl HttpUrl {
+    /// Parses `http://host[:port][/path]`
+    pub fn parse(url: &str) -> anyhow::Result<Self> {
+        let rest = match url.strip_prefix("http://") {
+            Some(rest) => rest,
+            None => bail!("Unsupported webhook url (only http:// is supported): {url}"),
+        };
+
+        let (authority, path) = match rest.find('/') {
+            Some(index) => rest.split_at(index),
+            None => (rest, "/"),
+        };
+
+        let (host, port) = match authority.rsplit_once(':') {
+            Some((host, port)) => (
+                host,
+                port.parse()
+                    .with_context(|| format!("Invalid webhook url port: {url}"))?,
+            ),
+            None => (authority, DEFAULT_HTTP_PORT),
+        };
+
+        if host.is_empty() {
+            bail!("Missing webhook url host: {url}")
+        }
+
+        Ok(Self {
+            host: host.to_string(),
+            port,
+            path: path.to_string(),
+        })
+    }
+
+    /// Checks the url is supported
+    pub fn is_valid(url: &str) -> bool {
+        Self::parse(url).is_ok()
+    }
+}
+
+/// POST the JSON body to the url & return the response status code
+pub async fn post_json(url: &str, body: &[u8], timeout: Duration) -> anyhow::Result<u16> {
+    let url = HttpUrl::parse(url)?;
+    let request = format!(
+        "POST {} HTTP/1.1\r\nHost: {}:{}\r\nUser-Agent: mina-indexer\r\nContent-Type: application/json\r\nContent-Length: {}\r\nConnection: close\r\n\r\n",
+        url.path,
+        url.host,
+        url.port,
+        body.len()
+    );
+
+    tokio::time::timeout(timeout, async {
+        let mut stream = TcpStream::connect((url.host.as_str(), url.port)).await?;
+        stream.write_all(request.as_bytes()).await?;
+        stream.write_all(body).await?;
+        stream.flush().await?;
+
+        let mut status_line = String::new();
+        BufReader::new(stream).read_line(&mut status_line).await?;
+        parse_status_line(&status_line)
+    })
+    .await
+    .with_context(|| format!("Webhook request timed out after {timeout:?}"))?
+}
+
+/// Status code of an `HTTP/1.x <code> <reason>` status line
+fn parse_status_line(status_line: &str) -> anyhow::Result<u16> {
+    let mut parts = status_line.split_whitespace();
+
+    match (parts.next(), parts.next()) {
+        (Some(version), Some(code)) if version.starts_with("HTTP/1.") => code
+            .parse()
+            .with_context(|| format!("Invalid HTTP status line: {status_line}")),
+        _ => bail!("Invalid HTTP status line: {status_line}"),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_url() -> anyhow::Result<()> {
+        assert_eq!(
+            HttpUrl::parse("http://localhost:8080/hooks/mina")?,
+            HttpUrl {
+                host: "localhost".to_string(),
+                port: 8080,
+                path: "/hooks/mina".to_string(),
+            }
+        );
+        assert_eq!(
+            HttpUrl::parse("http://example.com")?,
+            HttpUrl {
+                host: "example.com".to_string(),
+                port: DEFAULT_HTTP_PORT,
+                path: "/".to_string(),
+            }
+        );
+
+        assert!(!HttpUrl::is_valid("https://example.com"));
+        assert!(!HttpUrl::is_valid("http://:8080/"));
+        assert!(!HttpUrl::is_valid("http://localhost:port/"));
+        Ok(())
+    }
+
+    #[test]
+    fn parse_status() -> anyhow::Result<()> {
+        assert_eq!(parse_status_line("HTTP/1.1 200 OK\r\n")?, 200);
+        assert_eq!(parse_status_line("HTTP/1.0 503 Service Unavailable")?, 503);
+        assert!(parse_status_line("garbage").is_err());
+        Ok(())
+    }
+}
@@ -0,0 +1,234 @@
+//! Webhook notifications of watched public key activity
+
+pub mod http;
+pub mod notifier;
+pub mod store;
+
+use crate::{
+    base::{public_key::PublicKey, state_hash::StateHash},
+    command::{
+        internal::{store::InternalCommandStore, DbInternalCommandWithData},
+        store::UserCommandStore,
+        UserCommandWithStatusT,
+    },
+    event::{
+        db::{DbBlockEvent, DbCanonicityEvent, DbEvent},
+        IndexerEvent,
+    },
+    store::IndexerStore,
+};
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashSet};
+
+/// Notify `url` of `public_key`'s activity
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Webhook {
+    pub public_key: PublicKey,
+    pub url: String,
+}
+
+/// JSON body POSTed to webhook urls
+///
+/// Delivery is at-least-once, receivers can deduplicate on `seq_num`
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct WebhookPayload {
+    /// Event store sequence number of the triggering event
+    pub seq_num: u32,
+    pub event: WebhookEventKind,
+    pub state_hash: StateHash,
+    pub blockchain_length: u32,
+    pub activity: Vec<Activity>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum WebhookEventKind {
+    NewBlock,
+    NewCanonicalBlock,
+}
+
+/// Watched public key activity within a block
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum Activity {
+    UserCommand {
+        public_key: PublicKey,
+        txn_hash: String,
+        sender: PublicKey,
+        receivers: Vec<PublicKey>,
+        amount: u64,
+        fee: u64,
+        applied: bool,
+    },
+    InternalCommand {
+        public_key: PublicKey,
+        command_kind: String,
+        amount: u64,
+    },
+    ZkappUpdate {
+        public_key: PublicKey,
+        txn_hash: String,
+        token: String,
+        balance_change: i64,
+    },
+}
+
+impl WebhookEventKind {
+    /// Block events which trigger webhooks
+    pub fn from_event(event: &IndexerEvent) -> Option<(Self, StateHash, u32)> {
+        match event {
+            IndexerEvent::Db(DbEvent::Block(DbBlockEvent::NewBlock {
+                state_hash,
+                blockchain_length,
+            })) => Some((Self::NewBlock, state_hash.to_owned(), *blockchain_length)),
+            IndexerEvent::Db(DbEvent::Canonicity(DbCanonicityEvent::NewCanonicalBlock {
+                state_hash,
+                blockchain_length,
+            })) => Some((
+                Self::NewCanonicalBlock,
+                state_hash.to_owned(),
+                *blockchain_length,
+            )),
+            _ => None,
+        }
+    }
+}
+
+/// Watched public keys grouped by webhook url
+pub fn group_by_url(webhooks: Vec<Webhook>) -> BTreeMap<String, HashSet<PublicKey>> {
+    let mut urls = <BTreeMap<String, HashSet<PublicKey>>>::new();
+
+    for Webhook { public_key, url } in webhooks {
+        urls.entry(url).or_default().insert(public_key);
+    }
+
+    urls
+}
+
+/// Activity of the watched public keys in the block
+pub fn block_activity(
+    db: &IndexerStore,
+    state_hash: &StateHash,
+    watched: &HashSet<PublicKey>,
+) -> anyhow::Result<Vec<Activity>> {
+    let mut activity = vec![];
+
+    // user & zkapp commands
+    for cmd in db.get_block_user_commands(state_hash)?.unwrap_or_default() {
+        let txn_hash = cmd.hash()?.to_string();
+        let sender = cmd.sender();
+        let receivers = cmd.receiver();
+
+        for pk in watched.iter() {
+            if sender == *pk || receivers.contains(pk) || cmd.fee_payer_pk() == *pk {
+                activity.push(Activity::UserCommand {
+                    public_key: pk.to_owned(),
+                    txn_hash: txn_hash.to_owned(),
+                    sender: sender.to_owned(),
+                    receivers: receivers.to_owned(),
+                    amount: cmd.amount(),
+                    fee: cmd.fee(),
+                    applied: cmd.is_applied(),
+                });
+            }
+        }
+
+        if cmd.is_zkapp_command() {
+            for update in cmd.accounts_updated() {
+                if watched.contains(&update.public_key) {
+                    activity.push(Activity::ZkappUpdate {
+                        public_key: update.public_key,
+                        txn_hash: txn_hash.to_owned(),
+                        token: update.token.0,
+                        balance_change: update.balance_change,
+                    });
+                }
+            }
+        }
+    }
+
+    // internal commands
+    for cmd in db.get_internal_commands(state_hash)? {
+        let (receiver, amount, kind) = match &cmd {
+            DbInternalCommandWithData::Coinbase {
+                receiver,
+                amount,
+                kind,
+                ..
+            }
+            | DbInternalCommandWithData::FeeTransfer {
+                receiver,
+                amount,
+                kind,
+                ..
+            } => (receiver, *amount, kind),
+        };
+
+        if watched.contains(receiver) {
+            activity.push(Activity::InternalCommand {
+                public_key: receiver.to_owned(),
+                command_kind: kind.to_string(),
+                amount,
+            });
+        }
+    }
+
+    Ok(activity)
+}
+
+impl std::fmt::Display for Webhook {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{} -> {}", self.public_key, self.url)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn payload_json() -> anyhow::Result<()> {
+        let payload = WebhookPayload {
+            seq_num: 42,
+            event: WebhookEventKind::NewCanonicalBlock,
+            state_hash: StateHash::default(),
+            blockchain_length: 2,
+            activity: vec![Activity::InternalCommand {
+                public_key: PublicKey::default(),
+                command_kind: "Coinbase".to_string(),
+                amount: 720000000000,
+            }],
+        };
+        let json = serde_json::to_value(&payload)?;
+
+        assert_eq!(json["seq_num"], 42);
+        assert_eq!(json["event"], "new_canonical_block");
+        assert_eq!(json["activity"][0]["kind"], "internal_command");
+        assert_eq!(serde_json::from_value::<WebhookPayload>(json)?, payload);
+        Ok(())
+    }
+
+    #[test]
+    fn group_webhooks_by_url() {
+        let pk0 = PublicKey::default();
+        let pk1 = PublicKey::upper_bound();
+        let webhooks = vec![
+            Webhook {
+                public_key: pk0.clone(),
+                url: "http://a".to_string(),
+            },
+            Webhook {
+                public_key: pk1.clone(),
+                url: "http://a".to_string(),
+            },
+            Webhook {
+                public_key: pk1.clone(),
+                url: "http://b".to_string(),
+            },
+        ];
+        let urls = group_by_url(webhooks);
+
+        assert_eq!(urls["http://a"], HashSet::from([pk0, pk1.clone()]));
+        assert_eq!(urls["http://b"], HashSet::from([pk1]));
+    }
+}
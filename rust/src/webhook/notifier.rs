@@ -0,0 +1,184 @@
+//! Webhook notifier subsystem
+//!
+//! Walks the event log from each webhook url's persisted cursor, POSTing
+//! watched public key activity to the url. A url's cursor only advances once
+//! an event has been delivered, so failed deliveries are retried with backoff
+//! & restarts resume where the notifier left off.
+
+use super::{
+    block_activity, group_by_url, http::post_json, store::WebhookStore, WebhookEventKind,
+    WebhookPayload,
+};
+use crate::{base::public_key::PublicKey, event::store::EventStore, store::IndexerStore};
+use log::{debug, error, info, warn};
+use std::{
+    collections::{BTreeMap, HashSet},
+    sync::Arc,
+    time::Duration,
+};
+use tokio::sync::broadcast::error::RecvError;
+use tokio_graceful_shutdown::{FutureExt, SubsystemHandle};
+
+pub struct WebhookNotifier {
+    pub db: Arc<IndexerStore>,
+
+    /// Delivery attempts per url & event before backing off
+    pub max_attempts: u32,
+
+    /// Delay before the first retry, doubled after each failed attempt &
+    /// each round of undelivered events
+    pub initial_backoff: Duration,
+
+    /// Upper bound on the delay between attempts
+    pub max_backoff: Duration,
+
+    /// Per request timeout
+    pub timeout: Duration,
+}
+
+impl WebhookNotifier {
+    pub const DEFAULT_MAX_ATTEMPTS: u32 = 8;
+    pub const DEFAULT_INITIAL_BACKOFF: Duration = Duration::from_millis(500);
+    pub const DEFAULT_MAX_BACKOFF: Duration = Duration::from_secs(60);
+    pub const DEFAULT_TIMEOUT: Duration = Duration::from_secs(10);
+
+    pub fn new(db: Arc<IndexerStore>) -> Self {
+        Self {
+            db,
+            max_attempts: Self::DEFAULT_MAX_ATTEMPTS,
+            initial_backoff: Self::DEFAULT_INITIAL_BACKOFF,
+            max_backoff: Self::DEFAULT_MAX_BACKOFF,
+            timeout: Self::DEFAULT_TIMEOUT,
+        }
+    }
+
+    /// Runs until shutdown is requested
+    pub async fn run(self, subsys: SubsystemHandle) -> anyhow::Result<()> {
+        info!("Starting webhook notifier");
+
+        match self.notify_loop().cancel_on_shutdown(&subsys).await {
+            Ok(res) => res,
+            Err(_) => {
+                info!("Shutting down webhook notifier");
+                Ok(())
+            }
+        }
+    }
+
+    async fn notify_loop(&self) -> anyhow::Result<()> {
+        let mut events = self.db.subscribe_events();
+        let mut backoff = self.initial_backoff;
+
+        loop {
+            let next_seq_num = self.db.get_next_seq_num()?;
+            let cursors = self.process_pending().await?;
+
+            // retry undelivered events with backoff
+            if cursors.values().any(|cursor| *cursor < next_seq_num) {
+                tokio::time::sleep(backoff).await;
+                backoff = (backoff * 2).min(self.max_backoff);
+                continue;
+            }
+            backoff = self.initial_backoff;
+
+            // wait for new events, lagging only means there is more to do
+            match events.recv().await {
+                Ok(_) | Err(RecvError::Lagged(_)) => (),
+                Err(RecvError::Closed) => return Ok(()),
+            }
+        }
+    }
+
+    /// Delivers the events between each webhook url's cursor & the end of
+    /// the event log, returns the updated cursors
+    ///
+    /// A url's cursor only advances past an event once it has been delivered,
+    /// so undelivered events are retried by the next call. Urls without a
+    /// cursor start from the next event
+    pub async fn process_pending(&self) -> anyhow::Result<BTreeMap<String, u32>> {
+        let next_seq_num = self.db.get_next_seq_num()?;
+        let mut cursors = BTreeMap::new();
+
+        for (url, watched) in group_by_url(self.db.get_webhooks()?) {
+            let mut cursor = match self.db.get_webhook_cursor(&url)? {
+                Some(cursor) => cursor,
+                None => {
+                    self.db.set_webhook_cursor(&url, next_seq_num)?;
+                    next_seq_num
+                }
+            };
+
+            while cursor < next_seq_num {
+                if !self.process_event(&url, &watched, cursor).await? {
+                    break;
+                }
+
+                cursor += 1;
+                self.db.set_webhook_cursor(&url, cursor)?;
+            }
+
+            cursors.insert(url, cursor);
+        }
+
+        Ok(cursors)
+    }
+
+    /// Delivers the watched activity of the event to `url`, returns whether
+    /// the cursor can advance past the event
+    async fn process_event(
+        &self,
+        url: &str,
+        watched: &HashSet<PublicKey>,
+        seq_num: u32,
+    ) -> anyhow::Result<bool> {
+        let Some(event) = self.db.get_event(seq_num)? else {
+            return Ok(true);
+        };
+        let Some((event, state_hash, blockchain_length)) = WebhookEventKind::from_event(&event)
+        else {
+            return Ok(true);
+        };
+
+        let activity = block_activity(&self.db, &state_hash, watched)?;
+        if activity.is_empty() {
+            return Ok(true);
+        }
+
+        let payload = WebhookPayload {
+            seq_num,
+            event,
+            state_hash,
+            blockchain_length,
+            activity,
+        };
+        Ok(self.deliver(url, &serde_json::to_vec(&payload)?).await)
+    }
+
+    /// Retries with exponential backoff, returns whether the delivery
+    /// succeeded
+    async fn deliver(&self, url: &str, body: &[u8]) -> bool {
+        let mut backoff = self.initial_backoff;
+
+        for attempt in 1..=self.max_attempts {
+            match post_json(url, body, self.timeout).await {
+                Ok(status) if (200..300).contains(&status) => {
+                    debug!("Delivered webhook to {url}");
+                    return true;
+                }
+                Ok(status) => warn!("Webhook {url} responded {status} (attempt {attempt})"),
+                Err(e) => warn!("Webhook {url} delivery failed (attempt {attempt}): {e}"),
+            }
+
+            if attempt < self.max_attempts {
+                tokio::time::sleep(backoff).await;
+                backoff = (backoff * 2).min(self.max_backoff);
+            }
+        }
+
+        error!(
+            "Webhook {url} undelivered after {} attempts, retrying later",
+            self.max_attempts
+        );
+        false
+    }
+}
@@ -0,0 +1,27 @@
+//! Webhook store trait
+
+use super::Webhook;
+use crate::{base::public_key::PublicKey, store::Result};
+
+pub trait WebhookStore {
+    /// Watch `pk`'s activity, notifying `url`
+    ///
+    /// A new `url` is notified of events after the current end of the event
+    /// log
+    fn add_webhook(&self, pk: &PublicKey, url: &str) -> Result<()>;
+
+    /// Stop notifying `url` of `pk`'s activity, returns whether the webhook
+    /// existed
+    ///
+    /// The cursor of `url` is dropped once nothing is watched
+    fn remove_webhook(&self, pk: &PublicKey, url: &str) -> Result<bool>;
+
+    /// Get all webhooks, sorted by public key & url
+    fn get_webhooks(&self) -> Result<Vec<Webhook>>;
+
+    /// Get the sequence number of the next event to deliver to `url`
+    fn get_webhook_cursor(&self, url: &str) -> Result<Option<u32>>;
+
+    /// Set the sequence number of the next event to deliver to `url`
+    fn set_webhook_cursor(&self, url: &str, seq_num: u32) -> Result<()>;
+}
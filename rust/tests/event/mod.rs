@@ -9,4 +9,6 @@ mod store;
 #[cfg(all(test, feature = "tier2"))]
 mod sync;
 #[cfg(all(test, feature = "tier2"))]
+mod webhook;
+#[cfg(all(test, feature = "tier2"))]
 mod witness_tree;
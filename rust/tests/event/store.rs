@@ -36,6 +36,7 @@ fn add_and_get_events() -> anyhow::Result<()> {
         blockchain_length: 1,
         global_slot_since_genesis: 0,
         hash_last_vrf_output: VrfOutput::new("last_vrf_output".as_bytes().to_vec()),
+        fork_choice: None,
     };
 
     let event3 = IndexerEvent::WitnessTree(WitnessTreeEvent::UpdateBestTip {
@@ -60,3 +61,30 @@ fn add_and_get_events() -> anyhow::Result<()> {
 
     Ok(())
 }
+
+#[test]
+fn subscribe_to_events() -> anyhow::Result<()> {
+    let store_dir = setup_new_db_dir("event-store-subscribe")?;
+    let db = IndexerStore::new(store_dir.path(), true)?;
+    let mut receiver = db.subscribe_events();
+
+    let event0 = IndexerEvent::Db(DbEvent::Block(DbBlockEvent::NewBestTip {
+        blockchain_length: 23,
+        state_hash: StateHash::default(),
+    }));
+
+    let event1 = IndexerEvent::Db(DbEvent::Canonicity(DbCanonicityEvent::NewCanonicalBlock {
+        blockchain_length: 22,
+        state_hash: StateHash::default(),
+    }));
+
+    db.add_event(&event0)?;
+    db.add_event(&event1)?;
+
+    // subscribers receive recorded events in order
+    assert_eq!(receiver.try_recv()?, event0);
+    assert_eq!(receiver.try_recv()?, event1);
+    assert!(receiver.try_recv().is_err());
+
+    Ok(())
+}
@@ -0,0 +1,154 @@
+use crate::helpers::{state::*, store::*};
+use mina_indexer::{
+    block::{parser::BlockParser, precomputed::PcbVersion, store::BlockStore},
+    constants::*,
+    event::store::EventStore,
+    webhook::{notifier::WebhookNotifier, store::WebhookStore, Activity, WebhookPayload},
+};
+use std::{
+    path::PathBuf,
+    sync::{Arc, Mutex},
+    time::Duration,
+};
+use tokio::{
+    io::{AsyncBufReadExt, AsyncReadExt, AsyncWriteExt, BufReader},
+    net::TcpListener,
+};
+
+/// Local HTTP stand-in recording request bodies, responds with the given
+/// statuses in order, then 200
+async fn http_stand_in(statuses: Vec<u16>) -> anyhow::Result<(String, Arc<Mutex<Vec<Vec<u8>>>>)> {
+    let listener = TcpListener::bind("127.0.0.1:0").await?;
+    let url = format!("http://{}/hook", listener.local_addr()?);
+    let bodies = Arc::new(Mutex::new(vec![]));
+
+    tokio::spawn({
+        let bodies = bodies.clone();
+        let mut statuses = statuses.into_iter();
+
+        async move {
+            while let Ok((stream, _)) = listener.accept().await {
+                let mut reader = BufReader::new(stream);
+                let mut content_length = 0;
+
+                // headers
+                loop {
+                    let mut line = String::new();
+                    reader.read_line(&mut line).await.unwrap();
+
+                    if line == "\r\n" || line.is_empty() {
+                        break;
+                    }
+
+                    if let Some(len) = line.to_lowercase().strip_prefix("content-length:") {
+                        content_length = len.trim().parse().unwrap();
+                    }
+                }
+
+                // body
+                let mut body = vec![0; content_length];
+                reader.read_exact(&mut body).await.unwrap();
+
+                let status = statuses.next().unwrap_or(200);
+                if status == 200 {
+                    bodies.lock().unwrap().push(body);
+                }
+
+                reader
+                    .into_inner()
+                    .write_all(
+                        format!("HTTP/1.1 {status} X\r\nContent-Length: 0\r\n\r\n").as_bytes(),
+                    )
+                    .await
+                    .unwrap();
+            }
+        }
+    });
+
+    Ok((url, bodies))
+}
+
+#[tokio::test]
+async fn webhook_notifications() -> anyhow::Result<()> {
+    let store_dir = setup_new_db_dir("webhook-db")?;
+    let block_dir = &PathBuf::from("./tests/data/canonical_chain_discovery/contiguous");
+
+    let mut state = mainnet_genesis_state(store_dir.as_ref())?;
+    let store = state.indexer_store.as_ref().unwrap().clone();
+
+    // deliveries start from the end of the event log
+    let start = store.get_next_seq_num()?;
+    let notifier = WebhookNotifier {
+        max_attempts: 1,
+        initial_backoff: Duration::from_millis(1),
+        ..WebhookNotifier::new(store.clone())
+    };
+    assert!(notifier.process_pending().await?.is_empty());
+
+    // ingest the blocks
+    let mut bp = BlockParser::new_with_canonical_chain_discovery(
+        block_dir,
+        PcbVersion::V1,
+        MAINNET_CANONICAL_THRESHOLD,
+        false,
+        BLOCK_REPORTING_FREQ_NUM,
+    )
+    .await?;
+    state.add_blocks(&mut bp).await?;
+
+    // watch the best block's coinbase receiver from the start, the stand-in
+    // fails once
+    let best_hash = store.get_best_block_hash()?.unwrap();
+    let pk = store.get_coinbase_receiver(&best_hash)?.unwrap();
+    let (url, bodies) = http_stand_in(vec![500]).await?;
+    store.add_webhook(&pk, &url)?;
+    assert_eq!(
+        store.get_webhook_cursor(&url)?,
+        Some(store.get_next_seq_num()?)
+    );
+    store.set_webhook_cursor(&url, start)?;
+
+    // the failed delivery holds the cursor back
+    let cursor = notifier.process_pending().await?[&url];
+    assert!(cursor < store.get_next_seq_num()?);
+    assert_eq!(store.get_webhook_cursor(&url)?, Some(cursor));
+    assert!(bodies.lock().unwrap().is_empty());
+
+    // the retry delivers the events since the cursor
+    let cursor = notifier.process_pending().await?[&url];
+    assert_eq!(cursor, store.get_next_seq_num()?);
+    assert_eq!(store.get_webhook_cursor(&url)?, Some(cursor));
+
+    let payloads: Vec<WebhookPayload> = bodies
+        .lock()
+        .unwrap()
+        .iter()
+        .map(|body| serde_json::from_slice(body).unwrap())
+        .collect();
+    assert!(!payloads.is_empty());
+
+    for payload in payloads.iter() {
+        assert!(payload.seq_num >= start && payload.seq_num < cursor);
+        assert!(payload.activity.iter().all(|activity| match activity {
+            Activity::UserCommand { public_key, .. }
+            | Activity::InternalCommand { public_key, .. }
+            | Activity::ZkappUpdate { public_key, .. } => *public_key == pk,
+        }));
+    }
+
+    // the best block's coinbase is delivered
+    assert!(payloads
+        .iter()
+        .any(|payload| payload.state_hash == best_hash));
+
+    // nothing is redelivered
+    let num_delivered = payloads.len();
+    assert_eq!(notifier.process_pending().await?[&url], cursor);
+    assert_eq!(bodies.lock().unwrap().len(), num_delivered);
+
+    // unwatched urls drop their cursor
+    assert!(store.remove_webhook(&pk, &url)?);
+    assert_eq!(store.get_webhook_cursor(&url)?, None);
+
+    Ok(())
+}
@@ -1,4 +1,9 @@
-use mina_indexer::mina_blocks::v2::{ActionState, ZkappEvent};
+use mina_indexer::{
+    base::state_hash::StateHash,
+    command::TxnHash,
+    mina_blocks::v2::{ActionState, ZkappEvent},
+    store::zkapp::{actions::ZkappActionWithMeta, events::ZkappEventWithMeta},
+};
 use quickcheck::{Arbitrary, Gen};
 
 #[derive(Clone)]
@@ -37,3 +42,25 @@ impl Arbitrary for TestGen<ZkappEvent> {
         Self(format!("0x{}", hex::encode(bytes)).into())
     }
 }
+
+impl Arbitrary for TestGen<ZkappActionWithMeta> {
+    fn arbitrary(g: &mut Gen) -> Self {
+        Self(ZkappActionWithMeta {
+            action: <TestGen<ActionState>>::arbitrary(g).0,
+            state_hash: StateHash::default(),
+            block_height: u32::arbitrary(g),
+            txn_hash: TxnHash::V2("5JvH3LEJrazb9DpQb5Wym9Q1ZWyCVJmc9TNgubSjXPCHfSuDc2LL".into()),
+        })
+    }
+}
+
+impl Arbitrary for TestGen<ZkappEventWithMeta> {
+    fn arbitrary(g: &mut Gen) -> Self {
+        Self(ZkappEventWithMeta {
+            event: <TestGen<ZkappEvent>>::arbitrary(g).0,
+            state_hash: StateHash::default(),
+            block_height: u32::arbitrary(g),
+            txn_hash: TxnHash::V2("5JvH3LEJrazb9DpQb5Wym9Q1ZWyCVJmc9TNgubSjXPCHfSuDc2LL".into()),
+        })
+    }
+}
@@ -0,0 +1,145 @@
+use crate::helpers::{state::*, store::*};
+use mina_indexer::{
+    block::{parser::BlockParser, precomputed::PcbVersion},
+    constants::*,
+    ledger::store::{
+        balance_history::BalanceHistoryStore, best::BestLedgerStore, staged::StagedLedgerStore,
+    },
+    store::{
+        fixed_keys::FixedKeys,
+        version::{IndexerStoreVersion, VersionStore},
+        IndexerStore,
+    },
+};
+use std::path::PathBuf;
+
+#[tokio::test]
+async fn check_balance_history() -> anyhow::Result<()> {
+    let store_dir = setup_new_db_dir("balance-history-db")?;
+    let block_dir = &PathBuf::from("./tests/data/canonical_chain_discovery/contiguous");
+
+    let mut state = mainnet_genesis_state(store_dir.as_ref())?;
+    let mut bp = BlockParser::new_with_canonical_chain_discovery(
+        block_dir,
+        PcbVersion::V1,
+        MAINNET_CANONICAL_THRESHOLD,
+        false,
+        BLOCK_REPORTING_FREQ_NUM,
+    )
+    .await?;
+
+    // ingest the blocks
+    state.add_blocks(&mut bp).await?;
+
+    let store = state.indexer_store.as_ref().unwrap();
+    let best_ledger = store.get_best_ledger(false)?.unwrap();
+
+    for (token, token_ledger) in best_ledger.tokens.iter() {
+        for pk in token_ledger.accounts.keys() {
+            let history = store.get_balance_history(pk, token, None, None)?;
+
+            // ascending heights
+            assert!(history
+                .windows(2)
+                .all(|w| w[0].block_height < w[1].block_height));
+
+            // each entry matches the staged ledger account after the block
+            for entry in history.iter() {
+                let staged_acct = store
+                    .get_staged_account_display(pk, token, &entry.state_hash)?
+                    .unwrap();
+                assert_eq!(staged_acct.balance.0, entry.balance);
+            }
+
+            // the last entry matches the best ledger account
+            if let Some(entry) = history.last() {
+                let best_acct = store.get_best_account_display(pk, token)?.unwrap();
+                assert_eq!(best_acct.balance.0, entry.balance);
+
+                // height range is inclusive
+                let range = store.get_balance_history(
+                    pk,
+                    token,
+                    Some(entry.block_height),
+                    Some(entry.block_height),
+                )?;
+                assert_eq!(range, vec![entry.clone()]);
+            }
+        }
+    }
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn migrate_balance_history() -> anyhow::Result<()> {
+    let store_dir = setup_new_db_dir("balance-history-migration-db")?;
+    let block_dir = &PathBuf::from("./tests/data/canonical_chain_discovery/contiguous");
+
+    let mut state = mainnet_genesis_state(store_dir.as_ref())?;
+    let mut bp = BlockParser::new_with_canonical_chain_discovery(
+        block_dir,
+        PcbVersion::V1,
+        MAINNET_CANONICAL_THRESHOLD,
+        false,
+        BLOCK_REPORTING_FREQ_NUM,
+    )
+    .await?;
+
+    // ingest the blocks
+    state.add_blocks(&mut bp).await?;
+
+    let store = state.indexer_store.as_ref().unwrap();
+    let best_ledger = store.get_best_ledger(false)?.unwrap();
+
+    // simulate a v0.16.3 store without balance history
+    for (token, token_ledger) in best_ledger.tokens.iter() {
+        for pk in token_ledger.accounts.keys() {
+            for entry in store.get_balance_history(pk, token, None, None)? {
+                store.remove_balance_history(pk, token, entry.block_height)?;
+            }
+        }
+    }
+
+    let old_version = IndexerStoreVersion {
+        patch: 3,
+        ..Default::default()
+    };
+    store.database.put(
+        IndexerStore::INDEXER_STORE_VERSION_KEY,
+        serde_json::to_vec(&old_version)?,
+    )?;
+
+    // backfill
+    assert!(store.migrate()?);
+    assert_eq!(
+        store.get_db_version()?.semver(),
+        IndexerStoreVersion::current_semver()
+    );
+    assert_eq!(store.get_migration_checkpoint()?, None);
+
+    // nothing left to migrate
+    assert!(!store.migrate()?);
+
+    for (token, token_ledger) in best_ledger.tokens.iter() {
+        for pk in token_ledger.accounts.keys() {
+            let history = store.get_balance_history(pk, token, None, None)?;
+
+            // each entry matches the staged ledger account after the block
+            for entry in history.iter() {
+                let staged_acct = store
+                    .get_staged_account_display(pk, token, &entry.state_hash)?
+                    .unwrap();
+                assert_eq!(staged_acct.balance.0, entry.balance);
+            }
+
+            // the last entry matches the best ledger account
+            if let Some(entry) = history.last() {
+                let best_acct = store.get_best_account_display(pk, token)?.unwrap();
+                assert_eq!(best_acct.balance.0, entry.balance);
+            }
+        }
+    }
+
+    Ok(())
+}
@@ -0,0 +1,60 @@
+use crate::helpers::{state::*, store::*};
+use mina_indexer::{
+    block::parser::BlockParser,
+    canonicity::store::CanonicityStore,
+    chain::store::ChainStore,
+    constants::MAINNET_GENESIS_HASH,
+    ledger::{
+        staking::StakingLedger,
+        store::{staged::StagedLedgerStore, staking::StakingLedgerStore},
+    },
+};
+use std::path::PathBuf;
+
+#[tokio::test]
+async fn derived_staking_ledger_is_persisted() -> anyhow::Result<()> {
+    let store_dir = setup_new_db_dir("derived-staking-ledger-db")?;
+    let blocks_dir = PathBuf::from("./tests/data/canonical_chain_discovery/contiguous");
+
+    let mut state = mainnet_genesis_state(store_dir.as_ref())?;
+    let mut block_parser = BlockParser::new_testing(&blocks_dir)?;
+    state.add_blocks(&mut block_parser).await?;
+
+    let store = state.indexer_store.as_ref().unwrap();
+    let epoch = 1;
+    let genesis_state_hash = MAINNET_GENESIS_HASH.into();
+    assert!(store
+        .build_staking_ledger(epoch, &genesis_state_hash)?
+        .is_none());
+
+    // the snarked ledger is a canonical ancestor's staged ledger
+    let tip_hash = store.get_canonical_hash_at_height(3)?.unwrap();
+    let snarked_hash = store.get_canonical_hash_at_height(2)?.unwrap();
+    let ledger_hash = store.get_block_staged_ledger_hash(&snarked_hash)?.unwrap();
+
+    store.derive_staking_ledger(&tip_hash, epoch, &ledger_hash, &genesis_state_hash)?;
+
+    let expected = StakingLedger::from_staged_ledger(
+        &store
+            .get_staged_ledger_at_state_hash(&snarked_hash, false)?
+            .unwrap(),
+        epoch,
+        store.get_current_network()?,
+        ledger_hash.clone(),
+        genesis_state_hash.clone(),
+    );
+    let persisted = store
+        .build_staking_ledger(epoch, &genesis_state_hash)?
+        .expect("derived staking ledger");
+
+    assert_eq!(persisted.epoch, epoch);
+    assert_eq!(persisted.ledger_hash, ledger_hash);
+    assert_eq!(persisted.total_currency, expected.total_currency);
+    assert_eq!(
+        persisted.staking_ledger.len(),
+        expected.staking_ledger.len()
+    );
+    assert!(persisted.mismatches(&expected).is_empty());
+
+    Ok(())
+}
@@ -0,0 +1,56 @@
+use crate::helpers::{state::*, store::*};
+use anyhow::Context;
+use mina_indexer::{
+    block::{parser::BlockParser, precomputed::PcbVersion, store::BlockStore},
+    canonicity::store::CanonicityStore,
+    constants::*,
+    ledger::store::staged::StagedLedgerStore,
+};
+use std::path::PathBuf;
+
+#[tokio::test]
+async fn check_historical_staged_accounts() -> anyhow::Result<()> {
+    let store_dir = setup_new_db_dir("historical-staged-accounts-db")?;
+    let block_dir = &PathBuf::from("./tests/data/canonical_chain_discovery/contiguous");
+
+    let mut state = mainnet_genesis_state(store_dir.as_ref())?;
+    let mut bp = BlockParser::new_with_canonical_chain_discovery(
+        block_dir,
+        PcbVersion::V1,
+        MAINNET_CANONICAL_THRESHOLD,
+        false,
+        BLOCK_REPORTING_FREQ_NUM,
+    )
+    .await?;
+
+    // ingest the blocks
+    state.add_blocks(&mut bp).await?;
+
+    let store = state.indexer_store.as_ref().unwrap();
+    let best_height = store.get_best_block_height()?.unwrap();
+
+    // accounts rebuilt from the nearest persisted staged ledger match the
+    // full staged ledger at each canonical block, including accounts created
+    // after the persisted ledger
+    for height in 1..=best_height {
+        let Some(state_hash) = store.get_canonical_hash_at_height(height)? else {
+            continue;
+        };
+        let staged_ledger = store
+            .get_staged_ledger_at_state_hash(&state_hash, false)?
+            .unwrap();
+
+        for (token, token_ledger) in staged_ledger.tokens.iter() {
+            for (pk, staged_acct) in token_ledger.accounts.iter() {
+                let historical_acct = store
+                    .get_staged_account_block_height(pk, token, height)?
+                    .with_context(|| format!("\npk: {pk}\ntoken: {token}\nheight: {height}"))
+                    .unwrap();
+
+                assert_eq!(*staged_acct, historical_acct);
+            }
+        }
+    }
+
+    Ok(())
+}
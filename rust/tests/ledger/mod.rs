@@ -1,6 +1,12 @@
 #[cfg(all(test, feature = "tier2"))]
+mod balance_history;
+#[cfg(all(test, feature = "tier2"))]
 mod best_ledger_balance_sorted_accounts;
 #[cfg(all(test, feature = "tier2"))]
+mod derived_staking_ledger;
+#[cfg(all(test, feature = "tier2"))]
+mod historical_staged_accounts;
+#[cfg(all(test, feature = "tier2"))]
 mod staged_ledger_balance_sorted_accounts;
 #[cfg(all(test, feature = "tier2"))]
 mod staking_ledger_balance_sorted_accounts;
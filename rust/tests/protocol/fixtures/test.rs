@@ -13,8 +13,8 @@ pub struct BlockFixture {
     pub block_name: &'static str,
 }
 
-// FIXME: Move layouts into this crate?
-pub const BLOCK_LAYOUT: &str = include_str!("../layouts/external_transition.json");
+pub const BLOCK_LAYOUT: &str =
+    include_str!("../../../src/protocol/layouts/external_transition.json");
 
 lazy_static! {
     pub static ref BLOCK_RULE: BinProtRule = {
@@ -0,0 +1,60 @@
+use mina_indexer::{
+    base::amount::Amount,
+    block::{parser::BlockParser, precomputed::PrecomputedBlock},
+    constants::MINA_SCALE,
+    ledger::{account::Account, genesis::GenesisLedger, token::TokenAddress, Ledger},
+    state::{missing::SharedMissingBlocks, IndexerState},
+};
+use std::path::PathBuf;
+
+/// Adds all blocks in `./tests/data/sequential_blocks` in reverse height
+/// order, the incrementally updated missing blocks always match the missing
+/// blocks computed from the whole witness tree
+#[tokio::test]
+async fn incremental_missing_blocks() -> anyhow::Result<()> {
+    let block_dir = PathBuf::from("./tests/data/sequential_blocks");
+    let mut block_parser = BlockParser::new_testing(&block_dir)?;
+
+    let mut blocks = vec![];
+    while let Some((block, block_bytes)) = block_parser.next_block().await? {
+        blocks.push((PrecomputedBlock::from(block), block_bytes));
+    }
+
+    // root ledger with sufficient balances
+    let mut ledger: Ledger = GenesisLedger::new_v1()?.into();
+    for pk in [
+        "B62qrdhG66vK71Jbdz6Xs7cnDxQ8f6jZUFvefkp3pje4EejYUTvotGP",
+        "B62qrRvo5wngd5WA1dgXkQpCdQMRDndusmjfWXWT1LgsSFFdBS9RCsV",
+    ] {
+        ledger.insert_account(
+            Account {
+                public_key: pk.into(),
+                balance: Amount(1000 * MINA_SCALE),
+                ..Default::default()
+            },
+            &TokenAddress::default(),
+        );
+    }
+
+    // the lowest block is the root, the rest arrive highest first
+    let (root_block, root_bytes) = blocks.remove(0);
+    let mut state = IndexerState::new_testing(
+        &root_block,
+        root_bytes,
+        Some(&ledger),
+        None,
+        None,
+        None,
+        None,
+    )?;
+
+    for (block, _) in blocks.iter().rev() {
+        state.add_block_to_witness_tree(block, true, true)?;
+
+        let incremental = state.missing_blocks();
+        state.share_missing_blocks(SharedMissingBlocks::default());
+        assert_eq!(state.missing_blocks(), incremental);
+    }
+
+    Ok(())
+}
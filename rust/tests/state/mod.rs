@@ -1,6 +1,7 @@
 mod dangling_branches;
 mod hardfork;
 mod ledger;
+mod missing_blocks;
 #[cfg(all(test, feature = "tier2"))]
 mod orphaned_blocks;
 mod root_branch;
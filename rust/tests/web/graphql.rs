@@ -0,0 +1,133 @@
+use crate::helpers::store::*;
+use mina_indexer::{
+    block::{
+        precomputed::{PcbVersion, PrecomputedBlock},
+        store::BlockStore,
+    },
+    ledger::{
+        diff::{account::AccountDiff, LedgerDiff},
+        store::update::{AccountUpdate, DbAccountUpdate},
+    },
+    store::IndexerStore,
+    web::graphql::build_schema,
+};
+use serde_json::{json, Value};
+use std::{
+    collections::HashSet,
+    path::PathBuf,
+    sync::{Arc, RwLock},
+};
+
+const ZKAPP: &str = "B62qpr8QD2Q9uzJU2pp7XbWW6NB9CQxv4BS6vVXRwwXhcfPwJM7FdCs";
+const TXN_HASH: &str = "5JuXwUB4vq9qCGBZuUJ2YW3LtyoJLwCbwya8F2Da2L3Qu8yCbUXr";
+const EMPTY_ACTION_STATE: &str =
+    "0x3772BC5435B957F81F86F752E93F2E29E886AC24580B3D1EC879C1DAD26965F9";
+
+#[tokio::test]
+async fn zkapp_actions_and_events() -> anyhow::Result<()> {
+    let store_dir = setup_new_db_dir("graphql-zkapp-db")?;
+    let store = Arc::new(IndexerStore::new(store_dir.path(), true)?);
+
+    // block 412598 contains a zkapp command with both actions & events
+    let path = PathBuf::from("./tests/data/misc_blocks/mainnet-412598-3NK6LSkCCBNoHmiRfYhYijDuxwgYQsU5GcdEMbUGhNdHDkJyrh3x.json");
+    let block = PrecomputedBlock::parse_file(&path, PcbVersion::V2)?;
+    let state_hash = block.state_hash();
+    let block_height = block.blockchain_length();
+    store.add_block(&block, std::fs::metadata(&path)?.len())?;
+
+    // apply the block's actions & events diffs
+    let account_diffs = LedgerDiff::from_precomputed(&block)
+        .account_diffs
+        .into_iter()
+        .flatten()
+        .filter(|diff| {
+            matches!(
+                diff,
+                AccountDiff::ZkappActions(_) | AccountDiff::ZkappEvents(_)
+            )
+        })
+        .collect();
+    let update = AccountUpdate {
+        block_state_hash: state_hash.clone(),
+        blockchain_length: block_height,
+        account_diffs,
+        token_diffs: vec![],
+        new_accounts: HashSet::new(),
+        new_zkapp_accounts: HashSet::new(),
+    };
+    DbAccountUpdate::apply_updates(&store, vec![update], &state_hash, block_height)?;
+
+    let schema = build_schema(store, Arc::new(RwLock::new(Default::default())));
+    let query = format!(
+        r#"{{
+            actions: getActions(query: {{ address: "{ZKAPP}" }}, sortBy: BLOCKHEIGHT_ASC) {{
+                index
+                blockInfo {{ stateHash height }}
+                actionState {{ actionStateOne actionStateTwo actionStateFive }}
+                actionData {{ data transactionInfo {{ hash status }} }}
+            }}
+            events: getEvents(query: {{ address: "{ZKAPP}" }}, sortBy: BLOCKHEIGHT_ASC) {{
+                index
+                blockInfo {{ stateHash height }}
+                eventData {{ data }}
+                transactionInfo {{ hash status }}
+            }}
+            noAddress: getActions(query: {{}}) {{ index }}
+        }}"#
+    );
+
+    let response = schema.execute(query).await;
+    assert!(response.errors.is_empty(), "{:?}", response.errors);
+    let data: Value = response.data.into_json()?;
+
+    // each action field element is indexed
+    let actions = data["actions"].as_array().unwrap();
+    assert_eq!(actions.len(), 9);
+    assert_eq!(
+        actions[0],
+        json!({
+            "index": 0,
+            "blockInfo": {
+                "stateHash": state_hash.0,
+                "height": block_height,
+            },
+            "actionState": {
+                "actionStateOne": "0x29AC96C3E13080AC35EC11F86AA9024A8D2284FD175911971A85FD7987D7AE3D",
+                "actionStateTwo": EMPTY_ACTION_STATE,
+                "actionStateFive": EMPTY_ACTION_STATE,
+            },
+            "actionData": {
+                "data": "0x0000000000000000000000000000000000000000000000000000000000000001",
+                "transactionInfo": {
+                    "hash": TXN_HASH,
+                    "status": "applied",
+                },
+            },
+        })
+    );
+
+    // each event field element is indexed
+    let events = data["events"].as_array().unwrap();
+    assert_eq!(events.len(), 10);
+    assert_eq!(
+        events[9],
+        json!({
+            "index": 9,
+            "blockInfo": {
+                "stateHash": state_hash.0,
+                "height": block_height,
+            },
+            "eventData": {
+                "data": "0x0000000000000000000000000000000000000000000000000000000000000001",
+            },
+            "transactionInfo": {
+                "hash": TXN_HASH,
+                "status": "applied",
+            },
+        })
+    );
+
+    // no address, no actions
+    assert_eq!(data["noAddress"], Value::Null);
+    Ok(())
+}
@@ -0,0 +1,3 @@
+mod graphql;
+#[cfg(all(test, feature = "tier2"))]
+mod rosetta;
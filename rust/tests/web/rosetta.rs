@@ -0,0 +1,206 @@
+use crate::helpers::{state::*, store::*};
+use actix_web::{http::StatusCode, test, web, App};
+use mina_indexer::{
+    block::{parser::BlockParser, precomputed::PcbVersion, store::BlockStore},
+    chain::Network,
+    constants::*,
+    web::{
+        rosetta::{self, types::*, RosettaConfig},
+        ENDPOINT_ROSETTA,
+    },
+};
+use serde_json::{json, Value};
+use std::path::PathBuf;
+
+/// Posts the request body to the Rosetta endpoint, evaluates to the
+/// response's status & JSON body
+macro_rules! post {
+    ($app:expr, $endpoint:expr, $body:expr $(,)?) => {{
+        let req = test::TestRequest::post()
+            .uri(&format!("{ENDPOINT_ROSETTA}{}", $endpoint))
+            .set_json($body)
+            .to_request();
+        let res = test::call_service($app, req).await;
+        let status = res.status();
+        let body: Value = test::read_body_json(res).await;
+        (status, body)
+    }};
+}
+
+#[actix_web::test]
+async fn rosetta_endpoints() -> anyhow::Result<()> {
+    let store_dir = setup_new_db_dir("rosetta-db")?;
+    let block_dir = &PathBuf::from("./tests/data/canonical_chain_discovery/contiguous");
+
+    let mut state = mainnet_genesis_state(store_dir.as_ref())?;
+    let store = state.indexer_store.as_ref().unwrap().clone();
+
+    let mut bp = BlockParser::new_with_canonical_chain_discovery(
+        block_dir,
+        PcbVersion::V1,
+        MAINNET_CANONICAL_THRESHOLD,
+        false,
+        BLOCK_REPORTING_FREQ_NUM,
+    )
+    .await?;
+    state.add_blocks(&mut bp).await?;
+
+    let config = RosettaConfig::new(Network::Mainnet);
+    let network = serde_json::to_value(config.network_identifier())?;
+    let app =
+        test::init_service(App::new().app_data(web::Data::new(store.clone())).service(
+            web::scope(ENDPOINT_ROSETTA).configure(|cfg| rosetta::configure(cfg, config)),
+        ))
+        .await;
+
+    // the configured network is listed
+    let (status, body) = post!(&app, "/network/list", json!({}));
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(body["network_identifiers"], json!([network]));
+
+    // other networks are rejected with an error object
+    let devnet = RosettaConfig::new(Network::Devnet).network_identifier();
+    let (status, body) = post!(
+        &app,
+        "/network/status",
+        json!({ "network_identifier": devnet }),
+    );
+    assert_eq!(status, StatusCode::BAD_REQUEST);
+    assert_eq!(body["code"], 1);
+    assert_eq!(body["retriable"], false);
+
+    // malformed requests are rejected with an error object
+    let (status, body) = post!(&app, "/network/status", json!({ "network": "mainnet" }));
+    assert_eq!(status, StatusCode::BAD_REQUEST);
+    assert_eq!(body["code"], 5);
+
+    // the best block is the current block
+    let best_hash = store.get_best_block_hash()?.unwrap();
+    let (status, body) = post!(
+        &app,
+        "/network/status",
+        json!({ "network_identifier": network }),
+    );
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(body["current_block_identifier"]["hash"], best_hash.0);
+
+    // unknown blocks are not found
+    let (status, body) = post!(
+        &app,
+        "/block",
+        json!({
+            "network_identifier": network,
+            "block_identifier": { "index": u32::MAX },
+        }),
+    );
+    assert_eq!(status, StatusCode::NOT_FOUND);
+    assert_eq!(body["code"], 2);
+
+    // best block
+    let (status, body) = post!(
+        &app,
+        "/block",
+        json!({
+            "network_identifier": network,
+            "block_identifier": { "hash": best_hash.0 },
+        }),
+    );
+    assert_eq!(status, StatusCode::OK);
+
+    let block: BlockResponse = serde_json::from_value(body)?;
+    assert_eq!(block.block.block_identifier.hash, best_hash.0);
+
+    // the block's coinbase is an internal command
+    let coinbase = block
+        .block
+        .transactions
+        .iter()
+        .find(|txn| txn.operations.iter().any(|op| op.kind == "coinbase_inc"))
+        .expect("coinbase");
+    let coinbase_id = &coinbase.transaction_identifier;
+    assert!(coinbase_id
+        .hash
+        .starts_with(&format!("{}:internal:", best_hash.0)));
+
+    // internal commands are found by their identifier
+    let (status, body) = post!(
+        &app,
+        "/block/transaction",
+        json!({
+            "network_identifier": network,
+            "block_identifier": block.block.block_identifier,
+            "transaction_identifier": coinbase_id,
+        }),
+    );
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(
+        body["transaction"]["transaction_identifier"],
+        json!(coinbase_id)
+    );
+
+    let (status, body) = post!(
+        &app,
+        "/search/transactions",
+        json!({
+            "network_identifier": network,
+            "transaction_identifier": coinbase_id,
+        }),
+    );
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(body["total_count"], 1);
+    assert_eq!(
+        body["transactions"][0]["block_identifier"]["hash"],
+        best_hash.0
+    );
+    assert_eq!(
+        body["transactions"][0]["transaction"]["transaction_identifier"],
+        json!(coinbase_id)
+    );
+
+    // user commands are found by their hash
+    for txn in block.block.transactions.iter() {
+        if txn.transaction_identifier.hash.contains(":internal:") {
+            continue;
+        }
+
+        let (status, body) = post!(
+            &app,
+            "/search/transactions",
+            json!({
+                "network_identifier": network,
+                "transaction_identifier": txn.transaction_identifier,
+            }),
+        );
+        assert_eq!(status, StatusCode::OK);
+        assert!(body["total_count"].as_u64().unwrap() >= 1);
+    }
+
+    // the coinbase receiver's balance
+    let coinbase_receiver = store.get_coinbase_receiver(&best_hash)?.unwrap();
+    let (status, body) = post!(
+        &app,
+        "/account/balance",
+        json!({
+            "network_identifier": network,
+            "account_identifier": { "address": coinbase_receiver.0 },
+            "block_identifier": { "hash": best_hash.0 },
+        }),
+    );
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(body["block_identifier"]["hash"], best_hash.0);
+    assert_eq!(body["balances"][0]["currency"]["symbol"], "MINA");
+
+    // invalid accounts are rejected
+    let (status, body) = post!(
+        &app,
+        "/account/balance",
+        json!({
+            "network_identifier": network,
+            "account_identifier": { "address": "B62" },
+        }),
+    );
+    assert_eq!(status, StatusCode::BAD_REQUEST);
+    assert_eq!(body["code"], 4);
+
+    Ok(())
+}
@@ -2,8 +2,10 @@ use crate::{generators::*, helpers::store::*};
 use mina_indexer::{
     base::public_key::PublicKey,
     ledger::token::TokenAddress,
-    mina_blocks::v2::zkapp::action_state::ActionState,
-    store::{zkapp::actions::ZkappActionStore, IndexerStore},
+    store::{
+        zkapp::actions::{ZkappActionStore, ZkappActionWithMeta},
+        IndexerStore,
+    },
 };
 use quickcheck::Arbitrary;
 
@@ -15,9 +17,9 @@ fn action_store_test() -> anyhow::Result<()> {
     // generate arbitrary actions
     let g = &mut gen();
     let actions = vec![
-        <TestGen<ActionState>>::arbitrary(g).0,
-        <TestGen<ActionState>>::arbitrary(g).0,
-        <TestGen<ActionState>>::arbitrary(g).0,
+        <TestGen<ZkappActionWithMeta>>::arbitrary(g).0,
+        <TestGen<ZkappActionWithMeta>>::arbitrary(g).0,
+        <TestGen<ZkappActionWithMeta>>::arbitrary(g).0,
     ];
     let actions_length = actions.len() as u32;
 
@@ -58,7 +60,7 @@ fn action_store_test() -> anyhow::Result<()> {
 
     let index = u32::arbitrary(g);
     let index = index % actions_length;
-    let set_action = <TestGen<ActionState>>::arbitrary(g).0;
+    let set_action = <TestGen<ZkappActionWithMeta>>::arbitrary(g).0;
 
     indexer_store.set_action(&pk, &token, &set_action, index)?;
     assert_eq!(
@@ -2,8 +2,10 @@ use crate::{generators::*, helpers::store::*};
 use mina_indexer::{
     base::public_key::PublicKey,
     ledger::token::TokenAddress,
-    mina_blocks::v2::ZkappEvent,
-    store::{zkapp::events::ZkappEventStore, IndexerStore},
+    store::{
+        zkapp::events::{ZkappEventStore, ZkappEventWithMeta},
+        IndexerStore,
+    },
 };
 use quickcheck::Arbitrary;
 
@@ -15,9 +17,9 @@ fn event_store_test() -> anyhow::Result<()> {
     // generate arbitrary events
     let g = &mut gen();
     let events = vec![
-        <TestGen<ZkappEvent>>::arbitrary(g).0,
-        <TestGen<ZkappEvent>>::arbitrary(g).0,
-        <TestGen<ZkappEvent>>::arbitrary(g).0,
+        <TestGen<ZkappEventWithMeta>>::arbitrary(g).0,
+        <TestGen<ZkappEventWithMeta>>::arbitrary(g).0,
+        <TestGen<ZkappEventWithMeta>>::arbitrary(g).0,
     ];
     let events_length = events.len() as u32;
 
@@ -58,7 +60,7 @@ fn event_store_test() -> anyhow::Result<()> {
 
     let index = u32::arbitrary(g);
     let index = index % events_length;
-    let set_event = <TestGen<ZkappEvent>>::arbitrary(g).0;
+    let set_event = <TestGen<ZkappEventWithMeta>>::arbitrary(g).0;
 
     indexer_store.set_event(&pk, &token, &set_event, index)?;
     assert_eq!(
@@ -30,6 +30,7 @@ test_names = %w[
   ledgers
   sync
   replay
+  block_source
   transactions
   transactions_csv
   snark_work
@@ -39,6 +40,8 @@ test_names = %w[
   snapshot_database_dir
   rest_accounts_summary
   rest_blocks
+  rest_api_v1
+  address_book
   genesis_block_creator_v1
   genesis_block_creator_v2
   txn_nonces
@@ -46,6 +49,8 @@ test_names = %w[
   watch_staking_ledgers
   do_not_ingest_orphan_blocks
   staking_delegations
+  pool_payouts
+  producer_report
   internal_commands
   internal_commands_csv
   version_file
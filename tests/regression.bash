@@ -123,9 +123,18 @@ test_indexer_cli_reports() {
 	idxr accounts public-key --help 2>&1 |
 		grep -iq "Usage: mina-indexer accounts public-key"
 
+	idxr accounts balance-history --help 2>&1 |
+		grep -iq "Usage: mina-indexer accounts balance-history"
+
 	idxr blocks --help 2>&1 |
 		grep -iq "Usage: mina-indexer blocks"
 
+	idxr webhooks --help 2>&1 |
+		grep -iq "Usage: mina-indexer webhooks"
+
+	idxr address-book --help 2>&1 |
+		grep -iq "Usage: mina-indexer address-book"
+
 	idxr blocks best --help 2>&1 |
 		grep -iq "Usage: mina-indexer blocks best"
 
@@ -144,6 +153,9 @@ test_indexer_cli_reports() {
 	idxr blocks children --help 2>&1 |
 		grep -iq "Usage: mina-indexer blocks children"
 
+	idxr blocks missing --help 2>&1 |
+		grep -iq "Usage: mina-indexer blocks missing"
+
 	idxr ledgers --help 2>&1 |
 		grep -iq "Usage: mina-indexer ledgers"
 
@@ -165,6 +177,12 @@ test_indexer_cli_reports() {
 	idxr staking-ledgers hash --help 2>&1 |
 		grep -iq "Usage: mina-indexer staking-ledgers hash"
 
+	idxr staking-ledgers payouts --help 2>&1 |
+		grep -iq "Usage: mina-indexer staking-ledgers payouts"
+
+	idxr blocks production --help 2>&1 |
+		grep -iq "Usage: mina-indexer blocks production"
+
 	idxr shutdown --help 2>&1 |
 		grep -iq "Usage: mina-indexer shutdown"
 
@@ -174,6 +192,9 @@ test_indexer_cli_reports() {
 	idxr transactions hash --help 2>&1 |
 		grep -iq "Usage: mina-indexer transactions hash"
 
+	idxr transactions hash --help 2>&1 |
+		grep -iq -- "--command <COMMAND>"
+
 	idxr transactions public-key --help 2>&1 |
 		grep -iq "Usage: mina-indexer transactions public-key"
 
@@ -201,13 +222,25 @@ test_indexer_cli_reports() {
 	idxr db-version --help 2>&1 |
 		grep -iq "Usage: mina-indexer db-version"
 
+	idxr --help 2>&1 |
+		grep -iq -- "--output <OUTPUT>"
+
 	# Server commands
 	idxr server start --help 2>&1 |
 		grep -iq "Usage: mina-indexer server start"
 
+	idxr server start --help 2>&1 |
+		grep -iq -- "--poseidon-params <DIR>"
+
+	idxr server start --help 2>&1 |
+		grep -iq -- "--verify-signatures"
+
 	idxr server shutdown --help 2>&1 |
 		grep -iq "Usage: mina-indexer server shutdown"
 
+	idxr server replica --help 2>&1 |
+		grep -iq "Usage: mina-indexer server replica"
+
 	# Database commands
 	idxr database create --help 2>&1 |
 		grep -iq "Usage: mina-indexer database create"
@@ -218,6 +251,9 @@ test_indexer_cli_reports() {
 	idxr database restore --help 2>&1 |
 		grep -iq "Usage: mina-indexer database restore"
 
+	idxr database migrate --help 2>&1 |
+		grep -iq "Usage: mina-indexer database migrate"
+
 	idxr database version --help 2>&1 |
 		grep -iq "Usage: mina-indexer database version"
 }
@@ -514,6 +550,10 @@ test_missing_blocks() {
 	assert '3NKGgTk7en3347KH81yDra876GPAUSoSePrfVKPmwR1KHfMpvJC5' $best_hash
 	assert $MAINNET_GENESIS_STATE_HASH $canonical_hash
 
+	# missing blocks 11 & 21 are the parents of the dangling roots
+	assert '[11,21]' $(idxr blocks missing | jq -c .missing_heights)
+	assert '[11,21]' $(idxr blocks missing | jq -c '[.missing_parents[].blockchain_length] | unique')
+
 	# add missing block which connects the dangling branches
 	stage_blocks v1_single 21 "$BLOCKS_DIR"
 	sleep 1
@@ -771,6 +811,24 @@ test_replay() {
 	assert '3NKGgTk7en3347KH81yDra876GPAUSoSePrfVKPmwR1KHfMpvJC5' $root_hash_replay
 }
 
+# Indexer server ingests blocks from a tar archive source
+test_block_source() {
+	mkdir -p ./archive-blocks
+	stage_blocks v1 15 ./archive-blocks
+	(cd ./archive-blocks && ls | sort -t- -k2 -n | tar -czf ../blocks.tar.gz -T -)
+	rm -fr ./archive-blocks
+
+	start \
+		--blocks-dir ./blocks \
+		--blocks-source ./blocks.tar.gz \
+		--database-dir ./database
+
+	assert 26 $(idxr summary --json | jq -r .blocks_processed)
+	assert 15 $(idxr summary --json | jq -r .witness_tree.best_tip_length)
+
+	rm -f ./blocks.tar.gz
+}
+
 # Indexer server returns correct transactions
 test_transactions() {
 	stage_blocks v1 13 "$BLOCKS_DIR"
@@ -830,6 +888,18 @@ test_transactions() {
 	assert 'CkpZirFuoLVVab6x2ry4j8Ld5gMmQdak7VHW6f5C7VJYE34WAEWqa' $txn_hash
 	assert '3NKd5So3VNqGZtRZiWsti4yaEe1fX79yz5TbfG6jBZqgMnCQQp3R' $state_hash
 
+	# computed tx hashes, pre- & post-hardfork
+	v1_block="$SRC/rust/tests/data/sequential_blocks/mainnet-105489-3NK4huLvUDiL4XuCUcyrWCKynmvhqfKsx5h2MfBXVVUq2Qwzi5uT.json"
+	v2_block="$SRC/rust/tests/data/hardfork/mainnet-359606-3NKvvtFwjEtQLswWJzXBSxxiKuYVbLJrKXCnmhp6jctYMqAWcftg.json"
+
+	jq '.staged_ledger_diff.diff[0].commands[0]' "$v1_block" >transactions/v1-command.json
+	jq '.data.staged_ledger_diff.diff[0].commands[0] | del(.txn_hash)' "$v2_block" >transactions/v2-command.json
+
+	assert 'CkpZZsSm9hQpGkGzMi8rcsQEWPZwGJXktiqGYADNwLoBeeamhzqnX' \
+		$(idxr transactions hash --command "$PWD/transactions/v1-command.json")
+	assert '5JuJ1eRNWdE8jSMmCDoHnAdBGhLyBnCk2gkcvkfCZ7WvrKtGuWHB' \
+		$(idxr transactions hash --command "$PWD/transactions/v2-command.json")
+
 	# state hash query
 	amount=$(idxr transactions state-hash --state-hash 3NKd5So3VNqGZtRZiWsti4yaEe1fX79yz5TbfG6jBZqgMnCQQp3R | jq -r .[0].Payment.amount)
 	source=$(idxr transactions state-hash --state-hash 3NKd5So3VNqGZtRZiWsti4yaEe1fX79yz5TbfG6jBZqgMnCQQp3R | jq -r .[0].Payment.source)
@@ -1048,7 +1118,7 @@ test_rest_accounts_summary() {
 	assert '5f704cc0c82e0ed70e873f0893d7e06f148524e3f0bdae2afb02e7819a0c24d1' $chain_id
 
 	circulating_supply=$(cat output.json | jq -r .circulatingSupply)
-	assert '89031537.840039233' $circulating_supply
+	assert '590387864.6330577' $circulating_supply
 
 	# date_time=$(cat output.json | jq -r .dateTime)
 	# assert 'Wed, 17 Mar 2021 07:15:00 GMT' $date_time
@@ -1060,7 +1130,7 @@ test_rest_accounts_summary() {
 	assert '145' $global_slot
 
 	locked_supply=$(cat output.json | jq -r .lockedSupply)
-	assert '716354155' $locked_supply
+	assert '214997828.206981533' $locked_supply
 
 	min_window_density=$(cat output.json | jq -r .minWindowDensity)
 	assert '77' $min_window_density
@@ -1095,6 +1165,11 @@ test_rest_accounts_summary() {
 	assert $count $(cat output.json | jq -r .totalNumBlocks)
 
 	check-jsonschema --schemafile "$SUMMARY_SCHEMA" output.json
+
+	# Testing metrics endpoint
+	curl --silent http://localhost:${port}/metrics >metrics.txt
+	assert '100' $(grep '^mina_indexer_best_tip_height ' metrics.txt | cut -d' ' -f2)
+	grep -q '^# TYPE mina_indexer_blocks_processed_total counter$' metrics.txt
 }
 
 test_rest_blocks() {
@@ -1121,6 +1196,83 @@ test_rest_blocks() {
 	assert '3NKLtRnMaWAAfRvdizaeaucDPBePPKGbKw64RVcuRFtMMkE8aAD4' $(cat output.json | jq -r .[0].block.state_hash)
 }
 
+test_rest_api_v1() {
+	stage_blocks v1 100 "$BLOCKS_DIR"
+
+	port=$(ephemeral_port)
+	database_create
+	start \
+		--web-port "$port" \
+		--blocks-dir ./blocks \
+		--database-dir ./database
+	sleep 3
+
+	api="http://localhost:${port}/api/v1"
+	state_hash=3NKLtRnMaWAAfRvdizaeaucDPBePPKGbKw64RVcuRFtMMkE8aAD4
+
+	# blocks by height, slot & state hash
+	assert $state_hash $(curl --silent $api/blocks/height/100 | jq -r .[0].state_hash)
+	assert '100' $(curl --silent $api/blocks/$state_hash | jq -r .blockchain_length)
+
+	slot=$(curl --silent $api/blocks/$state_hash | jq -r .global_slot_since_genesis)
+	assert 'true' $(curl --silent $api/blocks/slot/$slot | jq --arg h $state_hash 'any(.[]; .state_hash == $h)')
+
+	# block internal commands & SNARK work
+	assert 'true' $(curl --silent $api/blocks/$state_hash/internal-commands | jq 'length > 0')
+	assert 'true' $(curl --silent $api/blocks/$state_hash/snark-work | jq 'type == "array"')
+
+	# consistent error bodies
+	assert '400' $(curl --silent --output /dev/null --write-out '%{http_code}' $api/blocks/height/tip)
+	assert 'invalid_parameter' $(curl --silent $api/blocks/height/tip | jq -r .error.code)
+	assert 'not_found' $(curl --silent $api/not-an-endpoint | jq -r .error.code)
+
+	# OpenAPI spec
+	curl --silent $api/openapi.json >openapi.json
+	assert '3.0.3' $(jq -r .openapi openapi.json)
+	assert 'getBlocksAtHeight' $(jq -r '.paths."/blocks/height/{height}".get.operationId' openapi.json)
+}
+
+# Address book labels override usernames & are filterable by category
+test_address_book() {
+	stage_blocks v1 20 "$BLOCKS_DIR"
+
+	port=$(ephemeral_port)
+	database_create
+	start \
+		--web-port "$port" \
+		--blocks-dir ./blocks \
+		--database-dir ./database
+	sleep 3
+
+	pk=B62qqDJCQsfDoHJvJCh1hgTpiVbmgBg8SbNKLMXsjuVsX5pxCELDyFk
+	labels=$(pwd)/labels.csv
+	echo "public_key,label,category,source" >"$labels"
+	echo "$pk,TestExchange,exchange,regression" >>"$labels"
+
+	idxr address-book load --path "$labels"
+	assert 'TestExchange' $(idxr accounts public-key --public-key $pk | jq -r .username)
+	assert '1' $(idxr address-book list --category exchange | jq -r length)
+	assert '0' $(idxr address-book list --category pool | jq -r length)
+
+	# GraphQL resolves & filters by the label
+	graphql="http://localhost:${port}/graphql"
+	accounts=$(curl --silent -H 'Content-Type: application/json' \
+		-d '{"query":"{ accounts(query: {category: EXCHANGE}) { publicKey username category } }"}' \
+		"$graphql")
+	assert $pk $(echo "$accounts" | jq -r '.data.accounts[0].publicKey')
+	assert 'EXCHANGE' $(echo "$accounts" | jq -r '.data.accounts[0].category')
+	assert '1' $(echo "$accounts" | jq -r '.data.accounts | length')
+
+	# re-categorize & remove
+	idxr address-book add --public-key $pk --label TestPool --category pool
+	assert '0' $(idxr address-book list --category exchange | jq -r length)
+	assert '1' $(idxr address-book list --category pool | jq -r length)
+
+	idxr address-book remove --public-key $pk
+	assert '0' $(idxr address-book list | jq -r length)
+	assert 'not_found' $(idxr address-book remove --public-key $pk --output json | jq -r .error.code || true)
+}
+
 test_best_chain_many_blocks() {
 	stage_blocks v1 5000 "$BLOCKS_DIR"
 
@@ -1411,6 +1563,61 @@ test_staking_delegations() {
 	assert $total_delegated $file_total_delegated
 }
 
+test_pool_payouts() {
+	idxr database create \
+		--blocks-dir "$BLOCKS_DIR" \
+		--database-dir ./database \
+		--staking-ledgers-dir $STAKING_LEDGERS
+	start --database-dir ./database
+
+	# pool delegators come from the staking ledger
+	pk=B62qrxNgwAdhGYZv1BXQRt2HgopUceFyrtXZMikwsuaHu5FigRJjhwY
+	payouts=$(idxr staking-ledgers payouts --epoch 0 --delegate $pk --fee-percent 5 --scheme fee-split)
+
+	assert $pk $(echo $payouts | jq -r .delegate)
+	assert '0' $(echo $payouts | jq -r .epoch)
+	assert 'fee_split' $(echo $payouts | jq -r .scheme)
+	assert '2' $(echo $payouts | jq -r '.payouts | length')
+	assert '57617370302858700' $(echo $payouts | jq -r .total_stake)
+
+	# rewards are split between the delegators & operator
+	total_rewards=$(echo $payouts | jq -r .total_rewards)
+	assert $total_rewards $(echo $payouts | jq -r '([.payouts[].payout] | add) + .operator_reward')
+
+	# write payouts to file
+	file=./epoch_0_pool_payouts.json
+	idxr staking-ledgers payouts --epoch 0 --delegate $pk --path $file
+
+	assert 'proportional' $(cat $file | jq -r .scheme)
+	assert '2' $(cat $file | jq -r '.payouts | length')
+}
+
+test_producer_report() {
+	idxr database create \
+		--blocks-dir "$BLOCKS_DIR" \
+		--database-dir ./database \
+		--staking-ledgers-dir $STAKING_LEDGERS
+	start --database-dir ./database
+
+	# stake share comes from the epoch's staking ledger
+	pk=B62qrxNgwAdhGYZv1BXQRt2HgopUceFyrtXZMikwsuaHu5FigRJjhwY
+	report=$(idxr blocks production --epoch 0 --public-key $pk)
+
+	assert $pk $(echo $report | jq -r .public_key)
+	assert '0' $(echo $report | jq -r .epoch)
+	assert '57617370302858700' $(echo $report | jq -r .stake)
+	assert 'true' $(echo $report | jq -r '.expected_blocks > 0')
+	assert 'true' $(echo $report | jq -r '.num_canonical_blocks <= .num_blocks_produced')
+	assert 'true' $(echo $report | jq -r '.slots_elapsed <= 7140')
+
+	# write report to file
+	file=./epoch_0_producer_report.json
+	idxr blocks production --epoch 0 --public-key $pk --path $file
+
+	assert $(echo $report | jq -r .expected_blocks) $(cat $file | jq -r .expected_blocks)
+	assert $(echo $report | jq -r .missed_slots) $(cat $file | jq -r .missed_slots)
+}
+
 test_internal_commands() {
 	stage_blocks v1 11 "$BLOCKS_DIR"
 
@@ -1882,8 +2089,11 @@ test_do_not_ingest_orphan_blocks() {
 	# each query should fail with an error
 	echo '========== vvv ERRORS LOGGED vvv =========='
 	for orphan in "${orphan_blocks[@]}"; do
-		result=$(idxr blocks state-hash --state-hash $orphan)
+		result=$(idxr blocks state-hash --state-hash $orphan 2>&1 || true)
 		assert "Block at state hash not present in store: $orphan" "$result"
+
+		code=$(idxr blocks state-hash --state-hash $orphan --output json | jq -r .error.code || true)
+		assert 'not_found' "$code"
 	done
 	echo '========== ^^^ ERRORS LOGGED ^^^ =========='
 
@@ -1938,6 +2148,7 @@ for test_name in "$@"; do
 	"test_ledgers") test_ledgers ;;
 	"test_sync") test_sync ;;
 	"test_replay") test_replay ;;
+	"test_block_source") test_block_source ;;
 	"test_transactions") test_transactions ;;
 	"test_transactions_csv") test_transactions_csv ;;
 	"test_snark_work") test_snark_work ;;
@@ -1945,12 +2156,16 @@ for test_name in "$@"; do
 	"test_restore_snapshot_failure_returns_proper_code") test_restore_snapshot_failure_returns_proper_code ;;
 	"test_rest_accounts_summary") test_rest_accounts_summary ;;
 	"test_rest_blocks") test_rest_blocks ;;
+	"test_rest_api_v1") test_rest_api_v1 ;;
+	"test_address_book") test_address_book ;;
 	"test_genesis_block_creator_v1") test_genesis_block_creator_v1 ;;
 	"test_genesis_block_creator_v2") test_genesis_block_creator_v2 ;;
 	"test_txn_nonces") test_txn_nonces ;;
 	"test_startup_staking_ledgers") test_startup_staking_ledgers ;;
 	"test_watch_staking_ledgers") test_watch_staking_ledgers ;;
 	"test_staking_delegations") test_staking_delegations ;;
+	"test_pool_payouts") test_pool_payouts ;;
+	"test_producer_report") test_producer_report ;;
 	"test_internal_commands") test_internal_commands ;;
 	"test_internal_commands_csv") test_internal_commands_csv ;;
 	"test_hurl_v1") test_hurl_v1 ;;